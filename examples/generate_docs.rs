@@ -10,7 +10,7 @@
 use forthic::module::Module;
 use forthic::modules::standard::{
     ArrayModule, BooleanModule, CoreModule, DateTimeModule, JSONModule, MathModule, RecordModule,
-    StringModule,
+    StatsModule, StringModule,
 };
 
 fn main() {
@@ -22,6 +22,7 @@ fn main() {
         ("json", JSONModule::new().module().clone()),
         ("math", MathModule::new().module().clone()),
         ("record", RecordModule::new().module().clone()),
+        ("stats", StatsModule::new().module().clone()),
         ("string", StringModule::new().module().clone()),
     ];
 
@@ -0,0 +1,189 @@
+//! Procedural macros for the forthic crate
+//!
+//! `#[forthic_word]` turns a typed Rust function into a Forthic word
+//! (plans/TS-PARITY-BACKLOG.md item 22(b)). Use it through the forthic
+//! crate's `macros` feature (`forthic::forthic_word`) rather than
+//! depending on this crate directly — the generated code refers to
+//! `::forthic` paths.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{FnArg, ItemFn, LitStr, Pat, Token, Type};
+
+/// Declare a Forthic word from a typed Rust function
+///
+/// ```ignore
+/// use forthic::forthic_word;
+///
+/// #[forthic_word("ADD-TAX", "( amount:float rate:float -- total:float )", "Add tax to an amount")]
+/// fn add_tax(amount: f64, rate: f64) -> f64 {
+///     amount * (1.0 + rate)
+/// }
+///
+/// let mut module = forthic::Module::new("billing".to_string());
+/// register_add_tax(&mut module);
+/// ```
+///
+/// The function is left as-is and a `register_<fn>(&mut Module)` sibling
+/// (same visibility) is generated. Its word pops one argument per
+/// parameter — the last parameter is the top of the stack — converting
+/// each through `forthic::convert::FromForthic` with an error naming the
+/// word and argument position on a type mismatch. The return value is
+/// pushed through `forthic::convert::WordReturn`: a convertible value is
+/// pushed, `()` pushes nothing, and `Result<_, ForthicError>` propagates
+/// its error. A `&mut dyn InterpreterContext` parameter receives the
+/// context instead of a stack value.
+///
+/// The stack effect and description become the word's `WordDoc`. The
+/// number of inputs in the stack effect must match the number of popped
+/// parameters; a mismatch is a compile error.
+#[proc_macro_attribute]
+pub fn forthic_word(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand(attr, item) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let args = Punctuated::<LitStr, Token![,]>::parse_terminated.parse(attr)?;
+    let [name, effect, description]: [LitStr; 3] = args
+        .into_iter()
+        .collect::<Vec<_>>()
+        .try_into()
+        .map_err(|_| {
+            syn::Error::new(
+                Span::call_site(),
+                "expected #[forthic_word(\"NAME\", \"( inputs -- outputs )\", \"description\")]",
+            )
+        })?;
+
+    let func: ItemFn = syn::parse(item)?;
+    let sig = &func.sig;
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "Forthic words are synchronous; #[forthic_word] cannot be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "#[forthic_word] functions cannot be generic",
+        ));
+    }
+
+    let word_name = name.value();
+    let mut pops = Vec::new();
+    let mut call_args = Vec::new();
+    let mut position = 0usize;
+    for input in &sig.inputs {
+        let FnArg::Typed(arg) = input else {
+            return Err(syn::Error::new_spanned(
+                input,
+                "#[forthic_word] functions cannot take self",
+            ));
+        };
+        if is_context_type(&arg.ty) {
+            call_args.push(quote! { &mut *context });
+            continue;
+        }
+        position += 1;
+        let arg_name = match &*arg.pat {
+            Pat::Ident(ident) => ident.ident.to_string(),
+            _ => format!("arg{position}"),
+        };
+        let var = format_ident!("__forthic_arg{}", position);
+        let ty = &arg.ty;
+        pops.push(quote! {
+            let #var: #ty = ::forthic::convert::pop_arg(context, #word_name, #position, #arg_name)?;
+        });
+        call_args.push(quote! { #var });
+    }
+
+    let declared = count_inputs(&effect)?;
+    if declared != position {
+        return Err(syn::Error::new_spanned(
+            &effect,
+            format!(
+                "stack effect declares {declared} input(s) but `{}` pops {position}",
+                sig.ident
+            ),
+        ));
+    }
+
+    // Top of stack is the last argument: pop in reverse
+    pops.reverse();
+    let fn_ident = &sig.ident;
+    let vis = &func.vis;
+    let register_ident = format_ident!("register_{}", fn_ident);
+    let register_doc =
+        format!("Register the `{word_name}` word (generated by `#[forthic_word]` on `{fn_ident}`)");
+
+    Ok(quote! {
+        #func
+
+        #[doc = #register_doc]
+        #vis fn #register_ident(module: &mut ::forthic::Module) {
+            fn handler(
+                context: &mut dyn ::forthic::module::InterpreterContext,
+            ) -> ::std::result::Result<(), ::forthic::ForthicError> {
+                #(#pops)*
+                let result = #fn_ident(#(#call_args),*);
+                ::forthic::convert::push_result(context, result)
+            }
+            module.add_exportable_word(::std::sync::Arc::new(
+                ::forthic::module::ModuleWord::with_doc(
+                    #name.to_string(),
+                    handler,
+                    #effect,
+                    #description,
+                ),
+            ));
+        }
+    })
+}
+
+/// `&mut dyn InterpreterContext` (any path ending in InterpreterContext)
+fn is_context_type(ty: &Type) -> bool {
+    let Type::Reference(reference) = ty else {
+        return false;
+    };
+    if reference.mutability.is_none() {
+        return false;
+    }
+    let Type::TraitObject(object) = &*reference.elem else {
+        return false;
+    };
+    object.bounds.iter().any(|bound| match bound {
+        syn::TypeParamBound::Trait(t) => t
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "InterpreterContext"),
+        _ => false,
+    })
+}
+
+/// Inputs declared by a stack effect: the names left of `--`, not counting
+/// `[optional]` entries (those are popped by hand, e.g. WordOptions)
+fn count_inputs(effect: &LitStr) -> syn::Result<usize> {
+    let text = effect.value();
+    let inner = text
+        .trim()
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(|| {
+            syn::Error::new_spanned(effect, "stack effect must be \"( ... -- ... )\"")
+        })?;
+    let (inputs, _) = inner
+        .split_once("--")
+        .ok_or_else(|| syn::Error::new_spanned(effect, "stack effect is missing `--`"))?;
+    Ok(inputs
+        .split_whitespace()
+        .filter(|entry| !entry.starts_with('['))
+        .count())
+}
@@ -1,14 +1,34 @@
 //! Forthic JSON-RPC server binary
 //!
 //! ```text
-//! forthic-jsonrpc [--port 8765] [--host 127.0.0.1] [--token SECRET]
+//! forthic-jsonrpc [--port 8765 [--host 127.0.0.1] | --stdio] [--token SECRET]
+//!                 [--tz UTC] [--max-sessions 64] [--session-idle-secs 900]
+//!                 [--bootstrap FILE.forthic]...
+//!                 [--allow-words W,...] [--allow-modules M,...]
+//!                 [--deny-words W,...] [--deny-modules M,...]
+//!                 [--audit-log FILE [--audit-stack sizes|types|values]]
 //! ```
 //!
 //! Defaults are conservative (loopback only, no auth needed). Binding a
 //! non-loopback host without --token logs a security warning; the server
 //! executes caller-supplied Forthic code.
+//!
+//! Each --bootstrap file is compiled once at startup and served as a module
+//! named after the file; the server refuses to start if one fails. The
+//! allow/deny flags set the word policy (comma-separated, repeatable).
+//!
+//! With --stdio the server reads Content-Length framed requests from stdin
+//! and writes responses to stdout (LSP-style) instead of listening on a
+//! port; PRINT, PEEK! and STACK! write to stderr instead. It exits when
+//! stdin closes. The HTTP server also accepts WebSocket connections at
+//! `/ws`, and serves `/healthz` and Prometheus `/metrics`.
+//!
+//! --audit-log appends an audit record (JSON lines) for every call and
+//! every refused token; --audit-stack sets how much of the stacks it shows
+//! (default: sizes only).
 
-use forthic::jsonrpc::{serve, ServeOptions};
+use forthic::jsonrpc::{serve, serve_stdio, AuditLog, JsonLinesSink, ServeOptions, StackRedaction};
+use forthic::word_policy::WordPolicy;
 
 fn flag_value(args: &[String], name: &str) -> Option<String> {
     args.iter()
@@ -17,18 +37,74 @@ fn flag_value(args: &[String], name: &str) -> Option<String> {
         .cloned()
 }
 
+fn flag_values(args: &[String], name: &str) -> Vec<String> {
+    args.iter()
+        .zip(args.iter().skip(1))
+        .filter(|(flag, _)| *flag == name)
+        .map(|(_, value)| value.clone())
+        .collect()
+}
+
+fn list_flag(args: &[String], name: &str) -> Vec<String> {
+    flag_values(args, name)
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
 #[tokio::main]
 async fn main() {
     let args: Vec<String> = std::env::args().skip(1).collect();
     let port: u16 = flag_value(&args, "--port")
         .and_then(|v| v.parse().ok())
         .unwrap_or(8765);
+    let audit = flag_value(&args, "--audit-log").map(|path| {
+        let redaction = match flag_value(&args, "--audit-stack") {
+            Some(value) => value.parse::<StackRedaction>().unwrap_or_else(|e| {
+                eprintln!("Fatal error: {e}");
+                std::process::exit(1);
+            }),
+            None => StackRedaction::default(),
+        };
+        let sink = JsonLinesSink::open(&path).unwrap_or_else(|e| {
+            eprintln!("Fatal error: audit log {path}: {e}");
+            std::process::exit(1);
+        });
+        AuditLog::new(std::sync::Arc::new(sink)).with_redaction(redaction)
+    });
     let options = ServeOptions {
         host: flag_value(&args, "--host"),
         token: flag_value(&args, "--token"),
+        timezone: flag_value(&args, "--tz"),
+        bootstrap_files: flag_values(&args, "--bootstrap")
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        max_sessions: flag_value(&args, "--max-sessions").and_then(|v| v.parse().ok()),
+        session_idle_timeout: flag_value(&args, "--session-idle-secs")
+            .and_then(|v| v.parse().ok())
+            .map(std::time::Duration::from_secs),
+        word_policy: WordPolicy {
+            allow_words: list_flag(&args, "--allow-words"),
+            allow_modules: list_flag(&args, "--allow-modules"),
+            deny_words: list_flag(&args, "--deny-words"),
+            deny_modules: list_flag(&args, "--deny-modules"),
+        },
+        audit,
         ..ServeOptions::default()
     };
 
+    if args.iter().any(|a| a == "--stdio") {
+        if let Err(e) = serve_stdio(options).await {
+            eprintln!("Fatal error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     match serve(port, options).await {
         Ok(handle) => {
             println!("Forthic JSON-RPC server listening on {}", handle.addr());
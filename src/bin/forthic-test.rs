@@ -0,0 +1,90 @@
+//! Forthic test runner binary
+//!
+//! ```text
+//! forthic-test [--format text|tap|junit] [--tz UTC] [--output PATH] FILE...
+//! ```
+//!
+//! Runs every `: TEST-... ;` definition in each file, each in a fresh
+//! interpreter. Exit status: 0 when all tests pass, 1 on any failure or
+//! error, 2 on bad usage or an unreadable file.
+
+use forthic::testing::{TestReport, TestRunner};
+use std::path::Path;
+use std::process::exit;
+
+const USAGE: &str =
+    "usage: forthic-test [--format text|tap|junit] [--tz UTC] [--output PATH] FILE...";
+
+fn main() {
+    let mut format = "text".to_string();
+    let mut timezone = "UTC".to_string();
+    let mut output: Option<String> = None;
+    let mut files = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let slot = match arg.as_str() {
+            "--format" => &mut format,
+            "--tz" => &mut timezone,
+            "--output" => output.insert(String::new()),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ => {
+                files.push(arg);
+                continue;
+            }
+        };
+        match args.next() {
+            Some(value) => *slot = value,
+            None => {
+                eprintln!("{arg} requires a value\n{USAGE}");
+                exit(2);
+            }
+        }
+    }
+    if files.is_empty() {
+        eprintln!("{USAGE}");
+        exit(2);
+    }
+
+    let runner = TestRunner::new(&timezone);
+    let mut report = TestReport::new();
+    for file in &files {
+        match runner.run_file(Path::new(file)) {
+            Ok(suite) => report.add_suite(suite),
+            Err(e) => {
+                eprintln!("Cannot read {file}: {e}");
+                exit(2);
+            }
+        }
+    }
+
+    let rendered = match format.as_str() {
+        "text" => report.to_text(),
+        "tap" => report.to_tap(),
+        "junit" => report.to_junit_xml(),
+        other => {
+            eprintln!("Unknown format '{other}'\n{USAGE}");
+            exit(2);
+        }
+    };
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, rendered) {
+                eprintln!("Cannot write {path}: {e}");
+                exit(2);
+            }
+            // Keep the console useful when the report goes to a file
+            if format != "text" {
+                print!("{}", report.to_text());
+            }
+        }
+        None => print!("{rendered}"),
+    }
+
+    if !report.all_passed() {
+        exit(1);
+    }
+}
@@ -0,0 +1,480 @@
+//! Typed conversions between `ForthicValue` and Rust types
+//!
+//! The runtime half of `#[forthic_word]` (the `macros` feature): generated
+//! handlers pop each argument with [`pop_arg`] and push the function's
+//! return value with [`push_result`]. Hand-written words use the
+//! [`StackArgs`] extension trait (`context.pop_int("WORD", 1)?`), which
+//! reports mismatches in the same "{word} argument {n} must be {type}"
+//! form.
+//!
+//! Conversions are strict, matching the runtime's no-leniency rule: a
+//! string is never parsed as a number. The one widening is int -> float
+//! (`f64` parameters accept both, as the math words do).
+
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::InterpreterContext;
+use crate::word_options::WordOptions;
+use chrono::{NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+use indexmap::IndexMap;
+
+/// Stack-effect type name of a value, for error messages
+pub fn type_label(value: &ForthicValue) -> &'static str {
+    match value {
+        ForthicValue::Null => "null",
+        ForthicValue::Bool(_) => "boolean",
+        ForthicValue::Int(_) => "int",
+        ForthicValue::Float(_) => "float",
+        ForthicValue::String(_) => "string",
+        ForthicValue::Array(_) => "array",
+        ForthicValue::Record(_) => "record",
+        ForthicValue::Date(_) => "date",
+        ForthicValue::Time(_) => "time",
+        ForthicValue::DateTime(_) => "datetime",
+        ForthicValue::WordOptions(_) => "WordOptions",
+        ForthicValue::Sequence(_) => "sequence",
+        ForthicValue::StartArrayMarker => "array marker",
+    }
+}
+
+/// A Rust type that can be taken from the stack
+pub trait FromForthic: Sized {
+    /// Stack-effect type name reported when a value doesn't convert
+    fn expected() -> String;
+
+    /// Convert, or hand the value back when it has the wrong type
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue>;
+}
+
+/// A Rust type that can be pushed onto the stack
+pub trait IntoForthic {
+    fn into_forthic(self) -> ForthicValue;
+}
+
+impl FromForthic for ForthicValue {
+    fn expected() -> String {
+        "any".to_string()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        Ok(value)
+    }
+}
+
+impl IntoForthic for ForthicValue {
+    fn into_forthic(self) -> ForthicValue {
+        self
+    }
+}
+
+impl FromForthic for bool {
+    fn expected() -> String {
+        "boolean".to_string()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        match value {
+            ForthicValue::Bool(b) => Ok(b),
+            other => Err(other),
+        }
+    }
+}
+
+impl IntoForthic for bool {
+    fn into_forthic(self) -> ForthicValue {
+        ForthicValue::Bool(self)
+    }
+}
+
+impl FromForthic for i64 {
+    fn expected() -> String {
+        "int".to_string()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        match value {
+            ForthicValue::Int(i) => Ok(i),
+            other => Err(other),
+        }
+    }
+}
+
+impl IntoForthic for i64 {
+    fn into_forthic(self) -> ForthicValue {
+        ForthicValue::Int(self)
+    }
+}
+
+impl FromForthic for f64 {
+    fn expected() -> String {
+        "float".to_string()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        match value {
+            ForthicValue::Float(f) => Ok(f),
+            ForthicValue::Int(i) => Ok(i as f64),
+            other => Err(other),
+        }
+    }
+}
+
+impl IntoForthic for f64 {
+    fn into_forthic(self) -> ForthicValue {
+        ForthicValue::Float(self)
+    }
+}
+
+impl FromForthic for String {
+    fn expected() -> String {
+        "string".to_string()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        match value {
+            ForthicValue::String(s) => Ok(s),
+            other => Err(other),
+        }
+    }
+}
+
+impl IntoForthic for String {
+    fn into_forthic(self) -> ForthicValue {
+        ForthicValue::String(self)
+    }
+}
+
+impl IntoForthic for &str {
+    fn into_forthic(self) -> ForthicValue {
+        ForthicValue::String(self.to_string())
+    }
+}
+
+impl FromForthic for NaiveDate {
+    fn expected() -> String {
+        "date".to_string()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        match value {
+            ForthicValue::Date(d) => Ok(d),
+            other => Err(other),
+        }
+    }
+}
+
+impl IntoForthic for NaiveDate {
+    fn into_forthic(self) -> ForthicValue {
+        ForthicValue::Date(self)
+    }
+}
+
+impl FromForthic for NaiveTime {
+    fn expected() -> String {
+        "time".to_string()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        match value {
+            ForthicValue::Time(t) => Ok(t),
+            other => Err(other),
+        }
+    }
+}
+
+impl IntoForthic for NaiveTime {
+    fn into_forthic(self) -> ForthicValue {
+        ForthicValue::Time(self)
+    }
+}
+
+impl FromForthic for chrono::DateTime<Tz> {
+    fn expected() -> String {
+        "datetime".to_string()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        match value {
+            ForthicValue::DateTime(dt) => Ok(dt),
+            other => Err(other),
+        }
+    }
+}
+
+impl IntoForthic for chrono::DateTime<Tz> {
+    fn into_forthic(self) -> ForthicValue {
+        ForthicValue::DateTime(self)
+    }
+}
+
+impl FromForthic for WordOptions {
+    fn expected() -> String {
+        "WordOptions".to_string()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        match value {
+            ForthicValue::WordOptions(options) => Ok(options),
+            other => Err(other),
+        }
+    }
+}
+
+/// NULL converts to None; anything else must convert to T
+impl<T: FromForthic> FromForthic for Option<T> {
+    fn expected() -> String {
+        T::expected()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        match value {
+            ForthicValue::Null => Ok(None),
+            other => T::from_forthic(other).map(Some),
+        }
+    }
+}
+
+impl<T: IntoForthic> IntoForthic for Option<T> {
+    fn into_forthic(self) -> ForthicValue {
+        self.map_or(ForthicValue::Null, IntoForthic::into_forthic)
+    }
+}
+
+/// Every element must convert; on a mismatch the whole array is handed
+/// back so the error reports what was actually passed
+impl<T: FromForthic> FromForthic for Vec<T> {
+    fn expected() -> String {
+        match T::expected().as_str() {
+            "any" => "array".to_string(),
+            item => format!("{item}[]"),
+        }
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        let ForthicValue::Array(items) = value else {
+            return Err(value);
+        };
+        items
+            .iter()
+            .cloned()
+            .map(T::from_forthic)
+            .collect::<Result<Vec<T>, _>>()
+            .map_err(|_| ForthicValue::Array(items))
+    }
+}
+
+impl<T: IntoForthic> IntoForthic for Vec<T> {
+    fn into_forthic(self) -> ForthicValue {
+        ForthicValue::Array(self.into_iter().map(IntoForthic::into_forthic).collect())
+    }
+}
+
+impl<T: FromForthic> FromForthic for IndexMap<String, T> {
+    fn expected() -> String {
+        "record".to_string()
+    }
+    fn from_forthic(value: ForthicValue) -> Result<Self, ForthicValue> {
+        let ForthicValue::Record(fields) = value else {
+            return Err(value);
+        };
+        fields
+            .iter()
+            .map(|(k, v)| T::from_forthic(v.clone()).map(|v| (k.clone(), v)))
+            .collect::<Result<IndexMap<String, T>, _>>()
+            .map_err(|_| ForthicValue::Record(fields))
+    }
+}
+
+impl<T: IntoForthic> IntoForthic for IndexMap<String, T> {
+    fn into_forthic(self) -> ForthicValue {
+        ForthicValue::Record(
+            self.into_iter()
+                .map(|(k, v)| (k, v.into_forthic()))
+                .collect(),
+        )
+    }
+}
+
+/// What a word function may return: a value to push, `()` for nothing,
+/// or a `Result` of either
+pub trait WordReturn {
+    fn push_onto(self, context: &mut dyn InterpreterContext) -> Result<(), ForthicError>;
+}
+
+impl<T: IntoForthic> WordReturn for T {
+    fn push_onto(self, context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        context.stack_push(self.into_forthic());
+        Ok(())
+    }
+}
+
+impl WordReturn for () {
+    fn push_onto(self, _context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Ok(())
+    }
+}
+
+impl<T: WordReturn> WordReturn for Result<T, ForthicError> {
+    fn push_onto(self, context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        self?.push_onto(context)
+    }
+}
+
+/// The uniform type-mismatch error: "{word} argument {position} [(name)]
+/// must be {expected}, got {actual}"
+pub fn argument_error(
+    word: &str,
+    position: usize,
+    name: Option<&str>,
+    expected: &str,
+    value: &ForthicValue,
+) -> ForthicError {
+    let name = name.map(|n| format!(" ({n})")).unwrap_or_default();
+    ForthicError::invalid_operation(format!(
+        "{word} argument {position}{name} must be {expected}, got {}",
+        type_label(value)
+    ))
+}
+
+/// Refuse a lazy sequence as argument `position` of `word`, a word that
+/// needs every item in hand (SORT, REVERSE, >JSON, ...)
+pub fn reject_sequence(
+    word: &str,
+    position: usize,
+    value: &ForthicValue,
+) -> Result<(), ForthicError> {
+    match value {
+        ForthicValue::Sequence(_) => Err(ForthicError::invalid_operation(format!(
+            "{word} argument {position} is a sequence: REALIZE first"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Pop argument `position` (1-based, in stack-effect order) of `word`,
+/// converting it to `T`. A mismatch is an InvalidOperation naming the
+/// word, the argument, and both types.
+pub fn pop_arg<T: FromForthic>(
+    context: &mut dyn InterpreterContext,
+    word: &str,
+    position: usize,
+    name: &str,
+) -> Result<T, ForthicError> {
+    let value = context.stack_pop()?;
+    T::from_forthic(value)
+        .map_err(|value| argument_error(word, position, Some(name), &T::expected(), &value))
+}
+
+/// Push a word function's return value
+pub fn push_result<R: WordReturn>(
+    context: &mut dyn InterpreterContext,
+    result: R,
+) -> Result<(), ForthicError> {
+    result.push_onto(context)
+}
+
+/// Typed pops for hand-written words, available on every
+/// `InterpreterContext`
+///
+/// `position` is the argument's 1-based place in the word's stack effect
+/// (the deepest input is 1): in `( items:array forthic:string -- )`,
+/// `forthic` is argument 2. Mismatches are reported by [`argument_error`]
+/// so messages read the same across the library.
+pub trait StackArgs {
+    /// Pop any convertible type (`Option<T>` accepts NULL)
+    fn pop_typed<T: FromForthic>(&mut self, word: &str, position: usize)
+        -> Result<T, ForthicError>;
+
+    fn pop_int(&mut self, word: &str, position: usize) -> Result<i64, ForthicError> {
+        self.pop_typed(word, position)
+    }
+
+    /// An int or a float, as a float
+    fn pop_float_like(&mut self, word: &str, position: usize) -> Result<f64, ForthicError>;
+
+    fn pop_string(&mut self, word: &str, position: usize) -> Result<String, ForthicError> {
+        self.pop_typed(word, position)
+    }
+
+    fn pop_array(
+        &mut self,
+        word: &str,
+        position: usize,
+    ) -> Result<Vec<ForthicValue>, ForthicError> {
+        self.pop_typed(word, position)
+    }
+
+    fn pop_record(
+        &mut self,
+        word: &str,
+        position: usize,
+    ) -> Result<IndexMap<String, ForthicValue>, ForthicError> {
+        self.pop_typed(word, position)
+    }
+
+    fn pop_date(&mut self, word: &str, position: usize) -> Result<NaiveDate, ForthicError> {
+        self.pop_typed(word, position)
+    }
+
+    /// A Forthic string to run
+    fn pop_code(&mut self, word: &str, position: usize) -> Result<String, ForthicError>;
+
+    /// Pop a WordOptions value if one sits on top of the stack (Forthic's
+    /// optional-trailing-options convention: `... [.push_rest TRUE] ~> TAKE`)
+    fn pop_options(&mut self) -> Option<WordOptions>;
+
+    /// Pop the top `N` values, returned in stack order (deepest first).
+    /// When fewer than `N` are there, the stack is left as it was.
+    fn pop_n<const N: usize>(&mut self) -> Result<[ForthicValue; N], ForthicError>;
+}
+
+impl<C: InterpreterContext + ?Sized> StackArgs for C {
+    fn pop_typed<T: FromForthic>(
+        &mut self,
+        word: &str,
+        position: usize,
+    ) -> Result<T, ForthicError> {
+        let value = self.stack_pop()?;
+        T::from_forthic(value)
+            .map_err(|value| argument_error(word, position, None, &T::expected(), &value))
+    }
+
+    fn pop_float_like(&mut self, word: &str, position: usize) -> Result<f64, ForthicError> {
+        match self.stack_pop()? {
+            ForthicValue::Float(f) => Ok(f),
+            ForthicValue::Int(i) => Ok(i as f64),
+            other => Err(argument_error(word, position, None, "number", &other)),
+        }
+    }
+
+    fn pop_code(&mut self, word: &str, position: usize) -> Result<String, ForthicError> {
+        match self.stack_pop()? {
+            ForthicValue::String(s) => Ok(s),
+            other => Err(argument_error(
+                word,
+                position,
+                None,
+                "Forthic string",
+                &other,
+            )),
+        }
+    }
+
+    fn pop_options(&mut self) -> Option<WordOptions> {
+        if matches!(self.stack_peek(), Some(ForthicValue::WordOptions(_))) {
+            if let Ok(ForthicValue::WordOptions(options)) = self.stack_pop() {
+                return Some(options);
+            }
+        }
+        None
+    }
+
+    fn pop_n<const N: usize>(&mut self) -> Result<[ForthicValue; N], ForthicError> {
+        let mut values = Vec::with_capacity(N);
+        for _ in 0..N {
+            match self.stack_pop() {
+                Ok(value) => values.push(value),
+                Err(e) => {
+                    while let Some(value) = values.pop() {
+                        self.stack_push(value);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        values.reverse();
+        Ok(values
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("exactly N values were popped")))
+    }
+}
@@ -224,9 +224,58 @@ pub enum ForthicError {
         #[source]
         cause: Option<Box<dyn std::error::Error + Send + Sync>>,
     },
+
+    /// A word refused by the interpreter's word policy (see
+    /// `crate::word_policy`)
+    #[error("Word not allowed: {word}")]
+    WordNotAllowed {
+        forthic: String,
+        word: String,
+        location: Option<CodeLocation>,
+        #[source]
+        cause: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+
+    /// A word proxied to another runtime failed there (see
+    /// `jsonrpc::RemoteModule`). `remote_location` is where the remote
+    /// runtime reports the failure, when it reports one.
+    #[error("Error in {runtime} runtime: {message} ({error_type})")]
+    Remote {
+        forthic: String,
+        runtime: String,
+        error_type: String,
+        message: String,
+        remote_location: Option<String>,
+        location: Option<CodeLocation>,
+        #[source]
+        cause: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+
+    /// A test assertion (ASSERT, ASSERT=, ASSERT-ERROR) did not hold. Kept
+    /// apart from InvalidOperation so a test runner can tell a failing test
+    /// from a broken one.
+    #[error("{message}")]
+    AssertionFailed {
+        forthic: String,
+        message: String,
+        location: Option<CodeLocation>,
+        #[source]
+        cause: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
 }
 
 impl ForthicError {
+    /// An InvalidOperation raised by a word; the interpreter fills in the
+    /// code and location as it propagates.
+    pub fn invalid_operation(message: impl Into<String>) -> Self {
+        Self::InvalidOperation {
+            forthic: String::new(),
+            message: message.into(),
+            location: None,
+            cause: None,
+        }
+    }
+
     /// Fill in a code location if the error doesn't already carry one.
     /// For WordExecution this fills `call_location` (where the definition
     /// was invoked); IntentionalStop has no location and passes through.
@@ -250,7 +299,10 @@ impl ForthicError {
             | Self::UnknownToken { location, .. }
             | Self::Module { location, .. }
             | Self::TooManyAttempts { location, .. }
-            | Self::InvalidOperation { location, .. } => {
+            | Self::InvalidOperation { location, .. }
+            | Self::AssertionFailed { location, .. }
+            | Self::WordNotAllowed { location, .. }
+            | Self::Remote { location, .. } => {
                 if location.is_none() {
                     *location = loc;
                 }
@@ -283,7 +335,10 @@ impl ForthicError {
             | Self::UnknownToken { forthic, .. }
             | Self::Module { forthic, .. }
             | Self::TooManyAttempts { forthic, .. }
-            | Self::InvalidOperation { forthic, .. } => {
+            | Self::InvalidOperation { forthic, .. }
+            | Self::AssertionFailed { forthic, .. }
+            | Self::WordNotAllowed { forthic, .. }
+            | Self::Remote { forthic, .. } => {
                 if forthic.is_empty() {
                     *forthic = code.to_string();
                 }
@@ -314,6 +369,9 @@ impl ForthicError {
             Self::TooManyAttempts { .. } => "TooManyAttempts",
             Self::IntentionalStop { .. } => "IntentionalStop",
             Self::InvalidOperation { .. } => "InvalidOperation",
+            Self::AssertionFailed { .. } => "AssertionFailed",
+            Self::WordNotAllowed { .. } => "WordNotAllowed",
+            Self::Remote { .. } => "Remote",
         }
     }
 
@@ -333,7 +391,10 @@ impl ForthicError {
             | Self::UnknownToken { forthic, .. }
             | Self::Module { forthic, .. }
             | Self::TooManyAttempts { forthic, .. }
-            | Self::InvalidOperation { forthic, .. } => Some(forthic),
+            | Self::InvalidOperation { forthic, .. }
+            | Self::AssertionFailed { forthic, .. }
+            | Self::WordNotAllowed { forthic, .. }
+            | Self::Remote { forthic, .. } => Some(forthic),
             Self::WordExecution { .. } | Self::IntentionalStop { .. } => None,
         }
     }
@@ -354,12 +415,39 @@ impl ForthicError {
             | Self::UnknownToken { location, .. }
             | Self::Module { location, .. }
             | Self::TooManyAttempts { location, .. }
-            | Self::InvalidOperation { location, .. } => location.as_ref(),
+            | Self::InvalidOperation { location, .. }
+            | Self::AssertionFailed { location, .. }
+            | Self::WordNotAllowed { location, .. }
+            | Self::Remote { location, .. } => location.as_ref(),
             Self::WordExecution { call_location, .. } => call_location.as_ref(),
             Self::IntentionalStop { .. } => None,
         }
     }
 
+    /// Unwrap WordExecution wrappers down to the error that actually failed
+    /// (e.g. the ASSERT inside a definition). Each wrapper's
+    /// definition_location — the failing word's capture site — is handed
+    /// down, so `.with_forthic(source)` followed by format_with_context
+    /// points into the program that defined it.
+    pub fn into_innermost(self) -> ForthicError {
+        let mut current = self;
+        loop {
+            match current {
+                Self::WordExecution {
+                    inner_error,
+                    definition_location,
+                    ..
+                } if inner_error.is::<ForthicError>() => {
+                    current = inner_error
+                        .downcast::<ForthicError>()
+                        .expect("checked with is::<ForthicError>")
+                        .with_location(definition_location);
+                }
+                other => return other,
+            }
+        }
+    }
+
     /// Get a formatted error description with code context
     pub fn format_with_context(&self) -> String {
         // Get the forthic code and location
@@ -17,11 +17,16 @@
 //! // interp.run("42 3.14 'hello'").unwrap();
 //! ```
 
+use crate::convert::IntoForthic;
 use crate::errors::{CodeLocation, ForthicError};
 use crate::literals::{to_bool, to_float, to_int, to_literal_date, to_time, to_zoned_datetime};
 use crate::literals::{ForthicValue, LiteralHandler};
 use crate::module::{DefinitionWord, InterpreterContext, Module, PushValueWord, Word};
+use crate::sequence::Sequence;
 use crate::tokenizer::{Token, TokenType, Tokenizer};
+use crate::word_policy::{WordGuard, WordPolicy};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::sync::Arc;
 
 // ========================================
@@ -248,6 +253,17 @@ impl Stack {
             items: self.items.clone(),
         }
     }
+
+    /// Pop the top value and deserialize it into `T` (see `value_serde`)
+    pub fn pop_as<T: DeserializeOwned>(&mut self) -> Result<T, ForthicError> {
+        Ok(crate::value_serde::from_value(self.pop()?)?)
+    }
+
+    /// Serialize `value` into a ForthicValue and push it (see `value_serde`)
+    pub fn push_as<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ForthicError> {
+        self.push(crate::value_serde::to_value(value)?);
+        Ok(())
+    }
 }
 
 impl Default for Stack {
@@ -256,6 +272,15 @@ impl Default for Stack {
     }
 }
 
+/// Host callback receiving the values PROGRESS reports while code runs
+pub type ProgressHandler = Arc<dyn Fn(&ForthicValue) + Send + Sync>;
+
+/// Host callback receiving the lines PRINT, PEEK! and STACK! write
+pub type OutputHandler = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Host predicate naming the holidays that business-day words skip
+pub type HolidayCalendar = Arc<dyn Fn(chrono::NaiveDate) -> bool + Send + Sync>;
+
 /// Interpreter - Main Forthic execution engine
 ///
 /// Manages the data stack, module stack, and execution state.
@@ -295,6 +320,18 @@ pub struct Interpreter {
 
     /// Literal handlers for parsing values (checked in registration order)
     literal_handlers: Vec<LiteralHandler>,
+
+    /// Word policy consulted on every lookup (see `crate::word_policy`)
+    word_guard: Option<Arc<WordGuard>>,
+
+    /// Receives values reported by PROGRESS
+    progress_handler: Option<ProgressHandler>,
+
+    /// Holidays for ADD-BUSINESS-DAYS and BUSINESS-DAYS-BETWEEN
+    holiday_calendar: Option<HolidayCalendar>,
+
+    /// Receives PRINT, PEEK! and STACK! output instead of stdout
+    output_handler: Option<OutputHandler>,
 }
 
 impl Interpreter {
@@ -324,6 +361,10 @@ impl Interpreter {
             is_memo_definition: false,
             cur_definition: None,
             literal_handlers: Vec::new(),
+            word_guard: None,
+            progress_handler: None,
+            holiday_calendar: None,
+            output_handler: None,
         };
 
         // Register default literal handlers
@@ -341,7 +382,7 @@ impl Interpreter {
     /// Create an interpreter with the full standard library imported
     ///
     /// Imports all standard modules (array, boolean, core, datetime, json,
-    /// math, record, string) without prefixes, so their exportable words are
+    /// math, record, stats, string) without prefixes, so their exportable words are
     /// directly available. The counterpart of forthic-ts's
     /// `StandardInterpreter`.
     ///
@@ -356,7 +397,7 @@ impl Interpreter {
     pub fn standard(timezone: &str) -> Self {
         use crate::modules::standard::{
             ArrayModule, BooleanModule, CoreModule, DateTimeModule, JSONModule, MathModule,
-            RecordModule, StringModule,
+            RecordModule, StatsModule, StringModule,
         };
 
         let mut interp = Self::new(timezone);
@@ -368,6 +409,7 @@ impl Interpreter {
             JSONModule::new().module().clone(),
             MathModule::new().module().clone(),
             RecordModule::new().module().clone(),
+            StatsModule::new().module().clone(),
             StringModule::new().module().clone(),
         ]);
         interp
@@ -383,6 +425,110 @@ impl Interpreter {
         self.timezone = timezone;
     }
 
+    /// Restrict the words this interpreter will look up, resolving the
+    /// policy's module names against the modules registered so far
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use forthic::interpreter::Interpreter;
+    /// use forthic::word_policy::WordPolicy;
+    ///
+    /// let mut interp = Interpreter::standard("UTC");
+    /// interp
+    ///     .set_word_policy(&WordPolicy {
+    ///         deny_words: vec!["RUN".to_string()],
+    ///         ..WordPolicy::default()
+    ///     })
+    ///     .unwrap();
+    /// assert!(interp.run("'1 2 +' RUN").is_err());
+    /// ```
+    pub fn set_word_policy(&mut self, policy: &WordPolicy) -> Result<(), ForthicError> {
+        let guard = policy.compile(self)?;
+        self.word_guard = Some(Arc::new(guard));
+        Ok(())
+    }
+
+    /// Install an already-resolved policy, or lift it with `None`. Lets many
+    /// interpreters share one [`WordGuard`].
+    pub fn set_word_guard(&mut self, guard: Option<Arc<WordGuard>>) {
+        self.word_guard = guard;
+    }
+
+    /// Receive the values PROGRESS reports, or stop with `None`. The
+    /// handler runs on the interpreter's thread, in the middle of the word
+    /// that reported, so it should hand the value off rather than block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use forthic::interpreter::Interpreter;
+    /// use forthic::ForthicValue;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let seen = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&seen);
+    /// let mut interp = Interpreter::standard("UTC");
+    /// interp.set_progress_handler(Some(Arc::new(move |value: &ForthicValue| {
+    ///     sink.lock().unwrap().push(value.clone());
+    /// })));
+    /// interp.run("[1 2 3] '0.5 * DUP PROGRESS' MAP").unwrap();
+    /// assert_eq!(seen.lock().unwrap().len(), 3);
+    /// ```
+    pub fn set_progress_handler(&mut self, handler: Option<ProgressHandler>) {
+        self.progress_handler = handler;
+    }
+
+    /// Name the holidays ADD-BUSINESS-DAYS and BUSINESS-DAYS-BETWEEN skip
+    /// besides weekends, or drop the calendar with `None`. A word's
+    /// `.holidays` option adds to this calendar rather than replacing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{Datelike, NaiveDate};
+    /// use forthic::interpreter::Interpreter;
+    /// use forthic::ForthicValue;
+    /// use std::sync::Arc;
+    ///
+    /// let mut interp = Interpreter::standard("UTC");
+    /// // New Year's Day
+    /// interp.set_holiday_calendar(Some(Arc::new(|date: NaiveDate| {
+    ///     date.month() == 1 && date.day() == 1
+    /// })));
+    /// interp.run("'2025-12-31' >DATE 1 ADD-BUSINESS-DAYS DATE>STR").unwrap();
+    /// assert_eq!(
+    ///     interp.get_stack_mut().pop().unwrap(),
+    ///     ForthicValue::String("2026-01-02".to_string())
+    /// );
+    /// ```
+    pub fn set_holiday_calendar(&mut self, calendar: Option<HolidayCalendar>) {
+        self.holiday_calendar = calendar;
+    }
+
+    /// Send the lines PRINT, PEEK! and STACK! write somewhere other than
+    /// stdout, or restore stdout with `None` — e.g. when stdout carries a
+    /// protocol, as under the JSON-RPC stdio transport.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use forthic::interpreter::Interpreter;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let lines = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&lines);
+    /// let mut interp = Interpreter::standard("UTC");
+    /// interp.set_output_handler(Some(Arc::new(move |line: &str| {
+    ///     sink.lock().unwrap().push(line.to_string());
+    /// })));
+    /// interp.run("'hello' PRINT").unwrap();
+    /// assert_eq!(*lines.lock().unwrap(), ["hello"]);
+    /// ```
+    pub fn set_output_handler(&mut self, handler: Option<OutputHandler>) {
+        self.output_handler = handler;
+    }
+
     /// Get a reference to the stack
     pub fn get_stack(&self) -> &Stack {
         &self.stack
@@ -403,6 +549,19 @@ impl Interpreter {
         self.stack.push(value);
     }
 
+    /// Push a host iterator as a lazy Sequence: it is read only as Forthic
+    /// pulls items (see `crate::sequence`)
+    pub fn push_sequence<I>(&mut self, items: I)
+    where
+        I: IntoIterator,
+        I::IntoIter: Send + 'static,
+        I::Item: IntoForthic + 'static,
+    {
+        let items = items.into_iter().map(IntoForthic::into_forthic);
+        self.stack
+            .push(ForthicValue::Sequence(Sequence::from_iterator(items)));
+    }
+
     /// Pop a value from the stack
     pub fn stack_pop(&mut self) -> Result<ForthicValue, ForthicError> {
         self.stack.pop()
@@ -599,6 +758,9 @@ impl Interpreter {
         // 1. Check module stack (dictionary words + variables)
         for module in self.module_stack.iter().rev() {
             if let Some(word) = module.find_word(name) {
+                if let Some(guard) = &self.word_guard {
+                    guard.check(word.as_ref())?;
+                }
                 return Ok(word);
             }
         }
@@ -1000,13 +1162,38 @@ impl Interpreter {
     /// interp.run_module_code(&module).unwrap();
     /// ```
     pub fn run_module_code(&mut self, module: &Module) -> Result<(), ForthicError> {
+        self.compile_module(module).map(|_| ())
+    }
+
+    /// Run a module's Forthic code and return the module it built
+    ///
+    /// Like [`run_module_code`](Self::run_module_code), but hands back the
+    /// module as it stands after the code ran: its definitions, memo words,
+    /// and variables. Modules the code left open are closed and discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use forthic::interpreter::Interpreter;
+    /// use forthic::module::Module;
+    ///
+    /// let mut interp = Interpreter::standard("UTC");
+    /// let module = Module::new_with_code("greet".to_string(), ": HELLO 'world' ;".to_string());
+    /// let compiled = interp.compile_module(&module).unwrap();
+    /// assert!(compiled.find_word("HELLO").is_some());
+    /// ```
+    pub fn compile_module(&mut self, module: &Module) -> Result<Module, ForthicError> {
+        let depth = self.module_stack.len();
         self.module_stack_push(module.clone());
 
         // Try to run the module's code
         let result = self.run(module.get_forthic_code());
 
         // Always pop the module, even if there was an error
-        self.module_stack_pop()?;
+        while self.module_stack.len() > depth + 1 {
+            self.module_stack_pop()?;
+        }
+        let compiled = self.module_stack_pop()?;
 
         // If there was an error, wrap it in a Module error
         if let Err(e) = result {
@@ -1021,7 +1208,7 @@ impl Interpreter {
             });
         }
 
-        Ok(())
+        Ok(compiled)
     }
 }
 
@@ -1048,6 +1235,25 @@ impl InterpreterContext for Interpreter {
         self.stack.items().to_vec()
     }
 
+    fn report_progress(&mut self, value: ForthicValue) {
+        if let Some(handler) = &self.progress_handler {
+            handler(&value);
+        }
+    }
+
+    fn write_output(&mut self, text: &str) {
+        match &self.output_handler {
+            Some(handler) => handler(text),
+            None => println!("{text}"),
+        }
+    }
+
+    fn is_holiday(&self, date: chrono::NaiveDate) -> bool {
+        self.holiday_calendar
+            .as_ref()
+            .is_some_and(|calendar| calendar(date))
+    }
+
     fn stack_restore(&mut self, items: Vec<ForthicValue>) {
         self.stack.set_items(items);
     }
@@ -0,0 +1,292 @@
+//! Audit log of the Forthic the JSON-RPC server executes
+//!
+//! With `ServeOptions.audit` set, every call any transport runs —
+//! notifications included — is written to an [`AuditSink`] as one JSON
+//! object, as are requests refused for a missing or wrong token:
+//!
+//! ```text
+//! {"timestamp":"2026-10-18T09:30:00.125Z","event":"call","transport":"http",
+//!  "peer":"127.0.0.1:51234","method":"executeWord","id":7,
+//!  "word_names":["ADD"],"stack_in":2,"stack_out":1,"duration_ms":0.412,
+//!  "outcome":"ok"}
+//! ```
+//!
+//! (one line per record in a [`JsonLinesSink`]). Calls record the word names
+//! of executeWord / executeSequence, or the SHA-256 of runCode's `code`
+//! (`code_sha256`) rather than the code itself. The server runs a word name
+//! as code too, so a name that is not one plain word is logged the same
+//! way, as `{"sha256": ...}`. Failed calls record `"outcome":"error"` with
+//! the `error_code` and, for Forthic errors, the `error_type`; a call whose
+//! handling panicked records `"outcome":"internal_error"`. `peer` is absent
+//! on stdio. Stack values are left out unless the [`StackRedaction`] allows
+//! them: by default only the stack sizes are logged.
+//!
+//! Sinks are called on the thread that ran the call; one that blocks delays
+//! only that call's response.
+
+use super::errors::JsonRpcErrorCode;
+use super::server::JsonRpcRequest;
+use chrono::{SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Longest word name logged as written
+const MAX_LOGGED_WORD_NAME: usize = 64;
+
+/// Receives each audit record
+///
+/// Implemented for closures, so a host can route records to its own
+/// logging with `Arc::new(|record: &Value| ...)`.
+pub trait AuditSink: Send + Sync {
+    fn record(&self, record: &Value);
+}
+
+impl<F> AuditSink for F
+where
+    F: Fn(&Value) + Send + Sync,
+{
+    fn record(&self, record: &Value) {
+        self(record)
+    }
+}
+
+/// Writes each record as one line of JSON
+pub struct JsonLinesSink<W> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+}
+
+impl JsonLinesSink<File> {
+    /// Append to the file at `path`, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(file))
+    }
+}
+
+impl<W: Write + Send> AuditSink for JsonLinesSink<W> {
+    fn record(&self, record: &Value) {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        // An audit write failure must not fail the call; report it instead
+        if let Err(e) = writeln!(writer, "{record}").and_then(|_| writer.flush()) {
+            eprintln!("Audit log write failed: {e}");
+        }
+    }
+}
+
+/// How much of the stacks an audit record shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StackRedaction {
+    /// Only the sizes (`stack_in`, `stack_out`)
+    #[default]
+    Sizes,
+    /// Also each item's type tag (`int_value`, `string_value`, ...)
+    Types,
+    /// Also the items themselves, in their wire form
+    Values,
+}
+
+impl std::str::FromStr for StackRedaction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sizes" => Ok(Self::Sizes),
+            "types" => Ok(Self::Types),
+            "values" => Ok(Self::Values),
+            other => Err(format!(
+                "Unknown stack redaction '{other}' (expected sizes, types or values)"
+            )),
+        }
+    }
+}
+
+/// Where audit records go, and how much of the stacks they show
+#[derive(Clone)]
+pub struct AuditLog {
+    sink: Arc<dyn AuditSink>,
+    redaction: StackRedaction,
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("redaction", &self.redaction)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuditLog {
+    pub fn new(sink: Arc<dyn AuditSink>) -> Self {
+        Self {
+            sink,
+            redaction: StackRedaction::default(),
+        }
+    }
+
+    pub fn with_redaction(mut self, redaction: StackRedaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    pub fn redaction(&self) -> StackRedaction {
+        self.redaction
+    }
+
+    /// Record one executed call and the response envelope it produced
+    pub fn record_call(
+        &self,
+        transport: &str,
+        peer: Option<SocketAddr>,
+        request: &JsonRpcRequest,
+        response: &Value,
+        elapsed: Duration,
+    ) {
+        let mut record = self.start_record("call", transport, peer);
+        record.insert("method".into(), json!(request.method));
+        record.insert("id".into(), request.id.clone());
+
+        let params = &request.params;
+        match request.method.as_str() {
+            "executeWord" => {
+                if let Some(word) = params.get("word_name") {
+                    record.insert("word_names".into(), json!([logged_word_name(word)]));
+                }
+            }
+            "executeSequence" => {
+                if let Some(words) = params.get("word_names") {
+                    let logged = match words.as_array() {
+                        Some(words) => words.iter().map(logged_word_name).collect(),
+                        None => Value::Null,
+                    };
+                    record.insert("word_names".into(), logged);
+                }
+            }
+            "runCode" => {
+                if let Some(code) = params.get("code").and_then(Value::as_str) {
+                    record.insert("code_sha256".into(), json!(sha256_hex(code)));
+                }
+            }
+            _ => {}
+        }
+
+        let stack_in = params.get("stack").and_then(Value::as_array);
+        let stack_out = response
+            .pointer("/result/result_stack")
+            .and_then(Value::as_array);
+        record.insert("stack_in".into(), json!(stack_in.map_or(0, Vec::len)));
+        if let Some(stack) = stack_out {
+            record.insert("stack_out".into(), json!(stack.len()));
+        }
+        if let Some(items) = stack_in.and_then(|stack| self.redact(stack)) {
+            record.insert("stack".into(), items);
+        }
+        if let Some(items) = stack_out.and_then(|stack| self.redact(stack)) {
+            record.insert("result_stack".into(), items);
+        }
+
+        record.insert("duration_ms".into(), json!(elapsed.as_secs_f64() * 1000.0));
+        match response.get("error") {
+            None => {
+                record.insert("outcome".into(), json!("ok"));
+            }
+            Some(error) => {
+                let outcome = if error["code"] == JsonRpcErrorCode::INTERNAL_ERROR {
+                    "internal_error"
+                } else {
+                    "error"
+                };
+                record.insert("outcome".into(), json!(outcome));
+                record.insert("error_code".into(), error["code"].clone());
+                if let Some(error_type) = error.pointer("/data/error_type") {
+                    record.insert("error_type".into(), error_type.clone());
+                }
+            }
+        }
+        self.sink.record(&Value::Object(record));
+    }
+
+    /// Record a request refused before it ran (`reason`, e.g.
+    /// `unauthorized`)
+    pub fn record_rejection(&self, transport: &str, peer: Option<SocketAddr>, reason: &str) {
+        let mut record = self.start_record("rejected", transport, peer);
+        record.insert("reason".into(), json!(reason));
+        self.sink.record(&Value::Object(record));
+    }
+
+    fn start_record(
+        &self,
+        event: &str,
+        transport: &str,
+        peer: Option<SocketAddr>,
+    ) -> Map<String, Value> {
+        let mut record = Map::new();
+        record.insert(
+            "timestamp".into(),
+            json!(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+        );
+        record.insert("event".into(), json!(event));
+        record.insert("transport".into(), json!(transport));
+        if let Some(peer) = peer {
+            record.insert("peer".into(), json!(peer.to_string()));
+        }
+        record
+    }
+
+    /// The stack items this redaction shows, if any
+    fn redact(&self, stack: &[Value]) -> Option<Value> {
+        match self.redaction {
+            StackRedaction::Sizes => None,
+            StackRedaction::Types => Some(
+                stack
+                    .iter()
+                    .map(|item| {
+                        item.as_object()
+                            .and_then(|tagged| tagged.keys().next())
+                            .map_or(Value::Null, |tag| json!(tag))
+                    })
+                    .collect(),
+            ),
+            StackRedaction::Values => Some(Value::Array(stack.to_vec())),
+        }
+    }
+}
+
+/// A word name as the log shows it: one plain word as written, anything
+/// else (code, string literals) as its SHA-256
+fn logged_word_name(name: &Value) -> Value {
+    match name.as_str() {
+        Some(word) if is_plain_word(word) => json!(word),
+        Some(code) => json!({ "sha256": sha256_hex(code) }),
+        None => Value::Null,
+    }
+}
+
+fn is_plain_word(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_LOGGED_WORD_NAME
+        && !name
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '\'' | '"' | '^' | '#'))
+}
+
+fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
@@ -0,0 +1,160 @@
+//! Forthic-defined modules served alongside the Rust runtime modules
+//!
+//! A bootstrap module is Forthic source — a `.forthic` file or a
+//! `Module::new_with_code` module — compiled once when the server starts.
+//! Every definition becomes an exported word, and the compiled module is
+//! cloned into each request interpreter like a runtime module.
+//!
+//! Docs come from comments in the source. A comment block directly above a
+//! definition documents it; its first line may start with the stack effect:
+//!
+//! ```text
+//! # Helpers shared by the ops dashboards
+//!
+//! # ( amount:float -- total:float ) Add the standard tax rate
+//! : ADD-TAX  1.08 * ;
+//! ```
+//!
+//! A leading comment block followed by a blank line describes the module.
+//! Definitions are compiled in the servicer's default timezone, so date
+//! literals inside them do not follow per-request `timezone` overrides.
+
+use crate::errors::ForthicError;
+use crate::interpreter::Interpreter;
+use crate::module::Module;
+use crate::tokenizer::{TokenType, Tokenizer};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Stack effect and description of one Forthic definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefinitionDoc {
+    pub stack_effect: String,
+    pub description: String,
+}
+
+/// A compiled bootstrap module and the docs read from its source
+#[derive(Debug, Clone)]
+pub struct ForthicModule {
+    module: Module,
+    description: String,
+    docs: HashMap<String, DefinitionDoc>,
+}
+
+impl ForthicModule {
+    /// Run `module`'s Forthic code in `interp` and export every word it
+    /// defines
+    pub fn compile(interp: &mut Interpreter, module: &Module) -> Result<Self, ForthicError> {
+        let mut compiled = interp.compile_module(module)?;
+        compiled.export_all_words();
+
+        let (description, docs) = extract_docs(module.get_forthic_code());
+        Ok(Self {
+            description: description
+                .unwrap_or_else(|| format!("Forthic {} module", module.get_name())),
+            module: compiled,
+            docs,
+        })
+    }
+
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
+    pub fn name(&self) -> &str {
+        self.module.get_name()
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The doc comment of a definition, if it has one
+    pub fn doc(&self, word_name: &str) -> Option<&DefinitionDoc> {
+        self.docs.get(word_name)
+    }
+}
+
+/// Read a `.forthic` file as a module named after the file stem
+/// (`shared/ops.forthic` becomes module `ops`)
+pub fn load_bootstrap_file(path: &Path) -> std::io::Result<Module> {
+    let code = std::fs::read_to_string(path)?;
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty())
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Cannot name a module after '{}'", path.display()),
+            )
+        })?;
+    Ok(Module::new_with_code(name.to_string(), code))
+}
+
+/// The module description and per-definition docs in `code`
+fn extract_docs(code: &str) -> (Option<String>, HashMap<String, DefinitionDoc>) {
+    let mut tokenizer = Tokenizer::new(code.to_string(), None, false);
+    let mut description = None;
+    let mut docs = HashMap::new();
+    let mut block: Vec<String> = Vec::new();
+    let mut block_end_line = 0;
+    let mut seen_code = false;
+
+    // The code already compiled, so tokenizing it cannot fail; stop quietly
+    // if it somehow does
+    while let Ok(token) = tokenizer.next_token() {
+        let line = token.location.line;
+        let follows_block = !block.is_empty() && line == block_end_line + 1;
+        let after_gap = !block.is_empty() && line > block_end_line + 1;
+        if after_gap && !seen_code && description.is_none() {
+            description = Some(block.join(" "));
+        }
+        if after_gap {
+            block.clear();
+        }
+
+        match token.token_type {
+            TokenType::Eos => break,
+            TokenType::Comment => {
+                block.push(comment_text(&token.string));
+                block_end_line = line;
+            }
+            TokenType::StartDef | TokenType::StartMemo => {
+                if follows_block {
+                    docs.insert(token.string.clone(), parse_doc(&block));
+                }
+                block.clear();
+                seen_code = true;
+            }
+            _ => {
+                block.clear();
+                seen_code = true;
+            }
+        }
+    }
+    (description, docs)
+}
+
+fn comment_text(comment: &str) -> String {
+    comment.trim_start_matches('#').trim().to_string()
+}
+
+/// `( a -- b ) text` on the first line sets the stack effect; everything
+/// else is the description
+fn parse_doc(lines: &[String]) -> DefinitionDoc {
+    let first = lines.first().map(String::as_str).unwrap_or("");
+    let (stack_effect, rest) = match (first.starts_with('('), first.find(')')) {
+        (true, Some(end)) => (first[..=end].to_string(), first[end + 1..].trim()),
+        _ => ("( -- )".to_string(), first),
+    };
+    let description = std::iter::once(rest)
+        .chain(lines.iter().skip(1).map(String::as_str))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    DefinitionDoc {
+        stack_effect,
+        description,
+    }
+}
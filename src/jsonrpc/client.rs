@@ -3,7 +3,10 @@
 //! The client-side counterpart of [`super::server`]: speaks the same wire
 //! format as the forthic-ts and forthic-py JSON-RPC servers (executeWord /
 //! executeSequence / listModules / getModuleInfo), so any of the three
-//! runtimes can drive any other.
+//! runtimes can drive any other. [`JsonRpcClient::batch`] sends several
+//! calls (and notifications) in one round trip, and
+//! [`JsonRpcClient::stream`] reads the values a long call reports with
+//! PROGRESS while it runs (server-sent events from rs servers).
 //!
 //! Deliberately dependency-free: a hand-rolled HTTP/1.1 POST over
 //! `std::net::TcpStream` with `Connection: close` (Content-Length and
@@ -14,7 +17,7 @@
 //! the runtime's execution model.
 
 use std::collections::HashMap;
-use std::io::{Read, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::net::TcpStream;
 use std::sync::atomic::{AtomicI64, Ordering};
 use std::time::Duration;
@@ -185,6 +188,22 @@ impl JsonRpcClient {
         self.parse_result_stack(&result)
     }
 
+    /// Like [`execute_word`](Self::execute_word), passing each value the
+    /// word reports with PROGRESS to `on_progress` as it arrives
+    pub fn execute_word_with_progress(
+        &self,
+        word_name: &str,
+        stack: &[ForthicValue],
+        on_progress: impl FnMut(ForthicValue),
+    ) -> Result<Vec<ForthicValue>, ClientError> {
+        let params = json!({
+            "word_name": word_name,
+            "stack": self.serialize_stack(stack)?,
+        });
+        let result = self.call_with_progress("executeWord", params, on_progress)?;
+        self.parse_result_stack(&result)
+    }
+
     /// Execute a sequence of words as ONE call (stack continuity between
     /// words — this is not a JSON-RPC batch)
     pub fn execute_sequence(
@@ -200,6 +219,22 @@ impl JsonRpcClient {
         self.parse_result_stack(&result)
     }
 
+    /// Like [`execute_sequence`](Self::execute_sequence), passing each
+    /// value the words report with PROGRESS to `on_progress` as it arrives
+    pub fn execute_sequence_with_progress(
+        &self,
+        word_names: &[&str],
+        stack: &[ForthicValue],
+        on_progress: impl FnMut(ForthicValue),
+    ) -> Result<Vec<ForthicValue>, ClientError> {
+        let params = json!({
+            "word_names": word_names,
+            "stack": self.serialize_stack(stack)?,
+        });
+        let result = self.call_with_progress("executeSequence", params, on_progress)?;
+        self.parse_result_stack(&result)
+    }
+
     /// List the remote runtime's runtime-specific modules
     pub fn list_modules(&self) -> Result<Vec<Value>, ClientError> {
         let result = self.call("listModules", json!({}))?;
@@ -215,16 +250,173 @@ impl JsonRpcClient {
         self.call("getModuleInfo", json!({ "module_name": module_name }))
     }
 
-    // ---- internals ----
+    /// Open a persistent interpreter session on the server (rs servers);
+    /// returns the session id for [`run_code`](Self::run_code)
+    pub fn create_session(&self) -> Result<String, ClientError> {
+        let result = self.call("createSession", json!({}))?;
+        result
+            .get("session_id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| ClientError::Protocol("response missing session_id".to_string()))
+    }
 
-    fn serialize_stack(&self, stack: &[ForthicValue]) -> Result<Vec<Value>, ClientError> {
-        stack
-            .iter()
-            .map(|v| serialize_value(v).map_err(ClientError::from))
-            .collect()
+    /// Run a Forthic program, pushing `stack` first; returns the whole
+    /// stack. With a session id the program runs in that session, otherwise
+    /// on a fresh interpreter.
+    pub fn run_code(
+        &self,
+        session_id: Option<&str>,
+        code: &str,
+        stack: &[ForthicValue],
+    ) -> Result<Vec<ForthicValue>, ClientError> {
+        let mut params = json!({
+            "code": code,
+            "stack": self.serialize_stack(stack)?,
+        });
+        if let Some(session_id) = session_id {
+            params["session_id"] = json!(session_id);
+        }
+        let result = self.call("runCode", params)?;
+        self.parse_result_stack(&result)
     }
 
-    fn parse_result_stack(&self, result: &Value) -> Result<Vec<ForthicValue>, ClientError> {
+    /// Like [`run_code`](Self::run_code), passing each value the program
+    /// reports with PROGRESS to `on_progress` as it arrives
+    pub fn run_code_with_progress(
+        &self,
+        session_id: Option<&str>,
+        code: &str,
+        stack: &[ForthicValue],
+        on_progress: impl FnMut(ForthicValue),
+    ) -> Result<Vec<ForthicValue>, ClientError> {
+        let mut params = json!({
+            "code": code,
+            "stack": self.serialize_stack(stack)?,
+        });
+        if let Some(session_id) = session_id {
+            params["session_id"] = json!(session_id);
+        }
+        let result = self.call_with_progress("runCode", params, on_progress)?;
+        self.parse_result_stack(&result)
+    }
+
+    /// Make any call, passing each PROGRESS value to `on_progress` as it
+    /// arrives; returns the call's result
+    pub fn call_with_progress(
+        &self,
+        method: &str,
+        params: Value,
+        mut on_progress: impl FnMut(ForthicValue),
+    ) -> Result<Value, ClientError> {
+        let mut call = self.stream(method, params)?;
+        for value in call.by_ref() {
+            on_progress(value);
+        }
+        call.finish()
+    }
+
+    /// Start a call whose PROGRESS values are read as they arrive: iterate
+    /// the returned [`CallStream`], then [`finish`](CallStream::finish) it
+    /// for the result. Servers that do not stream (forthic-ts, forthic-py)
+    /// answer with the result alone, so the iteration is empty.
+    ///
+    /// ```no_run
+    /// # use forthic::jsonrpc::JsonRpcClient;
+    /// # use serde_json::json;
+    /// let client = JsonRpcClient::new("127.0.0.1:8765")?;
+    /// let code = "[1 2 3] '10 * DUP PROGRESS' MAP";
+    /// let mut call = client.stream("runCode", json!({ "code": code }))?;
+    /// for value in call.by_ref() {
+    ///     println!("progress: {value:?}");
+    /// }
+    /// let stack = JsonRpcClient::result_stack(&call.finish()?)?;
+    /// # Ok::<(), forthic::jsonrpc::ClientError>(())
+    /// ```
+    pub fn stream(&self, method: &str, params: Value) -> Result<CallStream, ClientError> {
+        let (_, envelope) = self.envelope(method, params);
+        let stream =
+            self.send_post(&envelope.to_string(), "text/event-stream, application/json")?;
+        let mut reader = BufReader::new(stream);
+        let mut head = String::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line == "\r\n" {
+                break;
+            }
+            head.push_str(&line);
+        }
+        let head = Self::parse_head(head.trim_end())?;
+
+        if head.status == 200 && head.content_type.starts_with("text/event-stream") {
+            let body: Box<dyn Read + Send> = if head.chunked {
+                Box::new(ChunkedReader::new(reader))
+            } else {
+                Box::new(reader)
+            };
+            return Ok(CallStream {
+                events: Some(BufReader::new(body)),
+                outcome: None,
+            });
+        }
+
+        let mut payload = Vec::new();
+        reader.read_to_end(&mut payload)?;
+        let body = String::from_utf8_lossy(&Self::decode_body(&head, &payload)?).into_owned();
+        let outcome = if head.status == 200 {
+            serde_json::from_str(&body)
+                .map_err(|e| ClientError::Protocol(format!("invalid JSON-RPC response: {e}")))
+                .and_then(|response: Value| Self::response_result(&response))
+        } else {
+            Err(ClientError::Http {
+                status: head.status,
+                body,
+            })
+        };
+        Ok(CallStream {
+            events: None,
+            outcome: Some(outcome),
+        })
+    }
+
+    /// Send a notification: the server runs the call but sends no response,
+    /// so only transport-level failures are reported
+    pub fn notify(&self, method: &str, params: Value) -> Result<(), ClientError> {
+        let envelope = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        let (status, body) = self.http_post(&envelope.to_string())?;
+        match status {
+            200 | 204 => Ok(()),
+            _ => Err(ClientError::Http { status, body }),
+        }
+    }
+
+    /// Start a batch: calls added to the builder go out in one request
+    /// when it is sent
+    ///
+    /// ```no_run
+    /// # use forthic::jsonrpc::JsonRpcClient;
+    /// # use forthic::ForthicValue;
+    /// let client = JsonRpcClient::new("127.0.0.1:8765")?;
+    /// let results = client
+    ///     .batch()
+    ///     .execute_word("DUP", &[ForthicValue::Int(2)])
+    ///     .execute_sequence(&["DUP", "*"], &[ForthicValue::Int(3)])
+    ///     .send()?;
+    /// let squared = JsonRpcClient::result_stack(results[1].as_ref().unwrap())?;
+    /// # Ok::<(), forthic::jsonrpc::ClientError>(())
+    /// ```
+    pub fn batch(&self) -> BatchBuilder<'_> {
+        BatchBuilder {
+            client: self,
+            envelopes: Vec::new(),
+            ids: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// The `result_stack` of an executeWord / executeSequence / runCode
+    /// result, e.g. one returned by a batch
+    pub fn result_stack(result: &Value) -> Result<Vec<ForthicValue>, ClientError> {
         result
             .get("result_stack")
             .and_then(Value::as_array)
@@ -234,7 +426,26 @@ impl JsonRpcClient {
             .collect()
     }
 
-    fn call(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+    /// Close a session, discarding its interpreter
+    pub fn close_session(&self, session_id: &str) -> Result<(), ClientError> {
+        self.call("closeSession", json!({ "session_id": session_id }))?;
+        Ok(())
+    }
+
+    // ---- internals ----
+
+    fn serialize_stack(&self, stack: &[ForthicValue]) -> Result<Vec<Value>, ClientError> {
+        stack
+            .iter()
+            .map(|v| serialize_value(v).map_err(ClientError::from))
+            .collect()
+    }
+
+    fn parse_result_stack(&self, result: &Value) -> Result<Vec<ForthicValue>, ClientError> {
+        Self::result_stack(result)
+    }
+
+    fn envelope(&self, method: &str, params: Value) -> (i64, Value) {
         let id = self.next_id.fetch_add(1, Ordering::Relaxed);
         let envelope = json!({
             "jsonrpc": "2.0",
@@ -242,6 +453,11 @@ impl JsonRpcClient {
             "method": method,
             "params": params,
         });
+        (id, envelope)
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let (_, envelope) = self.envelope(method, params);
         let (status, body) = self.http_post(&envelope.to_string())?;
 
         // The server answers JSON-RPC errors with HTTP 200; anything else
@@ -252,7 +468,11 @@ impl JsonRpcClient {
 
         let response: Value = serde_json::from_str(&body)
             .map_err(|e| ClientError::Protocol(format!("invalid JSON-RPC response: {e}")))?;
+        Self::response_result(&response)
+    }
 
+    /// The `result` of one response envelope, or its error
+    fn response_result(response: &Value) -> Result<Value, ClientError> {
         if let Some(error) = response.get("error") {
             let code = error.get("code").and_then(Value::as_i64).unwrap_or(0);
             let message = error
@@ -278,6 +498,16 @@ impl JsonRpcClient {
     }
 
     fn http_post(&self, body: &str) -> Result<(u16, String), ClientError> {
+        let mut stream = self.send_post(body, "application/json")?;
+
+        // Connection: close — read the full response, then frame it
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw)?;
+        Self::parse_http_response(&raw)
+    }
+
+    /// Connect and write a POST of `body`; the response is left unread
+    fn send_post(&self, body: &str, accept: &str) -> Result<TcpStream, ClientError> {
         let addr = format!("{}:{}", self.host, self.port);
         let mut stream = TcpStream::connect(&addr)?;
         stream.set_read_timeout(Some(self.timeout))?;
@@ -288,19 +518,16 @@ impl JsonRpcClient {
             None => String::new(),
         };
         let request = format!(
-            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nAccept: {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
             self.path,
             addr,
+            accept,
             body.len(),
             auth_header,
             body,
         );
         stream.write_all(request.as_bytes())?;
-
-        // Connection: close — read the full response, then frame it
-        let mut raw = Vec::new();
-        stream.read_to_end(&mut raw)?;
-        Self::parse_http_response(&raw)
+        Ok(stream)
     }
 
     fn parse_http_response(raw: &[u8]) -> Result<(u16, String), ClientError> {
@@ -308,9 +535,16 @@ impl JsonRpcClient {
             .windows(4)
             .position(|w| w == b"\r\n\r\n")
             .ok_or_else(|| ClientError::Protocol("no HTTP header terminator".to_string()))?;
-        let head = String::from_utf8_lossy(&raw[..header_end]);
-        let payload = &raw[header_end + 4..];
+        let head = Self::parse_head(&String::from_utf8_lossy(&raw[..header_end]))?;
+        let body_bytes = Self::decode_body(&head, &raw[header_end + 4..])?;
+        Ok((
+            head.status,
+            String::from_utf8_lossy(&body_bytes).into_owned(),
+        ))
+    }
 
+    /// Status line and the headers that frame the body
+    fn parse_head(head: &str) -> Result<ResponseHead, ClientError> {
         let mut lines = head.split("\r\n");
         let status_line = lines
             .next()
@@ -321,8 +555,12 @@ impl JsonRpcClient {
             .and_then(|s| s.parse().ok())
             .ok_or_else(|| ClientError::Protocol(format!("bad status line: {status_line}")))?;
 
-        let mut content_length: Option<usize> = None;
-        let mut chunked = false;
+        let mut head = ResponseHead {
+            status,
+            content_length: None,
+            chunked: false,
+            content_type: String::new(),
+        };
         for line in lines {
             let Some((name, value)) = line.split_once(':') else {
                 continue;
@@ -330,20 +568,24 @@ impl JsonRpcClient {
             let name = name.trim().to_ascii_lowercase();
             let value = value.trim();
             if name == "content-length" {
-                content_length = value.parse().ok();
+                head.content_length = value.parse().ok();
             } else if name == "transfer-encoding" && value.eq_ignore_ascii_case("chunked") {
-                chunked = true;
+                head.chunked = true;
+            } else if name == "content-type" {
+                head.content_type = value.to_ascii_lowercase();
             }
         }
+        Ok(head)
+    }
 
-        let body_bytes = if chunked {
+    fn decode_body(head: &ResponseHead, payload: &[u8]) -> Result<Vec<u8>, ClientError> {
+        Ok(if head.chunked {
             Self::decode_chunked(payload)?
-        } else if let Some(len) = content_length {
+        } else if let Some(len) = head.content_length {
             payload.get(..len).unwrap_or(payload).to_vec()
         } else {
             payload.to_vec()
-        };
-        Ok((status, String::from_utf8_lossy(&body_bytes).into_owned()))
+        })
     }
 
     fn decode_chunked(mut payload: &[u8]) -> Result<Vec<u8>, ClientError> {
@@ -368,3 +610,264 @@ impl JsonRpcClient {
         }
     }
 }
+
+struct ResponseHead {
+    status: u16,
+    content_length: Option<usize>,
+    chunked: bool,
+    content_type: String,
+}
+
+/// A call in progress, started by [`JsonRpcClient::stream`]
+///
+/// Iterating yields each value the remote code reports with PROGRESS, in
+/// order; the iteration ends when the response arrives. A progress value
+/// that fails to deserialize, or a stream that breaks, ends it early and
+/// is reported by [`finish`](Self::finish).
+pub struct CallStream {
+    /// Server-sent events still to read; `None` once the outcome is known
+    events: Option<BufReader<Box<dyn Read + Send>>>,
+    outcome: Option<Result<Value, ClientError>>,
+}
+
+impl CallStream {
+    /// Wait for the response (skipping any progress not yet read) and
+    /// return the call's result
+    pub fn finish(mut self) -> Result<Value, ClientError> {
+        for _ in self.by_ref() {}
+        self.outcome.unwrap_or_else(|| {
+            Err(ClientError::Protocol(
+                "event stream ended without a response".to_string(),
+            ))
+        })
+    }
+
+    /// The data of the next server-sent event; `None` at end of stream
+    fn next_event(events: &mut impl BufRead) -> Result<Option<String>, ClientError> {
+        let mut data: Vec<String> = Vec::new();
+        loop {
+            let mut line = String::new();
+            if events.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.is_empty() {
+                if data.is_empty() {
+                    continue;
+                }
+                return Ok(Some(data.join("\n")));
+            }
+            // Other fields (event, id, retry) and `:` comments carry nothing
+            // the client needs
+            if let Some(value) = line.strip_prefix("data:") {
+                data.push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+        }
+    }
+}
+
+impl Iterator for CallStream {
+    type Item = ForthicValue;
+
+    fn next(&mut self) -> Option<ForthicValue> {
+        let events = self.events.as_mut()?;
+        let outcome = match Self::next_event(events) {
+            Ok(Some(data)) => match serde_json::from_str::<Value>(&data) {
+                Ok(message)
+                    if message.get("method").and_then(Value::as_str) == Some("progress") =>
+                {
+                    match deserialize_value(&message["params"]["value"]) {
+                        Ok(value) => return Some(value),
+                        Err(e) => Err(ClientError::from(e)),
+                    }
+                }
+                Ok(response) => JsonRpcClient::response_result(&response),
+                Err(e) => Err(ClientError::Protocol(format!(
+                    "invalid JSON-RPC event: {e}"
+                ))),
+            },
+            Ok(None) => Err(ClientError::Protocol(
+                "event stream ended without a response".to_string(),
+            )),
+            Err(e) => Err(e),
+        };
+        self.events = None;
+        self.outcome = Some(outcome);
+        None
+    }
+}
+
+/// Decodes a chunked response body as it is read, for streamed responses
+struct ChunkedReader<R> {
+    inner: R,
+    /// Bytes left in the current chunk
+    remaining: usize,
+    started: bool,
+    done: bool,
+}
+
+impl<R: BufRead> ChunkedReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            remaining: 0,
+            started: false,
+            done: false,
+        }
+    }
+
+    fn invalid(message: String) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+    }
+}
+
+impl<R: BufRead> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.done || buf.is_empty() {
+            return Ok(0);
+        }
+        if self.remaining == 0 {
+            let mut line = String::new();
+            if self.started {
+                // The CRLF closing the previous chunk
+                self.inner.read_line(&mut line)?;
+                line.clear();
+            }
+            self.started = true;
+            if self.inner.read_line(&mut line)? == 0 {
+                return Err(Self::invalid("truncated chunked body".to_string()));
+            }
+            let size_str = line.trim().split(';').next().unwrap_or("");
+            self.remaining = usize::from_str_radix(size_str, 16)
+                .map_err(|_| Self::invalid(format!("bad chunk size: {}", line.trim())))?;
+            if self.remaining == 0 {
+                self.done = true;
+                return Ok(0);
+            }
+        }
+        let limit = buf.len().min(self.remaining);
+        let read = self.inner.read(&mut buf[..limit])?;
+        if read == 0 {
+            return Err(Self::invalid("truncated chunk".to_string()));
+        }
+        self.remaining -= read;
+        Ok(read)
+    }
+}
+
+/// Calls collected for one JSON-RPC batch (see [`JsonRpcClient::batch`])
+///
+/// The server runs the calls concurrently; `send` returns one result per
+/// call (notifications excepted), in the order the calls were added.
+pub struct BatchBuilder<'a> {
+    client: &'a JsonRpcClient,
+    envelopes: Vec<Value>,
+    /// Id of each call expecting a response, in call order
+    ids: Vec<i64>,
+    /// First failure while building (stack serialization); reported by send
+    error: Option<ClientError>,
+}
+
+impl BatchBuilder<'_> {
+    /// Add any method call
+    pub fn call(mut self, method: &str, params: Value) -> Self {
+        let (id, envelope) = self.client.envelope(method, params);
+        self.ids.push(id);
+        self.envelopes.push(envelope);
+        self
+    }
+
+    /// Add a notification: it runs, but no result comes back for it
+    pub fn notify(mut self, method: &str, params: Value) -> Self {
+        self.envelopes
+            .push(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+        self
+    }
+
+    pub fn execute_word(self, word_name: &str, stack: &[ForthicValue]) -> Self {
+        self.with_stack(stack, |builder, stack| {
+            builder.call(
+                "executeWord",
+                json!({ "word_name": word_name, "stack": stack }),
+            )
+        })
+    }
+
+    pub fn execute_sequence(self, word_names: &[&str], stack: &[ForthicValue]) -> Self {
+        self.with_stack(stack, |builder, stack| {
+            builder.call(
+                "executeSequence",
+                json!({ "word_names": word_names, "stack": stack }),
+            )
+        })
+    }
+
+    pub fn run_code(self, session_id: Option<&str>, code: &str, stack: &[ForthicValue]) -> Self {
+        self.with_stack(stack, |builder, stack| {
+            let mut params = json!({ "code": code, "stack": stack });
+            if let Some(session_id) = session_id {
+                params["session_id"] = json!(session_id);
+            }
+            builder.call("runCode", params)
+        })
+    }
+
+    fn with_stack(
+        mut self,
+        stack: &[ForthicValue],
+        add: impl FnOnce(Self, Vec<Value>) -> Self,
+    ) -> Self {
+        match self.client.serialize_stack(stack) {
+            Ok(stack) => add(self, stack),
+            Err(e) => {
+                self.error.get_or_insert(e);
+                self
+            }
+        }
+    }
+
+    /// Send the batch. The outer error covers the whole request (transport,
+    /// a rejected batch); each inner result is one call's outcome.
+    pub fn send(self) -> Result<Vec<Result<Value, ClientError>>, ClientError> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        if self.envelopes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (status, body) = self
+            .client
+            .http_post(&Value::Array(self.envelopes).to_string())?;
+        if status == 204 && self.ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if status != 200 {
+            return Err(ClientError::Http { status, body });
+        }
+
+        let response: Value = serde_json::from_str(&body)
+            .map_err(|e| ClientError::Protocol(format!("invalid JSON-RPC response: {e}")))?;
+        // A batch rejected as a whole comes back as a single error envelope
+        let Value::Array(responses) = response else {
+            return Err(JsonRpcClient::response_result(&response)
+                .err()
+                .unwrap_or_else(|| {
+                    ClientError::Protocol("expected an array of batch responses".to_string())
+                }));
+        };
+        let mut by_id: HashMap<i64, Value> = responses
+            .into_iter()
+            .filter_map(|r| r.get("id").and_then(Value::as_i64).map(|id| (id, r)))
+            .collect();
+        Ok(self
+            .ids
+            .iter()
+            .map(|id| match by_id.remove(id) {
+                Some(response) => JsonRpcClient::response_result(&response),
+                None => Err(ClientError::Protocol(format!(
+                    "batch response missing id {id}"
+                ))),
+            })
+            .collect())
+    }
+}
@@ -19,6 +19,11 @@ impl JsonRpcErrorCode {
     pub const RUNTIME_ERROR: i64 = -32000;
     /// Unknown module name passed to getModuleInfo
     pub const MODULE_NOT_FOUND: i64 = -32001;
+    /// Unknown, closed, or expired session id (rs server extension)
+    pub const SESSION_NOT_FOUND: i64 = -32002;
+    /// createSession refused: the max-sessions cap is reached (rs server
+    /// extension)
+    pub const TOO_MANY_SESSIONS: i64 = -32003;
 }
 
 /// Structured payload of RUNTIME_ERROR responses
@@ -1,7 +1,8 @@
-//! HTTP transport for the JSON-RPC server (axum)
+//! HTTP and WebSocket transport for the JSON-RPC server (axum)
 //!
 //! Wraps [`dispatch`](super::server::dispatch) in an HTTP POST endpoint at
-//! `/rpc` (and `/`), with the hardening measures from forthic-ts #25 — the
+//! `/rpc` (and `/`) and a WebSocket endpoint at `/ws`, with the hardening
+//! measures from forthic-ts #25 — the
 //! server executes caller-supplied Forthic code, so the defaults are
 //! deliberately conservative:
 //!
@@ -9,35 +10,95 @@
 //!   `FORTHIC_JSONRPC_HOST`). Binding non-loopback without a token logs a
 //!   loud warning.
 //! - Optional bearer-token auth (`token` / `FORTHIC_JSONRPC_TOKEN`),
-//!   checked in constant time BEFORE the body is read.
+//!   checked in constant time BEFORE the body is read — or, for `/ws`,
+//!   before the upgrade (header, or `access_token` query parameter for
+//!   browsers, which cannot set headers on a WebSocket).
 //! - Request body cap (default 1 MiB), rejected up front via Content-Length
-//!   and enforced while streaming.
-//! - JSON-RPC envelope validation; batch arrays rejected with -32600.
+//!   and enforced while streaming; on `/ws` it caps each message.
+//! - JSON-RPC envelope validation. Batches (arrays) run their calls
+//!   concurrently, capped at `max_batch_size` calls and sharing the body
+//!   cap; notifications (no `id`) run but get no response.
+//! - Interpreter timezone (`timezone` / `FORTHIC_JSONRPC_TZ`, default
+//!   UTC) validated at startup; requests may override it per call.
+//! - Forthic bootstrap modules (`modules`, `bootstrap_files` /
+//!   `FORTHIC_JSONRPC_BOOTSTRAP`) compile at startup; a failure fails
+//!   `serve` rather than serving a partial vocabulary.
+//! - Optional word policy (`word_policy`): allow/deny lists of words and
+//!   modules, enforced at word lookup in every request interpreter.
 //! - Error details (`word_location`) stripped unless
 //!   `expose_error_details` is set.
+//! - Optional audit log (`audit` / `FORTHIC_JSONRPC_AUDIT_LOG`): every
+//!   call executed and every request refused for its token, as structured
+//!   records (see [`super::audit`]).
+//! - `GET /healthz` answers `{"status":"ok"}` without auth, for liveness
+//!   probes. `GET /metrics` serves [`ServerMetrics`] in the Prometheus text
+//!   format, behind the token like `/rpc`.
+//! - Progress streaming: a POST sent with `Accept: text/event-stream` is
+//!   answered with server-sent events — a `progress` notification for each
+//!   value PROGRESS reports, tagged with the request id, then the response.
+//!   WebSocket and stdio callers always get these notifications.
+//!
+//! Each WebSocket message is handled like a POST body, concurrently with
+//! the connection's other messages (at most 32 at once; further messages
+//! wait unread), so responses may arrive out of order
+//! (match them by `id`). [`ServerHandle::notify`] pushes a server-initiated
+//! notification to every open WebSocket.
 //!
 //! The sync interpreter runs on the blocking thread pool via
-//! `spawn_blocking`; each request gets a fresh interpreter (see servicer).
+//! `spawn_blocking`; each request gets a fresh interpreter (see servicer)
+//! unless it targets a session. A background task sweeps idle sessions.
+//! The stdio transport ([`super::stdio`]) shares this request handling.
 
+use super::audit::{AuditLog, JsonLinesSink};
+use super::bootstrap::load_bootstrap_file;
 use super::errors::JsonRpcErrorCode;
-use super::server::{dispatch, ForthicJsonRpcServicer, JsonRpcRequest};
+use super::metrics::ServerMetrics;
+use super::serializer::serialize_value;
+use super::server::{dispatch_with_progress, ForthicJsonRpcServicer, JsonRpcRequest};
+use super::sessions::{SessionConfig, DEFAULT_MAX_SESSIONS, DEFAULT_SESSION_IDLE_TIMEOUT};
+use crate::interpreter::{OutputHandler, ProgressHandler};
+use crate::literals::ForthicValue;
+use crate::module::Module;
+use crate::word_policy::WordPolicy;
 use axum::body::Body;
-use axum::extract::State;
+use axum::extract::ws::rejection::WebSocketUpgradeRejection;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, RawQuery, State};
 use axum::http::{header, HeaderMap, Request, StatusCode};
-use axum::response::Response;
-use axum::routing::post;
+use axum::response::sse::{Event, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
 use axum::Router;
 use http_body_util::BodyExt;
 use serde_json::{json, Value};
+use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use subtle::ConstantTimeEq;
 use tokio::net::TcpListener;
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, mpsc, watch, Semaphore};
+use tokio::task::JoinSet;
 
 /// Default cap on request body size (1 MiB)
 const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
 
+/// Default cap on calls in one batch
+const DEFAULT_MAX_BATCH_SIZE: usize = 50;
+
+/// Server notifications buffered per WebSocket before a slow reader starts
+/// missing them
+const NOTIFICATION_BUFFER: usize = 256;
+
+/// Messages one WebSocket or stdio stream may have running at once; the
+/// next waits for a slot
+pub(super) const MAX_MESSAGES_IN_FLIGHT: usize = 32;
+
+/// Responses and progress notifications queued per stream (or per
+/// server-sent event response) before the calls producing them wait
+pub(super) const OUTGOING_BUFFER: usize = 256;
+
 /// Options controlling how the JSON-RPC server is exposed
 ///
 /// Each `None` field falls back to its `FORTHIC_JSONRPC_*` environment
@@ -49,28 +110,84 @@ pub struct ServeOptions {
     /// Env: `FORTHIC_JSONRPC_HOST`.
     pub host: Option<String>,
     /// Shared secret. When set, every request must send
-    /// `Authorization: Bearer <token>`; others get 401.
-    /// Env: `FORTHIC_JSONRPC_TOKEN`.
+    /// `Authorization: Bearer <token>`; others get 401. Also required of
+    /// stdio messages. Env: `FORTHIC_JSONRPC_TOKEN`.
     pub token: Option<String>,
-    /// Maximum request body in bytes. Env: `FORTHIC_JSONRPC_MAX_BODY_BYTES`.
+    /// Maximum request body in bytes — for a batch, the whole batch; on
+    /// WebSocket and stdio, one message. Env: `FORTHIC_JSONRPC_MAX_BODY_BYTES`.
     pub max_body_bytes: Option<usize>,
+    /// Maximum calls in one batch; larger batches are rejected whole.
+    /// Default 50. Env: `FORTHIC_JSONRPC_MAX_BATCH_SIZE`.
+    pub max_batch_size: Option<usize>,
     /// Include code locations in error responses. Off by default; for
     /// local debugging only (rs analog of ts `exposeStackTraces`).
     pub expose_error_details: bool,
+    /// Default timezone of request interpreters (`TODAY`, `NOW`, date
+    /// literals), as an IANA name. Default `UTC`; an unknown name fails
+    /// `serve`. Env: `FORTHIC_JSONRPC_TZ`.
+    pub timezone: Option<String>,
+    /// Modules written in Forthic (`Module::new_with_code`), compiled once
+    /// at startup and imported into every request interpreter
+    pub modules: Vec<Module>,
+    /// `.forthic` files compiled after `modules`, each served as a module
+    /// named after its file stem. Env: `FORTHIC_JSONRPC_BOOTSTRAP`, a
+    /// path-separator list (used when this is empty).
+    pub bootstrap_files: Vec<PathBuf>,
+    /// Words and modules requests may (not) use. Default: no restriction;
+    /// an unknown module name fails `serve`.
+    pub word_policy: WordPolicy,
+    /// Close sessions unused for this long. Default 15 minutes.
+    /// Env: `FORTHIC_JSONRPC_SESSION_IDLE_SECS`.
+    pub session_idle_timeout: Option<Duration>,
+    /// Maximum concurrently open sessions. Default 64.
+    /// Env: `FORTHIC_JSONRPC_MAX_SESSIONS`.
+    pub max_sessions: Option<usize>,
+    /// Record into these metrics, e.g. to export them from the embedding
+    /// host. Default: fresh ones, read via [`ServerHandle::metrics`].
+    pub metrics: Option<Arc<ServerMetrics>>,
+    /// Write an audit record for every call and every refused token.
+    /// Default: none. Env: `FORTHIC_JSONRPC_AUDIT_LOG`, a file appended
+    /// to as JSON lines with stack values redacted.
+    pub audit: Option<AuditLog>,
 }
 
-struct ServerContext {
-    servicer: ForthicJsonRpcServicer,
-    token: Option<String>,
-    max_body_bytes: usize,
+/// Everything a transport needs to answer requests, shared by its tasks
+pub(super) struct ServerContext {
+    pub(super) servicer: ForthicJsonRpcServicer,
+    pub(super) token: Option<String>,
+    pub(super) max_body_bytes: usize,
+    max_batch_size: usize,
     expose_error_details: bool,
+    session_idle_timeout: Duration,
+    pub(super) metrics: Arc<ServerMetrics>,
+    audit: Option<AuditLog>,
+    notifications: broadcast::Sender<Value>,
+    closing: watch::Sender<bool>,
+}
+
+impl ServerContext {
+    /// Count and audit a request refused for its token
+    pub(super) fn unauthorized(&self, origin: Origin) {
+        self.metrics.record_auth_failure(origin.transport);
+        if let Some(audit) = &self.audit {
+            audit.record_rejection(origin.transport, origin.peer, "unauthorized");
+        }
+    }
+}
+
+/// Which transport (and peer, when there is one) a message came from
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Origin {
+    pub(super) transport: &'static str,
+    pub(super) peer: Option<SocketAddr>,
 }
 
 /// Handle to a running server: bound address + graceful shutdown
 pub struct ServerHandle {
     addr: SocketAddr,
-    shutdown_tx: oneshot::Sender<()>,
+    ctx: Arc<ServerContext>,
     join: tokio::task::JoinHandle<()>,
+    sweeper: tokio::task::JoinHandle<()>,
 }
 
 impl ServerHandle {
@@ -79,10 +196,24 @@ impl ServerHandle {
         self.addr
     }
 
-    /// Signal graceful shutdown and wait for the server to stop
+    /// The metrics this server records (also served at `/metrics`)
+    pub fn metrics(&self) -> &Arc<ServerMetrics> {
+        &self.ctx.metrics
+    }
+
+    /// Send a JSON-RPC notification (no `id`) to every open WebSocket.
+    /// Returns how many connections it was queued for.
+    pub fn notify(&self, method: &str, params: Value) -> usize {
+        let notification = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        self.ctx.notifications.send(notification).unwrap_or(0)
+    }
+
+    /// Signal graceful shutdown and wait for the server to stop. Open
+    /// WebSockets are closed.
     pub async fn shutdown(self) {
-        let _ = self.shutdown_tx.send(());
+        self.ctx.closing.send_replace(true);
         let _ = self.join.await;
+        self.sweeper.abort();
     }
 }
 
@@ -90,72 +221,202 @@ impl ServerHandle {
 pub async fn serve(port: u16, options: ServeOptions) -> std::io::Result<ServerHandle> {
     let host = options
         .host
+        .clone()
         .or_else(|| std::env::var("FORTHIC_JSONRPC_HOST").ok())
         .unwrap_or_else(|| "127.0.0.1".to_string());
-    let token = options
-        .token
-        .or_else(|| std::env::var("FORTHIC_JSONRPC_TOKEN").ok());
-    let max_body_bytes = options
-        .max_body_bytes
-        .or_else(|| {
-            std::env::var("FORTHIC_JSONRPC_MAX_BODY_BYTES")
-                .ok()
-                .and_then(|s| s.parse().ok())
-        })
-        .unwrap_or(DEFAULT_MAX_BODY_BYTES);
+    let ctx = Arc::new(build_context(options, None)?);
 
-    if !is_loopback_host(&host) && token.is_none() {
+    if !is_loopback_host(&host) && ctx.token.is_none() {
         eprintln!(
             "⚠ SECURITY: JSON-RPC server bound to non-loopback host '{host}' without an \
              auth token. It executes Forthic code from any client that can reach it. \
              Set ServeOptions.token / FORTHIC_JSONRPC_TOKEN, or bind to 127.0.0.1."
         );
     }
+    println!("{}", module_summary(&ctx.servicer));
 
-    let servicer = ForthicJsonRpcServicer::new();
-    let module_names = servicer.registered_module_names();
-    if module_names.is_empty() {
-        println!("  - No runtime-specific modules loaded");
-    } else {
-        println!("  - Available runtime modules: {}", module_names.join(", "));
-    }
-
-    let ctx = Arc::new(ServerContext {
-        servicer,
-        token,
-        max_body_bytes,
-        expose_error_details: options.expose_error_details,
-    });
-
-    // Undefined paths 404 via the default fallback; non-POST on defined
-    // paths gets 405 + Allow from axum's method router.
+    // Undefined paths 404 via the default fallback; wrong methods on
+    // defined paths get 405 + Allow from axum's method router.
     let app = Router::new()
         .route("/rpc", post(rpc_handler))
         .route("/", post(rpc_handler))
-        .with_state(ctx);
+        .route("/ws", get(ws_handler))
+        .route("/healthz", get(healthz_handler))
+        .route("/metrics", get(metrics_handler))
+        .with_state(Arc::clone(&ctx));
 
     let listener = TcpListener::bind((host.as_str(), port)).await?;
     let addr = listener.local_addr()?;
-    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let sweeper = spawn_session_sweeper(Arc::clone(&ctx));
+    let mut closing = ctx.closing.subscribe();
     let join = tokio::spawn(async move {
+        let app = app.into_make_service_with_connect_info::<SocketAddr>();
         let _ = axum::serve(listener, app)
-            .with_graceful_shutdown(async {
-                let _ = shutdown_rx.await;
+            .with_graceful_shutdown(async move {
+                let _ = closing.wait_for(|closing| *closing).await;
             })
             .await;
     });
 
     Ok(ServerHandle {
         addr,
-        shutdown_tx,
+        ctx,
         join,
+        sweeper,
+    })
+}
+
+/// Resolve `options` (env fallbacks, defaults) and build the servicer:
+/// timezone, sessions, bootstrap modules, word policy. `output` takes
+/// PRINT-style output in place of stdout.
+pub(super) fn build_context(
+    options: ServeOptions,
+    output: Option<OutputHandler>,
+) -> std::io::Result<ServerContext> {
+    let token = options
+        .token
+        .or_else(|| std::env::var("FORTHIC_JSONRPC_TOKEN").ok());
+    let max_body_bytes = options
+        .max_body_bytes
+        .or_else(|| {
+            std::env::var("FORTHIC_JSONRPC_MAX_BODY_BYTES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+        })
+        .unwrap_or(DEFAULT_MAX_BODY_BYTES);
+    let max_batch_size = options
+        .max_batch_size
+        .or_else(|| {
+            std::env::var("FORTHIC_JSONRPC_MAX_BATCH_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+        })
+        .unwrap_or(DEFAULT_MAX_BATCH_SIZE);
+    let session_config = SessionConfig {
+        idle_timeout: options
+            .session_idle_timeout
+            .or_else(|| {
+                std::env::var("FORTHIC_JSONRPC_SESSION_IDLE_SECS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .map(Duration::from_secs)
+            })
+            .unwrap_or(DEFAULT_SESSION_IDLE_TIMEOUT),
+        max_sessions: options
+            .max_sessions
+            .or_else(|| {
+                std::env::var("FORTHIC_JSONRPC_MAX_SESSIONS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+            })
+            .unwrap_or(DEFAULT_MAX_SESSIONS),
+    };
+
+    let bootstrap_files = if options.bootstrap_files.is_empty() {
+        std::env::var_os("FORTHIC_JSONRPC_BOOTSTRAP")
+            .map(|paths| std::env::split_paths(&paths).collect())
+            .unwrap_or_default()
+    } else {
+        options.bootstrap_files
+    };
+    let timezone = options
+        .timezone
+        .or_else(|| std::env::var("FORTHIC_JSONRPC_TZ").ok())
+        .unwrap_or_else(|| "UTC".to_string());
+
+    let mut servicer = ForthicJsonRpcServicer::new();
+    servicer
+        .set_timezone(&timezone)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.message))?;
+    servicer.set_session_config(session_config);
+    servicer.set_output_handler(output);
+    let audit = match options.audit {
+        Some(audit) => Some(audit),
+        None => match std::env::var_os("FORTHIC_JSONRPC_AUDIT_LOG") {
+            Some(path) => Some(AuditLog::new(Arc::new(
+                JsonLinesSink::open(&path).map_err(|e| {
+                    std::io::Error::new(
+                        e.kind(),
+                        format!("Audit log {}: {e}", Path::new(&path).display()),
+                    )
+                })?,
+            ))),
+            None => None,
+        },
+    };
+    let metrics = options.metrics.unwrap_or_default();
+    servicer.set_metrics(Some(Arc::clone(&metrics)));
+    let mut modules = options.modules;
+    for path in &bootstrap_files {
+        modules.push(
+            load_bootstrap_file(path)
+                .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {e}", path.display())))?,
+        );
+    }
+    for module in modules {
+        let name = module.get_name().to_string();
+        servicer.add_forthic_module(module).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Bootstrap module '{name}' failed to compile: {e}"),
+            )
+        })?;
+    }
+    servicer
+        .set_word_policy(options.word_policy)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+
+    Ok(ServerContext {
+        servicer,
+        token,
+        max_body_bytes,
+        max_batch_size,
+        expose_error_details: options.expose_error_details,
+        session_idle_timeout: session_config.idle_timeout,
+        metrics,
+        audit,
+        notifications: broadcast::channel(NOTIFICATION_BUFFER).0,
+        closing: watch::channel(false).0,
     })
 }
 
-async fn rpc_handler(State(ctx): State<Arc<ServerContext>>, req: Request<Body>) -> Response {
+/// The startup line listing the runtime modules being served
+pub(super) fn module_summary(servicer: &ForthicJsonRpcServicer) -> String {
+    let module_names = servicer.registered_module_names();
+    if module_names.is_empty() {
+        "  - No runtime-specific modules loaded".to_string()
+    } else {
+        format!("  - Available runtime modules: {}", module_names.join(", "))
+    }
+}
+
+/// Lookups evict lazily too; the sweep frees idle interpreters even when
+/// no calls arrive
+pub(super) fn spawn_session_sweeper(ctx: Arc<ServerContext>) -> tokio::task::JoinHandle<()> {
+    let sweep_every =
+        (ctx.session_idle_timeout / 2).clamp(Duration::from_millis(10), Duration::from_secs(30));
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(sweep_every);
+        loop {
+            interval.tick().await;
+            ctx.servicer.sessions().evict_idle();
+        }
+    })
+}
+
+async fn rpc_handler(
+    State(ctx): State<Arc<ServerContext>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    req: Request<Body>,
+) -> Response {
+    let origin = Origin {
+        transport: "http",
+        peer: Some(peer),
+    };
     // Authenticate before reading the body, so unauthorized callers can't
     // execute code or push a large payload.
     if !is_authorized(req.headers(), ctx.token.as_deref()) {
+        ctx.unauthorized(origin);
         return json_response(
             StatusCode::UNAUTHORIZED,
             Some(("WWW-Authenticate", "Bearer")),
@@ -177,6 +438,12 @@ async fn rpc_handler(State(ctx): State<Arc<ServerContext>>, req: Request<Body>)
         return plain_response(StatusCode::UNSUPPORTED_MEDIA_TYPE, "Unsupported Media Type");
     }
 
+    let wants_events = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"));
+
     // Reject an oversized body up front via Content-Length, before reading
     let declared_len = req
         .headers()
@@ -203,85 +470,407 @@ async fn rpc_handler(State(ctx): State<Arc<ServerContext>>, req: Request<Body>)
         }
     };
 
-    let parsed: Value = match serde_json::from_slice(&raw) {
+    if wants_events {
+        return event_stream(ctx, raw, origin);
+    }
+    match handle_message(&ctx, &raw, origin, None).await {
+        Some(response) => {
+            let body = response.to_string();
+            ctx.metrics.record_sent(body.len());
+            json_text_response(StatusCode::OK, None, body)
+        }
+        // A notification, or a batch of only notifications, gets no
+        // response body at all
+        None => no_content(),
+    }
+}
+
+/// Answer with server-sent events: each progress notification as it is
+/// reported, then the response (none for notifications), then the end of
+/// the stream
+fn event_stream(ctx: Arc<ServerContext>, raw: Vec<u8>, origin: Origin) -> Response {
+    let (events_tx, events) = mpsc::channel::<Value>(OUTGOING_BUFFER);
+    let metrics = Arc::clone(&ctx.metrics);
+    tokio::spawn(async move {
+        if let Some(response) = handle_message(&ctx, &raw, origin, Some(&events_tx)).await {
+            let _ = events_tx.send(response).await;
+        }
+    });
+    // The stream ends once the task and every progress handler holding a
+    // sender are gone
+    let stream = futures_util::stream::unfold(events, move |mut events| {
+        let metrics = Arc::clone(&metrics);
+        async move {
+            let message = events.recv().await?.to_string();
+            metrics.record_sent(message.len());
+            Some((Ok::<_, Infallible>(Event::default().data(message)), events))
+        }
+    });
+    Sse::new(stream).into_response()
+}
+
+async fn ws_handler(
+    State(ctx): State<Arc<ServerContext>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+    upgrade: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Response {
+    // Authenticate before upgrading; browsers cannot set headers on a
+    // WebSocket, so they may pass the token as `?access_token=`
+    let query_token = query.as_deref().and_then(access_token_param);
+    let authorized = match ctx.token.as_deref() {
+        None => true,
+        Some(token) => {
+            bearer_token(&headers).is_some_and(|presented| token_matches(presented, token))
+                || query_token.is_some_and(|presented| token_matches(presented, token))
+        }
+    };
+    let origin = Origin {
+        transport: "ws",
+        peer: Some(peer),
+    };
+    if !authorized {
+        ctx.unauthorized(origin);
+        return json_response(
+            StatusCode::UNAUTHORIZED,
+            Some(("WWW-Authenticate", "Bearer")),
+            error_envelope(
+                Value::Null,
+                JsonRpcErrorCode::INVALID_REQUEST,
+                "Unauthorized",
+            ),
+        );
+    }
+
+    match upgrade {
+        Ok(upgrade) => upgrade
+            .max_message_size(ctx.max_body_bytes)
+            .max_frame_size(ctx.max_body_bytes)
+            .on_upgrade(move |socket| ws_connection(ctx, socket, origin)),
+        Err(rejection) => rejection.into_response(),
+    }
+}
+
+/// Serve one WebSocket: each message is handled on its own task, at most
+/// MAX_MESSAGES_IN_FLIGHT at a time, and responses and server notifications
+/// are written back as they come
+async fn ws_connection(ctx: Arc<ServerContext>, mut socket: WebSocket, origin: Origin) {
+    let mut notifications = ctx.notifications.subscribe();
+    let mut closing = ctx.closing.subscribe();
+    let (responses_tx, mut responses) = mpsc::channel::<Value>(OUTGOING_BUFFER);
+    let slots = Arc::new(Semaphore::new(MAX_MESSAGES_IN_FLIGHT));
+    // A slot is taken before the next message is read, so a client at the
+    // limit waits in its socket buffer while responses still go out
+    let mut slot = None;
+    let mut in_flight = JoinSet::new();
+
+    loop {
+        while in_flight.try_join_next().is_some() {}
+        let outgoing = tokio::select! {
+            permit = Arc::clone(&slots).acquire_owned(), if slot.is_none() => {
+                slot = permit.ok();
+                continue;
+            }
+            incoming = socket.recv(), if slot.is_some() => {
+                let raw = match incoming {
+                    Some(Ok(Message::Text(text))) => text.as_bytes().to_vec(),
+                    Some(Ok(Message::Binary(bytes))) => bytes.to_vec(),
+                    // Pings are answered by axum; pongs need nothing
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                };
+                let ctx = Arc::clone(&ctx);
+                let responses_tx = responses_tx.clone();
+                let slot = slot.take();
+                in_flight.spawn(async move {
+                    let _slot = slot;
+                    if let Some(response) =
+                        handle_message(&ctx, &raw, origin, Some(&responses_tx)).await
+                    {
+                        let _ = responses_tx.send(response).await;
+                    }
+                });
+                continue;
+            }
+            Some(response) = responses.recv() => response,
+            notification = notifications.recv() => match notification {
+                Ok(notification) => notification,
+                // A reader too slow for the buffer misses the oldest ones
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = closing.changed() => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+        };
+        let outgoing = outgoing.to_string();
+        ctx.metrics.record_sent(outgoing.len());
+        if socket.send(Message::Text(outgoing.into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Handle one raw message — a single call or a batch — as every transport
+/// does. `None` means nothing to send back (notifications only). With a
+/// `progress` sink, calls that have an id stream their PROGRESS values
+/// into it before their response is returned.
+pub(super) async fn handle_message(
+    ctx: &Arc<ServerContext>,
+    raw: &[u8],
+    origin: Origin,
+    progress: Option<&mpsc::Sender<Value>>,
+) -> Option<Value> {
+    ctx.metrics.record_received(raw.len());
+    let parsed: Value = match serde_json::from_slice(raw) {
         Ok(v) => v,
         Err(e) => {
-            return json_response(
-                StatusCode::OK,
-                None,
+            return Some(invalid(
+                ctx,
                 error_envelope(
                     Value::Null,
                     JsonRpcErrorCode::PARSE_ERROR,
                     &format!("Parse error: {e}"),
                 ),
-            )
+            ))
         }
     };
 
-    if parsed.is_array() {
-        return json_response(
-            StatusCode::OK,
-            None,
+    let items = match parsed {
+        Value::Array(items) => items,
+        single => {
+            return match parse_envelope(single) {
+                Envelope::Invalid(error) => Some(invalid(ctx, error)),
+                Envelope::Call {
+                    request,
+                    notification,
+                } => {
+                    let progress = progress_for(progress, &request, notification);
+                    let response = run_call(Arc::clone(ctx), request, origin, progress).await;
+                    (!notification).then_some(response)
+                }
+            };
+        }
+    };
+
+    // Batch: an empty array is itself an invalid request (JSON-RPC 2.0 §6)
+    if items.is_empty() {
+        return Some(invalid(
+            ctx,
             error_envelope(
                 Value::Null,
                 JsonRpcErrorCode::INVALID_REQUEST,
-                "Batch requests are not supported",
+                "Invalid JSON-RPC 2.0 request",
             ),
-        );
+        ));
+    }
+    if items.len() > ctx.max_batch_size {
+        return Some(invalid(
+            ctx,
+            error_envelope(
+                Value::Null,
+                JsonRpcErrorCode::INVALID_REQUEST,
+                &format!("Batch too large (limit {})", ctx.max_batch_size),
+            ),
+        ));
     }
 
+    // Start every call before awaiting any, so they run concurrently on the
+    // blocking pool; responses keep the batch order
+    let pending: Vec<_> = items
+        .into_iter()
+        .map(|item| match parse_envelope(item) {
+            Envelope::Invalid(error) => (false, Err(invalid(ctx, error))),
+            Envelope::Call {
+                request,
+                notification,
+            } => {
+                let progress = progress_for(progress, &request, notification);
+                (
+                    notification,
+                    Ok(tokio::spawn(run_call(
+                        Arc::clone(ctx),
+                        request,
+                        origin,
+                        progress,
+                    ))),
+                )
+            }
+        })
+        .collect();
+    let mut responses = Vec::new();
+    for (notification, call) in pending {
+        let response = match call {
+            Err(error) => error,
+            Ok(handle) => handle.await.unwrap_or_else(|_| internal_error()),
+        };
+        if !notification {
+            responses.push(response);
+        }
+    }
+
+    (!responses.is_empty()).then_some(Value::Array(responses))
+}
+
+/// Count `error`, the answer to something that was not a valid call
+fn invalid(ctx: &ServerContext, error: Value) -> Value {
+    ctx.metrics.record_invalid(&error);
+    error
+}
+
+/// One parsed element of a request body
+enum Envelope {
+    /// A valid request; notifications (no `id` member) get no response
+    Call {
+        request: JsonRpcRequest,
+        notification: bool,
+    },
+    /// The error response for a structurally invalid element
+    Invalid(Value),
+}
+
+fn parse_envelope(parsed: Value) -> Envelope {
     let valid_envelope = parsed
         .as_object()
         .map(|obj| {
             obj.get("jsonrpc").and_then(Value::as_str) == Some("2.0")
                 && obj.get("method").is_some_and(Value::is_string)
-                && obj.contains_key("id")
         })
         .unwrap_or(false);
     if !valid_envelope {
         let id = parsed.get("id").cloned().unwrap_or(Value::Null);
-        return json_response(
-            StatusCode::OK,
-            None,
-            error_envelope(
-                id,
-                JsonRpcErrorCode::INVALID_REQUEST,
-                "Invalid JSON-RPC 2.0 request",
-            ),
-        );
+        return Envelope::Invalid(error_envelope(
+            id,
+            JsonRpcErrorCode::INVALID_REQUEST,
+            "Invalid JSON-RPC 2.0 request",
+        ));
     }
 
-    let request: JsonRpcRequest = match serde_json::from_value(parsed) {
-        Ok(r) => r,
-        Err(_) => {
-            return json_response(
-                StatusCode::OK,
-                None,
-                error_envelope(
-                    Value::Null,
-                    JsonRpcErrorCode::INVALID_REQUEST,
-                    "Invalid JSON-RPC 2.0 request",
-                ),
-            )
-        }
-    };
+    let notification = parsed.get("id").is_none();
+    match serde_json::from_value(parsed) {
+        Ok(request) => Envelope::Call {
+            request,
+            notification,
+        },
+        Err(_) => Envelope::Invalid(error_envelope(
+            Value::Null,
+            JsonRpcErrorCode::INVALID_REQUEST,
+            "Invalid JSON-RPC 2.0 request",
+        )),
+    }
+}
 
-    // The interpreter is synchronous by design; run it off the async
-    // workers. Everything the closure needs moves in with it.
-    let ctx2 = Arc::clone(&ctx);
+/// Dispatch one request on the blocking pool. The interpreter is
+/// synchronous by design; run it off the async workers. Everything the
+/// closure needs moves in with it. The audit record is written there too.
+async fn run_call(
+    ctx: Arc<ServerContext>,
+    request: JsonRpcRequest,
+    origin: Origin,
+    progress: Option<ProgressHandler>,
+) -> Value {
+    let metrics = Arc::clone(&ctx.metrics);
+    let method = request.method.clone();
+    let started = Instant::now();
+    let in_flight = metrics.blocking_call();
+    // Kept for the record of a call whose task panics before writing its own
+    let audited = ctx.audit.clone().map(|audit| (audit, request.clone()));
     let response = tokio::task::spawn_blocking(move || {
-        dispatch(&ctx2.servicer, &request, ctx2.expose_error_details)
+        let _in_flight = in_flight;
+        let dispatched = Instant::now();
+        let response = dispatch_with_progress(
+            &ctx.servicer,
+            &request,
+            ctx.expose_error_details,
+            progress.as_ref(),
+        );
+        if let Some(audit) = &ctx.audit {
+            audit.record_call(
+                origin.transport,
+                origin.peer,
+                &request,
+                &response,
+                dispatched.elapsed(),
+            );
+        }
+        response
     })
     .await
     .unwrap_or_else(|_| {
-        error_envelope(
-            Value::Null,
-            JsonRpcErrorCode::INTERNAL_ERROR,
-            "Internal error",
-        )
+        let response = internal_error();
+        if let Some((audit, request)) = &audited {
+            audit.record_call(
+                origin.transport,
+                origin.peer,
+                request,
+                &response,
+                started.elapsed(),
+            );
+        }
+        response
     });
+    metrics.record_response(&method, &response, started.elapsed());
+    response
+}
+
+/// A progress handler sending each PROGRESS value to `sink` as a `progress`
+/// notification tagged with the request's id. Notifications have no id to
+/// tag, so they report nothing. The handler runs on the blocking pool, so
+/// when the sink is full it waits for the reader there.
+fn progress_for(
+    sink: Option<&mpsc::Sender<Value>>,
+    request: &JsonRpcRequest,
+    notification: bool,
+) -> Option<ProgressHandler> {
+    let sink = sink.filter(|_| !notification)?.clone();
+    let request_id = request.id.clone();
+    Some(Arc::new(move |value: &ForthicValue| {
+        // A value with no wire form is not reported
+        if let Ok(value) = serialize_value(value) {
+            let _ = sink.blocking_send(json!({
+                "jsonrpc": "2.0",
+                "method": "progress",
+                "params": { "request_id": request_id, "value": value },
+            }));
+        }
+    }))
+}
+
+async fn healthz_handler() -> Response {
+    json_response(StatusCode::OK, None, json!({ "status": "ok" }))
+}
 
-    json_response(StatusCode::OK, None, response)
+async fn metrics_handler(
+    State(ctx): State<Arc<ServerContext>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_authorized(&headers, ctx.token.as_deref()) {
+        ctx.unauthorized(Origin {
+            transport: "metrics",
+            peer: Some(peer),
+        });
+        return Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .header("WWW-Authenticate", "Bearer")
+            .body(Body::empty())
+            .expect("static response parts are valid");
+    }
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/plain; version=0.0.4")
+        .body(Body::from(ctx.metrics.render()))
+        .expect("static response parts are valid")
+}
+
+fn internal_error() -> Value {
+    error_envelope(
+        Value::Null,
+        JsonRpcErrorCode::INTERNAL_ERROR,
+        "Internal error",
+    )
 }
 
 enum BodyReadError {
@@ -311,24 +900,35 @@ fn is_loopback_host(host: &str) -> bool {
 }
 
 /// True when no token is configured, or the request carries the matching
-/// Bearer token (compared in constant time to avoid a timing oracle)
+/// Bearer token
 fn is_authorized(headers: &HeaderMap, token: Option<&str>) -> bool {
     let Some(token) = token else { return true };
-    let Some(header_val) = headers
+    bearer_token(headers).is_some_and(|presented| token_matches(presented, token))
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
         .get(header::AUTHORIZATION)
         .and_then(|v| v.to_str().ok())
-    else {
-        return false;
-    };
-    let Some(presented) = header_val.strip_prefix("Bearer ") else {
-        return false;
-    };
+        .and_then(|v| v.strip_prefix("Bearer "))
+}
+
+/// The `access_token` query parameter. Tokens are compared verbatim, so
+/// one that needs percent-encoding must be sent in the header instead.
+fn access_token_param(query: &str) -> Option<&str> {
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("access_token="))
+}
+
+/// Compare a presented token in constant time to avoid a timing oracle
+pub(super) fn token_matches(presented: &str, token: &str) -> bool {
     // subtle's slice ct_eq returns false for length mismatch without
     // revealing where the strings differ
     presented.as_bytes().ct_eq(token.as_bytes()).into()
 }
 
-fn error_envelope(id: Value, code: i64, message: &str) -> Value {
+pub(super) fn error_envelope(id: Value, code: i64, message: &str) -> Value {
     json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
 }
 
@@ -344,10 +944,25 @@ fn payload_too_large() -> Response {
     )
 }
 
+fn no_content() -> Response {
+    Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Body::empty())
+        .expect("static response parts are valid")
+}
+
 fn json_response(
     status: StatusCode,
     extra_header: Option<(&'static str, &'static str)>,
     payload: Value,
+) -> Response {
+    json_text_response(status, extra_header, payload.to_string())
+}
+
+fn json_text_response(
+    status: StatusCode,
+    extra_header: Option<(&'static str, &'static str)>,
+    body: String,
 ) -> Response {
     let mut builder = Response::builder()
         .status(status)
@@ -356,7 +971,7 @@ fn json_response(
         builder = builder.header(name, value);
     }
     builder
-        .body(Body::from(payload.to_string()))
+        .body(Body::from(body))
         .expect("static response parts are valid")
 }
 
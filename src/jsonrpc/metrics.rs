@@ -0,0 +1,363 @@
+//! Request metrics for the JSON-RPC server
+//!
+//! Every transport records into one [`ServerMetrics`]; the HTTP server
+//! serves it at `GET /metrics` in the Prometheus text format. Hosts that
+//! embed the server pass their own through `ServeOptions.metrics` (or read
+//! [`ServerHandle::metrics`](super::ServerHandle::metrics)) to export it
+//! elsewhere; hosts calling [`dispatch`](super::dispatch) directly can
+//! record into one themselves with [`ServerMetrics::record_response`] and
+//! hand it to the servicer
+//! ([`ForthicJsonRpcServicer::set_metrics`](super::ForthicJsonRpcServicer::set_metrics))
+//! for the word errors.
+//!
+//! | metric | type | labels |
+//! |---|---|---|
+//! | `forthic_jsonrpc_requests_total` | counter | `method`, `outcome` |
+//! | `forthic_jsonrpc_request_duration_seconds` | histogram | `method` |
+//! | `forthic_jsonrpc_blocking_in_flight` | gauge | |
+//! | `forthic_jsonrpc_received_bytes_total` | counter | |
+//! | `forthic_jsonrpc_sent_bytes_total` | counter | |
+//! | `forthic_jsonrpc_auth_failures_total` | counter | `transport` |
+//! | `forthic_jsonrpc_word_errors_total` | counter | `word` |
+//!
+//! `outcome` is `ok`, or the error kind (`runtime_error`,
+//! `invalid_params`, ...). Method names callers make up count as
+//! `unknown`, and messages that are not a valid call (unparseable JSON, bad
+//! envelopes, oversized batches) as `invalid`, so label values stay
+//! bounded. Word errors are counted for the word an `executeWord` call
+//! names, or the `executeSequence` word that failed; `runCode` programs are
+//! not attributed to a word. A word name is caller-supplied code, so the
+//! servicer labels only words it serves by name and counts anything else
+//! as `(other)`; past [`MAX_WORD_LABELS`] distinct words, later ones count
+//! as `(other)` too.
+
+use super::errors::JsonRpcErrorCode;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Distinct `word` label values kept before the rest count as `(other)`
+pub const MAX_WORD_LABELS: usize = 500;
+
+/// The `word` label of errors not counted under their own word
+pub const OTHER_WORD_LABEL: &str = "(other)";
+
+/// Methods counted under their own name
+const KNOWN_METHODS: &[&str] = &[
+    "executeWord",
+    "executeSequence",
+    "listModules",
+    "getModuleInfo",
+    "createSession",
+    "closeSession",
+    "runCode",
+];
+
+/// Upper bounds of the latency histogram buckets, in seconds
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Counters and histograms of one server (see the module docs)
+#[derive(Debug, Default)]
+pub struct ServerMetrics {
+    requests: Mutex<BTreeMap<(String, &'static str), u64>>,
+    latency: Mutex<BTreeMap<String, Histogram>>,
+    blocking_in_flight: AtomicI64,
+    received_bytes: AtomicU64,
+    sent_bytes: AtomicU64,
+    auth_failures: Mutex<BTreeMap<&'static str, u64>>,
+    word_errors: Mutex<BTreeMap<String, u64>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Per-bucket (not cumulative) counts; the last is `+Inf`
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; LATENCY_BUCKETS.len() + 1];
+        }
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[bucket] += 1;
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// Marks a call queued or running on the blocking pool; dropping it ends
+/// the call
+pub struct BlockingCall {
+    metrics: Arc<ServerMetrics>,
+}
+
+impl Drop for BlockingCall {
+    fn drop(&mut self) {
+        self.metrics
+            .blocking_in_flight
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ServerMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one call's response envelope (as [`dispatch`](super::dispatch)
+    /// returns it) and how long it took
+    pub fn record_response(&self, method: &str, response: &Value, elapsed: Duration) {
+        let method = method_label(method);
+        let error = response.get("error");
+        let outcome = error.map_or("ok", |error| {
+            outcome_label(error.get("code").and_then(Value::as_i64))
+        });
+        *lock(&self.requests)
+            .entry((method.to_string(), outcome))
+            .or_default() += 1;
+        lock(&self.latency)
+            .entry(method.to_string())
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Count a failed executeWord / executeSequence call against the word
+    /// that failed
+    pub fn record_word_error(&self, word: &str) {
+        let mut word_errors = lock(&self.word_errors);
+        let label = if word_errors.contains_key(word) || word_errors.len() < MAX_WORD_LABELS {
+            word
+        } else {
+            OTHER_WORD_LABEL
+        };
+        *word_errors.entry(label.to_string()).or_default() += 1;
+    }
+
+    /// Record a message that was not a valid call, answered with `error`
+    pub fn record_invalid(&self, error: &Value) {
+        let outcome = outcome_label(error.pointer("/error/code").and_then(Value::as_i64));
+        *lock(&self.requests)
+            .entry(("invalid".to_string(), outcome))
+            .or_default() += 1;
+    }
+
+    /// Count a call as running on the blocking pool until the returned
+    /// guard is dropped
+    pub fn blocking_call(self: &Arc<Self>) -> BlockingCall {
+        self.blocking_in_flight.fetch_add(1, Ordering::Relaxed);
+        BlockingCall {
+            metrics: Arc::clone(self),
+        }
+    }
+
+    pub fn record_received(&self, bytes: usize) {
+        self.received_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_sent(&self, bytes: usize) {
+        self.sent_bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Count a request refused for a missing or wrong token
+    pub fn record_auth_failure(&self, transport: &'static str) {
+        *lock(&self.auth_failures).entry(transport).or_default() += 1;
+    }
+
+    /// Calls of `method` that ended with `outcome`
+    pub fn requests(&self, method: &str, outcome: &str) -> u64 {
+        lock(&self.requests)
+            .iter()
+            .filter(|((m, o), _)| m == method && *o == outcome)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    pub fn blocking_in_flight(&self) -> i64 {
+        self.blocking_in_flight.load(Ordering::Relaxed)
+    }
+
+    pub fn received_bytes(&self) -> u64 {
+        self.received_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn sent_bytes(&self) -> u64 {
+        self.sent_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn auth_failures(&self, transport: &str) -> u64 {
+        lock(&self.auth_failures)
+            .get(transport)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn word_errors(&self, word: &str) -> u64 {
+        lock(&self.word_errors).get(word).copied().unwrap_or(0)
+    }
+
+    /// Everything, in the Prometheus text exposition format (0.0.4)
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "forthic_jsonrpc_requests_total",
+            "counter",
+            "JSON-RPC calls handled, by method and outcome",
+        );
+        for ((method, outcome), count) in lock(&self.requests).iter() {
+            let _ = writeln!(
+                out,
+                "forthic_jsonrpc_requests_total{{method=\"{method}\",outcome=\"{outcome}\"}} {count}"
+            );
+        }
+
+        header(
+            &mut out,
+            "forthic_jsonrpc_request_duration_seconds",
+            "histogram",
+            "Time from dispatch to response, by method",
+        );
+        for (method, histogram) in lock(&self.latency).iter() {
+            let mut cumulative = 0;
+            for (i, count) in histogram.buckets.iter().enumerate() {
+                cumulative += count;
+                let bound = LATENCY_BUCKETS
+                    .get(i)
+                    .map_or("+Inf".to_string(), f64::to_string);
+                let _ = writeln!(
+                    out,
+                    "forthic_jsonrpc_request_duration_seconds_bucket{{method=\"{method}\",le=\"{bound}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "forthic_jsonrpc_request_duration_seconds_sum{{method=\"{method}\"}} {}",
+                histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "forthic_jsonrpc_request_duration_seconds_count{{method=\"{method}\"}} {}",
+                histogram.count
+            );
+        }
+
+        header(
+            &mut out,
+            "forthic_jsonrpc_blocking_in_flight",
+            "gauge",
+            "Calls running or queued on the blocking thread pool",
+        );
+        let _ = writeln!(
+            out,
+            "forthic_jsonrpc_blocking_in_flight {}",
+            self.blocking_in_flight()
+        );
+
+        header(
+            &mut out,
+            "forthic_jsonrpc_received_bytes_total",
+            "counter",
+            "Bytes of JSON-RPC messages received",
+        );
+        let _ = writeln!(
+            out,
+            "forthic_jsonrpc_received_bytes_total {}",
+            self.received_bytes()
+        );
+        header(
+            &mut out,
+            "forthic_jsonrpc_sent_bytes_total",
+            "counter",
+            "Bytes of JSON-RPC messages sent",
+        );
+        let _ = writeln!(
+            out,
+            "forthic_jsonrpc_sent_bytes_total {}",
+            self.sent_bytes()
+        );
+
+        header(
+            &mut out,
+            "forthic_jsonrpc_auth_failures_total",
+            "counter",
+            "Requests refused for a missing or wrong token, by transport",
+        );
+        for (transport, count) in lock(&self.auth_failures).iter() {
+            let _ = writeln!(
+                out,
+                "forthic_jsonrpc_auth_failures_total{{transport=\"{transport}\"}} {count}"
+            );
+        }
+
+        header(
+            &mut out,
+            "forthic_jsonrpc_word_errors_total",
+            "counter",
+            "Failed executeWord / executeSequence calls, by failing word",
+        );
+        for (word, count) in lock(&self.word_errors).iter() {
+            let _ = writeln!(
+                out,
+                "forthic_jsonrpc_word_errors_total{{word=\"{}\"}} {count}",
+                escape_label(word)
+            );
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// A poisoned lock only means a recording thread panicked; the counts are
+/// still usable
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn method_label(method: &str) -> &str {
+    if KNOWN_METHODS.contains(&method) {
+        method
+    } else {
+        "unknown"
+    }
+}
+
+fn outcome_label(code: Option<i64>) -> &'static str {
+    match code {
+        Some(JsonRpcErrorCode::PARSE_ERROR) => "parse_error",
+        Some(JsonRpcErrorCode::INVALID_REQUEST) => "invalid_request",
+        Some(JsonRpcErrorCode::METHOD_NOT_FOUND) => "method_not_found",
+        Some(JsonRpcErrorCode::INVALID_PARAMS) => "invalid_params",
+        Some(JsonRpcErrorCode::INTERNAL_ERROR) => "internal_error",
+        Some(JsonRpcErrorCode::RUNTIME_ERROR) => "runtime_error",
+        Some(JsonRpcErrorCode::MODULE_NOT_FOUND) => "module_not_found",
+        Some(JsonRpcErrorCode::SESSION_NOT_FOUND) => "session_not_found",
+        Some(JsonRpcErrorCode::TOO_MANY_SESSIONS) => "too_many_sessions",
+        _ => "error",
+    }
+}
+
+/// Escape a label value: backslash, double quote, and newline
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -4,14 +4,26 @@
 //! so a forthic-ts `JsonRpcClient` can call the Rust runtime unchanged.
 //! See `plans/JSONRPC-PLAN.md` for the full design.
 //!
+pub mod audit;
+pub mod bootstrap;
 pub mod client;
 pub mod errors;
 pub mod http;
+pub mod metrics;
+pub mod remote;
 pub mod serializer;
 pub mod server;
+pub mod sessions;
+pub mod stdio;
 
-pub use client::{ClientError, JsonRpcClient, RemoteErrorInfo};
+pub use audit::{AuditLog, AuditSink, JsonLinesSink, StackRedaction};
+pub use bootstrap::{load_bootstrap_file, DefinitionDoc, ForthicModule};
+pub use client::{BatchBuilder, CallStream, ClientError, JsonRpcClient, RemoteErrorInfo};
 pub use errors::{ErrorInfo, JsonRpcErrorCode, MethodError};
 pub use http::{serve, ServeOptions, ServerHandle};
+pub use metrics::ServerMetrics;
+pub use remote::{RemoteModule, RemoteWord};
 pub use serializer::{deserialize_value, serialize_value, SerializerError};
-pub use server::{dispatch, ForthicJsonRpcServicer, JsonRpcRequest};
+pub use server::{dispatch, dispatch_with_progress, ForthicJsonRpcServicer, JsonRpcRequest};
+pub use sessions::{Session, SessionConfig, SessionGuard, SessionStore};
+pub use stdio::{serve_framed, serve_stdio};
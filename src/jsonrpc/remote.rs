@@ -0,0 +1,271 @@
+//! Modules whose words run on another runtime
+//!
+//! [`RemoteModule`] asks a remote runtime for a module's words
+//! (`getModuleInfo`) and builds a local module of proxy words with the same
+//! names. Register it and Forthic code uses the remote words like local
+//! ones:
+//!
+//! ```no_run
+//! # use forthic::interpreter::Interpreter;
+//! # use forthic::jsonrpc::{JsonRpcClient, RemoteModule};
+//! # use std::sync::Arc;
+//! let client = Arc::new(JsonRpcClient::new("127.0.0.1:8765")?);
+//! let remote = RemoteModule::new(client, "fs")?;
+//! let mut interp = Interpreter::standard("UTC");
+//! interp.register_module(remote.module().clone());
+//! interp.run("['fs'] USE-MODULES  '/tmp' LIST-DIR").unwrap();
+//! # Ok::<(), forthic::jsonrpc::ClientError>(())
+//! ```
+//!
+//! A proxy word sends the remote word as many stack items as its
+//! `stack_effect` lists inputs (`( a:number b:number -- sum:number )` takes
+//! two; a bracketed optional input is sent only when a WordOptions value is
+//! on top) and pushes back every item of the remote result stack. The
+//! stack effect is trusted: a remote word documented with the placeholder
+//! `( -- )` receives nothing. WordOptions have no wire form, so options go
+//! as their flat `[key value ...]` array and the remote runs `~>` on them
+//! before the word. A call that fails leaves its arguments on the stack.
+//!
+//! Calls block the interpreter's thread, like every word. Values the remote
+//! word reports with PROGRESS are reported locally in turn. A Forthic error
+//! in the remote runtime surfaces as [`ForthicError::Remote`], with the
+//! remote error type and location; transport failures as
+//! [`ForthicError::Module`] wrapping the [`ClientError`].
+
+use super::client::{ClientError, JsonRpcClient};
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{InterpreterContext, Module, Word, WordDoc};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// A module of proxies for the words of a module on another runtime
+pub struct RemoteModule {
+    module: Module,
+    description: String,
+}
+
+impl RemoteModule {
+    /// Fetch `module_name`'s word list from the remote runtime and build
+    /// its proxies
+    pub fn new(client: Arc<JsonRpcClient>, module_name: &str) -> Result<Self, ClientError> {
+        let info = client.get_module_info(module_name)?;
+        Self::from_module_info(client, &info)
+    }
+
+    /// Build the proxies from a `getModuleInfo` result already in hand
+    pub fn from_module_info(client: Arc<JsonRpcClient>, info: &Value) -> Result<Self, ClientError> {
+        let name = info
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ClientError::Protocol("module info missing name".to_string()))?;
+        let words = info
+            .get("words")
+            .and_then(Value::as_array)
+            .ok_or_else(|| ClientError::Protocol("module info missing words".to_string()))?;
+
+        let mut module = Module::new(name.to_string());
+        for word in words {
+            let word_name = word
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| ClientError::Protocol("module word missing name".to_string()))?;
+            let text = |key: &str| {
+                word.get(key)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            module.add_exportable_word(Arc::new(RemoteWord::new(
+                Arc::clone(&client),
+                name,
+                word_name,
+                text("stack_effect"),
+                text("description"),
+            )));
+        }
+
+        Ok(Self {
+            module,
+            description: info
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+/// Inputs a stack effect lists before `--`
+struct Inputs {
+    required: usize,
+    optional: usize,
+}
+
+impl Inputs {
+    /// `None` when the stack effect has no `--` to read inputs from
+    fn parse(stack_effect: &str) -> Option<Self> {
+        let (inputs, _) = stack_effect.trim().strip_prefix('(')?.split_once("--")?;
+        let (optional, required): (Vec<&str>, Vec<&str>) = inputs
+            .split_whitespace()
+            .partition(|input| input.starts_with('['));
+        Some(Self {
+            required: required.len(),
+            optional: optional.len(),
+        })
+    }
+}
+
+/// Proxy for one remote word (see the module docs)
+pub struct RemoteWord {
+    name: String,
+    module_name: String,
+    doc: WordDoc,
+    inputs: Option<Inputs>,
+    client: Arc<JsonRpcClient>,
+}
+
+impl RemoteWord {
+    pub fn new(
+        client: Arc<JsonRpcClient>,
+        module_name: &str,
+        name: &str,
+        stack_effect: String,
+        description: String,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            module_name: module_name.to_string(),
+            inputs: Inputs::parse(&stack_effect),
+            doc: WordDoc {
+                stack_effect: stack_effect.into(),
+                description: description.into(),
+            },
+            client,
+        }
+    }
+
+    pub fn stack_effect(&self) -> &str {
+        &self.doc.stack_effect
+    }
+
+    pub fn description(&self) -> &str {
+        &self.doc.description
+    }
+
+    /// Pop the arguments the stack effect calls for, deepest first. On a
+    /// stack underflow the values already popped go back.
+    fn pop_args(
+        &self,
+        inputs: &Inputs,
+        context: &mut dyn InterpreterContext,
+    ) -> Result<Vec<ForthicValue>, ForthicError> {
+        let options_on_top = matches!(context.stack_peek(), Some(ForthicValue::WordOptions(_)));
+        let count = inputs.required + usize::from(inputs.optional > 0 && options_on_top);
+        let mut args = Vec::with_capacity(count);
+        for _ in 0..count {
+            match context.stack_pop() {
+                Ok(value) => args.push(value),
+                Err(e) => {
+                    while let Some(value) = args.pop() {
+                        context.stack_push(value);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        args.reverse();
+        Ok(args)
+    }
+
+    /// Run the word remotely on `args`, a trailing WordOptions rebuilt
+    /// there from its flat array
+    fn call(
+        &self,
+        args: &[ForthicValue],
+        context: &mut dyn InterpreterContext,
+    ) -> Result<Vec<ForthicValue>, ClientError> {
+        let on_progress = |value| context.report_progress(value);
+        match args.split_last() {
+            Some((ForthicValue::WordOptions(options), rest)) => {
+                let mut stack = rest.to_vec();
+                stack.push(ForthicValue::Array(
+                    options
+                        .as_map()
+                        .iter()
+                        .flat_map(|(key, value)| [ForthicValue::String(key.clone()), value.clone()])
+                        .collect(),
+                ));
+                self.client
+                    .execute_sequence_with_progress(&["~>", &self.name], &stack, on_progress)
+            }
+            _ => self
+                .client
+                .execute_word_with_progress(&self.name, args, on_progress),
+        }
+    }
+
+    fn remote_error(&self, error: ClientError) -> ForthicError {
+        match error {
+            ClientError::Remote(info) => ForthicError::Remote {
+                forthic: String::new(),
+                runtime: info.runtime,
+                error_type: info.error_type,
+                message: info.message,
+                remote_location: info.word_location,
+                location: None,
+                cause: None,
+            },
+            other => ForthicError::Module {
+                forthic: String::new(),
+                module_name: self.module_name.clone(),
+                inner_message: format!("{}: {other}", self.name),
+                inner_error: Box::new(other),
+                location: None,
+                cause: None,
+            },
+        }
+    }
+}
+
+impl Word for RemoteWord {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn doc(&self) -> Option<&WordDoc> {
+        Some(&self.doc)
+    }
+
+    fn execute(&self, context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let Some(inputs) = &self.inputs else {
+            return Err(ForthicError::invalid_operation(format!(
+                "Cannot call remote word {}: unreadable stack effect '{}'",
+                self.name, self.doc.stack_effect
+            )));
+        };
+        let args = self.pop_args(inputs, context)?;
+        match self.call(&args, context) {
+            Ok(results) => {
+                for value in results {
+                    context.stack_push(value);
+                }
+                Ok(())
+            }
+            Err(error) => {
+                for value in args {
+                    context.stack_push(value);
+                }
+                Err(self.remote_error(error))
+            }
+        }
+    }
+}
@@ -124,6 +124,9 @@ fn serialize_at(value: &ForthicValue, path: &str) -> Result<Value, SerializerErr
             "plain_time_value": { "iso8601_time": t.format("%H:%M:%S%.f").to_string() }
         })),
         ForthicValue::WordOptions(_) => Err(unsupported("WordOptions", path)),
+        // Pulling a sequence runs Forthic, which needs an interpreter;
+        // REALIZE it before it leaves the runtime
+        ForthicValue::Sequence(_) => Err(unsupported("Sequence", path)),
         ForthicValue::StartArrayMarker => Err(unsupported("StartArrayMarker", path)),
     }
 }
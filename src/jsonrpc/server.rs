@@ -5,24 +5,52 @@
 //! JSON-RPC 2.0 envelopes. Same param/result keys, same error codes, same
 //! validation messages, so a forthic-ts `JsonRpcClient` works unchanged.
 //!
+//! rs extension: both execute methods, sessionless `runCode` and
+//! `createSession` take an optional `timezone` param overriding the
+//! servicer default for that request (or that session).
+//!
+//! rs extension: Forthic bootstrap modules ([`super::bootstrap`]) are served
+//! next to the runtime modules and listed with `runtime_specific: false`.
+//!
+//! rs extension: `runCode` runs a whole Forthic program, on a fresh
+//! interpreter or — with a `session_id` — on a session's interpreter kept
+//! alive between calls by `createSession` / `closeSession` (see
+//! [`super::sessions`]).
+//!
+//! rs extension: values the PROGRESS word reports during `executeWord`,
+//! `executeSequence` or `runCode` go to the progress handler passed to
+//! [`dispatch_with_progress`]; the transports stream them to the caller as
+//! `progress` notifications carrying the request id.
+//!
+//! Every method, executeWord included (its `word_name` is run as code),
+//! honors the servicer's [`WordPolicy`]: request interpreters refuse
+//! disallowed words at lookup, wherever the lookup comes from.
+//!
 //! This layer knows nothing about HTTP; Phase 3 wraps [`dispatch`] in the
 //! axum transport (envelope validation, auth, body limits happen there).
 
+use super::bootstrap::ForthicModule;
 use super::errors::{ErrorInfo, JsonRpcErrorCode, MethodError};
+use super::metrics::{ServerMetrics, OTHER_WORD_LABEL};
 use super::serializer::{deserialize_value, serialize_value};
+use super::sessions::{session_not_found, SessionConfig, SessionStore};
 use crate::errors::ForthicError;
-use crate::interpreter::Interpreter;
+use crate::interpreter::{Interpreter, OutputHandler, ProgressHandler};
 use crate::literals::ForthicValue;
-use crate::module::Module;
+use crate::module::{InterpreterContext, Module};
+use crate::word_policy::{WordGuard, WordPolicy};
+use chrono_tz::Tz;
 use serde::Deserialize;
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 /// A parsed JSON-RPC 2.0 request envelope
 ///
 /// `id` defaults to `Value::Null` and `params` to `Value::Null` when absent;
 /// the transport layer is responsible for rejecting envelopes that are
-/// structurally invalid (wrong `jsonrpc`, missing `id`, batch arrays).
+/// structurally invalid (wrong `jsonrpc`, non-string `method`), splitting
+/// batch arrays, and withholding responses to notifications (no `id`).
 #[derive(Debug, Clone, Deserialize)]
 pub struct JsonRpcRequest {
     pub jsonrpc: String,
@@ -33,52 +61,208 @@ pub struct JsonRpcRequest {
     pub params: Value,
 }
 
-/// Implements the four Forthic RPC methods
+/// Implements the Forthic RPC methods
 ///
 /// Each execute call runs on a fresh standard interpreter (full stdlib plus
 /// any registered runtime modules), so requests are isolated from each other.
+/// Sessions are the opt-in exception: `runCode` runs against the persistent
+/// interpreter of a session opened with `createSession`.
 pub struct ForthicJsonRpcServicer {
     /// Runtime-specific modules exposed over RPC (ts registers `fs` here;
     /// rs has none yet — the registry is ready for them)
     runtime_modules: Vec<Module>,
+    /// Modules written in Forthic, compiled once when added
+    forthic_modules: Vec<ForthicModule>,
     timezone: String,
+    sessions: SessionStore,
+    word_policy: WordPolicy,
+    /// `word_policy` resolved against the registered modules
+    word_guard: Option<Arc<WordGuard>>,
+    /// Where failing executeWord / executeSequence words are counted
+    metrics: Option<Arc<ServerMetrics>>,
+    /// Names of the words request interpreters serve (the standard
+    /// library and every added module), the labels word errors may use
+    served_words: HashSet<String>,
+    /// Where PRINT, PEEK! and STACK! write; stdout when unset
+    output: Option<OutputHandler>,
 }
 
 impl ForthicJsonRpcServicer {
     pub fn new() -> Self {
-        Self {
+        let mut servicer = Self {
             runtime_modules: Vec::new(),
+            forthic_modules: Vec::new(),
             timezone: "UTC".to_string(),
+            sessions: SessionStore::default(),
+            word_policy: WordPolicy::default(),
+            word_guard: None,
+            metrics: None,
+            served_words: HashSet::new(),
+            output: None,
+        };
+        servicer.refresh_modules();
+        servicer
+    }
+
+    /// Set the default timezone of request interpreters (`TODAY`, `NOW`,
+    /// date literals). Must be an IANA name, e.g. `America/Los_Angeles`.
+    pub fn set_timezone(&mut self, timezone: &str) -> Result<(), MethodError> {
+        self.timezone = validate_timezone(timezone)?;
+        Ok(())
+    }
+
+    /// The default timezone of request interpreters
+    pub fn timezone(&self) -> &str {
+        &self.timezone
+    }
+
+    /// Restrict the words requests may use. Module names resolve against
+    /// the standard library and the modules added so far (and are
+    /// re-resolved as more are added); an unknown module name is an error.
+    pub fn set_word_policy(&mut self, policy: WordPolicy) -> Result<(), ForthicError> {
+        self.word_guard = Self::resolve_policy(&policy, &self.make_interpreter(&self.timezone))?;
+        self.word_policy = policy;
+        Ok(())
+    }
+
+    /// The policy applied to request interpreters
+    pub fn word_policy(&self) -> &WordPolicy {
+        &self.word_policy
+    }
+
+    fn resolve_policy(
+        policy: &WordPolicy,
+        interp: &Interpreter,
+    ) -> Result<Option<Arc<WordGuard>>, ForthicError> {
+        if policy.is_empty() {
+            return Ok(None);
         }
+        Ok(Some(Arc::new(policy.compile(interp)?)))
+    }
+
+    /// Recompute the served word names and re-resolve the policy after a
+    /// module is added: the new module's words are library words the
+    /// policy may now refuse
+    fn refresh_modules(&mut self) {
+        let interp = self.make_interpreter(&self.timezone);
+        self.served_words = interp
+            .get_app_module()
+            .registered_modules()
+            .flat_map(|m| m.exportable_words())
+            .map(|w| w.name().to_string())
+            .collect();
+        // Policy module names resolved when it was set, and adding a module
+        // never unregisters one, so this cannot fail
+        self.word_guard = Self::resolve_policy(&self.word_policy, &interp)
+            .expect("word policy modules stay registered");
+    }
+
+    /// Count the words that fail executeWord / executeSequence calls in
+    /// `metrics` (the transports record everything else)
+    pub fn set_metrics(&mut self, metrics: Option<Arc<ServerMetrics>>) {
+        self.metrics = metrics;
+    }
+
+    /// Replace the session limits. Closes any open sessions.
+    pub fn set_session_config(&mut self, config: SessionConfig) {
+        self.sessions = SessionStore::new(config);
+    }
+
+    /// The open sessions (for idle sweeps and introspection)
+    pub fn sessions(&self) -> &SessionStore {
+        &self.sessions
     }
 
     /// Register a runtime-specific module: imported into every request
     /// interpreter and exposed via listModules / getModuleInfo
     pub fn add_runtime_module(&mut self, module: Module) {
         self.runtime_modules.push(module);
+        self.refresh_modules();
     }
 
-    /// Names of the registered runtime-specific modules
+    /// Compile a module written in Forthic (`Module::new_with_code`, or a
+    /// bootstrap file via [`load_bootstrap_file`](super::load_bootstrap_file))
+    /// and serve it like a runtime module. The code runs once, here, in the
+    /// default timezone and can use every module added before it; its
+    /// definitions are then imported into every request interpreter.
+    pub fn add_forthic_module(&mut self, module: Module) -> Result<(), ForthicError> {
+        let mut interp = self.make_interpreter(&self.timezone);
+        let compiled = ForthicModule::compile(&mut interp, &module)?;
+        self.forthic_modules.push(compiled);
+        self.refresh_modules();
+        Ok(())
+    }
+
+    /// Names of the registered runtime-specific and Forthic modules
     pub fn registered_module_names(&self) -> Vec<String> {
         self.runtime_modules
             .iter()
             .map(|m| m.get_name().to_string())
+            .chain(self.forthic_modules.iter().map(|m| m.name().to_string()))
             .collect()
     }
 
-    fn make_interpreter(&self) -> Interpreter {
-        let mut interp = Interpreter::standard(&self.timezone);
+    /// Send PRINT, PEEK! and STACK! output of every interpreter made from
+    /// here on (Forthic modules compiled later included) to `output`
+    /// rather than stdout
+    pub fn set_output_handler(&mut self, output: Option<OutputHandler>) {
+        self.output = output;
+    }
+
+    /// A standard interpreter with the registered modules and no word
+    /// policy (server-side compilation)
+    fn make_interpreter(&self, timezone: &str) -> Interpreter {
+        let mut interp = Interpreter::standard(timezone);
+        interp.set_output_handler(self.output.clone());
         for module in &self.runtime_modules {
             interp.import_module(module.clone(), "");
         }
+        for module in &self.forthic_modules {
+            interp.import_module(module.module().clone(), "");
+        }
         interp
     }
 
+    /// An interpreter for caller-supplied code: the word policy applies
+    fn request_interpreter(&self, timezone: &str) -> Interpreter {
+        let mut interp = self.make_interpreter(timezone);
+        interp.set_word_guard(self.word_guard.clone());
+        interp
+    }
+
+    /// A request interpreter reporting PROGRESS to `progress`
+    fn call_interpreter(&self, timezone: &str, progress: Option<&ProgressHandler>) -> Interpreter {
+        let mut interp = self.request_interpreter(timezone);
+        interp.set_progress_handler(progress.cloned());
+        interp
+    }
+
+    /// Count a failed word. The name is caller-supplied code, so only a
+    /// word served by that exact name gets its own label.
+    fn record_word_error(&self, word_name: &str) {
+        if let Some(metrics) = &self.metrics {
+            let served = self.served_words.contains(word_name);
+            metrics.record_word_error(if served { word_name } else { OTHER_WORD_LABEL });
+        }
+    }
+
+    /// The optional per-request `timezone` param, else the servicer default
+    fn request_timezone(&self, params: &Value, method: &str) -> Result<String, MethodError> {
+        match params.get("timezone") {
+            None | Some(Value::Null) => Ok(self.timezone.clone()),
+            Some(Value::String(name)) => validate_timezone(name),
+            Some(_) => Err(MethodError::invalid_params(format!(
+                "{method} \"timezone\" must be a string"
+            ))),
+        }
+    }
+
     /// `executeWord`: push the supplied stack, run one word, return the stack
     pub fn execute_word(
         &self,
         params: &Value,
         expose_error_details: bool,
+        progress: Option<&ProgressHandler>,
     ) -> Result<Value, MethodError> {
         let word_name = params
             .get("word_name")
@@ -90,15 +274,17 @@ impl ForthicJsonRpcServicer {
             .get("stack")
             .and_then(Value::as_array)
             .ok_or_else(|| MethodError::invalid_params("executeWord requires array \"stack\""))?;
+        let timezone = self.request_timezone(params, "executeWord")?;
 
         let context = HashMap::from([("word_name".to_string(), word_name.to_string())]);
         let stack = self.deserialize_stack(stack_json, &context)?;
 
-        let mut interp = self.make_interpreter();
+        let mut interp = self.call_interpreter(&timezone, progress);
         for item in stack {
             interp.stack_push(item);
         }
         interp.run(word_name).map_err(|e| {
+            self.record_word_error(word_name);
             self.runtime_error_from_forthic(&e, context.clone(), expose_error_details)
         })?;
 
@@ -111,6 +297,7 @@ impl ForthicJsonRpcServicer {
         &self,
         params: &Value,
         expose_error_details: bool,
+        progress: Option<&ProgressHandler>,
     ) -> Result<Value, MethodError> {
         let word_names: Vec<&str> = params
             .get("word_names")
@@ -125,16 +312,18 @@ impl ForthicJsonRpcServicer {
             .ok_or_else(|| {
                 MethodError::invalid_params("executeSequence requires array \"stack\"")
             })?;
+        let timezone = self.request_timezone(params, "executeSequence")?;
 
         let context = HashMap::from([("word_sequence".to_string(), word_names.join(", "))]);
         let stack = self.deserialize_stack(stack_json, &context)?;
 
-        let mut interp = self.make_interpreter();
+        let mut interp = self.call_interpreter(&timezone, progress);
         for item in stack {
             interp.stack_push(item);
         }
         for word_name in &word_names {
             interp.run(word_name).map_err(|e| {
+                self.record_word_error(word_name);
                 self.runtime_error_from_forthic(&e, context.clone(), expose_error_details)
             })?;
         }
@@ -142,24 +331,140 @@ impl ForthicJsonRpcServicer {
         self.serialize_result_stack(&interp, &context)
     }
 
-    /// `listModules`: summaries of the runtime-specific modules
+    /// `createSession`: open a persistent interpreter, in the optional
+    /// `timezone` for its whole life; returns its id
+    pub fn create_session(&self, params: &Value) -> Result<Value, MethodError> {
+        let timezone = self.request_timezone(params, "createSession")?;
+        let session_id = self.sessions.create(self.request_interpreter(&timezone))?;
+        Ok(json!({
+            "session_id": session_id,
+            "idle_timeout_seconds": self.sessions.config().idle_timeout.as_secs(),
+        }))
+    }
+
+    /// `closeSession`: discard a session's interpreter
+    pub fn close_session(&self, params: &Value) -> Result<Value, MethodError> {
+        let session_id = Self::session_id_param(params, "closeSession")?;
+        if !self.sessions.close(session_id) {
+            return Err(session_not_found(session_id));
+        }
+        Ok(json!({ "closed": true }))
+    }
+
+    /// `runCode`: push the optional `stack`, run a Forthic program, and
+    /// return the whole stack.
+    ///
+    /// Without a `session_id` the program runs on a fresh interpreter (in
+    /// the optional `timezone`, as for the execute methods). With
+    /// one, it runs on the session's interpreter: the stack persists between
+    /// calls, as do definitions and variables. A session keeps the timezone
+    /// it was created with, so `timezone` is then invalid params. A failing session call leaves
+    /// the stack as it was before the call, closes any module the code left
+    /// open, and drops a half-compiled definition; definitions and variable
+    /// writes made before the failure persist (TRY semantics).
+    pub fn run_code(
+        &self,
+        params: &Value,
+        expose_error_details: bool,
+        progress: Option<&ProgressHandler>,
+    ) -> Result<Value, MethodError> {
+        let session_id = match params.get("session_id") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(id)) => Some(id.as_str()),
+            Some(_) => {
+                return Err(MethodError::invalid_params(
+                    "runCode \"session_id\" must be a string",
+                ))
+            }
+        };
+        let code = params
+            .get("code")
+            .and_then(Value::as_str)
+            .ok_or_else(|| MethodError::invalid_params("runCode requires string \"code\""))?;
+        let stack_json = match params.get("stack") {
+            None | Some(Value::Null) => &[][..],
+            Some(stack) => stack
+                .as_array()
+                .map(Vec::as_slice)
+                .ok_or_else(|| MethodError::invalid_params("runCode \"stack\" must be an array"))?,
+        };
+
+        let context = HashMap::from([("code".to_string(), code.to_string())]);
+        let stack = self.deserialize_stack(stack_json, &context)?;
+
+        let Some(session_id) = session_id else {
+            let timezone = self.request_timezone(params, "runCode")?;
+            let mut interp = self.call_interpreter(&timezone, progress);
+            for item in stack {
+                interp.stack_push(item);
+            }
+            interp.run(code).map_err(|e| {
+                self.runtime_error_from_forthic(&e, context.clone(), expose_error_details)
+            })?;
+            return self.serialize_result_stack(&interp, &context);
+        };
+        if !matches!(params.get("timezone"), None | Some(Value::Null)) {
+            return Err(MethodError::invalid_params(
+                "runCode \"timezone\" cannot be used with \"session_id\"; pass it to createSession",
+            ));
+        }
+        let session = self.sessions.get(session_id)?;
+        let mut interp = session.lock();
+        let snapshot = interp.get_stack().items().to_vec();
+        let module_depth = interp.module_stack_depth();
+        for item in stack {
+            interp.stack_push(item);
+        }
+        // Only for this call: the session outlives the caller's stream
+        interp.set_progress_handler(progress.cloned());
+        let outcome = interp.run(code);
+        interp.set_progress_handler(None);
+        if let Err(e) = outcome {
+            interp.get_stack_mut().set_items(snapshot);
+            while interp.module_stack_depth() > module_depth {
+                let _ = interp.module_stack_pop();
+            }
+            // A definition the code was compiling when it failed is dropped
+            interp.set_compiling(false);
+            interp.set_memo_definition(false);
+            return Err(self.runtime_error_from_forthic(&e, context, expose_error_details));
+        }
+
+        self.serialize_result_stack(&interp, &context)
+    }
+
+    fn session_id_param<'a>(params: &'a Value, method: &str) -> Result<&'a str, MethodError> {
+        params
+            .get("session_id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                MethodError::invalid_params(format!("{method} requires string \"session_id\""))
+            })
+    }
+
+    /// `listModules`: summaries of the runtime-specific and Forthic modules
     pub fn list_modules(&self) -> Result<Value, MethodError> {
-        let modules: Vec<Value> = self
-            .runtime_modules
-            .iter()
-            .map(|m| {
-                json!({
-                    "name": m.get_name(),
-                    "description": format!("Rust-specific {} module", m.get_name()),
-                    "word_count": m.exportable_words().len(),
-                    "runtime_specific": true,
-                })
+        let runtime = self.runtime_modules.iter().map(|m| {
+            json!({
+                "name": m.get_name(),
+                "description": format!("Rust-specific {} module", m.get_name()),
+                "word_count": m.exportable_words().len(),
+                "runtime_specific": true,
             })
-            .collect();
+        });
+        let forthic = self.forthic_modules.iter().map(|m| {
+            json!({
+                "name": m.name(),
+                "description": m.description(),
+                "word_count": m.module().exportable_words().len(),
+                "runtime_specific": false,
+            })
+        });
+        let modules: Vec<Value> = runtime.chain(forthic).collect();
         Ok(json!({ "modules": modules }))
     }
 
-    /// `getModuleInfo`: word listing for one runtime-specific module
+    /// `getModuleInfo`: word listing for one runtime-specific or Forthic module
     pub fn get_module_info(&self, params: &Value) -> Result<Value, MethodError> {
         let module_name = params
             .get("module_name")
@@ -167,25 +472,39 @@ impl ForthicJsonRpcServicer {
             .ok_or_else(|| {
                 MethodError::invalid_params("getModuleInfo requires string \"module_name\"")
             })?;
-        let module = self
+        let runtime = self
             .runtime_modules
             .iter()
-            .find(|m| m.get_name() == module_name)
-            .ok_or_else(|| {
-                MethodError::new(
+            .find(|m| m.get_name() == module_name);
+        let forthic = match runtime {
+            Some(_) => None,
+            None => self
+                .forthic_modules
+                .iter()
+                .find(|m| m.name() == module_name),
+        };
+        let (module, module_description) = match (runtime, forthic) {
+            (Some(m), _) => (m, format!("Rust-specific {module_name} module")),
+            (None, Some(m)) => (m.module(), m.description().to_string()),
+            (None, None) => {
+                return Err(MethodError::new(
                     JsonRpcErrorCode::MODULE_NOT_FOUND,
                     format!("Module '{module_name}' not found"),
-                )
-            })?;
+                ))
+            }
+        };
         let words: Vec<Value> = module
             .exportable_words()
             .iter()
             .map(|w| {
-                // Real word metadata when the word carries it (item 22);
-                // placeholder shape only for doc-less direct registrations
-                let (stack_effect, description) = match w.doc() {
-                    Some(doc) => (doc.stack_effect.to_string(), doc.description.to_string()),
-                    None => (
+                // Real word metadata when the word carries it (item 22) or
+                // a Forthic definition has a doc comment; placeholder shape
+                // only for undocumented words
+                let definition_doc = forthic.and_then(|m| m.doc(w.name()));
+                let (stack_effect, description) = match (w.doc(), definition_doc) {
+                    (Some(doc), _) => (doc.stack_effect.to_string(), doc.description.to_string()),
+                    (None, Some(doc)) => (doc.stack_effect.clone(), doc.description.clone()),
+                    (None, None) => (
                         "( -- )".to_string(),
                         format!("{} word from {} module", w.name(), module_name),
                     ),
@@ -199,7 +518,7 @@ impl ForthicJsonRpcServicer {
             .collect();
         Ok(json!({
             "name": module_name,
-            "description": format!("Rust-specific {module_name} module"),
+            "description": module_description,
             "words": words,
         }))
     }
@@ -313,6 +632,14 @@ impl Default for ForthicJsonRpcServicer {
     }
 }
 
+/// Check an IANA timezone name against chrono-tz; returns its canonical
+/// spelling
+fn validate_timezone(name: &str) -> Result<String, MethodError> {
+    name.parse::<Tz>()
+        .map(|tz| tz.name().to_string())
+        .map_err(|_| MethodError::invalid_params(format!("Unknown timezone '{name}'")))
+}
+
 /// The `error_type` wire string for each ForthicError variant
 fn forthic_error_type(e: &ForthicError) -> &'static str {
     e.type_name()
@@ -324,12 +651,28 @@ pub fn dispatch(
     servicer: &ForthicJsonRpcServicer,
     request: &JsonRpcRequest,
     expose_error_details: bool,
+) -> Value {
+    dispatch_with_progress(servicer, request, expose_error_details, None)
+}
+
+/// [`dispatch`], passing the values PROGRESS reports during the call to
+/// `progress` as they happen
+pub fn dispatch_with_progress(
+    servicer: &ForthicJsonRpcServicer,
+    request: &JsonRpcRequest,
+    expose_error_details: bool,
+    progress: Option<&ProgressHandler>,
 ) -> Value {
     let outcome = match request.method.as_str() {
-        "executeWord" => servicer.execute_word(&request.params, expose_error_details),
-        "executeSequence" => servicer.execute_sequence(&request.params, expose_error_details),
+        "executeWord" => servicer.execute_word(&request.params, expose_error_details, progress),
+        "executeSequence" => {
+            servicer.execute_sequence(&request.params, expose_error_details, progress)
+        }
         "listModules" => servicer.list_modules(),
         "getModuleInfo" => servicer.get_module_info(&request.params),
+        "createSession" => servicer.create_session(&request.params),
+        "closeSession" => servicer.close_session(&request.params),
+        "runCode" => servicer.run_code(&request.params, expose_error_details, progress),
         method => Err(MethodError::new(
             JsonRpcErrorCode::METHOD_NOT_FOUND,
             format!("Method not found: {method}"),
@@ -0,0 +1,213 @@
+//! Server-side interpreter sessions
+//!
+//! The execute methods run on a fresh interpreter per request. A session
+//! instead keeps one interpreter alive between calls, so a caller can
+//! define words and set variables once and use them in later `runCode`
+//! calls. Sessions are keyed by an opaque random id, evicted after an idle
+//! timeout, and capped in number. Each session's interpreter sits behind its
+//! own mutex: concurrent calls to one session serialize, calls to different
+//! sessions run in parallel.
+
+use super::errors::{JsonRpcErrorCode, MethodError};
+use crate::interpreter::Interpreter;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::time::{Duration, Instant};
+
+/// Default idle timeout before a session is evicted (15 minutes)
+pub const DEFAULT_SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Default cap on concurrently open sessions
+pub const DEFAULT_MAX_SESSIONS: usize = 64;
+
+/// Limits applied to the session store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionConfig {
+    /// A session unused for this long is closed
+    pub idle_timeout: Duration,
+    /// createSession fails once this many sessions are open
+    pub max_sessions: usize,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: DEFAULT_SESSION_IDLE_TIMEOUT,
+            max_sessions: DEFAULT_MAX_SESSIONS,
+        }
+    }
+}
+
+/// One persistent interpreter
+pub struct Session {
+    interp: Mutex<Interpreter>,
+    last_used: Mutex<Instant>,
+}
+
+impl Session {
+    /// Lock the interpreter for one call. Blocks while another call on the
+    /// same session is running; the session counts as used until the
+    /// returned guard is dropped.
+    pub fn lock(&self) -> SessionGuard<'_> {
+        // A panic inside a word poisons the lock; the interpreter itself is
+        // still structurally sound, so keep serving the session
+        let interp = self.interp.lock().unwrap_or_else(|e| e.into_inner());
+        self.touch();
+        SessionGuard {
+            session: self,
+            interp,
+        }
+    }
+
+    /// True while a call holds the interpreter
+    fn is_busy(&self) -> bool {
+        matches!(self.interp.try_lock(), Err(TryLockError::WouldBlock))
+    }
+
+    fn touch(&self) {
+        *self.last_used.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.last_used
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .elapsed()
+    }
+}
+
+/// A session's interpreter, locked for one call. Dropping it releases the
+/// lock and restarts the session's idle clock.
+pub struct SessionGuard<'a> {
+    session: &'a Session,
+    interp: MutexGuard<'a, Interpreter>,
+}
+
+impl Deref for SessionGuard<'_> {
+    type Target = Interpreter;
+
+    fn deref(&self) -> &Interpreter {
+        &self.interp
+    }
+}
+
+impl DerefMut for SessionGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Interpreter {
+        &mut self.interp
+    }
+}
+
+impl Drop for SessionGuard<'_> {
+    fn drop(&mut self) {
+        self.session.touch();
+    }
+}
+
+/// The open sessions of one servicer
+pub struct SessionStore {
+    config: SessionConfig,
+    sessions: Mutex<HashMap<String, Arc<Session>>>,
+}
+
+impl SessionStore {
+    pub fn new(config: SessionConfig) -> Self {
+        Self {
+            config,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> SessionConfig {
+        self.config
+    }
+
+    fn map(&self) -> MutexGuard<'_, HashMap<String, Arc<Session>>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Store `interp` as a new session and return its id. Idle sessions are
+    /// evicted first, so only live sessions count against the cap.
+    pub fn create(&self, interp: Interpreter) -> Result<String, MethodError> {
+        self.evict_idle();
+        let mut sessions = self.map();
+        if sessions.len() >= self.config.max_sessions {
+            return Err(MethodError::new(
+                JsonRpcErrorCode::TOO_MANY_SESSIONS,
+                format!(
+                    "Too many open sessions (limit {}); close one with closeSession",
+                    self.config.max_sessions
+                ),
+            ));
+        }
+        let id = new_session_id()?;
+        sessions.insert(
+            id.clone(),
+            Arc::new(Session {
+                interp: Mutex::new(interp),
+                last_used: Mutex::new(Instant::now()),
+            }),
+        );
+        Ok(id)
+    }
+
+    /// Look up a live session. The returned handle stays valid even if the
+    /// session is closed or evicted while a call is using it.
+    pub fn get(&self, id: &str) -> Result<Arc<Session>, MethodError> {
+        self.evict_idle();
+        self.map()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| session_not_found(id))
+    }
+
+    /// Close a session; false when no such session is open
+    pub fn close(&self, id: &str) -> bool {
+        self.map().remove(id).is_some()
+    }
+
+    /// Drop every session idle for longer than the timeout; returns how
+    /// many were evicted. A session in the middle of a call is never idle,
+    /// however long the call runs.
+    pub fn evict_idle(&self) -> usize {
+        let timeout = self.config.idle_timeout;
+        let mut sessions = self.map();
+        let before = sessions.len();
+        sessions.retain(|_, session| session.is_busy() || session.idle_for() <= timeout);
+        before - sessions.len()
+    }
+
+    /// Number of open sessions
+    pub fn len(&self) -> usize {
+        self.map().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::new(SessionConfig::default())
+    }
+}
+
+pub(super) fn session_not_found(id: &str) -> MethodError {
+    MethodError::new(
+        JsonRpcErrorCode::SESSION_NOT_FOUND,
+        format!("Session '{id}' not found (closed or expired)"),
+    )
+}
+
+/// 128 bits from the OS random source, hex-encoded
+fn new_session_id() -> Result<String, MethodError> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| {
+        MethodError::new(
+            JsonRpcErrorCode::INTERNAL_ERROR,
+            format!("Could not generate a session id: {e}"),
+        )
+    })?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
@@ -0,0 +1,217 @@
+//! stdio transport for the JSON-RPC server
+//!
+//! Messages are framed as in the Language Server Protocol: a header block
+//! of `Name: value` lines ending in a blank line, then exactly
+//! `Content-Length` bytes of JSON.
+//!
+//! ```text
+//! Content-Length: 57\r\n
+//! \r\n
+//! {"jsonrpc":"2.0","id":1,"method":"listModules","params":{}}
+//! ```
+//!
+//! Requests are handled exactly as HTTP bodies are (batches,
+//! notifications, the body cap per message), concurrently, so responses
+//! may arrive out of order — match them by `id`. At most 32 messages run at
+//! once; past that the transport stops reading until one finishes. Values
+//! a call reports with PROGRESS arrive before its response as `progress`
+//! notifications. When a token is configured each message must carry
+//! `Authorization: Bearer <token>` among its headers. The transport ends
+//! cleanly at end of input, after the calls in flight have answered; a
+//! header block it cannot frame ends it with an `InvalidData` error, since
+//! the stream can no longer be resynchronized.
+
+use super::errors::JsonRpcErrorCode;
+use super::http::{
+    build_context, error_envelope, handle_message, module_summary, spawn_session_sweeper,
+    token_matches, Origin, ServeOptions, MAX_MESSAGES_IN_FLIGHT, OUTGOING_BUFFER,
+};
+use super::metrics::ServerMetrics;
+use serde_json::Value;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
+
+/// Longest header line accepted, in bytes
+const MAX_HEADER_LINE: u64 = 1024;
+
+const STDIO: Origin = Origin {
+    transport: "stdio",
+    peer: None,
+};
+
+/// Serve JSON-RPC on this process's stdin/stdout until stdin closes.
+/// Diagnostics, and what PRINT, PEEK! and STACK! write, go to stderr;
+/// stdout carries only framed responses.
+pub async fn serve_stdio(options: ServeOptions) -> std::io::Result<()> {
+    serve_framed(tokio::io::stdin(), tokio::io::stdout(), options).await
+}
+
+/// Serve JSON-RPC over any byte stream pair with Content-Length framing
+pub async fn serve_framed<R, W>(reader: R, writer: W, options: ServeOptions) -> std::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    // The output stream carries the framing, so program output goes to stderr
+    let ctx = Arc::new(build_context(
+        options,
+        Some(Arc::new(|line: &str| eprintln!("{line}"))),
+    )?);
+    eprintln!("{}", module_summary(&ctx.servicer));
+    let sweeper = spawn_session_sweeper(Arc::clone(&ctx));
+
+    let (responses_tx, responses) = mpsc::channel::<Value>(OUTGOING_BUFFER);
+    let writer_task = tokio::spawn(write_responses(writer, responses, Arc::clone(&ctx.metrics)));
+    let slots = Arc::new(Semaphore::new(MAX_MESSAGES_IN_FLIGHT));
+    let mut in_flight = JoinSet::new();
+    let mut reader = BufReader::new(reader);
+
+    let result = loop {
+        while in_flight.try_join_next().is_some() {}
+        // At the limit, stop reading until a call finishes
+        let slot = Arc::clone(&slots)
+            .acquire_owned()
+            .await
+            .expect("the semaphore is never closed");
+
+        let headers = match read_headers(&mut reader).await {
+            Ok(Some(headers)) => headers,
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(e),
+        };
+
+        // Oversized and unauthorized bodies are still consumed, so the
+        // next message starts where the framing says it does
+        let rejection = if headers.content_length > ctx.max_body_bytes {
+            Some("Payload too large")
+        } else if !ctx.token.as_deref().is_none_or(|token| {
+            headers
+                .bearer
+                .as_deref()
+                .is_some_and(|presented| token_matches(presented, token))
+        }) {
+            ctx.unauthorized(STDIO);
+            Some("Unauthorized")
+        } else {
+            None
+        };
+        if let Some(message) = rejection {
+            let mut body = (&mut reader).take(headers.content_length as u64);
+            if let Err(e) = tokio::io::copy(&mut body, &mut tokio::io::sink()).await {
+                break Err(e);
+            }
+            let _ = responses_tx
+                .send(error_envelope(
+                    Value::Null,
+                    JsonRpcErrorCode::INVALID_REQUEST,
+                    message,
+                ))
+                .await;
+            continue;
+        }
+
+        let mut raw = vec![0; headers.content_length];
+        if let Err(e) = reader.read_exact(&mut raw).await {
+            break Err(e);
+        }
+        let ctx = Arc::clone(&ctx);
+        let responses_tx = responses_tx.clone();
+        in_flight.spawn(async move {
+            let _slot = slot;
+            if let Some(response) = handle_message(&ctx, &raw, STDIO, Some(&responses_tx)).await {
+                let _ = responses_tx.send(response).await;
+            }
+        });
+    };
+
+    // Answer what is already running before closing the output
+    while in_flight.join_next().await.is_some() {}
+    drop(responses_tx);
+    let written = writer_task
+        .await
+        .unwrap_or_else(|e| Err(Error::other(e.to_string())));
+    sweeper.abort();
+    result.and(written)
+}
+
+struct FrameHeaders {
+    content_length: usize,
+    bearer: Option<String>,
+}
+
+/// Read one header block. `None` at a clean end of input.
+async fn read_headers<R>(reader: &mut BufReader<R>) -> std::io::Result<Option<FrameHeaders>>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut content_length = None;
+    let mut bearer = None;
+    let mut first = true;
+    loop {
+        let mut line = String::new();
+        let read = (&mut *reader)
+            .take(MAX_HEADER_LINE)
+            .read_line(&mut line)
+            .await?;
+        if read == 0 {
+            if first {
+                return Ok(None);
+            }
+            return Err(framing_error("Unexpected end of input in message headers"));
+        }
+        if !line.ends_with('\n') {
+            return Err(framing_error("Message header line too long"));
+        }
+        first = false;
+
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(framing_error(&format!("Malformed message header '{line}'")));
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("Content-Length") {
+            content_length = Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|_| framing_error(&format!("Invalid Content-Length '{value}'")))?,
+            );
+        } else if name.eq_ignore_ascii_case("Authorization") {
+            bearer = value.strip_prefix("Bearer ").map(String::from);
+        }
+    }
+
+    let content_length =
+        content_length.ok_or_else(|| framing_error("Message has no Content-Length header"))?;
+    Ok(Some(FrameHeaders {
+        content_length,
+        bearer,
+    }))
+}
+
+async fn write_responses<W>(
+    mut writer: W,
+    mut responses: mpsc::Receiver<Value>,
+    metrics: Arc<ServerMetrics>,
+) -> std::io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    while let Some(response) = responses.recv().await {
+        let body = response.to_string();
+        metrics.record_sent(body.len());
+        let frame = format!("Content-Length: {}\r\n\r\n{body}", body.len());
+        writer.write_all(frame.as_bytes()).await?;
+        writer.flush().await?;
+    }
+    Ok(())
+}
+
+fn framing_error(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
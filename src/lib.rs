@@ -9,30 +9,44 @@
 // until then the size is an accepted trade-off.
 #![allow(clippy::result_large_err)]
 
+pub mod convert;
 pub mod errors;
 pub mod interpreter;
 #[cfg(feature = "jsonrpc")]
 pub mod jsonrpc;
+pub mod linter;
 pub mod literals;
 pub mod module;
 pub mod modules;
+pub mod sequence;
+pub mod testing;
 pub mod tokenizer;
 pub mod utils;
+pub mod value_serde;
 pub mod word_options;
+pub mod word_policy;
 
 // Re-export commonly used types
 pub use errors::{CodeLocation, ForthicError};
-pub use interpreter::{Interpreter, Stack};
+pub use interpreter::{HolidayCalendar, Interpreter, OutputHandler, ProgressHandler, Stack};
 pub use literals::ForthicValue;
 pub use module::{Module, Variable, Word};
+pub use sequence::Sequence;
 pub use tokenizer::{Token, TokenType, Tokenizer};
+pub use value_serde::{from_value, to_value, ValueError};
 pub use word_options::WordOptions;
+pub use word_policy::{WordGuard, WordPolicy};
 
 // Re-export modules
 pub use modules::standard::*;
 
+/// Declare a Forthic word from a typed Rust function (see `forthic_macros`)
+#[cfg(feature = "macros")]
+pub use forthic_macros::forthic_word;
+
 /// Prelude module for convenient imports
 pub mod prelude {
+    pub use crate::convert::StackArgs;
     pub use crate::errors::{CodeLocation, ForthicError};
     pub use crate::interpreter::{Interpreter, Stack};
     pub use crate::literals::{ForthicValue, LiteralHandler};
@@ -0,0 +1,621 @@
+//! Static linter for Forthic programs
+//!
+//! A token-level pass over Forthic source that reports likely mistakes
+//! without running anything. Each finding names its rule, carries a
+//! human-readable message, and points at a `CodeLocation` in the source.
+//!
+//! Rules (ids are the keys of the config record):
+//! - `unused_definition`: `: NAME` / `@: NAME` never referenced
+//! - `unused_variable`: a name declared by VARIABLES that is never used
+//! - `shadowed_word`: a definition that shadows a standard-library word
+//! - `dynamic_code`: RUN (or its ts alias INTERPRET) applied directly to a
+//!   string built by CONCAT or INTERPOLATE — code assembled from data is an
+//!   injection risk (the same concern that removed |REC@ and keeps
+//!   INTERPOLATE holes to variables)
+//! - `deprecated_word`: a ts classic word with a canonical rs sibling
+//!   (plans/WORD-INVENTORY.md); the replacement is named in the message
+//! - `redefinition`: the same name defined twice in one module
+//! - `long_definition`: a definition body longer than
+//!   `max_definition_length` tokens
+//!
+//! Forthic passes code as strings (`'2 *' MAP`), so string literals are
+//! scanned as code too: their words count as references, and deprecated
+//! words and CONCAT-then-RUN inside them are reported at their real
+//! position in the enclosing source. Definitions, modules, and VARIABLES
+//! are only tracked at the top level.
+
+use crate::errors::{CodeLocation, ForthicError};
+use crate::literals::ForthicValue;
+use crate::modules::standard::{
+    ArrayModule, BooleanModule, CoreModule, DateTimeModule, JSONModule, MathModule, RecordModule,
+    StatsModule, StringModule,
+};
+use crate::tokenizer::{Token, TokenType, Tokenizer};
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+
+/// Nesting bound for scanning code strings inside code strings
+const MAX_STRING_DEPTH: usize = 8;
+
+/// Words that build a string from runtime data
+const CODE_BUILDERS: [&str; 2] = ["CONCAT", "INTERPOLATE"];
+
+/// ts classic words that have a canonical rs replacement
+/// (plans/WORD-INVENTORY.md, "ts classic module")
+const CLASSIC_WORDS: [(&str, &str); 16] = [
+    ("ADD", "+"),
+    ("SUBTRACT", "-"),
+    ("MULTIPLY", "*"),
+    ("DIVIDE", "/"),
+    ("POP", "DROP"),
+    ("IDENTITY", "NOP"),
+    ("INTERPRET", "RUN"),
+    ("*DEFAULT", "DEFAULT-RUN"),
+    ("SELECT", "FILTER"),
+    ("IN", "CONTAINS?"),
+    ("<REPEAT", "TIMES-RUN"),
+    ("<DEL", "DELETE"),
+    (">FIXED", "FORMAT-FIXED"),
+    ("REC-DEFAULTS", "MERGE"),
+    ("SUBTRACT-DATES", "DAYS-BETWEEN"),
+    ("|REC@", "JQ@"),
+];
+
+/// The rs word a ts classic word stands for (`INTERPRET` -> `RUN`)
+fn canonical_word(word: &str) -> &str {
+    CLASSIC_WORDS
+        .iter()
+        .find(|(classic, _)| *classic == word)
+        .map_or(word, |(_, replacement)| replacement)
+}
+
+/// A lint rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    UnusedDefinition,
+    UnusedVariable,
+    ShadowedWord,
+    DynamicCode,
+    DeprecatedWord,
+    Redefinition,
+    LongDefinition,
+}
+
+impl LintRule {
+    /// Every rule, in reporting order
+    pub const ALL: [LintRule; 7] = [
+        LintRule::UnusedDefinition,
+        LintRule::UnusedVariable,
+        LintRule::ShadowedWord,
+        LintRule::DynamicCode,
+        LintRule::DeprecatedWord,
+        LintRule::Redefinition,
+        LintRule::LongDefinition,
+    ];
+
+    /// Stable rule id (config record key and `rule` field of findings)
+    pub fn id(&self) -> &'static str {
+        match self {
+            LintRule::UnusedDefinition => "unused_definition",
+            LintRule::UnusedVariable => "unused_variable",
+            LintRule::ShadowedWord => "shadowed_word",
+            LintRule::DynamicCode => "dynamic_code",
+            LintRule::DeprecatedWord => "deprecated_word",
+            LintRule::Redefinition => "redefinition",
+            LintRule::LongDefinition => "long_definition",
+        }
+    }
+
+    /// Look up a rule by its id
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.id() == id)
+    }
+}
+
+/// Linter configuration
+///
+/// All rules are enabled by default. Build one in Rust, or from a Forthic
+/// config record with [`LintConfig::from_value`].
+#[derive(Debug, Clone)]
+pub struct LintConfig {
+    disabled: HashSet<LintRule>,
+    /// Longest allowed definition body, in tokens (comments excluded)
+    pub max_definition_length: usize,
+    /// Deprecated word -> suggested replacement
+    pub deprecated_words: IndexMap<String, String>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            disabled: HashSet::new(),
+            max_definition_length: 50,
+            deprecated_words: CLASSIC_WORDS
+                .iter()
+                .map(|(old, new)| (old.to_string(), new.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl LintConfig {
+    /// Create the default configuration (every rule enabled)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a configuration from a config record
+    ///
+    /// Rule ids map to booleans; `max_definition_length` takes an integer
+    /// and `deprecated_words` a record of word -> replacement string, `''`
+    /// for none (replacing the default list). Unknown keys are errors so a typo can't silently
+    /// leave a rule on:
+    ///
+    /// ```text
+    /// [["unused_definition" FALSE] ["max_definition_length" 20]] REC
+    /// ```
+    pub fn from_value(value: &ForthicValue) -> Result<Self, ForthicError> {
+        let fields = match value {
+            ForthicValue::Record(fields) => fields,
+            ForthicValue::Null => return Ok(Self::default()),
+            _ => return Err(config_error("config must be a record")),
+        };
+
+        let mut config = Self::default();
+        for (key, val) in fields {
+            if let Some(rule) = LintRule::from_id(key) {
+                match val {
+                    ForthicValue::Bool(enabled) => config.set_enabled(rule, *enabled),
+                    _ => return Err(config_error(&format!("'{key}' must be a boolean"))),
+                }
+                continue;
+            }
+            match key.as_str() {
+                "max_definition_length" => match val {
+                    ForthicValue::Int(n) if *n > 0 => config.max_definition_length = *n as usize,
+                    _ => {
+                        return Err(config_error(
+                            "'max_definition_length' must be a positive integer",
+                        ))
+                    }
+                },
+                "deprecated_words" => {
+                    let ForthicValue::Record(words) = val else {
+                        return Err(config_error("'deprecated_words' must be a record"));
+                    };
+                    config.deprecated_words = words
+                        .iter()
+                        .map(|(word, replacement)| match replacement {
+                            ForthicValue::String(replacement) => {
+                                Ok((word.clone(), replacement.clone()))
+                            }
+                            _ => Err(config_error("'deprecated_words' values must be strings")),
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                _ => return Err(config_error(&format!("unknown config key '{key}'"))),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Enable or disable a rule
+    pub fn set_enabled(&mut self, rule: LintRule, enabled: bool) {
+        if enabled {
+            self.disabled.remove(&rule);
+        } else {
+            self.disabled.insert(rule);
+        }
+    }
+
+    /// Builder form of [`LintConfig::set_enabled`] for disabling a rule
+    pub fn without(mut self, rule: LintRule) -> Self {
+        self.set_enabled(rule, false);
+        self
+    }
+
+    /// Whether a rule is enabled
+    pub fn is_enabled(&self, rule: LintRule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+}
+
+fn config_error(message: &str) -> ForthicError {
+    ForthicError::invalid_operation(format!("Lint config: {message}"))
+}
+
+/// A single lint finding
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub message: String,
+    pub location: CodeLocation,
+}
+
+impl LintFinding {
+    /// Machine-readable form:
+    /// `{"rule", "message", "location": {"source", "line", "column", "start_pos", "end_pos"}}`
+    pub fn to_value(&self) -> ForthicValue {
+        let loc = &self.location;
+        let mut location = IndexMap::new();
+        location.insert(
+            "source".to_string(),
+            loc.source
+                .clone()
+                .map_or(ForthicValue::Null, ForthicValue::String),
+        );
+        location.insert("line".to_string(), ForthicValue::Int(loc.line as i64));
+        location.insert("column".to_string(), ForthicValue::Int(loc.column as i64));
+        location.insert(
+            "start_pos".to_string(),
+            ForthicValue::Int(loc.start_pos as i64),
+        );
+        location.insert(
+            "end_pos".to_string(),
+            loc.end_pos
+                .map_or(ForthicValue::Null, |p| ForthicValue::Int(p as i64)),
+        );
+
+        let mut finding = IndexMap::new();
+        finding.insert(
+            "rule".to_string(),
+            ForthicValue::String(self.rule.id().to_string()),
+        );
+        finding.insert(
+            "message".to_string(),
+            ForthicValue::String(self.message.clone()),
+        );
+        finding.insert("location".to_string(), ForthicValue::Record(location));
+        ForthicValue::Record(finding)
+    }
+}
+
+/// The linter: a config plus the standard-library vocabulary
+pub struct Linter {
+    config: LintConfig,
+    standard_words: HashSet<String>,
+}
+
+/// A `: NAME` / `@: NAME` seen at the top level
+struct Definition {
+    module: String,
+    name: String,
+    is_memo: bool,
+    location: CodeLocation,
+}
+
+/// Mutable state of one lint pass
+#[derive(Default)]
+struct Scan {
+    findings: Vec<LintFinding>,
+    /// Reference counts, so VARIABLES can retract its own name strings
+    references: HashMap<String, usize>,
+    definitions: Vec<Definition>,
+    variables: Vec<(String, CodeLocation)>,
+}
+
+impl Linter {
+    /// Create a linter with the given configuration
+    pub fn new(config: LintConfig) -> Self {
+        let standard_words = [
+            ArrayModule::new().module().clone(),
+            BooleanModule::new().module().clone(),
+            CoreModule::new().module().clone(),
+            DateTimeModule::new().module().clone(),
+            JSONModule::new().module().clone(),
+            MathModule::new().module().clone(),
+            RecordModule::new().module().clone(),
+            StatsModule::new().module().clone(),
+            StringModule::new().module().clone(),
+        ]
+        .iter()
+        .flat_map(|m| m.exportable_words())
+        .map(|w| w.name().to_string())
+        .collect();
+
+        Self {
+            config,
+            standard_words,
+        }
+    }
+
+    /// The active configuration
+    pub fn config(&self) -> &LintConfig {
+        &self.config
+    }
+
+    /// Lint a program. Tokenizer errors (e.g. an unterminated string) are
+    /// returned as-is — there is nothing meaningful to lint past them.
+    pub fn lint(&self, code: &str) -> Result<Vec<LintFinding>, ForthicError> {
+        self.lint_at(code, CodeLocation::default())
+    }
+
+    /// Lint a program, tagging every finding's location with `source`
+    /// (e.g. a file path)
+    pub fn lint_source(&self, code: &str, source: &str) -> Result<Vec<LintFinding>, ForthicError> {
+        self.lint_at(
+            code,
+            CodeLocation::default().with_source(source.to_string()),
+        )
+    }
+
+    fn lint_at(
+        &self,
+        code: &str,
+        reference: CodeLocation,
+    ) -> Result<Vec<LintFinding>, ForthicError> {
+        let tokens = tokenize(code, reference)?;
+        let mut scan = Scan::default();
+        self.scan_top_level(&tokens, &mut scan);
+        self.report_unused(&mut scan);
+
+        let mut findings: Vec<LintFinding> = scan
+            .findings
+            .into_iter()
+            .filter(|f| self.config.is_enabled(f.rule))
+            .collect();
+        findings.sort_by_key(|f| f.location.start_pos);
+        Ok(findings)
+    }
+
+    fn scan_top_level(&self, tokens: &[Token], scan: &mut Scan) {
+        let mut module_path: Vec<String> = Vec::new();
+        let mut current: Option<(Definition, usize)> = None;
+        let mut defined: HashMap<(String, String), CodeLocation> = HashMap::new();
+        // String tokens directly inside the open arrays, and the strings of
+        // the array that just closed (a candidate VARIABLES argument)
+        let mut open_arrays: Vec<Vec<Token>> = Vec::new();
+        let mut closed_array: Option<Vec<Token>> = None;
+        let mut prev: Option<&Token> = None;
+
+        for token in tokens {
+            let just_closed = closed_array.take();
+            if let Some((_, body_len)) = current.as_mut() {
+                if !matches!(token.token_type, TokenType::EndDef) {
+                    *body_len += 1;
+                }
+            }
+
+            match token.token_type {
+                TokenType::StartDef | TokenType::StartMemo => {
+                    let module = module_path.join(".");
+                    let def = Definition {
+                        module: module.clone(),
+                        name: token.string.clone(),
+                        is_memo: token.token_type == TokenType::StartMemo,
+                        location: token.location.clone(),
+                    };
+                    if self.standard_words.contains(&def.name) {
+                        scan.push(
+                            LintRule::ShadowedWord,
+                            format!("'{}' shadows the standard word of the same name", def.name),
+                            &def.location,
+                        );
+                    }
+                    let key = (module, def.name.clone());
+                    if let Some(first) = defined.get(&key) {
+                        scan.push(
+                            LintRule::Redefinition,
+                            format!(
+                                "'{}' is already defined in this module at line {}",
+                                def.name, first.line
+                            ),
+                            &def.location,
+                        );
+                    } else {
+                        defined.insert(key, def.location.clone());
+                    }
+                    current = Some((def, 0));
+                }
+                TokenType::EndDef => {
+                    if let Some((def, body_len)) = current.take() {
+                        if body_len > self.config.max_definition_length {
+                            scan.push(
+                                LintRule::LongDefinition,
+                                format!(
+                                    "'{}' is {} tokens long (max {})",
+                                    def.name, body_len, self.config.max_definition_length
+                                ),
+                                &def.location,
+                            );
+                        }
+                        scan.definitions.push(def);
+                    }
+                }
+                TokenType::StartModule => {
+                    if token.string.is_empty() {
+                        module_path.clear();
+                    } else {
+                        module_path.push(token.string.clone());
+                    }
+                }
+                TokenType::EndModule => {
+                    module_path.pop();
+                }
+                TokenType::StartArray => open_arrays.push(Vec::new()),
+                TokenType::EndArray => closed_array = open_arrays.pop(),
+                TokenType::String => {
+                    if let Some(strings) = open_arrays.last_mut() {
+                        strings.push(token.clone());
+                    }
+                    self.scan_string(token, 1, scan);
+                }
+                TokenType::Word => {
+                    if token.string == "VARIABLES" {
+                        for name in just_closed.iter().flatten() {
+                            // The name strings were scanned as code; they
+                            // declare the variable, they don't use it
+                            scan.retract(&name.string);
+                            scan.variables
+                                .push((name.string.clone(), name.location.clone()));
+                        }
+                    }
+                    self.check_word(token, prev, scan);
+                }
+                // `.name` pushes a variable name for ! / @
+                TokenType::DotSymbol => scan.reference(&token.string),
+                TokenType::Comment | TokenType::Eos => {}
+            }
+            prev = Some(token);
+        }
+    }
+
+    /// Checks shared by top-level words and words inside code strings
+    fn check_word(&self, token: &Token, prev: Option<&Token>, scan: &mut Scan) {
+        scan.reference(&token.string);
+        if let Some((_, unprefixed)) = token.string.rsplit_once('.') {
+            scan.reference(unprefixed);
+        }
+
+        if let Some(replacement) = self.config.deprecated_words.get(&token.string) {
+            let message = if replacement.is_empty() {
+                format!("'{}' is deprecated", token.string)
+            } else {
+                format!("'{}' is deprecated; use {replacement}", token.string)
+            };
+            scan.push(LintRule::DeprecatedWord, message, &token.location);
+        }
+
+        if canonical_word(&token.string) == "RUN" {
+            if let Some(builder) = prev.filter(|p| {
+                p.token_type == TokenType::Word
+                    && CODE_BUILDERS.contains(&canonical_word(&p.string))
+            }) {
+                scan.push(
+                    LintRule::DynamicCode,
+                    format!(
+                        "{} executes a string built by {} — pass data on the stack instead of assembling code",
+                        token.string, builder.string
+                    ),
+                    &token.location,
+                );
+            }
+        }
+    }
+
+    /// Treat a string literal as code: its words are references, and the
+    /// per-word checks apply. Strings that don't tokenize are plain data.
+    fn scan_string(&self, token: &Token, depth: usize, scan: &mut Scan) {
+        for hole in interpolation_holes(&token.string) {
+            scan.reference(&hole);
+        }
+        if depth > MAX_STRING_DEPTH {
+            return;
+        }
+        let Ok(tokens) = tokenize(&token.string, token.location.clone()) else {
+            return;
+        };
+        let mut prev: Option<&Token> = None;
+        for inner in &tokens {
+            match inner.token_type {
+                TokenType::Word => self.check_word(inner, prev, scan),
+                TokenType::String => self.scan_string(inner, depth + 1, scan),
+                TokenType::DotSymbol => scan.reference(&inner.string),
+                TokenType::Comment => continue,
+                _ => {}
+            }
+            prev = Some(inner);
+        }
+    }
+
+    fn report_unused(&self, scan: &mut Scan) {
+        let mut unused = Vec::new();
+        for def in &scan.definitions {
+            let used = scan.is_referenced(&def.name)
+                || (def.is_memo
+                    && (scan.is_referenced(&format!("{}!", def.name))
+                        || scan.is_referenced(&format!("{}!@", def.name))));
+            if !used {
+                let scope = if def.module.is_empty() {
+                    String::new()
+                } else {
+                    format!(" in module '{}'", def.module)
+                };
+                unused.push((
+                    LintRule::UnusedDefinition,
+                    format!("'{}'{scope} is defined but never used", def.name),
+                    def.location.clone(),
+                ));
+            }
+        }
+        for (name, location) in &scan.variables {
+            if !scan.is_referenced(name) {
+                unused.push((
+                    LintRule::UnusedVariable,
+                    format!("Variable '{name}' is declared but never used"),
+                    location.clone(),
+                ));
+            }
+        }
+        for (rule, message, location) in unused {
+            scan.push(rule, message, &location);
+        }
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new(LintConfig::default())
+    }
+}
+
+impl Scan {
+    fn reference(&mut self, name: &str) {
+        *self.references.entry(name.to_string()).or_default() += 1;
+    }
+
+    fn retract(&mut self, name: &str) {
+        if let Some(count) = self.references.get_mut(name) {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    fn is_referenced(&self, name: &str) -> bool {
+        self.references.get(name).is_some_and(|count| *count > 0)
+    }
+
+    fn push(&mut self, rule: LintRule, message: String, location: &CodeLocation) {
+        self.findings.push(LintFinding {
+            rule,
+            message,
+            location: location.clone(),
+        });
+    }
+}
+
+/// Tokenize to completion, dropping comments and the final EOS
+fn tokenize(code: &str, reference: CodeLocation) -> Result<Vec<Token>, ForthicError> {
+    let mut tokenizer = Tokenizer::new(code.to_string(), Some(reference), false);
+    let mut tokens = Vec::new();
+    loop {
+        let token = tokenizer.next_token()?;
+        match token.token_type {
+            TokenType::Eos => return Ok(tokens),
+            TokenType::Comment => {}
+            _ => tokens.push(token),
+        }
+    }
+}
+
+/// Variable names read by INTERPOLATE holes (`${name}` / `${.name}`)
+fn interpolation_holes(s: &str) -> Vec<String> {
+    let mut holes = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let name = rest[..end].trim().trim_start_matches('.');
+        if !name.is_empty() {
+            holes.push(name.to_string());
+        }
+        rest = &rest[end + 1..];
+    }
+    holes
+}
+
+/// Lint a program with the default configuration
+pub fn lint(code: &str) -> Result<Vec<LintFinding>, ForthicError> {
+    Linter::default().lint(code)
+}
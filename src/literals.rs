@@ -11,6 +11,7 @@
 //! - Date: 2020-06-05, YYYY-MM-DD (with wildcards)
 //! - ZonedDateTime: ISO 8601 timestamps with timezone support
 
+use crate::sequence::Sequence;
 use crate::word_options::WordOptions;
 use chrono::{Datelike, NaiveDate, NaiveTime, TimeZone, Utc};
 use chrono_tz::Tz;
@@ -34,6 +35,9 @@ pub enum ForthicValue {
     Time(NaiveTime),
     DateTime(chrono::DateTime<Tz>),
     WordOptions(WordOptions),
+    /// A lazy stream of values (LAZY, `[.lazy TRUE] ~> RANGE`, or a host
+    /// iterator); REALIZE turns it into an Array
+    Sequence(Sequence),
     /// Marker for array construction (used internally by interpreter)
     StartArrayMarker,
 }
@@ -97,7 +101,7 @@ impl ForthicValue {
 ///
 /// Takes a string and returns a parsed ForthicValue or None if can't parse.
 /// Uses Box<dyn Fn> to support both regular functions and closures (e.g., from factory functions).
-pub type LiteralHandler = Box<dyn Fn(&str) -> Option<ForthicValue>>;
+pub type LiteralHandler = Box<dyn Fn(&str) -> Option<ForthicValue> + Send + Sync>;
 
 /// Parse boolean literals: TRUE, FALSE
 ///
@@ -183,6 +187,8 @@ pub fn to_int(s: &str) -> Option<ForthicValue> {
 /// assert!(to_time("2:30 PM").is_some());
 /// assert!(to_time("11:30 AM").is_some());
 /// ```
+// The AM and PM arms read best side by side as nested ifs.
+#[allow(clippy::collapsible_match)]
 pub fn to_time(s: &str) -> Option<ForthicValue> {
     // Regex: HH:MM or H:MM with optional AM/PM
     let re = Regex::new(r"^(\d{1,2}):(\d{2})(?:\s*(AM|PM))?$").ok()?;
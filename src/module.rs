@@ -26,6 +26,7 @@
 
 use crate::errors::{CodeLocation, ForthicError};
 use crate::literals::ForthicValue;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
@@ -116,6 +117,25 @@ pub trait InterpreterContext {
         None
     }
 
+    /// Hand a progress or partial value to the host (PROGRESS). The real
+    /// Interpreter passes it to its progress handler, if one is set;
+    /// simple contexts drop it.
+    fn report_progress(&mut self, _value: ForthicValue) {}
+
+    /// Write one line of PRINT, PEEK! or STACK! output. The real
+    /// Interpreter passes it to its output handler, if one is set; the
+    /// default is stdout.
+    fn write_output(&mut self, text: &str) {
+        println!("{text}");
+    }
+
+    /// Whether the host's holiday calendar names `date` (business-day
+    /// words). The real Interpreter asks its calendar, if one is set;
+    /// simple contexts have no holidays.
+    fn is_holiday(&self, _date: chrono::NaiveDate) -> bool {
+        false
+    }
+
     /// Import a registered module's exportable words into the app module
     /// under `prefix` ("" imports bare names; "m" imports `m.WORD`
     /// delegates). Used by USE-MODULES; errors with UnknownModule for an
@@ -216,16 +236,23 @@ impl Variable {
 /// the stack-effect comment and a one-line description, mirroring ts's
 /// @ForthicWord(stackEffect, description). Consumed by
 /// Module::word_docs / the docs generator (and eventually a REPL).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Native words borrow static text; proxies for remote words own theirs.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WordDoc {
-    pub stack_effect: &'static str,
-    pub description: &'static str,
+    pub stack_effect: Cow<'static, str>,
+    pub description: Cow<'static, str>,
 }
 
 pub trait Word: Send + Sync {
     /// Get the word name
     fn name(&self) -> &str;
 
+    /// The name the word was registered under in its own module. A
+    /// prefixed import (`m.WORD`) reports the imported word's name.
+    fn registered_name(&self) -> &str {
+        self.name()
+    }
+
     /// Documentation metadata, when the word carries any
     fn doc(&self) -> Option<&WordDoc> {
         None
@@ -525,6 +552,10 @@ impl Word for ExecuteWord {
         &self.name
     }
 
+    fn registered_name(&self) -> &str {
+        self.target_word.registered_name()
+    }
+
     fn execute(&self, context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         self.target_word.execute(context)
     }
@@ -564,8 +595,8 @@ impl ModuleWord {
     ) -> Self {
         let mut word = Self::new(name, handler);
         word.doc = Some(WordDoc {
-            stack_effect,
-            description,
+            stack_effect: Cow::Borrowed(stack_effect),
+            description: Cow::Borrowed(description),
         });
         word
     }
@@ -748,6 +779,17 @@ impl Module {
         self.exportable.push(name);
     }
 
+    /// Mark every word in the module exportable (modules built from Forthic
+    /// code, whose definitions are not exported by default)
+    pub fn export_all_words(&mut self) {
+        for word in &self.words {
+            let name = word.name().to_string();
+            if !self.exportable.contains(&name) {
+                self.exportable.push(name);
+            }
+        }
+    }
+
     /// Get all exportable words
     /// (name, doc) for every documented exportable word, registration order
     pub fn word_docs(&self) -> Vec<(&str, &WordDoc)> {
@@ -824,6 +866,11 @@ impl Module {
         self.modules.get(name)
     }
 
+    /// Every module registered in this one
+    pub fn registered_modules(&self) -> impl Iterator<Item = &Module> {
+        self.modules.values()
+    }
+
     /// Register a module with a prefix
     pub fn register_module(&mut self, module_name: String, prefix: String, module: Module) {
         self.modules.insert(module_name.clone(), module);
@@ -881,6 +928,16 @@ impl Module {
     }
 }
 
+impl std::fmt::Debug for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Module")
+            .field("name", &self.name)
+            .field("words", &self.words.len())
+            .field("exportable", &self.exportable)
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -10,17 +10,27 @@
 // - Higher-order: FOREACH, REDUCE, FIND, COUNT, TIMES-RUN
 // - Sort: SORT, SORT-BY, SORT-U, MIN-BY, MAX-BY
 // - Group: GROUP-BY, GROUP-BY-FIELD, BY-FIELD, GROUPS-OF, INDEX, KEY-OF
+// - Relational: JOIN-ON, PIVOT, UNPIVOT, DISTINCT-ON, AGGREGATE (relational.rs)
+// - Lazy: LAZY, REALIZE, TAKE-WHILE, DROP-WHILE, CHUNKED (lazy.rs)
+// - Windowing: WINDOW, PAIRWISE, SCAN, PARTITION, SPLIT-WHEN, CHUNK-BY,
+//   INTERLEAVE, TRANSPOSE, FLAT-MAP (windowing.rs)
 // - Utility: FLATTEN, RANGE, UNPACK
 //
 // Record-aware words follow the ts #33 contract: record in -> record out,
 // entries in insertion order.
 
+use crate::convert::{reject_sequence, StackArgs};
 use crate::errors::ForthicError;
 use crate::literals::ForthicValue;
 use crate::module::{register_words, InterpreterContext, Module};
+use crate::sequence::{Sequence, Stage};
 use indexmap::IndexMap;
 use std::collections::HashSet;
 
+mod lazy;
+mod relational;
+mod windowing;
+
 /// SLICE pads out-of-range indexes, so a huge span would materialize a huge
 /// array; guard it (same limit as forthic-ts)
 const MAX_MATERIALIZED_ELEMENTS: i64 = 10_000_000;
@@ -50,6 +60,9 @@ impl ArrayModule {
         Self::register_utility_words(&mut module);
         Self::register_higher_order_words(&mut module);
         Self::register_query_words(&mut module);
+        Self::register_relational_words(&mut module);
+        Self::register_lazy_words(&mut module);
+        Self::register_windowing_words(&mut module);
 
         Self { module }
     }
@@ -123,22 +136,10 @@ impl ArrayModule {
     /// and the ts `interps` option (parallel interpreters) is not
     /// supported — the rs interpreter is deliberately synchronous.
     fn word_map(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let options = Self::pop_options(context);
-        let forthic_val = context.stack_pop()?;
+        let options = context.pop_options();
+        let forthic = context.pop_code("MAP", 2)?;
         let items = context.stack_pop()?;
 
-        let forthic = match forthic_val {
-            ForthicValue::String(s) => s,
-            other => {
-                return Err(ForthicError::InvalidOperation {
-                    forthic: String::new(),
-                    message: format!("MAP requires a Forthic string, got {other:?}"),
-                    location: None,
-                    cause: None,
-                })
-            }
-        };
-
         let flags = MapFlags {
             with_key: options
                 .as_ref()
@@ -178,6 +179,10 @@ impl ArrayModule {
                 flags.depth,
                 &flags,
             )?),
+            ForthicValue::Sequence(sequence) => {
+                Self::reject_sequence_options("MAP", &options)?;
+                ForthicValue::Sequence(sequence.then(Stage::Map(forthic)))
+            }
             // Non-container: pass through unchanged (ts's behavior here is a
             // JS truthiness accident — {} for most scalars — not worth parity)
             other => other,
@@ -311,6 +316,7 @@ impl ArrayModule {
 
     fn word_first(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let container = context.stack_pop()?;
+        reject_sequence("FIRST", 1, &container)?;
 
         let result = match container {
             ForthicValue::Array(arr) => arr.first().cloned().unwrap_or(ForthicValue::Null),
@@ -337,6 +343,15 @@ impl ArrayModule {
             // reports UTF-16 units (2 for '🦀') — unifying on code points
             // is backlog item 18.
             ForthicValue::String(ref s) => s.chars().count() as i64,
+            // Pulls (and runs the stages on) every item, keeping none
+            ForthicValue::Sequence(ref sequence) => {
+                let mut count = 0;
+                Self::pull_each(context, sequence, |_, _, _| {
+                    count += 1;
+                    Ok(true)
+                })?;
+                count
+            }
             ForthicValue::Null => 0,
             _ => 0,
         };
@@ -348,6 +363,7 @@ impl ArrayModule {
     fn word_nth(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let n_val = context.stack_pop()?;
         let container = context.stack_pop()?;
+        reject_sequence("NTH", 1, &container)?;
 
         let n = match n_val {
             ForthicValue::Int(i) => i,
@@ -384,6 +400,7 @@ impl ArrayModule {
 
     fn word_last(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let container = context.stack_pop()?;
+        reject_sequence("LAST", 1, &container)?;
 
         let result = match container {
             ForthicValue::Array(arr) => {
@@ -410,6 +427,7 @@ impl ArrayModule {
         let end_val = context.stack_pop()?;
         let start_val = context.stack_pop()?;
         let container = context.stack_pop()?;
+        reject_sequence("SLICE", 1, &container)?;
 
         let start = match start_val {
             ForthicValue::Int(i) => i,
@@ -481,14 +499,9 @@ impl ArrayModule {
 
         let span = (end - start).abs() + 1;
         if span > MAX_MATERIALIZED_ELEMENTS {
-            return Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: format!(
-                    "SLICE span {span} is too large (limit {MAX_MATERIALIZED_ELEMENTS})"
-                ),
-                location: None,
-                cause: None,
-            });
+            return Err(ForthicError::invalid_operation(format!(
+                "SLICE span {span} is too large (limit {MAX_MATERIALIZED_ELEMENTS})"
+            )));
         }
 
         if start < 0 || start >= len {
@@ -514,7 +527,7 @@ impl ArrayModule {
 
     fn word_take(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         // Optional trailing WordOptions: ( container n [options] -- result )
-        let options = Self::pop_options(context);
+        let options = context.pop_options();
         let n_val = context.stack_pop()?;
         let container = context.stack_pop()?;
 
@@ -548,6 +561,10 @@ impl ArrayModule {
                     .collect();
                 (ForthicValue::Record(taken), ForthicValue::Record(rest))
             }
+            ForthicValue::Sequence(sequence) => (
+                ForthicValue::Sequence(sequence.then(Stage::Take(n))),
+                ForthicValue::Sequence(sequence.then(Stage::Skip(n))),
+            ),
             ForthicValue::Null => (ForthicValue::Array(vec![]), ForthicValue::Array(vec![])),
             other => (other, ForthicValue::Array(vec![])),
         };
@@ -591,6 +608,9 @@ impl ArrayModule {
                     .collect();
                 ForthicValue::Record(rest)
             }
+            ForthicValue::Sequence(sequence) => {
+                ForthicValue::Sequence(sequence.then(Stage::Skip(n)))
+            }
             ForthicValue::Null => ForthicValue::Array(vec![]),
             _ => container,
         };
@@ -602,6 +622,7 @@ impl ArrayModule {
     fn word_take_last(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let n_val = context.stack_pop()?;
         let container = context.stack_pop()?;
+        reject_sequence("TAKE-LAST", 1, &container)?;
 
         let n = match n_val {
             ForthicValue::Int(i) => i,
@@ -726,46 +747,37 @@ impl ArrayModule {
         context.stack_pop()
     }
 
-    /// Total order over ForthicValues for SORT/SORT-BY/MIN-BY/MAX-BY: numbers
-    /// numeric (Int and Float share the number line), strings lexicographic,
-    /// NULL sorts LAST (ts natural_cmp), cross-type by fixed rank. Ties are
-    /// Equal, and the sorts are stable, so ties keep input order.
+    /// Total order over ForthicValues for SORT/SORT-BY/MIN-BY/MAX-BY: values
+    /// the comparison words order (numbers, strings, like-with-like temporal
+    /// values, DateTimes by instant) order the same way here; NULL sorts
+    /// LAST (ts natural_cmp); anything else by fixed type rank, so Dates
+    /// precede Times precede DateTimes. NaN, which has no order among
+    /// numbers, ranks after all of them. Ties are Equal, and the sorts are
+    /// stable, so ties (the same instant in two timezones) keep input order.
     fn natural_cmp(a: &ForthicValue, b: &ForthicValue) -> std::cmp::Ordering {
         use std::cmp::Ordering;
         fn rank(v: &ForthicValue) -> u8 {
             match v {
                 ForthicValue::Bool(_) => 0,
+                ForthicValue::Float(f) if f.is_nan() => 2,
                 ForthicValue::Int(_) | ForthicValue::Float(_) => 1,
-                ForthicValue::String(_) => 2,
-                ForthicValue::Date(_) => 3,
-                ForthicValue::Time(_) => 4,
-                ForthicValue::DateTime(_) => 5,
-                ForthicValue::Array(_) => 6,
-                ForthicValue::Record(_) => 7,
-                ForthicValue::Null => 9, // null sorts last
-                _ => 8,
-            }
-        }
-        fn as_f64(v: &ForthicValue) -> Option<f64> {
-            match v {
-                ForthicValue::Int(i) => Some(*i as f64),
-                ForthicValue::Float(f) => Some(*f),
-                _ => None,
+                ForthicValue::String(_) => 3,
+                ForthicValue::Date(_) => 4,
+                ForthicValue::Time(_) => 5,
+                ForthicValue::DateTime(_) => 6,
+                ForthicValue::Array(_) => 7,
+                ForthicValue::Record(_) => 8,
+                ForthicValue::Null => 10, // null sorts last
+                _ => 9,
             }
         }
         match (a, b) {
             (ForthicValue::Null, ForthicValue::Null) => Ordering::Equal,
-            _ => match (as_f64(a), as_f64(b)) {
-                (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
-                _ => match (a, b) {
-                    (ForthicValue::String(x), ForthicValue::String(y)) => x.cmp(y),
-                    (ForthicValue::Bool(x), ForthicValue::Bool(y)) => x.cmp(y),
-                    (ForthicValue::Date(x), ForthicValue::Date(y)) => x.cmp(y),
-                    (ForthicValue::Time(x), ForthicValue::Time(y)) => x.cmp(y),
-                    (ForthicValue::DateTime(x), ForthicValue::DateTime(y)) => x.cmp(y),
-                    _ => rank(a).cmp(&rank(b)),
-                },
-            },
+            (ForthicValue::Bool(x), ForthicValue::Bool(y)) => x.cmp(y),
+            // compare_values has no order for NaN, which falls back to its
+            // own rank
+            _ => crate::modules::standard::boolean::BooleanModule::compare_values(a, b)
+                .unwrap_or_else(|| rank(a).cmp(&rank(b))),
         }
     }
 
@@ -778,12 +790,9 @@ impl ArrayModule {
             ForthicValue::Float(f) => Ok(f.to_string()),
             ForthicValue::Bool(b) => Ok(b.to_string()),
             ForthicValue::Null => Ok("null".to_string()),
-            other => Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: format!("Cannot use {other:?} as a record key"),
-                location: None,
-                cause: None,
-            }),
+            other => Err(ForthicError::invalid_operation(format!(
+                "Cannot use {other:?} as a record key"
+            ))),
         }
     }
 
@@ -808,8 +817,8 @@ impl ArrayModule {
     /// whose predicate result is truthy. Record in -> record out (keys and
     /// insertion order preserved); falsy container passes through unchanged.
     fn word_filter(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let options = Self::pop_options(context);
-        let forthic = Self::pop_code(context, "FILTER")?;
+        let options = context.pop_options();
+        let forthic = context.pop_code("FILTER", 2)?;
         let container = context.stack_pop()?;
         let with_key = options
             .as_ref()
@@ -837,6 +846,10 @@ impl ArrayModule {
                 }
                 ForthicValue::Record(kept)
             }
+            ForthicValue::Sequence(sequence) => {
+                Self::reject_sequence_options("FILTER", &options)?;
+                ForthicValue::Sequence(sequence.then(Stage::Filter(forthic.clone())))
+            }
             _ => container.clone(),
         };
         context.stack_push(result);
@@ -847,14 +860,24 @@ impl ArrayModule {
     /// whatever it leaves stays on the stack. Error tolerance is
     /// composition: items "'W' TRY" FOREACH-style via MAP outcomes.
     fn word_foreach(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let options = Self::pop_options(context);
-        let forthic = Self::pop_code(context, "FOREACH")?;
+        let options = context.pop_options();
+        let forthic = context.pop_code("FOREACH", 2)?;
         let container = context.stack_pop()?;
         let with_key = options
             .as_ref()
             .and_then(|o| o.get_bool("with_key"))
             .unwrap_or(false);
 
+        if let ForthicValue::Sequence(sequence) = &container {
+            return Self::pull_each(context, sequence, |context, i, item| {
+                if with_key {
+                    context.stack_push(ForthicValue::Int(i as i64));
+                }
+                context.stack_push(item);
+                context.run(&forthic)?;
+                Ok(true)
+            });
+        }
         for (key, item) in Self::keyed_items(&container) {
             if with_key {
                 context.stack_push(key);
@@ -869,11 +892,18 @@ impl ArrayModule {
     /// run the code per element (which must net `( acc item -- acc )`),
     /// pop once at the end. Records reduce over their values.
     fn word_reduce(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = Self::pop_code(context, "REDUCE")?;
+        let forthic = context.pop_code("REDUCE", 3)?;
         let initial = context.stack_pop()?;
         let container = context.stack_pop()?;
 
         context.stack_push(initial);
+        if let ForthicValue::Sequence(sequence) = &container {
+            Self::pull_each(context, sequence, |context, _, item| {
+                context.stack_push(item);
+                context.run(&forthic)?;
+                Ok(true)
+            })?;
+        }
         for (_, item) in Self::keyed_items(&container) {
             context.stack_push(item);
             context.run(&forthic)?;
@@ -886,9 +916,22 @@ impl ArrayModule {
     /// FIND: ( items forthic -- item|NULL ) — first element whose predicate
     /// is truthy; SHORT-CIRCUITS (remaining elements never run)
     fn word_find(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = Self::pop_code(context, "FIND")?;
+        let forthic = context.pop_code("FIND", 2)?;
         let container = context.stack_pop()?;
 
+        if let ForthicValue::Sequence(sequence) = &container {
+            let mut found = ForthicValue::Null;
+            Self::pull_each(context, sequence, |context, _, item| {
+                if Self::run_on_item(context, None, item.clone(), &forthic)?.is_truthy() {
+                    found = item;
+                    return Ok(false);
+                }
+                Ok(true)
+            })?;
+            context.stack_push(found);
+            return Ok(());
+        }
+
         for (_, item) in Self::keyed_items(&container) {
             if Self::run_on_item(context, None, item.clone(), &forthic)?.is_truthy() {
                 context.stack_push(item);
@@ -902,10 +945,18 @@ impl ArrayModule {
     /// COUNT: ( items forthic -- n ) — number of elements whose predicate is
     /// truthy (runs the code on every element)
     fn word_count(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = Self::pop_code(context, "COUNT")?;
+        let forthic = context.pop_code("COUNT", 2)?;
         let container = context.stack_pop()?;
 
         let mut count = 0i64;
+        if let ForthicValue::Sequence(sequence) = &container {
+            Self::pull_each(context, sequence, |context, _, item| {
+                if Self::run_on_item(context, None, item, &forthic)?.is_truthy() {
+                    count += 1;
+                }
+                Ok(true)
+            })?;
+        }
         for (_, item) in Self::keyed_items(&container) {
             if Self::run_on_item(context, None, item, &forthic)?.is_truthy() {
                 count += 1;
@@ -923,8 +974,9 @@ impl ArrayModule {
     /// input order BEFORE sorting. Non-arrays (including records) pass
     /// through unchanged.
     fn word_sort(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let options = Self::pop_options(context);
+        let options = context.pop_options();
         let container = context.stack_pop()?;
+        reject_sequence("SORT", 1, &container)?;
         let comparator = options.as_ref().and_then(|o| o.get_string("comparator"));
 
         let ForthicValue::Array(arr) = container else {
@@ -947,8 +999,9 @@ impl ArrayModule {
     /// SORT-BY: ( items forthic -- sorted ) — ascending by the code-produced
     /// key; stable (ties keep input order). Non-arrays pass through.
     fn word_sort_by(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = Self::pop_code(context, "SORT-BY")?;
+        let forthic = context.pop_code("SORT-BY", 2)?;
         let container = context.stack_pop()?;
+        reject_sequence("SORT-BY", 1, &container)?;
         let ForthicValue::Array(arr) = container else {
             context.stack_push(container);
             return Ok(());
@@ -989,8 +1042,9 @@ impl ArrayModule {
         word: &str,
         wanted: std::cmp::Ordering,
     ) -> Result<(), ForthicError> {
-        let forthic = Self::pop_code(context, word)?;
+        let forthic = context.pop_code(word, 2)?;
         let container = context.stack_pop()?;
+        reject_sequence(word, 1, &container)?;
         let ForthicValue::Array(arr) = container else {
             context.stack_push(ForthicValue::Null);
             return Ok(());
@@ -1016,8 +1070,9 @@ impl ArrayModule {
     /// (structural equality), keeping the FIRST occurrence, input order
     /// preserved. Non-arrays pass through.
     fn word_unique_by(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = Self::pop_code(context, "UNIQUE-BY")?;
+        let forthic = context.pop_code("UNIQUE-BY", 2)?;
         let container = context.stack_pop()?;
+        reject_sequence("UNIQUE-BY", 1, &container)?;
         let ForthicValue::Array(arr) = container else {
             context.stack_push(container);
             return Ok(());
@@ -1067,9 +1122,11 @@ impl ArrayModule {
     /// shorter pads NULL, longer truncates); record mode (c2 is a record)
     /// iterates c1's keys, missing c2 entries are NULL.
     fn word_zip_with(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = Self::pop_code(context, "ZIP-WITH")?;
+        let forthic = context.pop_code("ZIP-WITH", 3)?;
         let c2 = context.stack_pop()?;
         let c1 = context.stack_pop()?;
+        reject_sequence("ZIP-WITH", 1, &c1)?;
+        reject_sequence("ZIP-WITH", 2, &c2)?;
 
         let result = match (&c1, &c2) {
             (ForthicValue::Array(a1), ForthicValue::Array(a2)) => {
@@ -1104,9 +1161,10 @@ impl ArrayModule {
     /// UNCHANGED, silently. Persistent update: clones along the touched
     /// path only. Empty path transforms the whole container.
     fn word_map_at(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = Self::pop_code(context, "MAP-AT")?;
+        let forthic = context.pop_code("MAP-AT", 3)?;
         let key = context.stack_pop()?;
         let container = context.stack_pop()?;
+        reject_sequence("MAP-AT", 1, &container)?;
 
         if matches!(container, ForthicValue::Null) {
             context.stack_push(container);
@@ -1184,6 +1242,7 @@ impl ArrayModule {
     /// structural dedupe keeping the first occurrence in sorted order
     fn word_sort_u(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let container = context.stack_pop()?;
+        reject_sequence("SORT-U", 1, &container)?;
         let ForthicValue::Array(arr) = container else {
             context.stack_push(container);
             return Ok(());
@@ -1209,9 +1268,10 @@ impl ArrayModule {
     /// key -> array of items; group keys are the code's popped results,
     /// coerced to key strings; group order is first-encounter order
     fn word_group_by(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let options = Self::pop_options(context);
-        let forthic = Self::pop_code(context, "GROUP-BY")?;
+        let options = context.pop_options();
+        let forthic = context.pop_code("GROUP-BY", 2)?;
         let container = context.stack_pop()?;
+        reject_sequence("GROUP-BY", 1, &container)?;
         let with_key = options
             .as_ref()
             .and_then(|o| o.get_bool("with_key"))
@@ -1243,12 +1303,9 @@ impl ArrayModule {
     ) -> Result<ForthicValue, ForthicError> {
         match item {
             ForthicValue::Record(rec) => Ok(rec.get(field).cloned().unwrap_or(ForthicValue::Null)),
-            ForthicValue::Null => Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: format!("{word}: cannot read field '{field}' of NULL"),
-                location: None,
-                cause: None,
-            }),
+            ForthicValue::Null => Err(ForthicError::invalid_operation(format!(
+                "{word}: cannot read field '{field}' of NULL"
+            ))),
             _ => Ok(ForthicValue::Null),
         }
     }
@@ -1257,18 +1314,9 @@ impl ArrayModule {
     /// field's value; an ARRAY field value puts the element in EVERY named
     /// group (multi-membership). Missing fields group under "null".
     fn word_group_by_field(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let field = match context.stack_pop()? {
-            ForthicValue::String(s) => s,
-            other => {
-                return Err(ForthicError::InvalidOperation {
-                    forthic: String::new(),
-                    message: format!("GROUP-BY-FIELD requires a string field name, got {other:?}"),
-                    location: None,
-                    cause: None,
-                })
-            }
-        };
+        let field = context.pop_string("GROUP-BY-FIELD", 2)?;
         let container = context.stack_pop()?;
+        reject_sequence("GROUP-BY-FIELD", 1, &container)?;
 
         let mut groups: IndexMap<String, Vec<ForthicValue>> = IndexMap::new();
         for (_, item) in Self::keyed_items(&container) {
@@ -1297,18 +1345,9 @@ impl ArrayModule {
     /// BY-FIELD: ( container field -- indexed ) — record of field value ->
     /// element (LAST wins on duplicates); falsy elements are skipped
     fn word_by_field(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let field = match context.stack_pop()? {
-            ForthicValue::String(s) => s,
-            other => {
-                return Err(ForthicError::InvalidOperation {
-                    forthic: String::new(),
-                    message: format!("BY-FIELD requires a string field name, got {other:?}"),
-                    location: None,
-                    cause: None,
-                })
-            }
-        };
+        let field = context.pop_string("BY-FIELD", 2)?;
         let container = context.stack_pop()?;
+        reject_sequence("BY-FIELD", 1, &container)?;
 
         let mut result: IndexMap<String, ForthicValue> = IndexMap::new();
         for (_, item) in Self::keyed_items(&container) {
@@ -1334,14 +1373,12 @@ impl ArrayModule {
             _ => 0,
         };
         if n <= 0 {
-            return Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: "GROUPS-OF requires group size > 0".to_string(),
-                location: None,
-                cause: None,
-            });
+            return Err(ForthicError::invalid_operation(
+                "GROUPS-OF requires group size > 0",
+            ));
         }
         let container = context.stack_pop()?;
+        reject_sequence("GROUPS-OF", 1, &container)?;
         let n = n as usize;
 
         let result = match container {
@@ -1369,8 +1406,9 @@ impl ArrayModule {
     /// string keys per item; the item lands in every named bucket, with
     /// keys LOWERCASED. Arrays only (records yield an empty record).
     fn word_index(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = Self::pop_code(context, "INDEX")?;
+        let forthic = context.pop_code("INDEX", 2)?;
         let container = context.stack_pop()?;
+        reject_sequence("INDEX", 1, &container)?;
 
         let ForthicValue::Array(arr) = &container else {
             context.stack_push(match container {
@@ -1388,12 +1426,9 @@ impl ArrayModule {
             };
             for key_val in keys {
                 let ForthicValue::String(key) = key_val else {
-                    return Err(ForthicError::InvalidOperation {
-                        forthic: String::new(),
-                        message: format!("INDEX keys must be strings, got {key_val:?}"),
-                        location: None,
-                        cause: None,
-                    });
+                    return Err(ForthicError::invalid_operation(format!(
+                        "INDEX keys must be strings, got {key_val:?}"
+                    )));
                 };
                 buckets
                     .entry(key.to_lowercase())
@@ -1416,6 +1451,7 @@ impl ArrayModule {
     fn word_key_of(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let value = context.stack_pop()?;
         let container = context.stack_pop()?;
+        reject_sequence("KEY-OF", 1, &container)?;
 
         let result = match &container {
             ForthicValue::Array(arr) => arr
@@ -1442,6 +1478,7 @@ impl ArrayModule {
     /// Non-arrays (including records) yield an EMPTY array.
     fn word_numbered(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let container = context.stack_pop()?;
+        reject_sequence("NUMBERED", 1, &container)?;
         let result = match container {
             ForthicValue::Array(arr) => ForthicValue::Array(
                 arr.into_iter()
@@ -1455,32 +1492,6 @@ impl ArrayModule {
         Ok(())
     }
 
-    /// Pop a required Forthic-string code argument
-    fn pop_code(context: &mut dyn InterpreterContext, word: &str) -> Result<String, ForthicError> {
-        match context.stack_pop()? {
-            ForthicValue::String(s) => Ok(s),
-            other => Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: format!("{word} requires a Forthic string, got {other:?}"),
-                location: None,
-                cause: None,
-            }),
-        }
-    }
-
-    /// Pop a WordOptions value if one sits on top of the stack (Forthic's
-    /// optional-trailing-options convention: `... [.push_rest TRUE] ~> TAKE`)
-    fn pop_options(
-        context: &mut dyn InterpreterContext,
-    ) -> Option<crate::word_options::WordOptions> {
-        if matches!(context.stack_peek(), Some(ForthicValue::WordOptions(_))) {
-            if let Ok(ForthicValue::WordOptions(options)) = context.stack_pop() {
-                return Some(options);
-            }
-        }
-        None
-    }
-
     // ===== Transform Operations =====
 
     fn register_transform_words(module: &mut Module) {
@@ -1493,6 +1504,7 @@ impl ArrayModule {
 
     fn word_reverse(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let container = context.stack_pop()?;
+        reject_sequence("REVERSE", 1, &container)?;
 
         let result = match container {
             ForthicValue::Array(mut arr) => {
@@ -1522,6 +1534,7 @@ impl ArrayModule {
     fn word_append(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let item = context.stack_pop()?;
         let container = context.stack_pop()?;
+        reject_sequence("APPEND", 1, &container)?;
 
         let result = match container {
             ForthicValue::Array(mut arr) => {
@@ -1550,6 +1563,8 @@ impl ArrayModule {
     fn word_zip(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let right = context.stack_pop()?;
         let left = context.stack_pop()?;
+        reject_sequence("ZIP", 1, &left)?;
+        reject_sequence("ZIP", 2, &right)?;
 
         let result = match (left, right) {
             (ForthicValue::Array(l), ForthicValue::Array(r)) => {
@@ -1588,6 +1603,7 @@ impl ArrayModule {
 
     fn word_unique(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let container = context.stack_pop()?;
+        reject_sequence("UNIQUE", 1, &container)?;
 
         let result = match container {
             ForthicValue::Array(arr) => {
@@ -1613,6 +1629,8 @@ impl ArrayModule {
     fn word_difference(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let right = context.stack_pop()?;
         let left = context.stack_pop()?;
+        reject_sequence("DIFFERENCE", 1, &left)?;
+        reject_sequence("DIFFERENCE", 2, &right)?;
         context.stack_push(Self::set_op(left, right, false));
         Ok(())
     }
@@ -1620,6 +1638,8 @@ impl ArrayModule {
     fn word_intersection(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let right = context.stack_pop()?;
         let left = context.stack_pop()?;
+        reject_sequence("INTERSECTION", 1, &left)?;
+        reject_sequence("INTERSECTION", 2, &right)?;
         context.stack_push(Self::set_op(left, right, true));
         Ok(())
     }
@@ -1670,6 +1690,8 @@ impl ArrayModule {
     fn word_union(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let right = context.stack_pop()?;
         let left = context.stack_pop()?;
+        reject_sequence("UNION", 1, &left)?;
+        reject_sequence("UNION", 2, &right)?;
 
         let result = match (left, right) {
             (ForthicValue::Array(l), ForthicValue::Array(r)) => {
@@ -1700,8 +1722,8 @@ impl ArrayModule {
                 "( container:any [options:WordOptions] -- flat:any )",
                 "Flatten nested arrays or records (fully by default; the depth option limits descent; records flatten to tab-joined key paths)";
             "RANGE" => Self::word_range,
-                "( start:number end:number -- numbers:number[] )",
-                "Generate inclusive integer range from start to end (e.g. 1 5 RANGE -> [1,2,3,4,5]). Empty if start > end.";
+                "( start:number end:number [options:WordOptions] -- numbers:number[] )",
+                "Generate inclusive integer range from start to end (e.g. 1 5 RANGE -> [1,2,3,4,5]). Empty if start > end. Option lazy (bool) returns a sequence over the same bounds instead of an array, so it allocates nothing up front.";
             "UNPACK" => Self::word_unpack,
                 "( container:any -- elements:any )",
                 "Unpack array or record elements onto stack (insertion order for records)";
@@ -1715,8 +1737,9 @@ impl ArrayModule {
     /// option limits descent. Records flatten to tab-joined key paths
     /// ("k1\tk2"), matching ts; empty records are leaves.
     fn word_flatten(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let options = Self::pop_options(context);
+        let options = context.pop_options();
         let container = context.stack_pop()?;
+        reject_sequence("FLATTEN", 1, &container)?;
         let depth = options.as_ref().and_then(|o| o.get_int("depth"));
 
         let result = match container {
@@ -1774,6 +1797,7 @@ impl ArrayModule {
     }
 
     fn word_range(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
         let end_val = context.stack_pop()?;
         let start_val = context.stack_pop()?;
 
@@ -1789,20 +1813,21 @@ impl ArrayModule {
             _ => 0,
         };
 
+        // A lazy range allocates nothing, so it needs no size limit
+        if options.and_then(|o| o.get_bool("lazy")).unwrap_or(false) {
+            context.stack_push(ForthicValue::Sequence(Sequence::range(start, end)));
+            return Ok(());
+        }
+
         // end < start yields an empty range (ts contract — the old rs
         // behavior produced a reversed descending range, a silent divergence)
         // and needs no bound; guard pathological sizes before allocating
         // (ts #34)
         if end >= start && end - start + 1 > MAX_MATERIALIZED_ELEMENTS {
-            return Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: format!(
-                    "RANGE size {} is too large (limit {MAX_MATERIALIZED_ELEMENTS})",
-                    end - start + 1
-                ),
-                location: None,
-                cause: None,
-            });
+            return Err(ForthicError::invalid_operation(format!(
+                "RANGE size {} is too large (limit {MAX_MATERIALIZED_ELEMENTS})",
+                end - start + 1
+            )));
         }
         let range: Vec<_> = if start <= end {
             (start..=end).map(ForthicValue::Int).collect()
@@ -1816,6 +1841,7 @@ impl ArrayModule {
 
     fn word_unpack(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let container = context.stack_pop()?;
+        reject_sequence("UNPACK", 1, &container)?;
 
         match container {
             ForthicValue::Array(arr) => {
@@ -0,0 +1,200 @@
+// Lazy sequence words
+//
+// - LAZY, REALIZE: array <-> sequence
+// - TAKE-WHILE, DROP-WHILE, CHUNKED: lazy on sequences, eager on arrays
+//
+// MAP, FILTER, TAKE, SKIP and FIND add a stage to (or pull from) a
+// sequence instead of building an array; FOREACH, REDUCE, COUNT and LENGTH
+// pull one item at a time. Other words that take an array (in this module,
+// math, string, boolean, core, record and json) refuse a sequence with
+// "sequence: REALIZE first" (convert::reject_sequence) rather than treat it
+// as some other value. See crate::sequence for the model.
+
+use super::{ArrayModule, MAX_MATERIALIZED_ELEMENTS};
+use crate::convert::{argument_error, StackArgs};
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{register_words, InterpreterContext, Module};
+use crate::sequence::{Sequence, Stage};
+use crate::word_options::WordOptions;
+
+impl ArrayModule {
+    pub(super) fn register_lazy_words(module: &mut Module) {
+        register_words!(module, {
+            "LAZY" => Self::word_lazy,
+                "( items:any[] -- sequence:sequence )",
+                "Wrap an array as a lazy sequence: MAP, FILTER, TAKE, SKIP, TAKE-WHILE, DROP-WHILE and CHUNKED then add stages instead of building arrays, and nothing runs until the sequence is pulled (REALIZE, FIND, FOREACH, ...)";
+            "REALIZE" => Self::word_realize,
+                "( sequence:sequence -- items:any[] )",
+                "Pull every item of a sequence into an array (other values pass through unchanged)";
+            "TAKE-WHILE" => Self::word_take_while,
+                "( items:any forthic:string -- taken:any )",
+                "Leading items for which forthic is truthy; stops at the first falsy one (lazy on sequences)";
+            "DROP-WHILE" => Self::word_drop_while,
+                "( items:any forthic:string -- rest:any )",
+                "Items after the leading run for which forthic is truthy (lazy on sequences)";
+            "CHUNKED" => Self::word_chunked,
+                "( items:any n:int -- chunks:any )",
+                "Arrays of n consecutive items; the last may be short (lazy on sequences)";
+        });
+    }
+
+    fn word_lazy(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let sequence = match context.stack_pop()? {
+            ForthicValue::Null => Sequence::from_items(Vec::new()),
+            ForthicValue::Array(items) => Sequence::from_items(items),
+            ForthicValue::Sequence(sequence) => sequence,
+            other => return Err(argument_error("LAZY", 1, None, "array or sequence", &other)),
+        };
+        context.stack_push(ForthicValue::Sequence(sequence));
+        Ok(())
+    }
+
+    fn word_realize(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let value = context.stack_pop()?;
+        let result = match value {
+            ForthicValue::Sequence(sequence) => {
+                ForthicValue::Array(Self::realize(context, &sequence)?)
+            }
+            other => other,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_take_while(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let forthic = context.pop_code("TAKE-WHILE", 2)?;
+        let result = match context.stack_pop()? {
+            ForthicValue::Sequence(sequence) => {
+                ForthicValue::Sequence(sequence.then(Stage::TakeWhile(forthic)))
+            }
+            ForthicValue::Null => ForthicValue::Array(Vec::new()),
+            ForthicValue::Array(items) => {
+                let mut taken = Vec::new();
+                for item in items {
+                    if !Self::run_on_item(context, None, item.clone(), &forthic)?.is_truthy() {
+                        break;
+                    }
+                    taken.push(item);
+                }
+                ForthicValue::Array(taken)
+            }
+            other => {
+                return Err(argument_error(
+                    "TAKE-WHILE",
+                    1,
+                    None,
+                    "array or sequence",
+                    &other,
+                ))
+            }
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_drop_while(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let forthic = context.pop_code("DROP-WHILE", 2)?;
+        let result = match context.stack_pop()? {
+            ForthicValue::Sequence(sequence) => {
+                ForthicValue::Sequence(sequence.then(Stage::DropWhile(forthic)))
+            }
+            ForthicValue::Null => ForthicValue::Array(Vec::new()),
+            ForthicValue::Array(items) => {
+                let mut dropped = 0;
+                for item in &items {
+                    if !Self::run_on_item(context, None, item.clone(), &forthic)?.is_truthy() {
+                        break;
+                    }
+                    dropped += 1;
+                }
+                ForthicValue::Array(items.into_iter().skip(dropped).collect())
+            }
+            other => {
+                return Err(argument_error(
+                    "DROP-WHILE",
+                    1,
+                    None,
+                    "array or sequence",
+                    &other,
+                ))
+            }
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_chunked(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let size = match context.stack_pop()? {
+            ForthicValue::Int(n) if n > 0 => n as usize,
+            other => return Err(argument_error("CHUNKED", 2, None, "positive int", &other)),
+        };
+        let result = match context.stack_pop()? {
+            ForthicValue::Sequence(sequence) => {
+                ForthicValue::Sequence(sequence.then(Stage::Chunked(size)))
+            }
+            ForthicValue::Null => ForthicValue::Array(Vec::new()),
+            ForthicValue::Array(items) => ForthicValue::Array(
+                items
+                    .chunks(size)
+                    .map(|chunk| ForthicValue::Array(chunk.to_vec()))
+                    .collect(),
+            ),
+            other => {
+                return Err(argument_error(
+                    "CHUNKED",
+                    1,
+                    None,
+                    "array or sequence",
+                    &other,
+                ))
+            }
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// Pull a whole sequence, bounded like RANGE and SLICE
+    pub(super) fn realize(
+        context: &mut dyn InterpreterContext,
+        sequence: &Sequence,
+    ) -> Result<Vec<ForthicValue>, ForthicError> {
+        sequence.realize(context, MAX_MATERIALIZED_ELEMENTS as usize)
+    }
+
+    /// Pull items one at a time, passing each with its index to `f` until
+    /// the sequence ends or `f` returns false
+    pub(super) fn pull_each(
+        context: &mut dyn InterpreterContext,
+        sequence: &Sequence,
+        mut f: impl FnMut(
+            &mut dyn InterpreterContext,
+            usize,
+            ForthicValue,
+        ) -> Result<bool, ForthicError>,
+    ) -> Result<(), ForthicError> {
+        let mut cursor = sequence.cursor();
+        let mut index = 0;
+        while let Some(item) = cursor.next(context)? {
+            if !f(context, index, item)? {
+                break;
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+
+    /// MAP and FILTER options index or restructure whole containers, which
+    /// a stage never sees
+    pub(super) fn reject_sequence_options(
+        word: &str,
+        options: &Option<WordOptions>,
+    ) -> Result<(), ForthicError> {
+        match options {
+            Some(_) => Err(ForthicError::invalid_operation(format!(
+                "{word} takes no options on a sequence; REALIZE it first"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,545 @@
+// Relational words over arrays of records
+//
+// - JOIN-ON: inner / left / right / full join on key fields or key code
+// - PIVOT, UNPIVOT: long <-> wide reshaping
+// - DISTINCT-ON: first record per key
+// - AGGREGATE: per-column reducers, optionally grouped
+//
+// Records keep insertion order throughout: output rows follow input order
+// (first-seen for groups), and output fields follow the order their
+// columns were first seen. NULL in place of an input array is an empty
+// table; any element that is not a record is an error.
+
+use super::ArrayModule;
+use crate::convert::{argument_error, type_label, StackArgs};
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{register_words, InterpreterContext, Module};
+use crate::word_options::WordOptions;
+use indexmap::{IndexMap, IndexSet};
+use std::collections::HashMap;
+
+type Row = IndexMap<String, ForthicValue>;
+
+/// Which unmatched rows JOIN-ON keeps
+#[derive(Clone, Copy, PartialEq)]
+enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Full,
+}
+
+/// A per-column reducer of AGGREGATE and PIVOT
+#[derive(Clone, Copy)]
+enum Reducer {
+    Sum,
+    Count,
+    Mean,
+    Min,
+    Max,
+    First,
+    Last,
+    List,
+}
+
+impl Reducer {
+    fn parse(name: &str, word: &str) -> Result<Self, ForthicError> {
+        Ok(match name {
+            "sum" => Self::Sum,
+            "count" => Self::Count,
+            "mean" => Self::Mean,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "first" => Self::First,
+            "last" => Self::Last,
+            "list" => Self::List,
+            other => {
+                return Err(ForthicError::invalid_operation(format!(
+                    "{word}: unknown reducer '{other}' (expected sum, count, mean, min, max, first, last or list)"
+                )))
+            }
+        })
+    }
+
+    /// Reduce one column's values. NULLs are skipped by every reducer but
+    /// `list`; with no values left, `count` is 0 and the rest are NULL.
+    fn apply(self, values: &[ForthicValue], word: &str) -> Result<ForthicValue, ForthicError> {
+        if let Self::List = self {
+            return Ok(ForthicValue::Array(values.to_vec()));
+        }
+        let present: Vec<&ForthicValue> = values
+            .iter()
+            .filter(|v| !matches!(v, ForthicValue::Null))
+            .collect();
+        Ok(match self {
+            Self::Count => ForthicValue::Int(present.len() as i64),
+            Self::First => present.first().map_or(ForthicValue::Null, |v| (*v).clone()),
+            Self::Last => present.last().map_or(ForthicValue::Null, |v| (*v).clone()),
+            Self::Min => present
+                .iter()
+                .copied()
+                .reduce(|min, v| {
+                    if ArrayModule::natural_cmp(v, min).is_lt() {
+                        v
+                    } else {
+                        min
+                    }
+                })
+                .map_or(ForthicValue::Null, Clone::clone),
+            Self::Max => present
+                .iter()
+                .copied()
+                .reduce(|max, v| {
+                    if ArrayModule::natural_cmp(v, max).is_gt() {
+                        v
+                    } else {
+                        max
+                    }
+                })
+                .map_or(ForthicValue::Null, Clone::clone),
+            Self::Sum | Self::Mean if present.is_empty() => ForthicValue::Null,
+            Self::Sum => Self::sum(&present, word)?,
+            Self::Mean => {
+                let total = match Self::sum(&present, word)? {
+                    ForthicValue::Int(i) => i as f64,
+                    ForthicValue::Float(f) => f,
+                    _ => unreachable!("sum yields a number"),
+                };
+                ForthicValue::Float(total / present.len() as f64)
+            }
+            Self::List => unreachable!("handled above"),
+        })
+    }
+
+    /// Int while every value is an Int and the total fits; Float otherwise
+    fn sum(values: &[&ForthicValue], word: &str) -> Result<ForthicValue, ForthicError> {
+        let mut int_total = Some(0i64);
+        let mut float_total = 0.0;
+        for value in values {
+            match value {
+                ForthicValue::Int(i) => {
+                    int_total = int_total.and_then(|t| t.checked_add(*i));
+                    float_total += *i as f64;
+                }
+                ForthicValue::Float(f) => {
+                    int_total = None;
+                    float_total += f;
+                }
+                other => {
+                    return Err(ForthicError::invalid_operation(format!(
+                        "{word}: cannot sum {} values",
+                        type_label(other)
+                    )))
+                }
+            }
+        }
+        Ok(int_total.map_or(ForthicValue::Float(float_total), ForthicValue::Int))
+    }
+}
+
+impl ArrayModule {
+    pub(super) fn register_relational_words(module: &mut Module) {
+        register_words!(module, {
+            "JOIN-ON" => Self::word_join_on,
+                "( left:record[] right:record[] keys:any [options:WordOptions] -- joined:record[] )",
+                "Join two record arrays on key fields (a field name or array of names; NULL keys never match). Options: how (\"inner\" default, \"left\", \"right\", \"full\"), code (bool: keys are Forthic run per record), suffix (for right fields clashing with left ones; default \"_right\").";
+            "PIVOT" => Self::word_pivot,
+                "( records:record[] row_field:string column_field:string value_field:string reducer:string -- pivoted:record[] )",
+                "One record per distinct row_field value, with a field per distinct column_field value holding the reduced value_field values (reducers as for AGGREGATE)";
+            "UNPIVOT" => Self::word_unpivot,
+                "( records:record[] id_fields:any [options:WordOptions] -- long:record[] )",
+                "Melt each record into one record per non-id field: the id fields plus the field's name and value. Options: key (default \"field\"), value (default \"value\").";
+            "DISTINCT-ON" => Self::word_distinct_on,
+                "( records:record[] keys:any -- distinct:record[] )",
+                "Keep the first record for each distinct value of the key field(s)";
+            "AGGREGATE" => Self::word_aggregate,
+                "( records:record[] spec:any[] [options:WordOptions] -- result:any )",
+                "Reduce columns with a spec of reducer/column pairs, e.g. [.sum \"amount\" .count \"*\"] -> {sum_amount, count}. Reducers: sum, count, mean, min, max, first, last, list. Option by (field or fields) returns one record per group instead.";
+        });
+    }
+
+    /// JOIN-ON: ( left right keys [options] -- joined ) — rows pair up when
+    /// their keys are equal (Int and Float compare numerically). Each left
+    /// row is followed by its matches in right order; a right or full join
+    /// appends the unmatched right rows at the end. Output fields are the
+    /// left columns, then the right columns: a key field joined by name
+    /// appears once, and other clashing right fields get `suffix`. Fields a
+    /// row lacks (including the whole missing side of an outer join) are
+    /// NULL, so every output record has the same fields.
+    fn word_join_on(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "JOIN-ON";
+        let options = context.pop_options();
+        let keys = Self::key_names(context.stack_pop()?, WORD, 3)?;
+        let right = Self::pop_rows(context, WORD, 2)?;
+        let left = Self::pop_rows(context, WORD, 1)?;
+
+        let kind = match options.as_ref().and_then(|o| o.get_string("how")) {
+            None | Some("inner") => JoinKind::Inner,
+            Some("left") => JoinKind::Left,
+            Some("right") => JoinKind::Right,
+            Some("full") => JoinKind::Full,
+            Some(other) => {
+                return Err(ForthicError::invalid_operation(format!(
+                    "{WORD}: unknown join '{other}' (expected inner, left, right or full)"
+                )))
+            }
+        };
+        let by_code = options
+            .as_ref()
+            .and_then(|o| o.get_bool("code"))
+            .unwrap_or(false);
+        let suffix = options
+            .as_ref()
+            .and_then(|o| o.get_string("suffix"))
+            .unwrap_or("_right")
+            .to_string();
+
+        let mut left_keys = Vec::with_capacity(left.len());
+        for row in &left {
+            left_keys.push(Self::join_key(context, row, &keys, by_code)?);
+        }
+        let mut right_index: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, row) in right.iter().enumerate() {
+            if let Some(key) = Self::join_key(context, row, &keys, by_code)? {
+                right_index.entry(key).or_default().push(i);
+            }
+        }
+
+        let left_columns = Self::columns(&left);
+        let right_columns = Self::columns(&right);
+        // Fields joined by name are shared; everything else from the right
+        // is renamed when the left already has it
+        let shared: IndexSet<&String> = if by_code {
+            IndexSet::new()
+        } else {
+            keys.iter().filter(|k| left_columns.contains(*k)).collect()
+        };
+        let merge = |l: Option<&Row>, r: Option<&Row>| -> Row {
+            let mut out = Row::new();
+            for column in &left_columns {
+                let value = l.and_then(|l| l.get(column)).or_else(|| {
+                    shared
+                        .contains(column)
+                        .then(|| r.and_then(|r| r.get(column)))
+                        .flatten()
+                });
+                out.insert(column.clone(), value.cloned().unwrap_or(ForthicValue::Null));
+            }
+            for column in &right_columns {
+                if shared.contains(column) {
+                    continue;
+                }
+                let name = if left_columns.contains(column) {
+                    format!("{column}{suffix}")
+                } else {
+                    column.clone()
+                };
+                let value = r.and_then(|r| r.get(column)).cloned();
+                out.insert(name, value.unwrap_or(ForthicValue::Null));
+            }
+            out
+        };
+
+        let mut matched_right = vec![false; right.len()];
+        let mut joined = Vec::new();
+        for (row, key) in left.iter().zip(&left_keys) {
+            let matches = key.as_ref().and_then(|k| right_index.get(k));
+            match matches {
+                Some(matches) => {
+                    for &i in matches {
+                        matched_right[i] = true;
+                        joined.push(ForthicValue::Record(merge(Some(row), Some(&right[i]))));
+                    }
+                }
+                None if matches!(kind, JoinKind::Left | JoinKind::Full) => {
+                    joined.push(ForthicValue::Record(merge(Some(row), None)));
+                }
+                None => {}
+            }
+        }
+        if matches!(kind, JoinKind::Right | JoinKind::Full) {
+            for (row, matched) in right.iter().zip(matched_right) {
+                if !matched {
+                    joined.push(ForthicValue::Record(merge(None, Some(row))));
+                }
+            }
+        }
+        context.stack_push(ForthicValue::Array(joined));
+        Ok(())
+    }
+
+    /// A row's join key; `None` when any part is NULL (NULL never matches)
+    fn join_key(
+        context: &mut dyn InterpreterContext,
+        row: &Row,
+        keys: &[String],
+        by_code: bool,
+    ) -> Result<Option<String>, ForthicError> {
+        let mut parts = Vec::with_capacity(keys.len());
+        for key in keys {
+            let value = if by_code {
+                Self::run_on_item(context, None, ForthicValue::Record(row.clone()), key)?
+            } else {
+                row.get(key).cloned().unwrap_or(ForthicValue::Null)
+            };
+            if matches!(value, ForthicValue::Null) {
+                return Ok(None);
+            }
+            parts.push(Self::value_to_key(&value));
+        }
+        Ok(Some(parts.join("\u{1f}")))
+    }
+
+    /// PIVOT: ( records row_field column_field value_field reducer --
+    /// pivoted ) — rows and columns in first-seen order. A row with no
+    /// values for a column gets the reducer's empty result (0 for count,
+    /// NULL otherwise). Column values become field names as record keys
+    /// do (5 -> "5").
+    fn word_pivot(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "PIVOT";
+        let reducer = Reducer::parse(&context.pop_string(WORD, 5)?, WORD)?;
+        let value_field = context.pop_string(WORD, 4)?;
+        let column_field = context.pop_string(WORD, 3)?;
+        let row_field = context.pop_string(WORD, 2)?;
+        let rows = Self::pop_rows(context, WORD, 1)?;
+
+        let mut columns: IndexSet<String> = IndexSet::new();
+        let mut groups: IndexMap<String, (ForthicValue, HashMap<String, Vec<ForthicValue>>)> =
+            IndexMap::new();
+        for row in &rows {
+            let row_value = row.get(&row_field).cloned().unwrap_or(ForthicValue::Null);
+            let column =
+                Self::value_to_key_string(row.get(&column_field).unwrap_or(&ForthicValue::Null))?;
+            let value = row.get(&value_field).cloned().unwrap_or(ForthicValue::Null);
+            columns.insert(column.clone());
+            groups
+                .entry(Self::value_to_key(&row_value))
+                .or_insert_with(|| (row_value, HashMap::new()))
+                .1
+                .entry(column)
+                .or_default()
+                .push(value);
+        }
+
+        let mut pivoted = Vec::with_capacity(groups.len());
+        for (_, (row_value, cells)) in groups {
+            let mut out = Row::new();
+            out.insert(row_field.clone(), row_value);
+            for column in &columns {
+                let values = cells.get(column).map_or(&[][..], Vec::as_slice);
+                out.insert(column.clone(), reducer.apply(values, WORD)?);
+            }
+            pivoted.push(ForthicValue::Record(out));
+        }
+        context.stack_push(ForthicValue::Array(pivoted));
+        Ok(())
+    }
+
+    /// UNPIVOT: ( records id_fields [options] -- long ) — the inverse of
+    /// PIVOT (without the reduction): each non-id field of each record
+    /// becomes its own record of id fields, field name, and value
+    fn word_unpivot(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "UNPIVOT";
+        let options = context.pop_options();
+        let id_fields = Self::key_names(context.stack_pop()?, WORD, 2)?;
+        let rows = Self::pop_rows(context, WORD, 1)?;
+        let key_name = option_string(&options, "key", "field");
+        let value_name = option_string(&options, "value", "value");
+
+        let mut long = Vec::new();
+        for row in &rows {
+            for (field, value) in row {
+                if id_fields.contains(field) {
+                    continue;
+                }
+                let mut out: Row = id_fields
+                    .iter()
+                    .map(|id| {
+                        let id_value = row.get(id).cloned().unwrap_or(ForthicValue::Null);
+                        (id.clone(), id_value)
+                    })
+                    .collect();
+                out.insert(key_name.clone(), ForthicValue::String(field.clone()));
+                out.insert(value_name.clone(), value.clone());
+                long.push(ForthicValue::Record(out));
+            }
+        }
+        context.stack_push(ForthicValue::Array(long));
+        Ok(())
+    }
+
+    /// DISTINCT-ON: ( records keys -- distinct ) — first record per key;
+    /// missing fields are NULL and NULL is a key like any other
+    fn word_distinct_on(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "DISTINCT-ON";
+        let keys = Self::key_names(context.stack_pop()?, WORD, 2)?;
+        let rows = Self::pop_rows(context, WORD, 1)?;
+
+        let mut seen = std::collections::HashSet::new();
+        let mut distinct = Vec::new();
+        for row in rows {
+            let key: Vec<String> = keys
+                .iter()
+                .map(|k| Self::value_to_key(row.get(k).unwrap_or(&ForthicValue::Null)))
+                .collect();
+            if seen.insert(key) {
+                distinct.push(ForthicValue::Record(row));
+            }
+        }
+        context.stack_push(ForthicValue::Array(distinct));
+        Ok(())
+    }
+
+    /// AGGREGATE: ( records spec [options] -- result ) — `spec` pairs
+    /// reducers with columns; each pair yields the field
+    /// `<reducer>_<column>`, or just `count` for `.count "*"` (the number
+    /// of rows). With the `by` option the records are grouped first
+    /// (first-seen order) and the result is an array of records holding the
+    /// group fields then the aggregates.
+    fn word_aggregate(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "AGGREGATE";
+        let options = context.pop_options();
+        let spec = context.pop_array(WORD, 2)?;
+        let rows = Self::pop_rows(context, WORD, 1)?;
+
+        if spec.len() % 2 != 0 {
+            return Err(ForthicError::invalid_operation(format!(
+                "{WORD}: spec must pair each reducer with a column, got {} items",
+                spec.len()
+            )));
+        }
+        let mut aggregates = Vec::with_capacity(spec.len() / 2);
+        for pair in spec.chunks(2) {
+            let (ForthicValue::String(reducer), ForthicValue::String(column)) =
+                (&pair[0], &pair[1])
+            else {
+                return Err(ForthicError::invalid_operation(format!(
+                    "{WORD}: spec entries must be reducer/column strings, got {} and {}",
+                    type_label(&pair[0]),
+                    type_label(&pair[1])
+                )));
+            };
+            let parsed = Reducer::parse(reducer, WORD)?;
+            if column == "*" && !matches!(parsed, Reducer::Count) {
+                return Err(ForthicError::invalid_operation(format!(
+                    "{WORD}: only count applies to \"*\", not {reducer}"
+                )));
+            }
+            let name = if column == "*" {
+                reducer.clone()
+            } else {
+                format!("{reducer}_{column}")
+            };
+            aggregates.push((name, parsed, column.clone()));
+        }
+
+        let reduce = |group: &[&Row], out: &mut Row| -> Result<(), ForthicError> {
+            for (name, reducer, column) in &aggregates {
+                let value = if column == "*" {
+                    ForthicValue::Int(group.len() as i64)
+                } else {
+                    let values: Vec<ForthicValue> = group
+                        .iter()
+                        .map(|row| row.get(column).cloned().unwrap_or(ForthicValue::Null))
+                        .collect();
+                    reducer.apply(&values, WORD)?
+                };
+                out.insert(name.clone(), value);
+            }
+            Ok(())
+        };
+
+        let Some(by) = options.as_ref().and_then(|o| o.get("by")).cloned() else {
+            let mut out = Row::new();
+            reduce(&rows.iter().collect::<Vec<_>>(), &mut out)?;
+            context.stack_push(ForthicValue::Record(out));
+            return Ok(());
+        };
+        let by = Self::key_names(by, WORD, 3)?;
+        let mut groups: IndexMap<Vec<String>, Vec<&Row>> = IndexMap::new();
+        for row in &rows {
+            let key = by
+                .iter()
+                .map(|k| Self::value_to_key(row.get(k).unwrap_or(&ForthicValue::Null)))
+                .collect();
+            groups.entry(key).or_default().push(row);
+        }
+        let mut result = Vec::with_capacity(groups.len());
+        for group in groups.values() {
+            let mut out: Row = by
+                .iter()
+                .map(|k| {
+                    let value = group[0].get(k).cloned().unwrap_or(ForthicValue::Null);
+                    (k.clone(), value)
+                })
+                .collect();
+            reduce(group, &mut out)?;
+            result.push(ForthicValue::Record(out));
+        }
+        context.stack_push(ForthicValue::Array(result));
+        Ok(())
+    }
+
+    /// Pop an array of records (NULL is an empty one)
+    fn pop_rows(
+        context: &mut dyn InterpreterContext,
+        word: &str,
+        position: usize,
+    ) -> Result<Vec<Row>, ForthicError> {
+        let items = match context.stack_pop()? {
+            ForthicValue::Null => return Ok(Vec::new()),
+            ForthicValue::Array(items) => items,
+            other => return Err(argument_error(word, position, None, "record[]", &other)),
+        };
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| match item {
+                ForthicValue::Record(row) => Ok(row),
+                other => Err(ForthicError::invalid_operation(format!(
+                    "{word}: element {i} of argument {position} must be a record, got {}",
+                    type_label(&other)
+                ))),
+            })
+            .collect()
+    }
+
+    /// Key fields given as one name or an array of names
+    fn key_names(
+        value: ForthicValue,
+        word: &str,
+        position: usize,
+    ) -> Result<Vec<String>, ForthicError> {
+        match value {
+            ForthicValue::String(name) => Ok(vec![name]),
+            ForthicValue::Array(names) if !names.is_empty() => names
+                .into_iter()
+                .map(|name| match name {
+                    ForthicValue::String(name) => Ok(name),
+                    other => Err(argument_error(word, position, None, "string[]", &other)),
+                })
+                .collect(),
+            other => Err(argument_error(
+                word,
+                position,
+                None,
+                "string or non-empty string[]",
+                &other,
+            )),
+        }
+    }
+
+    /// Field names across all rows, in first-seen order
+    fn columns(rows: &[Row]) -> IndexSet<String> {
+        rows.iter().flat_map(|row| row.keys().cloned()).collect()
+    }
+}
+
+fn option_string(options: &Option<WordOptions>, key: &str, default: &str) -> String {
+    options
+        .as_ref()
+        .and_then(|o| o.get_string(key))
+        .unwrap_or(default)
+        .to_string()
+}
@@ -0,0 +1,354 @@
+// Windowing, scanning and partitioning words
+//
+// - WINDOW, PAIRWISE: sliding windows
+// - SCAN: running REDUCE keeping every intermediate
+// - PARTITION: split by predicate into two containers
+// - SPLIT-WHEN, CHUNK-BY: break runs of adjacent items
+// - INTERLEAVE, TRANSPOSE: reshape arrays of arrays
+// - FLAT-MAP: MAP, then splice array results
+//
+// Words taking code accept the .with_key option as MAP and FILTER do: the
+// index (array) or key (record) is pushed before each item. Records are
+// walked in insertion order; where a word returns pieces of its input
+// (WINDOW, PARTITION, SPLIT-WHEN, CHUNK-BY), a record yields records.
+// NULL in place of the input is an empty array.
+
+use super::ArrayModule;
+use crate::convert::{argument_error, type_label, StackArgs};
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{register_words, InterpreterContext, Module};
+use crate::word_options::WordOptions;
+
+/// (index or key, item) pairs of an array or record input
+struct Entries {
+    entries: Vec<(ForthicValue, ForthicValue)>,
+    is_record: bool,
+}
+
+impl Entries {
+    /// Rebuild a piece of the input: an array of the items, or a record
+    /// of the entries
+    fn collect(&self, piece: &[(ForthicValue, ForthicValue)]) -> ForthicValue {
+        if self.is_record {
+            ForthicValue::Record(
+                piece
+                    .iter()
+                    .map(|(key, item)| match key {
+                        ForthicValue::String(key) => (key.clone(), item.clone()),
+                        _ => unreachable!("record keys are strings"),
+                    })
+                    .collect(),
+            )
+        } else {
+            ForthicValue::Array(piece.iter().map(|(_, item)| item.clone()).collect())
+        }
+    }
+}
+
+impl ArrayModule {
+    pub(super) fn register_windowing_words(module: &mut Module) {
+        register_words!(module, {
+            "WINDOW" => Self::word_window,
+                "( items:any size:int [options:WordOptions] -- windows:any[] )",
+                "Sliding windows of size items. Options: step (int, default 1: how far each window starts after the last), partial (bool: also keep the shorter windows at the end).";
+            "PAIRWISE" => Self::word_pairwise,
+                "( items:any[] -- pairs:any[] )",
+                "Each item paired with the next: [1 2 3] -> [[1 2] [2 3]]";
+            "SCAN" => Self::word_scan,
+                "( items:any initial:any forthic:string [options:WordOptions] -- running:any )",
+                "Like REDUCE, but keeps the accumulator after every item (record in -> record of running values). Options: with_key (bool).";
+            "PARTITION" => Self::word_partition,
+                "( items:any forthic:string [options:WordOptions] -- matching:any rest:any )",
+                "Split items by predicate into those it finds truthy and the rest, both in input order. Options: with_key (bool).";
+            "SPLIT-WHEN" => Self::word_split_when,
+                "( items:any forthic:string -- runs:any[] )",
+                "Break items into runs, starting a new run between two adjacent items when forthic ( prev next -- bool ) is truthy";
+            "CHUNK-BY" => Self::word_chunk_by,
+                "( items:any forthic:string [options:WordOptions] -- runs:any[] )",
+                "Break items into runs of adjacent items whose key (computed by forthic) is the same. Options: with_key (bool).";
+            "INTERLEAVE" => Self::word_interleave,
+                "( arrays:any[] -- interleaved:any[] )",
+                "Take one item from each array in turn, continuing with the longer arrays once shorter ones run out";
+            "TRANSPOSE" => Self::word_transpose,
+                "( rows:any[] -- columns:any[] )",
+                "Swap rows and columns of an array of arrays; short rows are padded with NULL";
+            "FLAT-MAP" => Self::word_flat_map,
+                "( items:any forthic:string [options:WordOptions] -- flat:any[] )",
+                "Map forthic over items and splice array results into one array (NULL results add nothing; other values add one item). Options: with_key (bool).";
+        });
+    }
+
+    /// WINDOW: ( items size [options] -- windows ) — windows start every
+    /// `step` items; without `partial`, only full windows are kept, so
+    /// fewer than `size` items yield none.
+    fn word_window(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "WINDOW";
+        let options = context.pop_options();
+        let size = Self::pop_positive(context, WORD, 2)?;
+        let step = match options.as_ref().and_then(|o| o.get("step")) {
+            None => 1,
+            Some(ForthicValue::Int(n)) if *n > 0 => *n as usize,
+            Some(other) => {
+                return Err(argument_error(WORD, 3, Some("step"), "positive int", other))
+            }
+        };
+        let partial = options
+            .as_ref()
+            .and_then(|o| o.get_bool("partial"))
+            .unwrap_or(false);
+        let entries = Self::pop_entries(context, WORD, 1)?;
+
+        let len = entries.entries.len();
+        let mut windows = Vec::new();
+        let mut start = 0;
+        while start < len && (partial || start + size <= len) {
+            let end = (start + size).min(len);
+            windows.push(entries.collect(&entries.entries[start..end]));
+            start += step;
+        }
+        context.stack_push(ForthicValue::Array(windows));
+        Ok(())
+    }
+
+    fn word_pairwise(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let items = Self::pop_array_or_null(context, "PAIRWISE", 1)?;
+        let pairs = items
+            .windows(2)
+            .map(|pair| ForthicValue::Array(pair.to_vec()))
+            .collect();
+        context.stack_push(ForthicValue::Array(pairs));
+        Ok(())
+    }
+
+    /// SCAN: ( items initial forthic [options] -- running ) — the code nets
+    /// `( acc item -- acc )` (or `( acc key item -- acc )` with with_key),
+    /// as for REDUCE; the result holds the accumulator after each item, so
+    /// its LAST is what REDUCE would return.
+    fn word_scan(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "SCAN";
+        let options = context.pop_options();
+        let forthic = context.pop_code(WORD, 3)?;
+        let mut acc = context.stack_pop()?;
+        let entries = Self::pop_entries(context, WORD, 1)?;
+        let with_key = Self::with_key(&options);
+
+        let mut running = Vec::with_capacity(entries.entries.len());
+        for (key, item) in &entries.entries {
+            context.stack_push(acc);
+            acc = Self::run_on_item(
+                context,
+                with_key.then(|| key.clone()),
+                item.clone(),
+                &forthic,
+            )?;
+            running.push((key.clone(), acc.clone()));
+        }
+        context.stack_push(entries.collect(&running));
+        Ok(())
+    }
+
+    fn word_partition(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "PARTITION";
+        let options = context.pop_options();
+        let forthic = context.pop_code(WORD, 2)?;
+        let entries = Self::pop_entries(context, WORD, 1)?;
+        let with_key = Self::with_key(&options);
+
+        let mut matching = Vec::new();
+        let mut rest = Vec::new();
+        for (key, item) in &entries.entries {
+            let keep = Self::run_on_item(
+                context,
+                with_key.then(|| key.clone()),
+                item.clone(),
+                &forthic,
+            )?;
+            if keep.is_truthy() {
+                matching.push((key.clone(), item.clone()));
+            } else {
+                rest.push((key.clone(), item.clone()));
+            }
+        }
+        context.stack_push(entries.collect(&matching));
+        context.stack_push(entries.collect(&rest));
+        Ok(())
+    }
+
+    fn word_split_when(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "SPLIT-WHEN";
+        let forthic = context.pop_code(WORD, 2)?;
+        let entries = Self::pop_entries(context, WORD, 1)?;
+
+        let mut runs = Vec::new();
+        let mut start = 0;
+        for i in 1..entries.entries.len() {
+            context.stack_push(entries.entries[i - 1].1.clone());
+            let split = Self::run_on_item(context, None, entries.entries[i].1.clone(), &forthic)?;
+            if split.is_truthy() {
+                runs.push(entries.collect(&entries.entries[start..i]));
+                start = i;
+            }
+        }
+        if start < entries.entries.len() {
+            runs.push(entries.collect(&entries.entries[start..]));
+        }
+        context.stack_push(ForthicValue::Array(runs));
+        Ok(())
+    }
+
+    /// CHUNK-BY: ( items forthic [options] -- runs ) — keys compare as
+    /// GROUP-BY's do (1 and 1.0 are the same key). Unlike GROUP-BY, a key
+    /// seen again later starts a new run.
+    fn word_chunk_by(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "CHUNK-BY";
+        let options = context.pop_options();
+        let forthic = context.pop_code(WORD, 2)?;
+        let entries = Self::pop_entries(context, WORD, 1)?;
+        let with_key = Self::with_key(&options);
+
+        let mut runs = Vec::new();
+        let mut start = 0;
+        let mut run_key = None;
+        for (i, (key, item)) in entries.entries.iter().enumerate() {
+            let item_key = Self::value_to_key(&Self::run_on_item(
+                context,
+                with_key.then(|| key.clone()),
+                item.clone(),
+                &forthic,
+            )?);
+            if run_key.as_ref().is_some_and(|k| *k != item_key) {
+                runs.push(entries.collect(&entries.entries[start..i]));
+                start = i;
+            }
+            run_key = Some(item_key);
+        }
+        if start < entries.entries.len() {
+            runs.push(entries.collect(&entries.entries[start..]));
+        }
+        context.stack_push(ForthicValue::Array(runs));
+        Ok(())
+    }
+
+    fn word_interleave(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let arrays = Self::pop_arrays(context, "INTERLEAVE")?;
+        let longest = arrays.iter().map(Vec::len).max().unwrap_or(0);
+        let mut interleaved = Vec::with_capacity(arrays.iter().map(Vec::len).sum());
+        for i in 0..longest {
+            interleaved.extend(arrays.iter().filter_map(|array| array.get(i).cloned()));
+        }
+        context.stack_push(ForthicValue::Array(interleaved));
+        Ok(())
+    }
+
+    fn word_transpose(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let rows = Self::pop_arrays(context, "TRANSPOSE")?;
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let columns = (0..width)
+            .map(|i| {
+                ForthicValue::Array(
+                    rows.iter()
+                        .map(|row| row.get(i).cloned().unwrap_or(ForthicValue::Null))
+                        .collect(),
+                )
+            })
+            .collect();
+        context.stack_push(ForthicValue::Array(columns));
+        Ok(())
+    }
+
+    fn word_flat_map(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "FLAT-MAP";
+        let options = context.pop_options();
+        let forthic = context.pop_code(WORD, 2)?;
+        let entries = Self::pop_entries(context, WORD, 1)?;
+        let with_key = Self::with_key(&options);
+
+        let mut flat = Vec::new();
+        for (key, item) in entries.entries {
+            match Self::run_on_item(context, with_key.then_some(key), item, &forthic)? {
+                ForthicValue::Array(items) => flat.extend(items),
+                ForthicValue::Null => {}
+                other => flat.push(other),
+            }
+        }
+        context.stack_push(ForthicValue::Array(flat));
+        Ok(())
+    }
+
+    // ===== Helpers =====
+
+    fn with_key(options: &Option<WordOptions>) -> bool {
+        options
+            .as_ref()
+            .and_then(|o| o.get_bool("with_key"))
+            .unwrap_or(false)
+    }
+
+    fn pop_positive(
+        context: &mut dyn InterpreterContext,
+        word: &str,
+        position: usize,
+    ) -> Result<usize, ForthicError> {
+        match context.stack_pop()? {
+            ForthicValue::Int(n) if n > 0 => Ok(n as usize),
+            other => Err(argument_error(word, position, None, "positive int", &other)),
+        }
+    }
+
+    fn pop_entries(
+        context: &mut dyn InterpreterContext,
+        word: &str,
+        position: usize,
+    ) -> Result<Entries, ForthicError> {
+        let container = context.stack_pop()?;
+        let is_record = match &container {
+            ForthicValue::Record(_) => true,
+            ForthicValue::Array(_) | ForthicValue::Null => false,
+            other => {
+                return Err(argument_error(
+                    word,
+                    position,
+                    None,
+                    "array or record",
+                    other,
+                ))
+            }
+        };
+        Ok(Entries {
+            entries: Self::keyed_items(&container),
+            is_record,
+        })
+    }
+
+    fn pop_array_or_null(
+        context: &mut dyn InterpreterContext,
+        word: &str,
+        position: usize,
+    ) -> Result<Vec<ForthicValue>, ForthicError> {
+        match context.stack_pop()? {
+            ForthicValue::Null => Ok(Vec::new()),
+            ForthicValue::Array(items) => Ok(items),
+            other => Err(argument_error(word, position, None, "array", &other)),
+        }
+    }
+
+    /// An array of arrays (NULL rows count as empty)
+    fn pop_arrays(
+        context: &mut dyn InterpreterContext,
+        word: &str,
+    ) -> Result<Vec<Vec<ForthicValue>>, ForthicError> {
+        Self::pop_array_or_null(context, word, 1)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| match row {
+                ForthicValue::Null => Ok(Vec::new()),
+                ForthicValue::Array(items) => Ok(items),
+                other => Err(ForthicError::invalid_operation(format!(
+                    "{word}: element {i} of argument 1 must be an array, got {}",
+                    type_label(&other)
+                ))),
+            })
+            .collect()
+    }
+}
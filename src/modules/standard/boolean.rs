@@ -8,9 +8,11 @@
 //! - Membership: CONTAINS?, ANY, ALL, ANY?, ALL?
 //! - Conversion: >BOOL
 
+use crate::convert::{reject_sequence, StackArgs};
 use crate::errors::ForthicError;
 use crate::literals::ForthicValue;
 use crate::module::{register_words, InterpreterContext, Module};
+use std::cmp::Ordering;
 
 /// BooleanModule provides comparison and logic operations
 pub struct BooleanModule {
@@ -47,22 +49,22 @@ impl BooleanModule {
         register_words!(module, {
             "==" => Self::word_equals,
                 "( a:any b:any -- equal:boolean )",
-                "Test equality";
+                "Test equality (DateTimes by instant, whatever their timezones)";
             "!=" => Self::word_not_equals,
                 "( a:any b:any -- not_equal:boolean )",
                 "Test inequality";
             "<" => Self::word_less_than,
                 "( a:any b:any -- less_than:boolean )",
-                "Less than";
+                "Less than: numbers, strings, or Dates, Times and DateTimes like with like (DateTimes by instant); false for values without an order";
             "<=" => Self::word_less_than_or_equal,
                 "( a:any b:any -- less_equal:boolean )",
-                "Less than or equal";
+                "Less than or equal (ordered as for <; otherwise ==)";
             ">" => Self::word_greater_than,
                 "( a:any b:any -- greater_than:boolean )",
-                "Greater than";
+                "Greater than (ordered as for <)";
             ">=" => Self::word_greater_than_or_equal,
                 "( a:any b:any -- greater_equal:boolean )",
-                "Greater than or equal";
+                "Greater than or equal (ordered as for <; otherwise ==)";
         });
     }
 
@@ -81,72 +83,41 @@ impl BooleanModule {
     }
 
     fn word_less_than(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let b = context.stack_pop()?;
-        let a = context.stack_pop()?;
-
-        let result = match (&a, &b) {
-            (ForthicValue::Int(av), ForthicValue::Int(bv)) => *av < *bv,
-            (ForthicValue::Float(av), ForthicValue::Float(bv)) => *av < *bv,
-            (ForthicValue::Int(av), ForthicValue::Float(bv)) => (*av as f64) < *bv,
-            (ForthicValue::Float(av), ForthicValue::Int(bv)) => *av < (*bv as f64),
-            (ForthicValue::String(av), ForthicValue::String(bv)) => av < bv,
-            _ => false,
-        };
-
-        context.stack_push(ForthicValue::Bool(result));
-        Ok(())
+        Self::compare_with(context, |a, b| {
+            Self::compare_values(a, b) == Some(Ordering::Less)
+        })
     }
 
     fn word_less_than_or_equal(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let b = context.stack_pop()?;
-        let a = context.stack_pop()?;
-
-        let result = match (&a, &b) {
-            (ForthicValue::Int(av), ForthicValue::Int(bv)) => *av <= *bv,
-            (ForthicValue::Float(av), ForthicValue::Float(bv)) => *av <= *bv,
-            (ForthicValue::Int(av), ForthicValue::Float(bv)) => (*av as f64) <= *bv,
-            (ForthicValue::Float(av), ForthicValue::Int(bv)) => *av <= (*bv as f64),
-            (ForthicValue::String(av), ForthicValue::String(bv)) => av <= bv,
-            _ => Self::values_equal(&a, &b),
-        };
-
-        context.stack_push(ForthicValue::Bool(result));
-        Ok(())
+        Self::compare_with(context, |a, b| match Self::compare_values(a, b) {
+            Some(order) => order != Ordering::Greater,
+            None => Self::values_equal(a, b),
+        })
     }
 
     fn word_greater_than(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let b = context.stack_pop()?;
-        let a = context.stack_pop()?;
-
-        let result = match (&a, &b) {
-            (ForthicValue::Int(av), ForthicValue::Int(bv)) => *av > *bv,
-            (ForthicValue::Float(av), ForthicValue::Float(bv)) => *av > *bv,
-            (ForthicValue::Int(av), ForthicValue::Float(bv)) => (*av as f64) > *bv,
-            (ForthicValue::Float(av), ForthicValue::Int(bv)) => *av > (*bv as f64),
-            (ForthicValue::String(av), ForthicValue::String(bv)) => av > bv,
-            _ => false,
-        };
-
-        context.stack_push(ForthicValue::Bool(result));
-        Ok(())
+        Self::compare_with(context, |a, b| {
+            Self::compare_values(a, b) == Some(Ordering::Greater)
+        })
     }
 
     fn word_greater_than_or_equal(
         context: &mut dyn InterpreterContext,
+    ) -> Result<(), ForthicError> {
+        Self::compare_with(context, |a, b| match Self::compare_values(a, b) {
+            Some(order) => order != Ordering::Less,
+            None => Self::values_equal(a, b),
+        })
+    }
+
+    /// ( a b -- result ) for the ordering words
+    fn compare_with(
+        context: &mut dyn InterpreterContext,
+        test: impl Fn(&ForthicValue, &ForthicValue) -> bool,
     ) -> Result<(), ForthicError> {
         let b = context.stack_pop()?;
         let a = context.stack_pop()?;
-
-        let result = match (&a, &b) {
-            (ForthicValue::Int(av), ForthicValue::Int(bv)) => *av >= *bv,
-            (ForthicValue::Float(av), ForthicValue::Float(bv)) => *av >= *bv,
-            (ForthicValue::Int(av), ForthicValue::Float(bv)) => (*av as f64) >= *bv,
-            (ForthicValue::Float(av), ForthicValue::Int(bv)) => *av >= (*bv as f64),
-            (ForthicValue::String(av), ForthicValue::String(bv)) => av >= bv,
-            _ => Self::values_equal(&a, &b),
-        };
-
-        context.stack_push(ForthicValue::Bool(result));
+        context.stack_push(ForthicValue::Bool(test(&a, &b)));
         Ok(())
     }
 
@@ -181,12 +152,9 @@ impl BooleanModule {
         let b = context.stack_pop()?;
         let a = context.stack_pop()?;
         if matches!(a, ForthicValue::Array(_)) || matches!(b, ForthicValue::Array(_)) {
-            return Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: "OR takes two values. For an array of booleans, use ANY?.".to_string(),
-                location: None,
-                cause: None,
-            });
+            return Err(ForthicError::invalid_operation(
+                "OR takes two values. For an array of booleans, use ANY?.",
+            ));
         }
         let result = Self::is_truthy(&a) || Self::is_truthy(&b);
         context.stack_push(ForthicValue::Bool(result));
@@ -198,12 +166,9 @@ impl BooleanModule {
         let b = context.stack_pop()?;
         let a = context.stack_pop()?;
         if matches!(a, ForthicValue::Array(_)) || matches!(b, ForthicValue::Array(_)) {
-            return Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: "AND takes two values. For an array of booleans, use ALL?.".to_string(),
-                location: None,
-                cause: None,
-            });
+            return Err(ForthicError::invalid_operation(
+                "AND takes two values. For an array of booleans, use ALL?.",
+            ));
         }
         let result = Self::is_truthy(&a) && Self::is_truthy(&b);
         context.stack_push(ForthicValue::Bool(result));
@@ -266,6 +231,7 @@ impl BooleanModule {
     fn word_contains_q(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let needle = context.stack_pop()?;
         let haystack = context.stack_pop()?;
+        reject_sequence("CONTAINS?", 1, &haystack)?;
         let result = match haystack {
             ForthicValue::Array(arr) => arr.iter().any(|v| Self::values_equal(v, &needle)),
             _ => false,
@@ -276,7 +242,7 @@ impl BooleanModule {
 
     /// ANY?: ( bools:any[] -- bool ) — any element truthy; false on empty
     fn word_any_q(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let items = Self::require_array(context, "ANY?")?;
+        let items = context.pop_array("ANY?", 1)?;
         let result = items.iter().any(Self::is_truthy);
         context.stack_push(ForthicValue::Bool(result));
         Ok(())
@@ -284,30 +250,17 @@ impl BooleanModule {
 
     /// ALL?: ( bools:any[] -- bool ) — all elements truthy; true on empty
     fn word_all_q(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let items = Self::require_array(context, "ALL?")?;
+        let items = context.pop_array("ALL?", 1)?;
         let result = items.iter().all(Self::is_truthy);
         context.stack_push(ForthicValue::Bool(result));
         Ok(())
     }
 
-    fn require_array(
-        context: &mut dyn InterpreterContext,
-        word: &str,
-    ) -> Result<Vec<ForthicValue>, ForthicError> {
-        match context.stack_pop()? {
-            ForthicValue::Array(arr) => Ok(arr),
-            other => Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: format!("{word} requires an array of booleans (got {other:?})"),
-                location: None,
-                cause: None,
-            }),
-        }
-    }
-
     fn word_any(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let items2 = context.stack_pop()?;
         let items1 = context.stack_pop()?;
+        reject_sequence("ANY", 1, &items1)?;
+        reject_sequence("ANY", 2, &items2)?;
 
         match (&items1, &items2) {
             (ForthicValue::Array(arr1), ForthicValue::Array(arr2)) => {
@@ -332,6 +285,8 @@ impl BooleanModule {
     fn word_all(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let items2 = context.stack_pop()?;
         let items1 = context.stack_pop()?;
+        reject_sequence("ALL", 1, &items1)?;
+        reject_sequence("ALL", 2, &items2)?;
 
         match (&items1, &items2) {
             (ForthicValue::Array(arr1), ForthicValue::Array(arr2)) => {
@@ -373,6 +328,45 @@ impl BooleanModule {
 
     // ===== Helper Functions =====
 
+    /// Order two values for <, <=, > and >= (and SORT, via natural_cmp):
+    /// numbers on one number line, strings lexicographically, and temporal
+    /// values like with like: Dates by day, Times by time of day, DateTimes
+    /// by instant whatever their timezones. None when the values have no
+    /// order, including a Date against a DateTime, which needs a timezone
+    /// to compare (convert one side with >DATE or >DATETIME first).
+    pub(crate) fn compare_values(a: &ForthicValue, b: &ForthicValue) -> Option<Ordering> {
+        match (a, b) {
+            (ForthicValue::Int(av), ForthicValue::Int(bv)) => Some(av.cmp(bv)),
+            (ForthicValue::Float(av), ForthicValue::Float(bv)) => av.partial_cmp(bv),
+            (ForthicValue::Int(av), ForthicValue::Float(bv)) => Self::cmp_int_float(*av, *bv),
+            (ForthicValue::Float(av), ForthicValue::Int(bv)) => {
+                Self::cmp_int_float(*bv, *av).map(Ordering::reverse)
+            }
+            (ForthicValue::String(av), ForthicValue::String(bv)) => Some(av.cmp(bv)),
+            (ForthicValue::Date(av), ForthicValue::Date(bv)) => Some(av.cmp(bv)),
+            (ForthicValue::Time(av), ForthicValue::Time(bv)) => Some(av.cmp(bv)),
+            // chrono orders DateTimes by instant alone
+            (ForthicValue::DateTime(av), ForthicValue::DateTime(bv)) => Some(av.cmp(bv)),
+            _ => None,
+        }
+    }
+
+    /// Exact Int-against-Float order. Converting the Int to f64 rounds past
+    /// 2^53, which would make 2^53 and 2^53 + 1 both equal to 2^53 as a
+    /// Float while unequal to each other, and break SORT's total order.
+    fn cmp_int_float(int: i64, float: f64) -> Option<Ordering> {
+        let rounded = (int as f64).partial_cmp(&float)?;
+        if rounded != Ordering::Equal {
+            return Some(rounded);
+        }
+        // The float is integral and within a rounding of the int; 2^63
+        // itself is past i64::MAX, where `as i64` would saturate
+        if float >= i64::MAX as f64 {
+            return Some(Ordering::Less);
+        }
+        Some(int.cmp(&(float as i64)))
+    }
+
     /// Check if two values are equal
     pub(crate) fn values_equal(a: &ForthicValue, b: &ForthicValue) -> bool {
         match (a, b) {
@@ -380,8 +374,10 @@ impl BooleanModule {
             (ForthicValue::Bool(av), ForthicValue::Bool(bv)) => av == bv,
             (ForthicValue::Int(av), ForthicValue::Int(bv)) => av == bv,
             (ForthicValue::Float(av), ForthicValue::Float(bv)) => av == bv,
-            (ForthicValue::Int(av), ForthicValue::Float(bv)) => (*av as f64) == *bv,
-            (ForthicValue::Float(av), ForthicValue::Int(bv)) => *av == (*bv as f64),
+            (ForthicValue::Int(av), ForthicValue::Float(bv))
+            | (ForthicValue::Float(bv), ForthicValue::Int(av)) => {
+                Self::cmp_int_float(*av, *bv) == Some(Ordering::Equal)
+            }
             (ForthicValue::String(av), ForthicValue::String(bv)) => av == bv,
             (ForthicValue::Array(av), ForthicValue::Array(bv)) => {
                 if av.len() != bv.len() {
@@ -391,15 +387,14 @@ impl BooleanModule {
                     .zip(bv.iter())
                     .all(|(a, b)| Self::values_equal(a, b))
             }
+            // Identity, as for Sequence's PartialEq
+            (ForthicValue::Sequence(av), ForthicValue::Sequence(bv)) => av == bv,
             (ForthicValue::Date(av), ForthicValue::Date(bv)) => av == bv,
             (ForthicValue::Time(av), ForthicValue::Time(bv)) => av == bv,
-            // ts compares Temporal values by ISO string, which includes the
-            // timezone annotation — the same instant in different timezones
-            // is NOT equal. chrono's == compares instants only, so the
-            // timezone check is required for parity.
-            (ForthicValue::DateTime(av), ForthicValue::DateTime(bv)) => {
-                av == bv && av.timezone().name() == bv.timezone().name()
-            }
+            // By instant, agreeing with compare_values: the same instant in
+            // two timezones is == (ts compares ISO strings, zone included;
+            // SAME-WALL-TIME? and TZ@ cover the zone-sensitive checks)
+            (ForthicValue::DateTime(av), ForthicValue::DateTime(bv)) => av == bv,
             (ForthicValue::Record(av), ForthicValue::Record(bv)) => {
                 av.len() == bv.len()
                     && av
@@ -9,10 +9,12 @@
 // - Execution: RUN
 // - Predicates: NULL?, EMPTY?, STRING?, NUMBER?, RECORD?
 // - Debug: PEEK!, STACK!
+// - Output: INTERPOLATE, PRINT, PROGRESS
 // - Errors: TRY, OK?, ERROR?, UNWRAP, UNWRAP-OR (Rust Result semantics:
 //   'CODE' TRY UNWRAP is CODE — mirrored with forthic-ts)
 // - Options: ~> (converts array to WordOptions)
 
+use crate::convert::{reject_sequence, StackArgs};
 use crate::errors::ForthicError;
 use crate::literals::ForthicValue;
 use crate::module::{register_words, InterpreterContext, Module};
@@ -88,7 +90,10 @@ impl CoreModule {
                 "Fill ${name} holes from variables (${.name} also works; read-only — a miss renders as null_text and creates nothing). Holes are variable names, never expressions. Escape a literal with \\${. Null template stays null.";
             "PRINT" => Self::word_print,
                 "( value:any [options:WordOptions] -- )",
-                "Print value to stdout. Strings interpolate ${name} holes first; other values format with the same options. Escape a literal with \\${.";
+                "Print value as a line to the host's output (stdout by default). Strings interpolate ${name} holes first; other values format with the same options. Escape a literal with \\${.";
+            "PROGRESS" => Self::word_progress,
+                "( value:any -- )",
+                "Report a progress or partial value to the host while code runs (e.g. streamed to a JSON-RPC caller). Dropped when the host does not listen.";
             "USE-MODULES" => Self::word_use_modules,
                 "( names:string[] [options:WordOptions] -- )",
                 "Import registered modules by name; entries are 'name' or ['name' 'prefix'] pairs. Option prefixed (bool) prefixes plain names with themselves.";
@@ -102,23 +107,14 @@ impl CoreModule {
     /// option (ts contract). NULL names is a no-op; an unregistered name
     /// errors with UnknownModule.
     fn word_use_modules(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let options = Self::pop_word_options(context);
+        let options = context.pop_options();
         let prefixed = options
             .as_ref()
             .and_then(|o| o.get_bool("prefixed"))
             .unwrap_or(false);
-        let names = context.stack_pop()?;
-        let entries = match names {
-            ForthicValue::Array(entries) => entries,
-            ForthicValue::Null => return Ok(()),
-            other => {
-                return Err(ForthicError::InvalidOperation {
-                    forthic: String::new(),
-                    message: format!("USE-MODULES requires an array of names, got {other:?}"),
-                    location: None,
-                    cause: None,
-                })
-            }
+        let Some(entries) = context.pop_typed::<Option<Vec<ForthicValue>>>("USE-MODULES", 1)?
+        else {
+            return Ok(());
         };
         for entry in entries {
             let (name, prefix) = match entry {
@@ -137,23 +133,15 @@ impl CoreModule {
                         (n.clone(), p.clone())
                     }
                     _ => {
-                        return Err(ForthicError::InvalidOperation {
-                            forthic: String::new(),
-                            message: "USE-MODULES entries must be 'name' or ['name' 'prefix']"
-                                .to_string(),
-                            location: None,
-                            cause: None,
-                        })
+                        return Err(ForthicError::invalid_operation(
+                            "USE-MODULES entries must be 'name' or ['name' 'prefix']",
+                        ))
                     }
                 },
                 _ => {
-                    return Err(ForthicError::InvalidOperation {
-                        forthic: String::new(),
-                        message: "USE-MODULES entries must be 'name' or ['name' 'prefix']"
-                            .to_string(),
-                        location: None,
-                        cause: None,
-                    })
+                    return Err(ForthicError::invalid_operation(
+                        "USE-MODULES entries must be 'name' or ['name' 'prefix']",
+                    ))
                 }
             };
             context.use_module(&name, &prefix)?;
@@ -161,20 +149,8 @@ impl CoreModule {
         Ok(())
     }
 
-    /// Pop a WordOptions value if one sits on top of the stack
-    fn pop_word_options(
-        context: &mut dyn InterpreterContext,
-    ) -> Option<crate::word_options::WordOptions> {
-        if matches!(context.stack_peek(), Some(ForthicValue::WordOptions(_))) {
-            if let Ok(ForthicValue::WordOptions(options)) = context.stack_pop() {
-                return Some(options);
-            }
-        }
-        None
-    }
-
     fn pop_interp_options(context: &mut dyn InterpreterContext) -> InterpOptions {
-        let options = Self::pop_word_options(context);
+        let options = context.pop_options();
         let mut opts = InterpOptions {
             separator: ", ".to_string(),
             // Template-first default: an unset/NULL hole renders as
@@ -220,10 +196,18 @@ impl CoreModule {
         Ok(())
     }
 
-    /// PRINT: ( value [options] -- ) — print to stdout, pushing NOTHING.
+    /// PROGRESS: ( value -- ) — hand a value to the host's progress handler
+    fn word_progress(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let value = context.stack_pop()?;
+        context.report_progress(value);
+        Ok(())
+    }
+
+    /// PRINT: ( value [options] -- ) — print one line, pushing NOTHING.
     /// Strings interpolate `${name}` holes first; other values format via
-    /// the same rendering rules. Reaches stdout only — safe under the
-    /// jsonrpc transport (HTTP, not stdio).
+    /// the same rendering rules. The line goes to the host's output handler
+    /// (stdout by default; the jsonrpc stdio transport sends it to stderr,
+    /// since its stdout carries the framed responses).
     fn word_print(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let opts = Self::pop_interp_options(context);
         let value = context.stack_pop()?;
@@ -231,7 +215,7 @@ impl CoreModule {
             ForthicValue::String(s) => Self::interpolate_string(context, s, &opts)?,
             other => Self::value_to_string(other, &opts),
         };
-        println!("{result}");
+        context.write_output(&result);
         Ok(())
     }
 
@@ -280,16 +264,11 @@ impl CoreModule {
                 .chars()
                 .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
         if !valid {
-            return Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: format!(
-                    "Invalid interpolation hole '${{{body}}}': holes are variable names \
-                     (${{name}} or ${{.name}}), not expressions. \
-                     Escape a literal with \\${{"
-                ),
-                location: None,
-                cause: None,
-            });
+            return Err(ForthicError::invalid_operation(format!(
+                "Invalid interpolation hole '${{{body}}}': holes are variable names \
+                 (${{name}} or ${{.name}}), not expressions. \
+                 Escape a literal with \\${{"
+            )));
         }
         if name.starts_with("__") {
             return Err(ForthicError::InvalidVariableName {
@@ -343,26 +322,23 @@ impl CoreModule {
         });
     }
 
+    /// Pop a Forthic-string code argument; NULL means "no code"
     fn pop_forthic(
         context: &mut dyn InterpreterContext,
         word: &str,
+        position: usize,
     ) -> Result<Option<String>, ForthicError> {
-        match context.stack_pop()? {
-            ForthicValue::String(s) => Ok(Some(s)),
-            ForthicValue::Null => Ok(None),
-            other => Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: format!("{word} requires a Forthic string, got {other:?}"),
-                location: None,
-                cause: None,
-            }),
+        if matches!(context.stack_peek(), Some(ForthicValue::Null)) {
+            context.stack_pop()?;
+            return Ok(None);
         }
+        context.pop_code(word, position).map(Some)
     }
 
     /// RUN: ( forthic -- ? ) — run a Forthic string in the current context;
     /// whatever it produces stays on the stack
     fn word_run(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        if let Some(forthic) = Self::pop_forthic(context, "RUN")? {
+        if let Some(forthic) = Self::pop_forthic(context, "RUN", 1)? {
             if !forthic.is_empty() {
                 context.run(&forthic)?;
             }
@@ -374,9 +350,7 @@ impl CoreModule {
     /// (post-scrub ts contract: IF does not execute anything; for lazy code
     /// execution use IF-RUN)
     fn word_if(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let else_value = context.stack_pop()?;
-        let then_value = context.stack_pop()?;
-        let bool_val = context.stack_pop()?;
+        let [bool_val, then_value, else_value] = context.pop_n()?;
         context.stack_push(if bool_val.is_truthy() {
             then_value
         } else {
@@ -388,8 +362,8 @@ impl CoreModule {
     /// IF-RUN: ( bool then_forthic else_forthic -- ? ) — conditional code
     /// execution; both branches are Forthic strings (NULL = do nothing)
     fn word_if_run(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let else_forthic = Self::pop_forthic(context, "IF-RUN")?;
-        let then_forthic = Self::pop_forthic(context, "IF-RUN")?;
+        let else_forthic = Self::pop_forthic(context, "IF-RUN", 3)?;
+        let then_forthic = Self::pop_forthic(context, "IF-RUN", 2)?;
         let bool_val = context.stack_pop()?;
         let branch = if bool_val.is_truthy() {
             then_forthic
@@ -406,7 +380,7 @@ impl CoreModule {
 
     /// WHEN: ( bool forthic -- ? ) — one-sided: run the code if truthy
     fn word_when(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = Self::pop_forthic(context, "WHEN")?;
+        let forthic = Self::pop_forthic(context, "WHEN", 2)?;
         let bool_val = context.stack_pop()?;
         if bool_val.is_truthy() {
             if let Some(forthic) = forthic {
@@ -421,7 +395,7 @@ impl CoreModule {
     /// DEFAULT-RUN: ( value forthic -- result ) — lazy default: the forthic
     /// only runs when value is NULL or "" (its result replaces the value)
     fn word_default_run(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = Self::pop_forthic(context, "DEFAULT-RUN")?;
+        let forthic = Self::pop_forthic(context, "DEFAULT-RUN", 2)?;
         let value = context.stack_pop()?;
         let is_empty = matches!(&value, ForthicValue::Null)
             || matches!(&value, ForthicValue::String(s) if s.is_empty());
@@ -470,6 +444,7 @@ impl CoreModule {
     /// EMPTY?: null, "", or a container with no entries
     fn word_empty_q(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let value = context.stack_pop()?;
+        reject_sequence("EMPTY?", 1, &value)?;
         let empty = match &value {
             ForthicValue::Null => true,
             ForthicValue::String(s) => s.is_empty(),
@@ -520,13 +495,13 @@ impl CoreModule {
 
     /// PEEK!: print top of stack and intentionally stop execution
     fn word_peek_bang(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        match context.stack_peek() {
-            Some(value) => println!(
-                "{}",
-                crate::modules::standard::json::JSONModule::forthic_to_json(value)
-            ),
-            None => println!("<STACK EMPTY>"),
-        }
+        let text = match context.stack_peek() {
+            Some(value) => {
+                crate::modules::standard::json::JSONModule::forthic_to_json(value).to_string()
+            }
+            None => "<STACK EMPTY>".to_string(),
+        };
+        context.write_output(&text);
         Err(ForthicError::IntentionalStop {
             message: "PEEK!".to_string(),
         })
@@ -540,9 +515,8 @@ impl CoreModule {
             .iter()
             .map(crate::modules::standard::json::JSONModule::forthic_to_json)
             .collect();
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&json).unwrap_or_else(|_| "<UNPRINTABLE>".to_string())
+        context.write_output(
+            &serde_json::to_string_pretty(&json).unwrap_or_else(|_| "<UNPRINTABLE>".to_string()),
         );
         Err(ForthicError::IntentionalStop {
             message: "STACK!".to_string(),
@@ -585,17 +559,7 @@ impl CoreModule {
     /// (`[.outcomes TRUE] ~> MAP`): TRY inside MAP would transactionally
     /// restore the item MAP pushed, stranding it beneath the outcome.
     fn word_try(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let forthic = match context.stack_pop()? {
-            ForthicValue::String(s) => s,
-            other => {
-                return Err(ForthicError::InvalidOperation {
-                    forthic: String::new(),
-                    message: format!("TRY requires a Forthic string, got {other:?}"),
-                    location: None,
-                    cause: None,
-                })
-            }
-        };
+        let forthic = context.pop_code("TRY", 1)?;
 
         let snapshot = context.stack_snapshot();
         let module_depth = context.module_stack_depth();
@@ -668,20 +632,14 @@ impl CoreModule {
                     _ => ("UNWRAP of error outcome".to_string(), None),
                 };
                 let suffix = error_type.map(|t| format!(" ({t})")).unwrap_or_default();
-                return Err(ForthicError::InvalidOperation {
-                    forthic: String::new(),
-                    message: format!("{message}{suffix}"),
-                    location: None,
-                    cause: None,
-                });
+                return Err(ForthicError::invalid_operation(format!(
+                    "{message}{suffix}"
+                )));
             }
         }
-        Err(ForthicError::InvalidOperation {
-            forthic: String::new(),
-            message: "UNWRAP requires a TRY outcome record with an 'ok' or 'error' key".to_string(),
-            location: None,
-            cause: None,
-        })
+        Err(ForthicError::invalid_operation(
+            "UNWRAP requires a TRY outcome record with an 'ok' or 'error' key",
+        ))
     }
 
     /// UNWRAP-OR: ( outcome default -- value ) — ok wins even when the ok
@@ -699,13 +657,9 @@ impl CoreModule {
                 return Ok(());
             }
         }
-        Err(ForthicError::InvalidOperation {
-            forthic: String::new(),
-            message: "UNWRAP-OR requires a TRY outcome record with an 'ok' or 'error' key"
-                .to_string(),
-            location: None,
-            cause: None,
-        })
+        Err(ForthicError::invalid_operation(
+            "UNWRAP-OR requires a TRY outcome record with an 'ok' or 'error' key",
+        ))
     }
 
     /// Get the underlying module
@@ -748,8 +702,7 @@ impl CoreModule {
     }
 
     fn word_swap(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let b = context.stack_pop()?;
-        let a = context.stack_pop()?;
+        let [a, b] = context.pop_n()?;
         context.stack_push(b);
         context.stack_push(a);
         Ok(())
@@ -776,6 +729,7 @@ impl CoreModule {
 
     fn word_variables(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let val = context.stack_pop()?;
+        reject_sequence("VARIABLES", 1, &val)?;
 
         if let ForthicValue::Array(varnames) = val {
             let cur_module = context.cur_module_mut();
@@ -965,6 +919,7 @@ impl CoreModule {
 
     fn word_to_options(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let val = context.stack_pop()?;
+        reject_sequence("~>", 1, &val)?;
 
         if let ForthicValue::Array(arr) = val {
             // Convert to WordOptions
@@ -10,11 +10,23 @@
 // - Date math: ADD-DAYS, DAYS-BETWEEN
 // - Components: YEAR, MONTH (1-based), DAY-OF-WEEK (ISO 1=Mon)
 // - Meridiem: AM, PM
+// - Calendar: ADD-MONTHS, ADD-YEARS, START-OF, END-OF, QUARTER, ISO-WEEK,
+//   DAY, HOUR, MINUTE, ADD-BUSINESS-DAYS, BUSINESS-DAYS-BETWEEN, DATE-RANGE
+//   (calendar.rs)
+// - Timezones: >TZ, >UTC, WITH-TZ, LOCAL-DATETIME, TZ@, UTC-OFFSET,
+//   SAME-INSTANT?, SAME-WALL-TIME? (timezones.rs)
+// - Patterns: FORMAT-DATETIME, PARSE-DATETIME (patterns.rs)
 
 use crate::errors::ForthicError;
 use crate::literals::ForthicValue;
 use crate::module::{register_words, InterpreterContext, Module};
-use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+
+mod calendar;
+mod patterns;
+mod timezones;
+
+use timezones::{resolve_local, Disambiguation};
 
 /// DateTimeModule provides date and time operations
 pub struct DateTimeModule {
@@ -34,6 +46,9 @@ impl DateTimeModule {
         Self::register_date_math_words(&mut module);
         Self::register_meridiem_words(&mut module);
         Self::register_component_words(&mut module);
+        Self::register_calendar_words(&mut module);
+        Self::register_pattern_words(&mut module);
+        Self::register_timezone_words(&mut module);
 
         Self { module }
     }
@@ -216,19 +231,14 @@ impl DateTimeModule {
             .unwrap_or(ForthicValue::Null)
     }
 
-    /// A naive wall clock resolved in a timezone. DST overlap picks the
-    /// earlier offset; a DST gap shifts forward an hour (approximating
-    /// Temporal's 'compatible' disambiguation).
+    /// A naive wall clock resolved in a timezone with Temporal's
+    /// 'compatible' disambiguation: DST overlap picks the earlier offset; a
+    /// DST gap shifts forward by the gap's length.
     fn wall_clock_in_tz(naive: chrono::NaiveDateTime, tz: chrono_tz::Tz) -> ForthicValue {
-        match tz.from_local_datetime(&naive) {
-            chrono::LocalResult::Single(dt) => ForthicValue::DateTime(dt),
-            chrono::LocalResult::Ambiguous(first, _) => ForthicValue::DateTime(first),
-            chrono::LocalResult::None => tz
-                .from_local_datetime(&(naive + Duration::hours(1)))
-                .earliest()
-                .map(ForthicValue::DateTime)
-                .unwrap_or(ForthicValue::Null),
-        }
+        resolve_local(naive, tz, Disambiguation::Compatible)
+            .ok()
+            .flatten()
+            .map_or(ForthicValue::Null, ForthicValue::DateTime)
     }
 
     /// AT: ( date time -- datetime ) — that wall clock in the INTERPRETER
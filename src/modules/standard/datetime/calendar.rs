@@ -0,0 +1,471 @@
+// Calendar arithmetic
+//
+// - Months and years: ADD-MONTHS, ADD-YEARS
+// - Period boundaries: START-OF, END-OF (day, week, month, quarter, year)
+// - Components: QUARTER, ISO-WEEK, DAY, HOUR, MINUTE
+// - Business days: ADD-BUSINESS-DAYS, BUSINESS-DAYS-BETWEEN
+// - Ranges: DATE-RANGE
+//
+// Month arithmetic clamps to the end of the month: Jan 31 plus one month is
+// Feb 29 in a leap year. Weeks are ISO weeks, starting on Monday. A DateTime
+// keeps its wall clock and timezone when its date moves.
+//
+// Business days are weekdays that are not holidays. Holidays come from the
+// host (Interpreter::set_holiday_calendar) and from a word's .holidays
+// option, an array of dates; the two add up.
+
+use super::DateTimeModule;
+use crate::convert::{argument_error, StackArgs};
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{register_words, InterpreterContext, Module};
+use crate::word_options::WordOptions;
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Timelike, Weekday};
+use std::collections::HashSet;
+
+/// DATE-RANGE materializes its dates and the business-day words walk day
+/// by day; guard pathological sizes (the same limit RANGE uses)
+const MAX_RANGE_DATES: usize = 10_000_000;
+
+/// ADD-BUSINESS-DAYS gives up when a calendar has no business day for this
+/// many days in a row, instead of walking to the end of time
+const MAX_DAYS_WITHOUT_BUSINESS_DAY: u32 = 366;
+
+/// The calendar periods START-OF, END-OF and DATE-RANGE work in
+#[derive(Clone, Copy)]
+enum Unit {
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl Unit {
+    fn from_value(word: &str, position: usize, value: &ForthicValue) -> Result<Self, ForthicError> {
+        let name = match value {
+            ForthicValue::String(s) => s,
+            other => {
+                return Err(argument_error(
+                    word,
+                    position,
+                    Some("unit"),
+                    "string",
+                    other,
+                ))
+            }
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "day" => Ok(Unit::Day),
+            "week" => Ok(Unit::Week),
+            "month" => Ok(Unit::Month),
+            "quarter" => Ok(Unit::Quarter),
+            "year" => Ok(Unit::Year),
+            _ => Err(ForthicError::invalid_operation(format!(
+                "{word}: unknown unit '{name}' (expected day, week, month, quarter or year)"
+            ))),
+        }
+    }
+
+    /// `date` moved by `count` of this unit
+    fn add(self, date: NaiveDate, count: i64) -> Option<NaiveDate> {
+        match self {
+            Unit::Day => date.checked_add_signed(Duration::try_days(count)?),
+            Unit::Week => date.checked_add_signed(Duration::try_weeks(count)?),
+            Unit::Month => add_months(date, count),
+            Unit::Quarter => add_months(date, count.checked_mul(3)?),
+            Unit::Year => add_months(date, count.checked_mul(12)?),
+        }
+    }
+
+    /// The first day of the period containing `date`
+    fn start(self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Unit::Day => Some(date),
+            Unit::Week => date
+                .checked_sub_signed(Duration::days(date.weekday().num_days_from_monday() as i64)),
+            Unit::Month => date.with_day(1),
+            Unit::Quarter => NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1),
+            Unit::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1),
+        }
+    }
+}
+
+/// `date` moved by `months`, clamped to the last day of the target month
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let total = (date.year() as i64 * 12 + date.month0() as i64).checked_add(months)?;
+    let year = i32::try_from(total.div_euclid(12)).ok()?;
+    let month = total.rem_euclid(12) as u32 + 1;
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let last_day = next_month_start(first)?.pred_opt()?.day();
+    first.with_day(date.day().min(last_day))
+}
+
+/// The first day of the month after `first`
+fn next_month_start(first: NaiveDate) -> Option<NaiveDate> {
+    if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    }
+}
+
+/// Weekends plus the host's holidays plus a word's .holidays option
+struct BusinessCalendar<'a> {
+    context: &'a dyn InterpreterContext,
+    holidays: HashSet<NaiveDate>,
+}
+
+impl<'a> BusinessCalendar<'a> {
+    fn new(
+        word: &str,
+        context: &'a dyn InterpreterContext,
+        options: Option<&WordOptions>,
+    ) -> Result<Self, ForthicError> {
+        let holidays = match options.and_then(|o| o.get("holidays")) {
+            None | Some(ForthicValue::Null) => HashSet::new(),
+            Some(ForthicValue::Array(items)) => items
+                .iter()
+                .map(|item| {
+                    DateTimeModule::as_calendar_date(item).ok_or_else(|| {
+                        argument_error(word, 3, Some("holidays"), "array of dates", item)
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            Some(other) => {
+                return Err(argument_error(
+                    word,
+                    3,
+                    Some("holidays"),
+                    "array of dates",
+                    other,
+                ))
+            }
+        };
+        Ok(Self { context, holidays })
+    }
+
+    fn is_business_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+            && !self.holidays.contains(&date)
+            && !self.context.is_holiday(date)
+    }
+}
+
+impl DateTimeModule {
+    pub(super) fn register_calendar_words(module: &mut Module) {
+        register_words!(module, {
+            "ADD-MONTHS" => Self::word_add_months,
+                "( date:Date|DateTime months:int -- date:Date|DateTime )",
+                "Move a date by whole months, clamping to the end of the month (Jan 31 + 1 -> Feb 28/29); NULL for non-dates";
+            "ADD-YEARS" => Self::word_add_years,
+                "( date:Date|DateTime years:int -- date:Date|DateTime )",
+                "Move a date by whole years (Feb 29 + 1 -> Feb 28); NULL for non-dates";
+            "START-OF" => Self::word_start_of,
+                "( date:Date|DateTime unit:string -- start:Date|DateTime )",
+                "First day (a DateTime: first instant) of the day, week (ISO, Monday), month, quarter or year containing the date";
+            "END-OF" => Self::word_end_of,
+                "( date:Date|DateTime unit:string -- end:Date|DateTime )",
+                "Last day (a DateTime: last instant) of the day, week (ISO, Sunday), month, quarter or year containing the date";
+            "QUARTER" => Self::word_quarter,
+                "( date:Date|DateTime -- quarter:int )",
+                "Quarter of the year (1-4); NULL for non-dates";
+            "ISO-WEEK" => Self::word_iso_week,
+                "( date:Date|DateTime -- week:int )",
+                "ISO 8601 week number (1-53; Jan 1 may fall in the previous year's last week); NULL for non-dates";
+            "DAY" => Self::word_day,
+                "( date:Date|DateTime -- day:int )",
+                "Day of the month (1-31); NULL for non-dates";
+            "HOUR" => Self::word_hour,
+                "( time:Time|DateTime -- hour:int )",
+                "Hour of the day (0-23); NULL for non-times";
+            "MINUTE" => Self::word_minute,
+                "( time:Time|DateTime -- minute:int )",
+                "Minute of the hour (0-59); NULL for non-times";
+            "ADD-BUSINESS-DAYS" => Self::word_add_business_days,
+                "( date:Date|DateTime days:int [options:WordOptions] -- date:Date|DateTime )",
+                "Move a date by business days, skipping weekends and holidays (host calendar plus the holidays option, an array of dates); NULL for non-dates";
+            "BUSINESS-DAYS-BETWEEN" => Self::word_business_days_between,
+                "( date1:Date|DateTime date2:Date|DateTime [options:WordOptions] -- days:int )",
+                "Business days from date2 up to (not including) date1, negative when date1 is earlier; holidays as for ADD-BUSINESS-DAYS";
+            "DATE-RANGE" => Self::word_date_range,
+                "( start:Date|DateTime end:Date|DateTime [options:WordOptions] -- dates:Date[] )",
+                "Dates from start through end (inclusive) by step (default 1) units (day, week, month, quarter or year; default day); empty if start > end";
+        });
+    }
+
+    /// Move the calendar date of a Date or DateTime with `shift`. A DateTime
+    /// keeps its wall clock in its own timezone. NULL for anything else, or
+    /// when the date leaves chrono's range.
+    fn shift_date(
+        value: &ForthicValue,
+        shift: impl FnOnce(NaiveDate) -> Option<NaiveDate>,
+    ) -> ForthicValue {
+        match value {
+            ForthicValue::Date(d) => shift(*d).map_or(ForthicValue::Null, ForthicValue::Date),
+            ForthicValue::DateTime(dt) => match shift(dt.date_naive()) {
+                Some(date) => Self::wall_clock_in_tz(date.and_time(dt.time()), dt.timezone()),
+                None => ForthicValue::Null,
+            },
+            _ => ForthicValue::Null,
+        }
+    }
+
+    fn add_months_word(
+        context: &mut dyn InterpreterContext,
+        months_per_unit: i64,
+    ) -> Result<(), ForthicError> {
+        let [date, count] = context.pop_n::<2>()?;
+        let result = match count {
+            ForthicValue::Int(n) => match n.checked_mul(months_per_unit) {
+                Some(months) => Self::shift_date(&date, |d| add_months(d, months)),
+                None => ForthicValue::Null,
+            },
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_add_months(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::add_months_word(context, 1)
+    }
+
+    fn word_add_years(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::add_months_word(context, 12)
+    }
+
+    /// START-OF: ( date unit -- start ) — a DateTime becomes midnight of
+    /// the first day in its own timezone (or the first instant after
+    /// midnight, where a DST gap skips it)
+    fn word_start_of(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let [date, unit] = context.pop_n::<2>()?;
+        let unit = Unit::from_value("START-OF", 2, &unit)?;
+        let result = match &date {
+            ForthicValue::Date(d) => unit
+                .start(*d)
+                .map_or(ForthicValue::Null, ForthicValue::Date),
+            ForthicValue::DateTime(dt) => match unit.start(dt.date_naive()) {
+                Some(start) => {
+                    Self::wall_clock_in_tz(start.and_time(NaiveTime::MIN), dt.timezone())
+                }
+                None => ForthicValue::Null,
+            },
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// END-OF: ( date unit -- end ) — a DateTime becomes the last
+    /// nanosecond before the next period starts, in its own timezone
+    fn word_end_of(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let [date, unit] = context.pop_n::<2>()?;
+        let unit = Unit::from_value("END-OF", 2, &unit)?;
+        let next_start = |d: NaiveDate| unit.start(d).and_then(|start| unit.add(start, 1));
+        let result = match &date {
+            ForthicValue::Date(d) => next_start(*d)
+                .and_then(|next| next.pred_opt())
+                .map_or(ForthicValue::Null, ForthicValue::Date),
+            ForthicValue::DateTime(dt) => match next_start(dt.date_naive()) {
+                Some(next) => {
+                    match Self::wall_clock_in_tz(next.and_time(NaiveTime::MIN), dt.timezone()) {
+                        ForthicValue::DateTime(next) => {
+                            ForthicValue::DateTime(next - Duration::nanoseconds(1))
+                        }
+                        other => other,
+                    }
+                }
+                None => ForthicValue::Null,
+            },
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// A date component of a Date or DateTime; NULL for anything else
+    fn date_component(
+        context: &mut dyn InterpreterContext,
+        component: impl Fn(NaiveDate) -> u32,
+    ) -> Result<(), ForthicError> {
+        let val = context.stack_pop()?;
+        let result = match Self::as_calendar_date(&val) {
+            Some(d) => ForthicValue::Int(component(d) as i64),
+            None => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// A time component of a Time or DateTime; NULL for anything else
+    fn time_component(
+        context: &mut dyn InterpreterContext,
+        component: impl Fn(NaiveTime) -> u32,
+    ) -> Result<(), ForthicError> {
+        let val = context.stack_pop()?;
+        let result = match &val {
+            ForthicValue::Time(t) => ForthicValue::Int(component(*t) as i64),
+            ForthicValue::DateTime(dt) => ForthicValue::Int(component(dt.time()) as i64),
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_quarter(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::date_component(context, |d| d.month0() / 3 + 1)
+    }
+
+    fn word_iso_week(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::date_component(context, |d| d.iso_week().week())
+    }
+
+    fn word_day(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::date_component(context, |d| d.day())
+    }
+
+    fn word_hour(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::time_component(context, |t| t.hour())
+    }
+
+    fn word_minute(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::time_component(context, |t| t.minute())
+    }
+
+    /// ADD-BUSINESS-DAYS: ( date days [options] -- date ) — each step moves
+    /// to the next (or, for negative days, previous) business day, so
+    /// Friday plus one is Monday and Saturday plus one is Monday too. Zero
+    /// days returns the date unchanged, business day or not.
+    fn word_add_business_days(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "ADD-BUSINESS-DAYS";
+        let options = context.pop_options();
+        let [date, days] = context.pop_n::<2>()?;
+        let calendar = BusinessCalendar::new(WORD, context, options.as_ref())?;
+
+        let result = match days {
+            ForthicValue::Int(days) => {
+                if days.unsigned_abs() > MAX_RANGE_DATES as u64 {
+                    return Err(ForthicError::invalid_operation(format!(
+                        "{WORD}: {days} days is too large (limit {MAX_RANGE_DATES})"
+                    )));
+                }
+                let step = Duration::days(days.signum());
+                let mut failure = None;
+                let result = Self::shift_date(&date, |start| {
+                    let mut current = start;
+                    let mut skipped = 0;
+                    let mut remaining = days.unsigned_abs();
+                    while remaining > 0 {
+                        current = current.checked_add_signed(step)?;
+                        if calendar.is_business_day(current) {
+                            remaining -= 1;
+                            skipped = 0;
+                        } else {
+                            skipped += 1;
+                            if skipped > MAX_DAYS_WITHOUT_BUSINESS_DAY {
+                                failure = Some(start);
+                                return None;
+                            }
+                        }
+                    }
+                    Some(current)
+                });
+                if let Some(start) = failure {
+                    return Err(ForthicError::invalid_operation(format!(
+                        "{WORD}: no business day within {MAX_DAYS_WITHOUT_BUSINESS_DAY} days of {start}"
+                    )));
+                }
+                result
+            }
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// BUSINESS-DAYS-BETWEEN: ( date1 date2 [options] -- days ) — the
+    /// DAYS-BETWEEN sign convention (date1 - date2), counting the business
+    /// days in [earlier, later). That makes it the inverse of
+    /// ADD-BUSINESS-DAYS from a business day.
+    fn word_business_days_between(
+        context: &mut dyn InterpreterContext,
+    ) -> Result<(), ForthicError> {
+        const WORD: &str = "BUSINESS-DAYS-BETWEEN";
+        let options = context.pop_options();
+        let [date1, date2] = context.pop_n::<2>()?;
+        let calendar = BusinessCalendar::new(WORD, context, options.as_ref())?;
+
+        let result = match (
+            Self::as_calendar_date(&date1),
+            Self::as_calendar_date(&date2),
+        ) {
+            (Some(d1), Some(d2)) => {
+                let (from, to, sign) = if d1 >= d2 { (d2, d1, 1) } else { (d1, d2, -1) };
+                if (to - from).num_days() > MAX_RANGE_DATES as i64 {
+                    return Err(ForthicError::invalid_operation(format!(
+                        "{WORD} from {from} to {to} is too large (limit {MAX_RANGE_DATES} days)"
+                    )));
+                }
+                let count = from
+                    .iter_days()
+                    .take_while(|d| *d < to)
+                    .filter(|d| calendar.is_business_day(*d))
+                    .count();
+                ForthicValue::Int(sign * count as i64)
+            }
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// DATE-RANGE: ( start end [options] -- dates ) — month, quarter and
+    /// year steps count from start, so a range from Jan 31 by month runs
+    /// Jan 31, Feb 29, Mar 31 rather than drifting to the 29th
+    fn word_date_range(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "DATE-RANGE";
+        let options = context.pop_options();
+        let [start, end] = context.pop_n::<2>()?;
+
+        let get = |key| options.as_ref().and_then(|o| o.get(key));
+        let step = match get("step") {
+            None => 1,
+            Some(ForthicValue::Int(n)) if *n > 0 => *n,
+            Some(other) => {
+                return Err(argument_error(WORD, 3, Some("step"), "positive int", other))
+            }
+        };
+        let unit = match get("unit") {
+            None => Unit::Day,
+            Some(value) => Unit::from_value(WORD, 3, value)?,
+        };
+
+        let (start, end) = match (Self::as_calendar_date(&start), Self::as_calendar_date(&end)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => {
+                context.stack_push(ForthicValue::Null);
+                return Ok(());
+            }
+        };
+
+        let mut dates = Vec::new();
+        let mut index: i64 = 0;
+        while let Some(date) = index
+            .checked_mul(step)
+            .and_then(|count| unit.add(start, count))
+            .filter(|date| *date <= end)
+        {
+            if dates.len() == MAX_RANGE_DATES {
+                return Err(ForthicError::invalid_operation(format!(
+                    "{WORD} from {start} to {end} is too large (limit {MAX_RANGE_DATES} dates)"
+                )));
+            }
+            dates.push(ForthicValue::Date(date));
+            index += 1;
+        }
+
+        context.stack_push(ForthicValue::Array(dates));
+        Ok(())
+    }
+}
@@ -0,0 +1,698 @@
+// Pattern-based formatting and parsing
+//
+// - FORMAT-DATETIME: Date, Time or DateTime to text
+// - PARSE-DATETIME: text to Date, Time or DateTime
+//
+// A pattern containing '%' is strftime (chrono's specifiers); anything else
+// is a CLDR-style pattern such as "yyyy-MM-dd'T'HH:mm", translated to
+// strftime first. The named patterns "rfc3339", "rfc2822" and "iso-week"
+// (2024-W03-2) cover the common standards.
+//
+// Month and weekday names follow .locale (its language part: "de-DE" and
+// "de" both mean German), from the NAMES table below; RFC 2822 is always
+// English. Failures to format or parse are NULL, or errors with .strict;
+// a malformed pattern is always an error.
+
+use super::DateTimeModule;
+use crate::convert::{argument_error, type_label, StackArgs};
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{register_words, InterpreterContext, Module};
+use crate::word_options::WordOptions;
+use chrono::format::{Fixed, Item, Parsed, StrftimeItems};
+use chrono::{Datelike, FixedOffset};
+use std::fmt::Write;
+
+/// Month and weekday names of one language; weekdays start on Monday
+struct Names {
+    language: &'static str,
+    months: [&'static str; 12],
+    months_short: [&'static str; 12],
+    weekdays: [&'static str; 7],
+    weekdays_short: [&'static str; 7],
+}
+
+const NAMES: &[Names] = &[
+    Names {
+        language: "en",
+        months: [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        months_short: [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ],
+        weekdays: [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ],
+        weekdays_short: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    },
+    Names {
+        language: "de",
+        months: [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        months_short: [
+            "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.",
+            "Dez.",
+        ],
+        weekdays: [
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+            "Sonntag",
+        ],
+        weekdays_short: ["Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa.", "So."],
+    },
+    Names {
+        language: "fr",
+        months: [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        months_short: [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ],
+        weekdays: [
+            "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+        ],
+        weekdays_short: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+    },
+    Names {
+        language: "es",
+        months: [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        months_short: [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+        ],
+        weekdays: [
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+            "domingo",
+        ],
+        weekdays_short: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+    },
+    Names {
+        language: "it",
+        months: [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        months_short: [
+            "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+        ],
+        weekdays: [
+            "lunedì",
+            "martedì",
+            "mercoledì",
+            "giovedì",
+            "venerdì",
+            "sabato",
+            "domenica",
+        ],
+        weekdays_short: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+    },
+    Names {
+        language: "nl",
+        months: [
+            "januari",
+            "februari",
+            "maart",
+            "april",
+            "mei",
+            "juni",
+            "juli",
+            "augustus",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        months_short: [
+            "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec",
+        ],
+        weekdays: [
+            "maandag",
+            "dinsdag",
+            "woensdag",
+            "donderdag",
+            "vrijdag",
+            "zaterdag",
+            "zondag",
+        ],
+        weekdays_short: ["ma", "di", "wo", "do", "vr", "za", "zo"],
+    },
+    Names {
+        language: "pt",
+        months: [
+            "janeiro",
+            "fevereiro",
+            "março",
+            "abril",
+            "maio",
+            "junho",
+            "julho",
+            "agosto",
+            "setembro",
+            "outubro",
+            "novembro",
+            "dezembro",
+        ],
+        months_short: [
+            "jan.", "fev.", "mar.", "abr.", "mai.", "jun.", "jul.", "ago.", "set.", "out.", "nov.",
+            "dez.",
+        ],
+        weekdays: [
+            "segunda-feira",
+            "terça-feira",
+            "quarta-feira",
+            "quinta-feira",
+            "sexta-feira",
+            "sábado",
+            "domingo",
+        ],
+        weekdays_short: ["seg.", "ter.", "qua.", "qui.", "sex.", "sáb.", "dom."],
+    },
+    Names {
+        language: "sv",
+        months: [
+            "januari",
+            "februari",
+            "mars",
+            "april",
+            "maj",
+            "juni",
+            "juli",
+            "augusti",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        months_short: [
+            "jan.", "feb.", "mars", "apr.", "maj", "juni", "juli", "aug.", "sep.", "okt.", "nov.",
+            "dec.",
+        ],
+        weekdays: [
+            "måndag", "tisdag", "onsdag", "torsdag", "fredag", "lördag", "söndag",
+        ],
+        weekdays_short: ["mån", "tis", "ons", "tors", "fre", "lör", "sön"],
+    },
+    Names {
+        language: "pl",
+        // Genitive, as in "5 marca 2024"
+        months: [
+            "stycznia",
+            "lutego",
+            "marca",
+            "kwietnia",
+            "maja",
+            "czerwca",
+            "lipca",
+            "sierpnia",
+            "września",
+            "października",
+            "listopada",
+            "grudnia",
+        ],
+        months_short: [
+            "sty", "lut", "mar", "kwi", "maj", "cze", "lip", "sie", "wrz", "paź", "lis", "gru",
+        ],
+        weekdays: [
+            "poniedziałek",
+            "wtorek",
+            "środa",
+            "czwartek",
+            "piątek",
+            "sobota",
+            "niedziela",
+        ],
+        weekdays_short: ["pon.", "wt.", "śr.", "czw.", "pt.", "sob.", "niedz."],
+    },
+];
+
+/// Options shared by both words
+struct PatternOptions {
+    pattern: Option<String>,
+    names: &'static Names,
+    strict: bool,
+}
+
+impl PatternOptions {
+    fn from_options(word: &str, options: Option<&WordOptions>) -> Result<Self, ForthicError> {
+        let get = |key| options.and_then(|o| o.get(key));
+        let pattern = match get("pattern") {
+            None => None,
+            Some(ForthicValue::String(s)) => Some(s.clone()),
+            Some(other) => return Err(argument_error(word, 2, Some("pattern"), "string", other)),
+        };
+        let names = match get("locale") {
+            None => &NAMES[0],
+            Some(ForthicValue::String(tag)) => {
+                let language = tag.split(['-', '_']).next().unwrap_or_default();
+                NAMES
+                    .iter()
+                    .find(|n| n.language.eq_ignore_ascii_case(language))
+                    .ok_or_else(|| {
+                        let supported: Vec<&str> = NAMES.iter().map(|n| n.language).collect();
+                        ForthicError::invalid_operation(format!(
+                            "{word}: no month and day names for locale '{tag}' (supported languages: {})",
+                            supported.join(", ")
+                        ))
+                    })?
+            }
+            Some(other) => return Err(argument_error(word, 2, Some("locale"), "string", other)),
+        };
+        let strict = match get("strict") {
+            None => false,
+            Some(ForthicValue::Bool(b)) => *b,
+            Some(other) => return Err(argument_error(word, 2, Some("strict"), "boolean", other)),
+        };
+        Ok(Self {
+            pattern,
+            names,
+            strict,
+        })
+    }
+
+    /// NULL, or with .strict the error `message` describes
+    fn fail(&self, word: &str, message: String) -> Result<ForthicValue, ForthicError> {
+        if self.strict {
+            Err(ForthicError::invalid_operation(format!(
+                "{word}: {message}"
+            )))
+        } else {
+            Ok(ForthicValue::Null)
+        }
+    }
+}
+
+/// Expand a named pattern, translate CLDR to strftime, and check that
+/// chrono understands the result
+fn compile(word: &str, pattern: &str) -> Result<Vec<Item<'static>>, ForthicError> {
+    let items: Vec<Item<'static>> = match pattern {
+        "rfc3339" => vec![Item::Fixed(Fixed::RFC3339)],
+        "rfc2822" => vec![Item::Fixed(Fixed::RFC2822)],
+        "iso-week" => StrftimeItems::new("%G-W%V-%u")
+            .map(|item| item.to_owned())
+            .collect(),
+        _ => {
+            let strftime = if pattern.contains('%') {
+                pattern.to_string()
+            } else {
+                cldr_to_strftime(pattern).map_err(|message| {
+                    ForthicError::invalid_operation(format!("{word}: {message} in '{pattern}'"))
+                })?
+            };
+            StrftimeItems::new(&strftime)
+                .map(|item| item.to_owned())
+                .collect()
+        }
+    };
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return Err(ForthicError::invalid_operation(format!(
+            "{word}: invalid pattern '{pattern}'"
+        )));
+    }
+    Ok(items)
+}
+
+/// "yyyy-MM-dd'T'HH:mm" to "%Y-%m-%dT%H:%M". Letters are fields (runs of
+/// one letter give the width); text in single quotes, and anything not a
+/// letter, is literal; '' is an apostrophe.
+fn cldr_to_strftime(pattern: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            if chars.next_if_eq(&'\'').is_some() {
+                out.push('\'');
+                continue;
+            }
+            loop {
+                match chars.next() {
+                    None => return Err("unterminated quote".to_string()),
+                    Some('\'') if chars.next_if_eq(&'\'').is_some() => out.push('\''),
+                    Some('\'') => break,
+                    Some('%') => out.push_str("%%"),
+                    Some(c) => out.push(c),
+                }
+            }
+        } else if c.is_ascii_alphabetic() {
+            let mut width = 1;
+            while chars.next_if_eq(&c).is_some() {
+                width += 1;
+            }
+            let spec = match (c, width) {
+                ('y', 2) => "%y",
+                ('y', _) => "%Y",
+                ('Y', 2) => "%g",
+                ('Y', _) => "%G",
+                ('Q', 1) => "%q",
+                ('M' | 'L', 1) => "%-m",
+                ('M' | 'L', 2) => "%m",
+                ('M' | 'L', 3) => "%b",
+                ('M' | 'L', 4) => "%B",
+                ('w', 1) => "%-V",
+                ('w', 2) => "%V",
+                ('d', 1) => "%-d",
+                ('d', 2) => "%d",
+                ('D', 1 | 2) => "%-j",
+                ('D', 3) => "%j",
+                ('E', 1..=3) => "%a",
+                ('E', 4) => "%A",
+                ('u', 1) => "%u",
+                ('a', 1) => "%p",
+                ('H', 1) => "%-H",
+                ('H', 2) => "%H",
+                ('h', 1) => "%-I",
+                ('h', 2) => "%I",
+                ('m', 1) => "%-M",
+                ('m', 2) => "%M",
+                ('s', 1) => "%-S",
+                ('s', 2) => "%S",
+                ('S', 3) => "%3f",
+                ('S', 6) => "%6f",
+                ('S', 9) => "%9f",
+                ('Z', 1..=3) | ('X' | 'x', 2) => "%z",
+                ('Z', 5) | ('X' | 'x', 3) => "%:z",
+                ('z', 1..=3) => "%Z",
+                _ => {
+                    let field: String = std::iter::repeat_n(c, width).collect();
+                    return Err(format!("unsupported field '{field}'"));
+                }
+            };
+            out.push_str(spec);
+        } else if c == '%' {
+            out.push_str("%%");
+        } else {
+            out.push(c);
+        }
+    }
+    Ok(out)
+}
+
+/// Case-insensitive match of the longest name (or abbreviation, with or
+/// without its trailing dot) at the start of `text`: (1-based index, length)
+fn match_name(text: &str, full: &[&str], short: &[&str]) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+    for list in [full, short] {
+        for (i, name) in list.iter().enumerate() {
+            for candidate in [*name, name.trim_end_matches('.')] {
+                let count = candidate.chars().count();
+                let end = text
+                    .char_indices()
+                    .nth(count)
+                    .map_or(text.len(), |(at, _)| at);
+                if text.chars().count() >= count
+                    && text[..end].to_lowercase() == candidate.to_lowercase()
+                    && best.is_none_or(|(_, len)| end > len)
+                {
+                    best = Some((i + 1, end));
+                }
+            }
+        }
+    }
+    best
+}
+
+impl DateTimeModule {
+    pub(super) fn register_pattern_words(module: &mut Module) {
+        register_words!(module, {
+            "FORMAT-DATETIME" => Self::word_format_datetime,
+                "( value:Date|Time|DateTime [options:WordOptions] -- text:string )",
+                "Format with .pattern (strftime, yyyy-MM-dd style, or rfc3339/rfc2822/iso-week); .locale names months and days; .strict errors instead of null";
+            "PARSE-DATETIME" => Self::word_parse_datetime,
+                "( text:string [options:WordOptions] -- value:Date|Time|DateTime )",
+                "Parse with .pattern (default rfc3339) into a Date, Time or DateTime by the fields present; .locale, .strict as for FORMAT-DATETIME";
+        });
+    }
+
+    /// FORMAT-DATETIME: ( value [options] -- text ) — without a pattern, a
+    /// DateTime is RFC 3339, a Date yyyy-MM-dd and a Time HH:mm:ss. A
+    /// pattern field the value lacks (an hour of a Date) fails.
+    fn word_format_datetime(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "FORMAT-DATETIME";
+        let options = context.pop_options();
+        let opts = PatternOptions::from_options(WORD, options.as_ref())?;
+        let value = context.stack_pop()?;
+
+        let default_pattern = match &value {
+            ForthicValue::DateTime(_) => "rfc3339",
+            ForthicValue::Date(_) => "%Y-%m-%d",
+            _ => "%H:%M:%S",
+        };
+        let pattern = opts.pattern.as_deref().unwrap_or(default_pattern);
+        let items = compile(WORD, pattern)?;
+
+        let (month, weekday) = match &value {
+            ForthicValue::Date(d) => (Some(d.month0()), Some(d.weekday())),
+            ForthicValue::DateTime(dt) => (Some(dt.month0()), Some(dt.weekday())),
+            _ => (None, None),
+        };
+        let names = opts.names;
+        let items: Vec<Item> = items
+            .into_iter()
+            .map(|item| {
+                let name = match (&item, month, weekday) {
+                    (Item::Fixed(Fixed::LongMonthName), Some(m), _) => names.months[m as usize],
+                    (Item::Fixed(Fixed::ShortMonthName), Some(m), _) => {
+                        names.months_short[m as usize]
+                    }
+                    (Item::Fixed(Fixed::LongWeekdayName), _, Some(w)) => {
+                        names.weekdays[w.num_days_from_monday() as usize]
+                    }
+                    (Item::Fixed(Fixed::ShortWeekdayName), _, Some(w)) => {
+                        names.weekdays_short[w.num_days_from_monday() as usize]
+                    }
+                    _ => return item,
+                };
+                Item::OwnedLiteral(name.into())
+            })
+            .collect();
+
+        let mut text = String::new();
+        let written = match &value {
+            ForthicValue::Null => {
+                context.stack_push(ForthicValue::Null);
+                return Ok(());
+            }
+            ForthicValue::DateTime(dt) => write!(text, "{}", dt.format_with_items(items.iter())),
+            ForthicValue::Date(d) => write!(text, "{}", d.format_with_items(items.iter())),
+            ForthicValue::Time(t) => write!(text, "{}", t.format_with_items(items.iter())),
+            other => {
+                let result = opts.fail(
+                    WORD,
+                    format!(
+                        "argument 1 must be Date, Time or DateTime, got {}",
+                        type_label(other)
+                    ),
+                )?;
+                context.stack_push(result);
+                return Ok(());
+            }
+        };
+        let result = match written {
+            Ok(()) => ForthicValue::String(text),
+            Err(_) => opts.fail(
+                WORD,
+                format!(
+                    "pattern '{pattern}' needs fields a {} does not have",
+                    type_label(&value)
+                ),
+            )?,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// PARSE-DATETIME: ( text [options] -- value ) — date and time fields
+    /// give a DateTime: with an offset, the instant it names in the
+    /// interpreter timezone; without, that wall clock in the interpreter
+    /// timezone (as >DATETIME). Date fields alone give a Date, time fields
+    /// alone a Time. The whole text must match.
+    fn word_parse_datetime(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "PARSE-DATETIME";
+        let options = context.pop_options();
+        let opts = PatternOptions::from_options(WORD, options.as_ref())?;
+        let pattern = opts.pattern.as_deref().unwrap_or("rfc3339");
+        let items = compile(WORD, pattern)?;
+        let tz = Self::context_tz(context);
+
+        let text = match context.stack_pop()? {
+            ForthicValue::Null => {
+                context.stack_push(ForthicValue::Null);
+                return Ok(());
+            }
+            ForthicValue::String(s) => s,
+            other => {
+                let result = opts.fail(
+                    WORD,
+                    format!("argument 1 must be string, got {}", type_label(&other)),
+                )?;
+                context.stack_push(result);
+                return Ok(());
+            }
+        };
+
+        let result = match Self::parse_with(&items, opts.names, &text) {
+            Err(reason) => opts.fail(
+                WORD,
+                format!("'{text}' does not match pattern '{pattern}' ({reason})"),
+            )?,
+            Ok(parsed) => match Self::from_parsed(&parsed, tz) {
+                Some(value) => value,
+                None => opts.fail(
+                    WORD,
+                    format!("'{text}' does not name a valid date or time for pattern '{pattern}'"),
+                )?,
+            },
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// Run the items over `text` one at a time so that month and weekday
+    /// names can be read in the locale's language
+    fn parse_with(items: &[Item], names: &Names, text: &str) -> Result<Parsed, String> {
+        let mut parsed = Parsed::new();
+        let mut rest = text;
+        for item in items {
+            match item {
+                Item::Fixed(Fixed::LongMonthName | Fixed::ShortMonthName) => {
+                    let (month, len) = match_name(rest, &names.months, &names.months_short)
+                        .ok_or_else(|| "unknown month name".to_string())?;
+                    parsed.set_month(month as i64).map_err(|e| e.to_string())?;
+                    rest = &rest[len..];
+                }
+                Item::Fixed(Fixed::LongWeekdayName | Fixed::ShortWeekdayName) => {
+                    let (day, len) = match_name(rest, &names.weekdays, &names.weekdays_short)
+                        .ok_or_else(|| "unknown weekday name".to_string())?;
+                    let weekday =
+                        chrono::Weekday::try_from(day as u8 - 1).map_err(|e| e.to_string())?;
+                    parsed.set_weekday(weekday).map_err(|e| e.to_string())?;
+                    rest = &rest[len..];
+                }
+                _ => {
+                    rest = chrono::format::parse_and_remainder(
+                        &mut parsed,
+                        rest,
+                        std::iter::once(item),
+                    )
+                    .map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        if rest.is_empty() {
+            Ok(parsed)
+        } else {
+            Err(format!("unexpected trailing '{rest}'"))
+        }
+    }
+
+    /// The value the parsed fields describe, or `None` when they are
+    /// inconsistent or incomplete (Feb 30, a minute without an hour)
+    fn from_parsed(parsed: &Parsed, tz: chrono_tz::Tz) -> Option<ForthicValue> {
+        if parsed.timestamp().is_some() {
+            let naive = parsed.to_naive_datetime_with_offset(0).ok()?;
+            return Some(ForthicValue::DateTime(naive.and_utc().with_timezone(&tz)));
+        }
+        let has_time = parsed.hour_mod_12().is_some();
+        let date = parsed.to_naive_date();
+        match (date, has_time) {
+            (Ok(date), true) => {
+                let naive = date.and_time(parsed.to_naive_time().ok()?);
+                match parsed.offset() {
+                    Some(offset) => {
+                        let offset = FixedOffset::east_opt(offset)?;
+                        let instant = naive.and_local_timezone(offset).single()?;
+                        Some(ForthicValue::DateTime(instant.with_timezone(&tz)))
+                    }
+                    None => Some(Self::wall_clock_in_tz(naive, tz)),
+                }
+            }
+            (Ok(date), false) => Some(ForthicValue::Date(date)),
+            (Err(_), true) if Self::no_date_fields(parsed) => {
+                parsed.to_naive_time().ok().map(ForthicValue::Time)
+            }
+            _ => None,
+        }
+    }
+
+    fn no_date_fields(parsed: &Parsed) -> bool {
+        parsed.year().is_none()
+            && parsed.isoyear().is_none()
+            && parsed.month().is_none()
+            && parsed.day().is_none()
+            && parsed.ordinal().is_none()
+            && parsed.isoweek().is_none()
+            && parsed.weekday().is_none()
+    }
+}
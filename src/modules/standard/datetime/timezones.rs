@@ -0,0 +1,282 @@
+// Timezone conversion
+//
+// - Same instant elsewhere: >TZ, >UTC
+// - Same wall clock elsewhere: WITH-TZ, LOCAL-DATETIME
+// - Inspection: TZ@, UTC-OFFSET
+// - Comparison: SAME-INSTANT?, SAME-WALL-TIME?
+//
+// ==, <, <= and SORT compare DateTimes by instant alone, whatever their
+// timezones. SAME-INSTANT? says the same explicitly; SAME-WALL-TIME? (or
+// TZ@) is the check for when the zone or the wall clock matters.
+//
+// Wall clocks that a DST change skips (a gap) or repeats (an overlap)
+// follow .on_ambiguous, with Temporal's meanings:
+// - "compatible" (default): the earlier instant of an overlap; a time in a
+//   gap moves forward by the gap's length
+// - "earlier" / "later": the earlier or later of the two candidate instants
+//   (in a gap, the wall clock read with the offset after or before the
+//   change)
+// - "error": fail instead of choosing
+//
+// Timezones are IANA names ("Europe/Berlin"); an unknown name is an error.
+
+use super::DateTimeModule;
+use crate::convert::{argument_error, StackArgs};
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{register_words, InterpreterContext, Module};
+use crate::utils::convert_timezone;
+use crate::word_options::WordOptions;
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// How to resolve a wall clock a DST change skips or repeats
+#[derive(Clone, Copy)]
+pub(super) enum Disambiguation {
+    Compatible,
+    Earlier,
+    Later,
+    Error,
+}
+
+/// Why a wall clock names no single instant under Disambiguation::Error
+pub(super) enum LocalTimeProblem {
+    Ambiguous,
+    Skipped,
+}
+
+impl Disambiguation {
+    fn from_options(
+        word: &str,
+        position: usize,
+        options: Option<&WordOptions>,
+    ) -> Result<Self, ForthicError> {
+        match options.and_then(|o| o.get("on_ambiguous")) {
+            None => Ok(Disambiguation::Compatible),
+            Some(ForthicValue::String(s)) => match s.to_ascii_lowercase().as_str() {
+                "compatible" => Ok(Disambiguation::Compatible),
+                "earlier" => Ok(Disambiguation::Earlier),
+                "later" => Ok(Disambiguation::Later),
+                "error" => Ok(Disambiguation::Error),
+                _ => Err(ForthicError::invalid_operation(format!(
+                    "{word}: unknown on_ambiguous '{s}' (expected compatible, earlier, later or error)"
+                ))),
+            },
+            Some(other) => Err(argument_error(
+                word,
+                position,
+                Some("on_ambiguous"),
+                "string",
+                other,
+            )),
+        }
+    }
+}
+
+/// The instant a wall clock names in `tz`. Ok(None) only when the result
+/// leaves chrono's range.
+pub(super) fn resolve_local(
+    naive: NaiveDateTime,
+    tz: Tz,
+    policy: Disambiguation,
+) -> Result<Option<DateTime<Tz>>, LocalTimeProblem> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => Ok(Some(dt)),
+        LocalResult::Ambiguous(earlier, later) => match policy {
+            Disambiguation::Compatible | Disambiguation::Earlier => Ok(Some(earlier)),
+            Disambiguation::Later => Ok(Some(later)),
+            Disambiguation::Error => Err(LocalTimeProblem::Ambiguous),
+        },
+        LocalResult::None => {
+            // Read with the offset in force before the gap, the wall clock
+            // lands after it (the later instant), and vice versa. A day
+            // either side is clear of the transition itself.
+            let offset_near = |shift: Duration| {
+                naive
+                    .checked_add_signed(shift)
+                    .map(|near| tz.offset_from_utc_datetime(&near).fix())
+            };
+            let offset = match policy {
+                Disambiguation::Compatible | Disambiguation::Later => {
+                    offset_near(Duration::days(-1))
+                }
+                Disambiguation::Earlier => offset_near(Duration::days(1)),
+                Disambiguation::Error => return Err(LocalTimeProblem::Skipped),
+            };
+            Ok(offset.and_then(|offset| {
+                naive
+                    .checked_sub_signed(Duration::seconds(offset.local_minus_utc() as i64))
+                    .map(|utc| tz.from_utc_datetime(&utc))
+            }))
+        }
+    }
+}
+
+/// A timezone argument: an IANA name
+fn timezone_arg(word: &str, position: usize, value: &ForthicValue) -> Result<Tz, ForthicError> {
+    match value {
+        ForthicValue::String(name) => name.parse().map_err(|_| {
+            ForthicError::invalid_operation(format!("{word}: unknown timezone '{name}'"))
+        }),
+        other => Err(argument_error(
+            word,
+            position,
+            Some("timezone"),
+            "string",
+            other,
+        )),
+    }
+}
+
+impl DateTimeModule {
+    pub(super) fn register_timezone_words(module: &mut Module) {
+        register_words!(module, {
+            ">TZ" => Self::word_to_tz,
+                "( datetime:DateTime timezone:string -- datetime:DateTime )",
+                "The same instant in another IANA timezone (the wall clock changes); NULL for non-datetimes";
+            ">UTC" => Self::word_to_utc,
+                "( datetime:DateTime -- datetime:DateTime )",
+                "The same instant in UTC; NULL for non-datetimes";
+            "WITH-TZ" => Self::word_with_tz,
+                "( datetime:DateTime timezone:string [options:WordOptions] -- datetime:DateTime )",
+                "The same wall clock in another IANA timezone (the instant changes); on_ambiguous: compatible (default), earlier, later or error";
+            "LOCAL-DATETIME" => Self::word_local_datetime,
+                "( date:Date time:Time timezone:string [options:WordOptions] -- datetime:DateTime )",
+                "The DateTime a date and wall clock name in an IANA timezone; on_ambiguous as for WITH-TZ";
+            "TZ@" => Self::word_tz_fetch,
+                "( datetime:DateTime -- timezone:string )",
+                "IANA name of a datetime's timezone; NULL for non-datetimes";
+            "UTC-OFFSET" => Self::word_utc_offset,
+                "( datetime:DateTime -- seconds:int )",
+                "Offset from UTC in seconds at that instant (east positive; -14400 for New York in summer); NULL for non-datetimes";
+            "SAME-INSTANT?" => Self::word_same_instant,
+                "( a:DateTime b:DateTime -- same:boolean )",
+                "Whether two datetimes are the same instant, whatever their timezones; false unless both are datetimes";
+            "SAME-WALL-TIME?" => Self::word_same_wall_time,
+                "( a:DateTime b:DateTime -- same:boolean )",
+                "Whether two datetimes show the same local date and time, whatever their timezones; false unless both are datetimes";
+        });
+    }
+
+    /// A wall clock resolved in `tz` under `policy`, as a value or the
+    /// error Disambiguation::Error asks for
+    fn local_datetime_value(
+        word: &str,
+        naive: NaiveDateTime,
+        tz: Tz,
+        policy: Disambiguation,
+    ) -> Result<ForthicValue, ForthicError> {
+        match resolve_local(naive, tz, policy) {
+            Ok(dt) => Ok(dt.map_or(ForthicValue::Null, ForthicValue::DateTime)),
+            Err(LocalTimeProblem::Ambiguous) => Err(ForthicError::invalid_operation(format!(
+                "{word}: {naive} is ambiguous in {} (repeated by a DST change)",
+                tz.name()
+            ))),
+            Err(LocalTimeProblem::Skipped) => Err(ForthicError::invalid_operation(format!(
+                "{word}: {naive} does not exist in {} (skipped by a DST change)",
+                tz.name()
+            ))),
+        }
+    }
+
+    /// >TZ: ( datetime timezone -- datetime ) — a view of the same instant
+    fn word_to_tz(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let [datetime, timezone] = context.pop_n::<2>()?;
+        let tz = timezone_arg(">TZ", 2, &timezone)?;
+        let result = match datetime {
+            ForthicValue::DateTime(dt) => convert_timezone(&dt.with_timezone(&Utc), tz.name())
+                .map_or(ForthicValue::Null, ForthicValue::DateTime),
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_to_utc(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let result = match context.stack_pop()? {
+            ForthicValue::DateTime(dt) => ForthicValue::DateTime(dt.with_timezone(&Tz::UTC)),
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// WITH-TZ: ( datetime timezone [options] -- datetime ) — keeps the
+    /// wall clock, so 09:00 in New York becomes 09:00 in Tokyo
+    fn word_with_tz(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "WITH-TZ";
+        let options = context.pop_options();
+        let [datetime, timezone] = context.pop_n::<2>()?;
+        let tz = timezone_arg(WORD, 2, &timezone)?;
+        let policy = Disambiguation::from_options(WORD, 3, options.as_ref())?;
+        let result = match datetime {
+            ForthicValue::DateTime(dt) => {
+                Self::local_datetime_value(WORD, dt.naive_local(), tz, policy)?
+            }
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// LOCAL-DATETIME: ( date time timezone [options] -- datetime ) — AT
+    /// with an explicit timezone and DST policy. A DateTime date operand
+    /// contributes its own-timezone calendar date, as with AT.
+    fn word_local_datetime(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "LOCAL-DATETIME";
+        let options = context.pop_options();
+        let [date, time, timezone] = context.pop_n::<3>()?;
+        let tz = timezone_arg(WORD, 3, &timezone)?;
+        let policy = Disambiguation::from_options(WORD, 4, options.as_ref())?;
+        let result = match (Self::as_calendar_date(&date), time) {
+            (Some(d), ForthicValue::Time(t)) => {
+                Self::local_datetime_value(WORD, d.and_time(t), tz, policy)?
+            }
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_tz_fetch(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let result = match context.stack_pop()? {
+            ForthicValue::DateTime(dt) => ForthicValue::String(dt.timezone().name().to_string()),
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_utc_offset(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let result = match context.stack_pop()? {
+            ForthicValue::DateTime(dt) => {
+                ForthicValue::Int(dt.offset().fix().local_minus_utc() as i64)
+            }
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_same_instant(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let [a, b] = context.pop_n::<2>()?;
+        let same = match (a, b) {
+            (ForthicValue::DateTime(a), ForthicValue::DateTime(b)) => a == b,
+            _ => false,
+        };
+        context.stack_push(ForthicValue::Bool(same));
+        Ok(())
+    }
+
+    fn word_same_wall_time(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let [a, b] = context.pop_n::<2>()?;
+        let same = match (a, b) {
+            (ForthicValue::DateTime(a), ForthicValue::DateTime(b)) => {
+                a.naive_local() == b.naive_local()
+            }
+            _ => false,
+        };
+        context.stack_push(ForthicValue::Bool(same));
+        Ok(())
+    }
+}
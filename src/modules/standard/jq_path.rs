@@ -26,12 +26,7 @@ pub(crate) enum PathSegment {
 }
 
 fn path_error(message: impl Into<String>) -> ForthicError {
-    ForthicError::InvalidOperation {
-        forthic: String::new(),
-        message: message.into(),
-        location: None,
-        cause: None,
-    }
+    ForthicError::invalid_operation(message)
 }
 
 /// Parse a path value (string or array) into segments
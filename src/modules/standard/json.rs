@@ -6,6 +6,7 @@
 // - Conversion: >JSON, JSON>
 // - Formatting: JSON-PRETTIFY
 
+use crate::convert::reject_sequence;
 use crate::errors::ForthicError;
 use crate::literals::ForthicValue;
 use crate::module::{register_words, InterpreterContext, Module};
@@ -54,6 +55,12 @@ impl JSONModule {
 
     fn word_to_json(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let val = context.stack_pop()?;
+        reject_sequence(">JSON", 1, &val)?;
+        if Self::holds_sequence(&val) {
+            return Err(ForthicError::invalid_operation(
+                ">JSON argument 1 holds a sequence: REALIZE first",
+            ));
+        }
 
         let json_val = Self::forthic_to_json(&val);
         let json_str = serde_json::to_string(&json_val).unwrap_or_else(|_| "null".to_string());
@@ -64,6 +71,7 @@ impl JSONModule {
 
     fn word_from_json(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let val = context.stack_pop()?;
+        reject_sequence("JSON>", 1, &val)?;
 
         let result = match val {
             ForthicValue::String(s) => {
@@ -95,6 +103,7 @@ impl JSONModule {
 
     fn word_json_prettify(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let val = context.stack_pop()?;
+        reject_sequence("JSON-PRETTIFY", 1, &val)?;
 
         let result = match val {
             ForthicValue::String(s) => {
@@ -120,6 +129,16 @@ impl JSONModule {
 
     // ===== Helper Functions =====
 
+    /// Whether a sequence sits anywhere inside an array or record
+    pub(crate) fn holds_sequence(val: &ForthicValue) -> bool {
+        match val {
+            ForthicValue::Sequence(_) => true,
+            ForthicValue::Array(arr) => arr.iter().any(Self::holds_sequence),
+            ForthicValue::Record(rec) => rec.values().any(Self::holds_sequence),
+            _ => false,
+        }
+    }
+
     /// Convert ForthicValue to serde_json::Value. Temporal values use the
     /// same ISO forms ts produces via Temporal.toJSON (times keep fractional
     /// seconds, zoned datetimes carry the bracketed timezone annotation).
@@ -6,13 +6,22 @@
 //! - Arithmetic: +, -, *, /, MOD
 //! - Aggregates: MEAN, MAX, MIN, SUM, PRODUCT
 //! - Type conversion: >INT, >FLOAT, ROUND, FLOOR, CEIL, FORMAT-FIXED
+//! - Locale formatting: FORMAT-NUMBER, PARSE-NUMBER (number_format.rs)
 //! - Math functions: ABS, SQRT, CLAMP
+//! - Scientific: POW, EXP, LN, LOG10, LOG2, SIN, COS, TAN, ASIN, ACOS, ATAN,
+//!   ATAN2, PI, E, SIGN (scientific.rs)
+//! - Integer: DIV, REM, GCD, LCM, IS-PRIME?, BIT-AND, BIT-OR, BIT-XOR,
+//!   BIT-NOT, SHL, SHR (scientific.rs)
 
+use crate::convert::{argument_error, reject_sequence, StackArgs};
 use crate::errors::ForthicError;
 use crate::literals::ForthicValue;
 use crate::module::{register_words, InterpreterContext, Module};
 use indexmap::IndexMap;
 
+mod number_format;
+mod scientific;
+
 /// MathModule provides mathematical operations
 pub struct MathModule {
     module: Module,
@@ -28,6 +37,9 @@ impl MathModule {
         Self::register_aggregate_words(&mut module);
         Self::register_conversion_words(&mut module);
         Self::register_math_functions(&mut module);
+        Self::register_scientific_words(&mut module);
+        Self::register_integer_words(&mut module);
+        Self::register_number_format_words(&mut module);
 
         Self { module }
     }
@@ -71,12 +83,9 @@ impl MathModule {
         // Strictly binary — an array operand errors, pointing at SUM
         // (matches forthic-ts and forthic-py).
         if matches!(a, ForthicValue::Array(_)) || matches!(b, ForthicValue::Array(_)) {
-            return Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: "+ takes two numbers. For an array of numbers, use SUM.".to_string(),
-                location: None,
-                cause: None,
-            });
+            return Err(ForthicError::invalid_operation(
+                "+ takes two numbers. For an array of numbers, use SUM.",
+            ));
         }
 
         let num_a = Self::to_number(&a).unwrap_or(0.0);
@@ -108,12 +117,9 @@ impl MathModule {
         // Strictly binary — an array operand errors, pointing at PRODUCT
         // (matches forthic-ts and forthic-py).
         if matches!(a, ForthicValue::Array(_)) || matches!(b, ForthicValue::Array(_)) {
-            return Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: "* takes two numbers. For an array of numbers, use PRODUCT.".to_string(),
-                location: None,
-                cause: None,
-            });
+            return Err(ForthicError::invalid_operation(
+                "* takes two numbers. For an array of numbers, use PRODUCT.",
+            ));
         }
 
         match (Self::to_number(&a), Self::to_number(&b)) {
@@ -188,6 +194,7 @@ impl MathModule {
 
     fn word_sum(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let val = context.stack_pop()?;
+        reject_sequence("SUM", 1, &val)?;
 
         if let ForthicValue::Array(arr) = val {
             let mut sum = 0.0;
@@ -205,6 +212,7 @@ impl MathModule {
 
     fn word_max(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let b = context.stack_pop()?;
+        reject_sequence("MAX", 1, &b)?;
 
         // Case 1: Array on top of stack
         if let ForthicValue::Array(arr) = b {
@@ -240,6 +248,7 @@ impl MathModule {
 
     fn word_min(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let b = context.stack_pop()?;
+        reject_sequence("MIN", 1, &b)?;
 
         // Case 1: Array on top of stack
         if let ForthicValue::Array(arr) = b {
@@ -283,6 +292,7 @@ impl MathModule {
     /// frequency, other fields dropped); anything else -> 0.
     fn word_mean(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let val = context.stack_pop()?;
+        reject_sequence("MEAN", 1, &val)?;
         context.stack_push(Self::mean_of(&val));
         Ok(())
     }
@@ -403,6 +413,7 @@ impl MathModule {
 
     fn word_to_int(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let val = context.stack_pop()?;
+        reject_sequence(">INT", 1, &val)?;
 
         let result = match val {
             ForthicValue::Int(i) => ForthicValue::Int(i),
@@ -532,6 +543,7 @@ impl MathModule {
     /// NULL/non-numeric element nulls the WHOLE result (SUM skips them).
     fn word_product(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let val = context.stack_pop()?;
+        reject_sequence("PRODUCT", 1, &val)?;
         let result = match val {
             ForthicValue::Array(arr) => {
                 let mut product = 1.0f64;
@@ -603,42 +615,26 @@ impl MathModule {
     /// "Infinity". ts's >=1e21 exponential-notation quirk is NOT
     /// reproduced — large values format in plain decimal (documented).
     fn word_format_fixed(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
-        let digits_val = context.stack_pop()?;
-        let num_val = context.stack_pop()?;
+        let [num_val, digits_val] = context.pop_n()?;
 
         if matches!(num_val, ForthicValue::Null) {
             context.stack_push(ForthicValue::Null);
             return Ok(());
         }
         let Some(num) = Self::to_number(&num_val) else {
-            return Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: "FORMAT-FIXED requires a number".to_string(),
-                location: None,
-                cause: None,
-            });
+            return Err(argument_error("FORMAT-FIXED", 1, None, "number", &num_val));
         };
         let digits = match &digits_val {
             ForthicValue::Null => 0,
             other => match Self::to_number(other) {
                 Some(d) => d.trunc() as i64,
-                None => {
-                    return Err(ForthicError::InvalidOperation {
-                        forthic: String::new(),
-                        message: "FORMAT-FIXED digits must be a number".to_string(),
-                        location: None,
-                        cause: None,
-                    })
-                }
+                None => return Err(argument_error("FORMAT-FIXED", 2, None, "number", other)),
             },
         };
         if !(0..=100).contains(&digits) {
-            return Err(ForthicError::InvalidOperation {
-                forthic: String::new(),
-                message: format!("FORMAT-FIXED digits must be between 0 and 100, got {digits}"),
-                location: None,
-                cause: None,
-            });
+            return Err(ForthicError::invalid_operation(format!(
+                "FORMAT-FIXED digits must be between 0 and 100, got {digits}"
+            )));
         }
 
         context.stack_push(ForthicValue::String(Self::to_fixed(num, digits as usize)));
@@ -657,7 +653,14 @@ impl MathModule {
             return if num > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
         }
         let factor = 10f64.powi(digits as i32);
-        let rounded = (num * factor).round() / factor;
+        let scaled = num * factor;
+        // Past 2^52 every f64 is a whole number, so there is nothing to
+        // round, and scaling a huge one overflows to infinity
+        let rounded = if scaled.is_finite() {
+            scaled.round() / factor
+        } else {
+            num
+        };
         format!("{rounded:.digits$}")
     }
 
@@ -674,7 +677,7 @@ impl MathModule {
     }
 
     /// Convert number to appropriate ForthicValue (Int or Float)
-    fn number_to_value(num: f64) -> ForthicValue {
+    pub(crate) fn number_to_value(num: f64) -> ForthicValue {
         // Collapse to Int only within the f64-exact integer range (2^53) —
         // beyond it `as i64` silently saturates (e.g. [1e18 100] PRODUCT)
         const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
@@ -0,0 +1,660 @@
+// Locale-aware number formatting and parsing
+//
+// - FORMAT-NUMBER: decimal, percent, currency, compact (1.2K) and
+//   scientific (1.23E4) styles with the locale's separators
+// - PARSE-NUMBER: the inverse, for text written in the same conventions
+//
+// Locale data is bundled (LOCALES below) rather than read from the host, so
+// output is the same on every machine. It follows CLDR for separators,
+// minimum grouping, currency and percent placement and the short compact
+// suffixes; currency symbols are the same in every locale. A bare language
+// ("de") picks that language's first locale, and "de_DE" reads as "de-DE".
+
+use super::MathModule;
+use crate::convert::{argument_error, StackArgs};
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{register_words, InterpreterContext, Module};
+use crate::word_options::WordOptions;
+
+struct Locale {
+    tag: &'static str,
+    decimal: char,
+    group: char,
+    /// CLDR minimumGroupingDigits: 2 leaves four-digit numbers ungrouped
+    min_grouping: usize,
+    /// Currency used when `.currency` is not given
+    currency: &'static str,
+    currency_prefix: bool,
+    currency_space: bool,
+    /// Appended to the number, including any space
+    percent: &'static str,
+    /// Thousand, million, billion and trillion suffixes; "" when the locale
+    /// does not abbreviate that magnitude
+    compact: [&'static str; 4],
+}
+
+const NBSP: &str = "\u{a0}";
+
+const EN_COMPACT: [&str; 4] = ["K", "M", "B", "T"];
+const DE_COMPACT: [&str; 4] = ["\u{a0}Tsd.", "\u{a0}Mio.", "\u{a0}Mrd.", "\u{a0}Bio."];
+
+const LOCALES: &[Locale] = &[
+    Locale {
+        tag: "en-US",
+        decimal: '.',
+        group: ',',
+        min_grouping: 1,
+        currency: "USD",
+        currency_prefix: true,
+        currency_space: false,
+        percent: "%",
+        compact: EN_COMPACT,
+    },
+    Locale {
+        tag: "en-GB",
+        decimal: '.',
+        group: ',',
+        min_grouping: 1,
+        currency: "GBP",
+        currency_prefix: true,
+        currency_space: false,
+        percent: "%",
+        compact: EN_COMPACT,
+    },
+    Locale {
+        tag: "de-DE",
+        decimal: ',',
+        group: '.',
+        min_grouping: 1,
+        currency: "EUR",
+        currency_prefix: false,
+        currency_space: true,
+        percent: "\u{a0}%",
+        compact: DE_COMPACT,
+    },
+    Locale {
+        tag: "de-CH",
+        decimal: '.',
+        group: '’',
+        min_grouping: 1,
+        currency: "CHF",
+        currency_prefix: true,
+        currency_space: true,
+        percent: "%",
+        compact: DE_COMPACT,
+    },
+    Locale {
+        tag: "fr-FR",
+        decimal: ',',
+        group: '\u{202f}',
+        min_grouping: 1,
+        currency: "EUR",
+        currency_prefix: false,
+        currency_space: true,
+        percent: "\u{202f}%",
+        compact: ["\u{a0}k", "\u{a0}M", "\u{a0}Md", "\u{a0}Bn"],
+    },
+    Locale {
+        tag: "es-ES",
+        decimal: ',',
+        group: '.',
+        min_grouping: 2,
+        currency: "EUR",
+        currency_prefix: false,
+        currency_space: true,
+        percent: "\u{a0}%",
+        compact: ["\u{a0}mil", "\u{a0}M", "\u{a0}mil\u{a0}M", "\u{a0}B"],
+    },
+    Locale {
+        tag: "it-IT",
+        decimal: ',',
+        group: '.',
+        min_grouping: 1,
+        currency: "EUR",
+        currency_prefix: false,
+        currency_space: true,
+        percent: "%",
+        compact: ["", "\u{a0}Mln", "\u{a0}Mrd", "\u{a0}Bln"],
+    },
+    Locale {
+        tag: "nl-NL",
+        decimal: ',',
+        group: '.',
+        min_grouping: 1,
+        currency: "EUR",
+        currency_prefix: true,
+        currency_space: true,
+        percent: "%",
+        compact: ["K", "\u{a0}mln.", "\u{a0}mld.", "\u{a0}bln."],
+    },
+    Locale {
+        tag: "pt-BR",
+        decimal: ',',
+        group: '.',
+        min_grouping: 1,
+        currency: "BRL",
+        currency_prefix: true,
+        currency_space: true,
+        percent: "%",
+        compact: ["\u{a0}mil", "\u{a0}mi", "\u{a0}bi", "\u{a0}tri"],
+    },
+    Locale {
+        tag: "sv-SE",
+        decimal: ',',
+        group: '\u{a0}',
+        min_grouping: 1,
+        currency: "SEK",
+        currency_prefix: false,
+        currency_space: true,
+        percent: "\u{a0}%",
+        compact: ["\u{a0}tn", "\u{a0}mn", "\u{a0}md", "\u{a0}bn"],
+    },
+    Locale {
+        tag: "pl-PL",
+        decimal: ',',
+        group: '\u{a0}',
+        min_grouping: 2,
+        currency: "PLN",
+        currency_prefix: false,
+        currency_space: true,
+        percent: "%",
+        compact: ["\u{a0}tys.", "\u{a0}mln", "\u{a0}mld", "\u{a0}bln"],
+    },
+];
+
+/// (code, symbol, minor-unit digits); other codes print as the code itself
+/// with two decimals
+const CURRENCIES: &[(&str, &str, usize)] = &[
+    ("USD", "$", 2),
+    ("EUR", "€", 2),
+    ("GBP", "£", 2),
+    ("JPY", "¥", 0),
+    ("CHF", "CHF", 2),
+    ("SEK", "kr", 2),
+    ("PLN", "zł", 2),
+    ("BRL", "R$", 2),
+    ("CAD", "CA$", 2),
+    ("AUD", "A$", 2),
+    ("INR", "₹", 2),
+    ("CNY", "CN¥", 2),
+    ("KRW", "₩", 0),
+];
+
+/// Default fraction digits where Intl.NumberFormat trims to a maximum
+const DEFAULT_MAX_DECIMALS: usize = 3;
+const MAX_DECIMALS: i64 = 20;
+
+#[derive(Clone, Copy)]
+enum Style {
+    Decimal,
+    Percent,
+    Currency,
+    Compact,
+    Scientific,
+}
+
+struct NumberFormat {
+    locale: &'static Locale,
+    style: Style,
+    /// Exact fraction digits; `None` uses the style's default
+    decimals: Option<usize>,
+    grouping: bool,
+    /// (symbol, minor-unit digits) for the currency style
+    currency: (String, usize),
+}
+
+impl NumberFormat {
+    fn from_options(word: &str, options: Option<&WordOptions>) -> Result<Self, ForthicError> {
+        let get = |key| options.and_then(|o| o.get(key));
+        let string = |key: &'static str| match get(key) {
+            None => Ok(None),
+            Some(ForthicValue::String(s)) => Ok(Some(s.as_str())),
+            Some(other) => Err(argument_error(word, 2, Some(key), "string", other)),
+        };
+
+        let locale = find_locale(word, string("locale")?)?;
+        let currency_code = string("currency")?;
+        let style = match string("style")? {
+            None if currency_code.is_some() => Style::Currency,
+            None | Some("decimal") => Style::Decimal,
+            Some("percent") => Style::Percent,
+            Some("currency") => Style::Currency,
+            Some("compact") => Style::Compact,
+            Some("scientific") => Style::Scientific,
+            Some(other) => {
+                return Err(ForthicError::invalid_operation(format!(
+                    "{word}: unknown style '{other}' (expected decimal, percent, currency, compact or scientific)"
+                )))
+            }
+        };
+        let decimals = match get("decimals") {
+            None => None,
+            Some(ForthicValue::Int(n)) if (0..=MAX_DECIMALS).contains(n) => Some(*n as usize),
+            Some(other) => {
+                return Err(argument_error(
+                    word,
+                    2,
+                    Some("decimals"),
+                    "int between 0 and 20",
+                    other,
+                ))
+            }
+        };
+        let grouping = match get("grouping") {
+            None => true,
+            Some(ForthicValue::Bool(b)) => *b,
+            Some(other) => return Err(argument_error(word, 2, Some("grouping"), "boolean", other)),
+        };
+        let code = currency_code.unwrap_or(locale.currency);
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(ForthicError::invalid_operation(format!(
+                "{word}: currency must be a three-letter code, got '{code}'"
+            )));
+        }
+        let code = code.to_ascii_uppercase();
+        let currency = CURRENCIES
+            .iter()
+            .find(|(c, _, _)| *c == code)
+            .map(|(_, symbol, minor)| (symbol.to_string(), *minor))
+            .unwrap_or((code, 2));
+
+        Ok(Self {
+            locale,
+            style,
+            decimals,
+            grouping,
+            currency,
+        })
+    }
+
+    fn format(&self, value: &ForthicValue, number: f64) -> String {
+        // Scale before the non-finite check: a huge ratio overflows to
+        // infinity as a percentage
+        let number = match self.style {
+            Style::Percent => number * 100.0,
+            _ => number,
+        };
+        if number.is_nan() {
+            return "NaN".to_string();
+        }
+        if number.is_infinite() {
+            return if number > 0.0 {
+                "Infinity"
+            } else {
+                "-Infinity"
+            }
+            .to_string();
+        }
+        // Ints print exactly where no scaling is involved
+        let exact = match value {
+            ForthicValue::Int(i) => Some(*i),
+            _ => None,
+        };
+        match self.style {
+            Style::Decimal => {
+                let (decimals, trim) = self.decimals_or(DEFAULT_MAX_DECIMALS);
+                self.fixed(number, exact, decimals, trim)
+            }
+            Style::Percent => {
+                let (decimals, trim) = self.decimals_or(0);
+                self.fixed(number, None, decimals, trim) + self.locale.percent
+            }
+            Style::Currency => self.currency(number, exact),
+            Style::Compact => self.compact(number),
+            Style::Scientific => self.scientific(number),
+        }
+    }
+
+    /// `.decimals` exactly, or up to `default` with trailing zeros trimmed
+    fn decimals_or(&self, default: usize) -> (usize, bool) {
+        match self.decimals {
+            Some(d) => (d, false),
+            None => (default, true),
+        }
+    }
+
+    /// Sign, grouped integer digits and fraction
+    fn fixed(&self, number: f64, exact: Option<i64>, decimals: usize, trim: bool) -> String {
+        let (int, frac) = match exact {
+            Some(i) if trim => (i.unsigned_abs().to_string(), String::new()),
+            Some(i) => (i.unsigned_abs().to_string(), "0".repeat(decimals)),
+            None => {
+                let text = MathModule::to_fixed(number.abs(), decimals);
+                let (int, frac) = text.split_once('.').unwrap_or((&text, ""));
+                let frac = if trim {
+                    frac.trim_end_matches('0')
+                } else {
+                    frac
+                };
+                (int.to_string(), frac.to_string())
+            }
+        };
+        // No "-0" when everything rounded away
+        let negative = number < 0.0 && (int.bytes().chain(frac.bytes())).any(|b| b != b'0');
+
+        let mut out = String::new();
+        if negative {
+            out.push('-');
+        }
+        out.push_str(&self.group(&int));
+        if !frac.is_empty() {
+            out.push(self.locale.decimal);
+            out.push_str(&frac);
+        }
+        out
+    }
+
+    fn group(&self, int: &str) -> String {
+        if !self.grouping || int.len() < 3 + self.locale.min_grouping {
+            return int.to_string();
+        }
+        let mut out = String::new();
+        for (i, digit) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i).is_multiple_of(3) {
+                out.push(self.locale.group);
+            }
+            out.push(digit);
+        }
+        out
+    }
+
+    fn currency(&self, number: f64, exact: Option<i64>) -> String {
+        let (symbol, minor) = &self.currency;
+        let body = self.fixed(number, exact, self.decimals.unwrap_or(*minor), false);
+        let (sign, digits) = match body.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", body.as_str()),
+        };
+        // A letter symbol never runs into the digits ("CHF 5.00", not "CHF5.00")
+        let space = if self.locale.currency_space
+            || symbol.chars().last().is_some_and(char::is_alphabetic)
+        {
+            NBSP
+        } else {
+            ""
+        };
+        if self.locale.currency_prefix {
+            format!("{sign}{symbol}{space}{digits}")
+        } else {
+            format!("{sign}{digits}{NBSP}{symbol}")
+        }
+    }
+
+    /// Largest magnitude the locale abbreviates, then two significant
+    /// digits by default (1.2K, 12K, 123K) as Intl's compact notation does
+    fn compact(&self, number: f64) -> String {
+        let suffixes = &self.locale.compact;
+        let magnitude_of = |abs: f64| {
+            (1..=suffixes.len())
+                .rev()
+                .find(|&m| !suffixes[m - 1].is_empty() && abs >= 1000f64.powi(m as i32))
+                .unwrap_or(0)
+        };
+        let mut magnitude = magnitude_of(number.abs());
+        loop {
+            let scaled = number / 1000f64.powi(magnitude as i32);
+            let (decimals, trim) = self.decimals_or(if scaled == 0.0 {
+                0
+            } else {
+                (1 - scaled.abs().log10().floor() as i64).clamp(0, MAX_DECIMALS) as usize
+            });
+            // 999.96K rounds to 1000K: say 1M instead
+            let rounded: f64 = MathModule::to_fixed(scaled.abs(), decimals)
+                .parse()
+                .unwrap_or(0.0);
+            if rounded >= 1000.0 && suffixes.get(magnitude).is_some_and(|s| !s.is_empty()) {
+                magnitude += 1;
+                continue;
+            }
+            let suffix = match magnitude {
+                0 => "",
+                m => suffixes[m - 1],
+            };
+            return self.fixed(scaled, None, decimals, trim) + suffix;
+        }
+    }
+
+    fn scientific(&self, number: f64) -> String {
+        let (decimals, trim) = self.decimals_or(DEFAULT_MAX_DECIMALS);
+        let abs = number.abs();
+        let mut exponent = if abs == 0.0 {
+            0
+        } else {
+            abs.log10().floor() as i32
+        };
+        // Round through to_fixed (ties away from zero, as Intl does); 9.9996
+        // rounds up to 10.000, so carry into the exponent
+        let mantissa = loop {
+            let text = MathModule::to_fixed(abs / 10f64.powi(exponent), decimals);
+            if text.parse::<f64>().is_ok_and(|m| m >= 10.0) {
+                exponent += 1;
+            } else {
+                break text;
+            }
+        };
+        let (int, frac) = mantissa.split_once('.').unwrap_or((&mantissa, ""));
+        let frac = if trim {
+            frac.trim_end_matches('0')
+        } else {
+            frac
+        };
+
+        let mut out = String::new();
+        if number < 0.0 {
+            out.push('-');
+        }
+        out.push_str(int);
+        if !frac.is_empty() {
+            out.push(self.locale.decimal);
+            out.push_str(frac);
+        }
+        format!("{out}E{exponent}")
+    }
+}
+
+fn find_locale(word: &str, tag: Option<&str>) -> Result<&'static Locale, ForthicError> {
+    let Some(tag) = tag else {
+        return Ok(&LOCALES[0]);
+    };
+    let wanted = tag.replace('_', "-");
+    LOCALES
+        .iter()
+        .find(|l| l.tag.eq_ignore_ascii_case(&wanted))
+        .or_else(|| {
+            LOCALES.iter().find(|l| {
+                l.tag
+                    .split('-')
+                    .next()
+                    .is_some_and(|language| language.eq_ignore_ascii_case(&wanted))
+            })
+        })
+        .ok_or_else(|| {
+            let supported: Vec<&str> = LOCALES.iter().map(|l| l.tag).collect();
+            ForthicError::invalid_operation(format!(
+                "{word}: unknown locale '{tag}' (supported: {})",
+                supported.join(", ")
+            ))
+        })
+}
+
+/// Read `text` as `locale` writes numbers; `None` when it is not a number
+fn parse_number(locale: &Locale, text: &str) -> Option<ForthicValue> {
+    // Space separators come in several widths (fr, sv, pl); drop them all
+    let compact_text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut s = compact_text.as_str();
+
+    let (leading_minus, rest) = strip_sign(s);
+    s = strip_currency(rest, true).unwrap_or(rest);
+    let (inner_minus, rest) = strip_sign(s);
+    if leading_minus && inner_minus {
+        return None;
+    }
+    let negative = leading_minus || inner_minus;
+    s = strip_currency(rest, false).unwrap_or(rest);
+
+    // Percent and compact suffixes shift the decimal exponent
+    let mut shift = 0;
+    if let Some(rest) = s.strip_suffix('%') {
+        s = rest;
+        shift = -2;
+    } else if let Some((rest, magnitude)) = strip_compact(locale, s) {
+        s = rest;
+        shift = 3 * magnitude as i32;
+    }
+
+    let (mantissa, exponent) = match s.find(['E', 'e']) {
+        Some(at) => (&s[..at], Some(s[at + 1..].parse::<i32>().ok()?)),
+        None => (s, None),
+    };
+    let (int, frac) = match mantissa.split_once(locale.decimal) {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (mantissa, None),
+    };
+    let int = ungroup(locale, int)?;
+    let frac = frac.unwrap_or("");
+    if (int.is_empty() && frac.is_empty()) || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let sign = if negative { "-" } else { "" };
+    if frac.is_empty() && exponent.is_none() && shift == 0 {
+        if let Ok(i) = format!("{sign}{int}").parse::<i64>() {
+            return Some(ForthicValue::Int(i));
+        }
+    }
+    let exponent = exponent.unwrap_or(0).checked_add(shift)?;
+    let number: f64 = format!("{sign}{int}.{frac}e{exponent}").parse().ok()?;
+    Some(MathModule::number_to_value(number))
+}
+
+fn strip_sign(s: &str) -> (bool, &str) {
+    if let Some(rest) = s.strip_prefix(['-', '\u{2212}']) {
+        (true, rest)
+    } else {
+        (false, s.strip_prefix('+').unwrap_or(s))
+    }
+}
+
+/// Remove a currency symbol, or any three-letter uppercase code, from the
+/// start or end of `s`
+fn strip_currency(s: &str, at_start: bool) -> Option<&str> {
+    let strip = |affix: &str| {
+        if at_start {
+            s.strip_prefix(affix)
+        } else {
+            s.strip_suffix(affix)
+        }
+    };
+    let mut symbols: Vec<&str> = CURRENCIES.iter().map(|(_, symbol, _)| *symbol).collect();
+    symbols.sort_by_key(|symbol| std::cmp::Reverse(symbol.len()));
+    if let Some(rest) = symbols.into_iter().find_map(strip) {
+        return Some(rest);
+    }
+    let code = if at_start {
+        s.get(..3)
+    } else {
+        s.get(s.len().checked_sub(3)?..)
+    }?;
+    if code.bytes().all(|b| b.is_ascii_uppercase()) {
+        strip(code)
+    } else {
+        None
+    }
+}
+
+/// Remove the locale's compact suffix (any case), returning the magnitude
+fn strip_compact<'a>(locale: &Locale, s: &'a str) -> Option<(&'a str, usize)> {
+    let lower = s.to_lowercase();
+    locale
+        .compact
+        .iter()
+        .enumerate()
+        .filter(|(_, suffix)| !suffix.is_empty())
+        .map(|(i, suffix)| {
+            let suffix: String = suffix.chars().filter(|c| !c.is_whitespace()).collect();
+            (i + 1, suffix.to_lowercase())
+        })
+        .filter(|(_, suffix)| lower.ends_with(suffix.as_str()))
+        .max_by_key(|(_, suffix)| suffix.len())
+        .and_then(|(magnitude, suffix)| {
+            let cut = s.len().checked_sub(suffix.len())?;
+            Some((s.get(..cut)?, magnitude))
+        })
+}
+
+/// Integer digits with valid group separators removed: the first group
+/// has one to three digits and every later one exactly three
+fn ungroup<'a>(locale: &Locale, int: &'a str) -> Option<std::borrow::Cow<'a, str>> {
+    let is_group = |c: char| c == locale.group || (locale.group == '’' && c == '\'');
+    if !int.contains(is_group) {
+        return int
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+            .then_some(std::borrow::Cow::Borrowed(int));
+    }
+    let groups: Vec<&str> = int.split(is_group).collect();
+    let valid = groups.iter().enumerate().all(|(i, group)| {
+        let len_ok = if i == 0 {
+            (1..=3).contains(&group.len())
+        } else {
+            group.len() == 3
+        };
+        len_ok && group.bytes().all(|b| b.is_ascii_digit())
+    });
+    valid.then(|| std::borrow::Cow::Owned(groups.concat()))
+}
+
+impl MathModule {
+    pub(super) fn register_number_format_words(module: &mut Module) {
+        register_words!(module, {
+            "FORMAT-NUMBER" => Self::word_format_number,
+                "( num:number [options:WordOptions] -- text:string )",
+                "Format for a locale. Options: .decimals, .grouping, .locale (default en-US), .style (decimal, percent, currency, compact, scientific), .currency";
+            "PARSE-NUMBER" => Self::word_parse_number,
+                "( text:string [options:WordOptions] -- num:number )",
+                "Read a number written in a locale's conventions (.locale); null when the text is not a number";
+        });
+    }
+
+    /// FORMAT-NUMBER: ( num [options] -- text ) — NULL is NULL and a
+    /// non-numeric value is an error, as for FORMAT-FIXED
+    fn word_format_number(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "FORMAT-NUMBER";
+        let options = context.pop_options();
+        let format = NumberFormat::from_options(WORD, options.as_ref())?;
+        let value = context.stack_pop()?;
+        if matches!(value, ForthicValue::Null) {
+            context.stack_push(ForthicValue::Null);
+            return Ok(());
+        }
+        let Some(number) = Self::to_number(&value) else {
+            return Err(argument_error(WORD, 1, None, "number", &value));
+        };
+        context.stack_push(ForthicValue::String(format.format(&value, number)));
+        Ok(())
+    }
+
+    /// PARSE-NUMBER: ( text [options] -- num ) — accepts the locale's
+    /// separators (grouping must be well-formed), a sign, any known
+    /// currency symbol or three-letter code, a percent sign (divides by
+    /// 100), the locale's compact suffixes and E notation. Whole numbers
+    /// come back as Int.
+    fn word_parse_number(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "PARSE-NUMBER";
+        let options = context.pop_options();
+        let tag = match options.as_ref().and_then(|o| o.get("locale")) {
+            None => None,
+            Some(ForthicValue::String(s)) => Some(s.as_str()),
+            Some(other) => return Err(argument_error(WORD, 2, Some("locale"), "string", other)),
+        };
+        let locale = find_locale(WORD, tag)?;
+        let result = match context.stack_pop()? {
+            ForthicValue::Null => ForthicValue::Null,
+            ForthicValue::String(s) => parse_number(locale, &s).unwrap_or(ForthicValue::Null),
+            other => return Err(argument_error(WORD, 1, None, "string", &other)),
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+}
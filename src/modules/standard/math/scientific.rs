@@ -0,0 +1,446 @@
+// Scientific and integer math words
+//
+// - Powers and logs: POW, EXP, LN, LOG10, LOG2
+// - Trigonometry (radians): SIN, COS, TAN, ASIN, ACOS, ATAN, ATAN2
+// - Constants: PI, E
+// - SIGN
+// - Integer division: DIV, REM (floored by default, .trunc to truncate)
+// - Number theory: GCD, LCM, IS-PRIME?
+// - Bits: BIT-AND, BIT-OR, BIT-XOR, BIT-NOT, SHL, SHR
+//
+// The float words follow SQRT: Int/Float/Bool operands are numbers, an
+// integral result collapses to Int, a domain error is NaN or an infinity
+// (as in JS Math), and a non-numeric operand is NULL. DIV and REM follow /:
+// exact on two Ints, through f64 otherwise, NULL on a zero divisor.
+//
+// The integer words need whole numbers: an integral Float such as 4.0 is
+// accepted, NULL is NULL, and anything else (2.5, a string) is an argument
+// error rather than a silent truncation.
+
+use super::MathModule;
+use crate::convert::{argument_error, StackArgs};
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{register_words, InterpreterContext, Module};
+
+/// Largest integer every f64 in between represents exactly (2^53)
+const MAX_SAFE_INTEGER: i64 = 9_007_199_254_740_992;
+
+impl MathModule {
+    pub(super) fn register_scientific_words(module: &mut Module) {
+        register_words!(module, {
+            "POW" => Self::word_pow,
+                "( base:number exponent:number -- power:number )",
+                "base raised to exponent (exact for Int base and non-negative Int exponent)";
+            "EXP" => Self::word_exp,
+                "( n:number -- e^n:number )",
+                "e raised to n";
+            "LN" => Self::word_ln,
+                "( n:number -- ln:number )",
+                "Natural logarithm (-Infinity at 0, NaN below)";
+            "LOG10" => Self::word_log10,
+                "( n:number -- log:number )",
+                "Base-10 logarithm";
+            "LOG2" => Self::word_log2,
+                "( n:number -- log:number )",
+                "Base-2 logarithm";
+            "SIN" => Self::word_sin,
+                "( radians:number -- sin:number )",
+                "Sine";
+            "COS" => Self::word_cos,
+                "( radians:number -- cos:number )",
+                "Cosine";
+            "TAN" => Self::word_tan,
+                "( radians:number -- tan:number )",
+                "Tangent";
+            "ASIN" => Self::word_asin,
+                "( n:number -- radians:number )",
+                "Arcsine (NaN outside [-1, 1])";
+            "ACOS" => Self::word_acos,
+                "( n:number -- radians:number )",
+                "Arccosine (NaN outside [-1, 1])";
+            "ATAN" => Self::word_atan,
+                "( n:number -- radians:number )",
+                "Arctangent";
+            "ATAN2" => Self::word_atan2,
+                "( y:number x:number -- radians:number )",
+                "Angle of the point (x, y) from the positive x axis, in (-PI, PI]";
+            "PI" => Self::word_pi,
+                "( -- pi:float )",
+                "The constant pi";
+            "E" => Self::word_e,
+                "( -- e:float )",
+                "Euler's number";
+            "SIGN" => Self::word_sign,
+                "( n:number -- sign:int )",
+                "-1, 0 or 1 by the sign of n (NaN stays NaN)";
+        });
+    }
+
+    pub(super) fn register_integer_words(module: &mut Module) {
+        register_words!(module, {
+            "DIV" => Self::word_div,
+                "( a:number b:number [options:WordOptions] -- quotient:number )",
+                "Integer quotient, rounded down (.trunc rounds toward zero). Null when b is 0";
+            "REM" => Self::word_rem,
+                "( a:number b:number [options:WordOptions] -- remainder:number )",
+                "Remainder matching DIV: a == b * quotient + remainder. Null when b is 0";
+            "GCD" => Self::word_gcd,
+                "( a:int b:int -- gcd:int )",
+                "Greatest common divisor (never negative; 0 GCD 0 is 0)";
+            "LCM" => Self::word_lcm,
+                "( a:int b:int -- lcm:int )",
+                "Least common multiple (never negative; 0 with any operand)";
+            "IS-PRIME?" => Self::word_is_prime,
+                "( n:int -- prime:boolean )",
+                "Whether n is a prime number";
+            "BIT-AND" => Self::word_bit_and,
+                "( a:int b:int -- result:int )",
+                "Bitwise AND";
+            "BIT-OR" => Self::word_bit_or,
+                "( a:int b:int -- result:int )",
+                "Bitwise OR";
+            "BIT-XOR" => Self::word_bit_xor,
+                "( a:int b:int -- result:int )",
+                "Bitwise exclusive OR";
+            "BIT-NOT" => Self::word_bit_not,
+                "( a:int -- result:int )",
+                "Bitwise complement (-a - 1)";
+            "SHL" => Self::word_shl,
+                "( a:int n:int -- result:int )",
+                "Shift left by n bits (0-63); bits shifted past the top are lost";
+            "SHR" => Self::word_shr,
+                "( a:int n:int -- result:int )",
+                "Arithmetic shift right by n bits (0-63); the sign is kept";
+        });
+    }
+
+    // ===== Float words =====
+
+    /// Pop one number, push `f(n)`; non-numeric is NULL
+    fn unary_float(
+        context: &mut dyn InterpreterContext,
+        f: fn(f64) -> f64,
+    ) -> Result<(), ForthicError> {
+        let val = context.stack_pop()?;
+        let result = match Self::to_number(&val) {
+            Some(n) => Self::number_to_value(f(n)),
+            None => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// POW: two Ints with a non-negative exponent multiply out exactly while
+    /// the result stays within the f64-exact range; otherwise powf
+    fn word_pow(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let [base, exponent] = context.pop_n()?;
+        let exact = match (&base, &exponent) {
+            (ForthicValue::Int(b), ForthicValue::Int(e)) => u32::try_from(*e)
+                .ok()
+                .and_then(|e| b.checked_pow(e))
+                .filter(|p| p.unsigned_abs() <= MAX_SAFE_INTEGER as u64),
+            _ => None,
+        };
+        let result = match (exact, Self::to_number(&base), Self::to_number(&exponent)) {
+            (Some(p), _, _) => ForthicValue::Int(p),
+            (None, Some(b), Some(e)) => Self::number_to_value(b.powf(e)),
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_exp(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::unary_float(context, f64::exp)
+    }
+
+    fn word_ln(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::unary_float(context, f64::ln)
+    }
+
+    fn word_log10(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::unary_float(context, f64::log10)
+    }
+
+    fn word_log2(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::unary_float(context, f64::log2)
+    }
+
+    fn word_sin(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::unary_float(context, f64::sin)
+    }
+
+    fn word_cos(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::unary_float(context, f64::cos)
+    }
+
+    fn word_tan(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::unary_float(context, f64::tan)
+    }
+
+    fn word_asin(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::unary_float(context, f64::asin)
+    }
+
+    fn word_acos(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::unary_float(context, f64::acos)
+    }
+
+    fn word_atan(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::unary_float(context, f64::atan)
+    }
+
+    fn word_atan2(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let [y, x] = context.pop_n()?;
+        let result = match (Self::to_number(&y), Self::to_number(&x)) {
+            (Some(y), Some(x)) => Self::number_to_value(y.atan2(x)),
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn word_pi(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        context.stack_push(ForthicValue::Float(std::f64::consts::PI));
+        Ok(())
+    }
+
+    fn word_e(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        context.stack_push(ForthicValue::Float(std::f64::consts::E));
+        Ok(())
+    }
+
+    fn word_sign(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let val = context.stack_pop()?;
+        let result = match Self::to_number(&val) {
+            Some(n) if n.is_nan() => ForthicValue::Float(f64::NAN),
+            Some(n) if n > 0.0 => ForthicValue::Int(1),
+            Some(n) if n < 0.0 => ForthicValue::Int(-1),
+            Some(_) => ForthicValue::Int(0),
+            None => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    // ===== Integer division =====
+
+    fn word_div(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::integer_division(context, "DIV", |quotient, _| quotient)
+    }
+
+    fn word_rem(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::integer_division(context, "REM", |_, remainder| remainder)
+    }
+
+    /// Shared by DIV and REM: compute (quotient, remainder) for the chosen
+    /// rounding and push the one `pick` selects
+    fn integer_division(
+        context: &mut dyn InterpreterContext,
+        word: &str,
+        pick: fn(ForthicValue, ForthicValue) -> ForthicValue,
+    ) -> Result<(), ForthicError> {
+        let truncate = context
+            .pop_options()
+            .and_then(|o| o.get_bool("trunc"))
+            .unwrap_or(false);
+        let [a, b] = context.pop_n()?;
+
+        let result = match (&a, &b) {
+            (_, ForthicValue::Int(0)) => ForthicValue::Null,
+            (ForthicValue::Int(a), ForthicValue::Int(b)) => {
+                // Only i64::MIN / -1 overflows
+                let (Some(mut q), Some(mut r)) = (a.checked_div(*b), a.checked_rem(*b)) else {
+                    return Err(overflow(word));
+                };
+                if !truncate && r != 0 && (r < 0) != (*b < 0) {
+                    q -= 1;
+                    r += b;
+                }
+                pick(ForthicValue::Int(q), ForthicValue::Int(r))
+            }
+            _ => match (Self::to_number(&a), Self::to_number(&b)) {
+                (Some(_), Some(0.0)) => ForthicValue::Null,
+                (Some(a), Some(b)) => {
+                    let q = if truncate {
+                        (a / b).trunc()
+                    } else {
+                        (a / b).floor()
+                    };
+                    pick(Self::number_to_value(q), Self::number_to_value(a - b * q))
+                }
+                _ => ForthicValue::Null,
+            },
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    // ===== Number theory and bits =====
+
+    /// A whole-number operand: Int, Bool, or an integral Float in the
+    /// f64-exact range; `None` for NULL
+    fn to_integer(
+        word: &str,
+        position: usize,
+        val: &ForthicValue,
+    ) -> Result<Option<i64>, ForthicError> {
+        match val {
+            ForthicValue::Null => Ok(None),
+            ForthicValue::Int(i) => Ok(Some(*i)),
+            ForthicValue::Bool(b) => Ok(Some(*b as i64)),
+            ForthicValue::Float(f) if f.fract() == 0.0 && f.abs() <= MAX_SAFE_INTEGER as f64 => {
+                Ok(Some(*f as i64))
+            }
+            other => Err(argument_error(word, position, None, "int", other)),
+        }
+    }
+
+    /// Pop two whole numbers and push `f(a, b)`; either NULL is NULL
+    fn binary_integer(
+        context: &mut dyn InterpreterContext,
+        word: &str,
+        f: impl Fn(i64, i64) -> Result<i64, ForthicError>,
+    ) -> Result<(), ForthicError> {
+        let [a, b] = context.pop_n()?;
+        let a = Self::to_integer(word, 1, &a)?;
+        let b = Self::to_integer(word, 2, &b)?;
+        let result = match (a, b) {
+            (Some(a), Some(b)) => ForthicValue::Int(f(a, b)?),
+            _ => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn gcd(a: i64, b: i64) -> u64 {
+        let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+
+    fn word_gcd(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::binary_integer(context, "GCD", |a, b| {
+            i64::try_from(Self::gcd(a, b)).map_err(|_| overflow("GCD"))
+        })
+    }
+
+    fn word_lcm(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::binary_integer(context, "LCM", |a, b| {
+            if a == 0 || b == 0 {
+                return Ok(0);
+            }
+            (a.unsigned_abs() / Self::gcd(a, b))
+                .checked_mul(b.unsigned_abs())
+                .and_then(|l| i64::try_from(l).ok())
+                .ok_or_else(|| overflow("LCM"))
+        })
+    }
+
+    fn word_is_prime(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let val = context.stack_pop()?;
+        let result = match Self::to_integer("IS-PRIME?", 1, &val)? {
+            Some(n) => ForthicValue::Bool(n > 0 && Self::is_prime(n as u64)),
+            None => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    /// Deterministic Miller-Rabin: these witnesses decide every n < 2^64
+    fn is_prime(n: u64) -> bool {
+        const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+        if n < 2 {
+            return false;
+        }
+        for p in WITNESSES {
+            if n.is_multiple_of(p) {
+                return n == p;
+            }
+        }
+        let mul = |a: u64, b: u64| (a as u128 * b as u128 % n as u128) as u64;
+        let pow = |mut base: u64, mut exp: u64| {
+            let mut acc = 1;
+            while exp > 0 {
+                if exp & 1 == 1 {
+                    acc = mul(acc, base);
+                }
+                base = mul(base, base);
+                exp >>= 1;
+            }
+            acc
+        };
+        let d = (n - 1) >> (n - 1).trailing_zeros();
+        WITNESSES.iter().all(|&a| {
+            let mut x = pow(a, d);
+            let mut d = d;
+            if x == 1 || x == n - 1 {
+                return true;
+            }
+            while d != n - 1 {
+                x = mul(x, x);
+                d <<= 1;
+                if x == n - 1 {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+
+    fn word_bit_and(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::binary_integer(context, "BIT-AND", |a, b| Ok(a & b))
+    }
+
+    fn word_bit_or(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::binary_integer(context, "BIT-OR", |a, b| Ok(a | b))
+    }
+
+    fn word_bit_xor(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::binary_integer(context, "BIT-XOR", |a, b| Ok(a ^ b))
+    }
+
+    fn word_bit_not(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let val = context.stack_pop()?;
+        let result = match Self::to_integer("BIT-NOT", 1, &val)? {
+            Some(n) => ForthicValue::Int(!n),
+            None => ForthicValue::Null,
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    fn shift_amount(word: &str, n: i64) -> Result<u32, ForthicError> {
+        u32::try_from(n)
+            .ok()
+            .filter(|n| *n < i64::BITS)
+            .ok_or_else(|| {
+                ForthicError::invalid_operation(format!(
+                    "{word}: shift must be between 0 and 63, got {n}"
+                ))
+            })
+    }
+
+    fn word_shl(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::binary_integer(
+            context,
+            "SHL",
+            |a, n| Ok(a << Self::shift_amount("SHL", n)?),
+        )
+    }
+
+    fn word_shr(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        Self::binary_integer(
+            context,
+            "SHR",
+            |a, n| Ok(a >> Self::shift_amount("SHR", n)?),
+        )
+    }
+}
+
+fn overflow(word: &str) -> ForthicError {
+    ForthicError::invalid_operation(format!("{word}: result overflows a 64-bit integer"))
+}
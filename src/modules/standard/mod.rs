@@ -9,6 +9,7 @@
 //! - **string**: Text processing (to be implemented)
 //! - **json**: Serialization (to be implemented)
 //! - **datetime**: Date/time operations (to be implemented)
+//! - **stats**: Descriptive statistics over arrays of numbers
 
 pub mod array;
 pub mod boolean;
@@ -18,6 +19,7 @@ pub(crate) mod jq_path;
 pub mod json;
 pub mod math;
 pub mod record;
+pub mod stats;
 pub mod string;
 
 pub use array::ArrayModule;
@@ -27,4 +29,5 @@ pub use datetime::DateTimeModule;
 pub use json::JSONModule;
 pub use math::MathModule;
 pub use record::RecordModule;
+pub use stats::StatsModule;
 pub use string::StringModule;
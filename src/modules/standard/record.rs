@@ -8,6 +8,7 @@
 // - Access: KEYS, VALUES
 
 use super::jq_path::{jq_del, jq_get, jq_set, parse_jq_path};
+use crate::convert::{argument_error, reject_sequence};
 use crate::errors::ForthicError;
 use crate::literals::ForthicValue;
 use crate::module::{register_words, InterpreterContext, Module};
@@ -106,6 +107,7 @@ impl RecordModule {
     fn word_jq_at(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let path = context.stack_pop()?;
         let container = context.stack_pop()?;
+        reject_sequence("JQ@", 1, &container)?;
         let segments = parse_jq_path(&path)?;
         context.stack_push(jq_get(&container, &segments));
         Ok(())
@@ -119,6 +121,7 @@ impl RecordModule {
         let path = context.stack_pop()?;
         let value = context.stack_pop()?;
         let mut container = context.stack_pop()?;
+        reject_sequence("JQ!", 1, &container)?;
         let segments = parse_jq_path(&path)?;
 
         if segments.is_empty() {
@@ -138,6 +141,7 @@ impl RecordModule {
     fn word_jq_del(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let path = context.stack_pop()?;
         let mut container = context.stack_pop()?;
+        reject_sequence("JQ-DEL", 1, &container)?;
         let segments = parse_jq_path(&path)?;
         if !segments.is_empty() && !matches!(container, ForthicValue::Null) {
             jq_del(&mut container, &segments)?;
@@ -169,6 +173,7 @@ impl RecordModule {
     fn word_pick(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let keys = context.stack_pop()?;
         let rec = context.stack_pop()?;
+        reject_sequence("PICK", 2, &keys)?;
         let mut out = IndexMap::new();
         if let (ForthicValue::Record(rec), ForthicValue::Array(keys)) = (&rec, &keys) {
             for key_val in keys {
@@ -188,6 +193,7 @@ impl RecordModule {
     fn word_omit(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let keys = context.stack_pop()?;
         let rec = context.stack_pop()?;
+        reject_sequence("OMIT", 2, &keys)?;
         let drop: Vec<String> = match &keys {
             ForthicValue::Array(keys) => keys.iter().map(Self::key_string).collect(),
             _ => Vec::new(),
@@ -223,6 +229,7 @@ impl RecordModule {
     fn word_delete(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let key = context.stack_pop()?;
         let container = context.stack_pop()?;
+        reject_sequence("DELETE", 1, &container)?;
         let result = match container {
             ForthicValue::Record(rec) => {
                 let mut copy = rec.clone();
@@ -234,16 +241,7 @@ impl RecordModule {
                 let idx = match &key {
                     ForthicValue::Int(i) => Some(*i),
                     ForthicValue::Float(f) if f.fract() == 0.0 => Some(*f as i64),
-                    _ => {
-                        return Err(ForthicError::InvalidOperation {
-                            forthic: String::new(),
-                            message: format!(
-                                "DELETE on an array requires an integer index, got {key:?}"
-                            ),
-                            location: None,
-                            cause: None,
-                        })
-                    }
+                    _ => return Err(argument_error("DELETE", 2, Some("index"), "int", &key)),
                 };
                 let mut copy = arr.clone();
                 if let Some(n) = idx {
@@ -282,6 +280,7 @@ impl RecordModule {
     /// duplicates win, keeping the first insertion position)
     fn word_entries_to_rec(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let pairs = context.stack_pop()?;
+        reject_sequence("ENTRIES>REC", 1, &pairs)?;
         let rec = Self::build_record(&pairs, "ENTRIES>REC")?;
         context.stack_push(ForthicValue::Record(rec));
         Ok(())
@@ -299,25 +298,15 @@ impl RecordModule {
         };
         for (i, pair) in pairs.iter().enumerate() {
             let ForthicValue::Array(kv) = pair else {
-                return Err(ForthicError::InvalidOperation {
-                    forthic: String::new(),
-                    message: format!(
-                        "{word_name} requires each pair to be a [key, value] array; pair at index {i} is {pair:?}"
-                    ),
-                    location: None,
-                    cause: None,
-                });
+                return Err(ForthicError::invalid_operation(format!(
+                    "{word_name} requires each pair to be a [key, value] array; pair at index {i} is {pair:?}"
+                )));
             };
             if kv.len() != 2 {
-                return Err(ForthicError::InvalidOperation {
-                    forthic: String::new(),
-                    message: format!(
-                        "{word_name} requires each pair to be a [key, value] array with exactly 2 elements; pair at index {i} has {}",
-                        kv.len()
-                    ),
-                    location: None,
-                    cause: None,
-                });
+                return Err(ForthicError::invalid_operation(format!(
+                    "{word_name} requires each pair to be a [key, value] array with exactly 2 elements; pair at index {i} has {}",
+                    kv.len()
+                )));
             }
             rec.insert(Self::key_string(&kv[0]), kv[1].clone());
         }
@@ -340,6 +329,7 @@ impl RecordModule {
 
     fn word_rec(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let key_vals = context.stack_pop()?;
+        reject_sequence("REC", 1, &key_vals)?;
 
         let result = match key_vals {
             ForthicValue::Array(pairs) => {
@@ -368,6 +358,8 @@ impl RecordModule {
     fn word_rec_at(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let field = context.stack_pop()?;
         let rec = context.stack_pop()?;
+        reject_sequence("REC@", 1, &rec)?;
+        reject_sequence("REC@", 2, &field)?;
 
         let result = match rec {
             ForthicValue::Record(record) => {
@@ -393,6 +385,8 @@ impl RecordModule {
         let field = context.stack_pop()?;
         let value = context.stack_pop()?;
         let rec = context.stack_pop()?;
+        reject_sequence("<REC!", 1, &rec)?;
+        reject_sequence("<REC!", 3, &field)?;
 
         let mut record = match rec {
             ForthicValue::Record(r) => r,
@@ -484,6 +478,8 @@ impl RecordModule {
         let new_keys_val = context.stack_pop()?;
         let old_keys_val = context.stack_pop()?;
         let container = context.stack_pop()?;
+        reject_sequence("RELABEL", 2, &old_keys_val)?;
+        reject_sequence("RELABEL", 3, &new_keys_val)?;
 
         let (old_keys, new_keys) = match (old_keys_val, new_keys_val) {
             (ForthicValue::Array(old), ForthicValue::Array(new)) => (old, new),
@@ -570,6 +566,7 @@ impl RecordModule {
 
     fn word_keys(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let container = context.stack_pop()?;
+        reject_sequence("KEYS", 1, &container)?;
 
         let result = match container {
             ForthicValue::Record(rec) => {
@@ -593,6 +590,7 @@ impl RecordModule {
 
     fn word_values(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let container = context.stack_pop()?;
+        reject_sequence("VALUES", 1, &container)?;
 
         let result = match container {
             ForthicValue::Record(rec) => {
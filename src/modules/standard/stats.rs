@@ -0,0 +1,556 @@
+//! Statistics module for Forthic
+//!
+//! Descriptive statistics over arrays of numbers.
+//!
+//! ## Categories
+//! - Location: MEDIAN, PERCENTILE, QUANTILES, MODE
+//! - Spread: VARIANCE, STDDEV, ZSCORES
+//! - Distribution: HISTOGRAM
+//! - Relationship: CORRELATION, LINEAR-REGRESSION
+//! - Smoothing: MOVING-AVERAGE
+//!
+//! Every word takes an optional `.skip_null` option. Without it a NULL
+//! element is an error like any other non-number; with it NULLs are left
+//! out (ZSCORES and MOVING-AVERAGE keep their positions, so the output
+//! still lines up with the input). Bools are not numbers here. NULL in
+//! place of the whole array is an empty array. Results follow MathModule's
+//! convention: integral values come back as ints.
+
+use super::math::MathModule;
+use crate::convert::{argument_error, type_label, StackArgs};
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{register_words, InterpreterContext, Module};
+use crate::word_options::WordOptions;
+use indexmap::IndexMap;
+
+/// HISTOGRAM and QUANTILES allocate one entry per requested bin; guard
+/// pathological counts (the same limit RANGE uses)
+const MAX_BINS: i64 = 10_000_000;
+
+/// How PERCENTILE and QUANTILES pick a value between two data points
+#[derive(Clone, Copy)]
+enum Interpolation {
+    Linear,
+    Lower,
+    Higher,
+    Nearest,
+    Midpoint,
+}
+
+impl Interpolation {
+    fn from_options(options: &Option<WordOptions>, word: &str) -> Result<Self, ForthicError> {
+        let method = options.as_ref().and_then(|o| o.get_string("method"));
+        Ok(match method.unwrap_or("linear") {
+            "linear" => Self::Linear,
+            "lower" => Self::Lower,
+            "higher" => Self::Higher,
+            "nearest" => Self::Nearest,
+            "midpoint" => Self::Midpoint,
+            other => {
+                return Err(ForthicError::invalid_operation(format!(
+                    "{word}: unknown method '{other}' (expected linear, lower, higher, nearest or midpoint)"
+                )))
+            }
+        })
+    }
+
+    /// The value at percentile `p` (0-100) of ascending `sorted` (non-empty)
+    fn at(self, sorted: &[f64], p: f64) -> f64 {
+        let rank = p / 100.0 * (sorted.len() - 1) as f64;
+        let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+        match self {
+            Self::Linear => sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64),
+            Self::Lower => sorted[lo],
+            Self::Higher => sorted[hi],
+            Self::Nearest => sorted[rank.round() as usize],
+            Self::Midpoint => (sorted[lo] + sorted[hi]) / 2.0,
+        }
+    }
+}
+
+/// StatsModule provides descriptive statistics
+pub struct StatsModule {
+    module: Module,
+}
+
+impl StatsModule {
+    /// Create a new StatsModule
+    pub fn new() -> Self {
+        let mut module = Module::new("stats".to_string());
+
+        Self::register_location_words(&mut module);
+        Self::register_spread_words(&mut module);
+        Self::register_relationship_words(&mut module);
+
+        Self { module }
+    }
+
+    /// Get the underlying module
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
+    /// Get a mutable reference to the underlying module
+    pub fn module_mut(&mut self) -> &mut Module {
+        &mut self.module
+    }
+
+    // ===== Location =====
+
+    fn register_location_words(module: &mut Module) {
+        register_words!(module, {
+            "MEDIAN" => Self::word_median,
+                "( numbers:number[] [options:WordOptions] -- median:number )",
+                "Middle value (mean of the middle two for an even count; null if empty). Options: skip_null (bool).";
+            "PERCENTILE" => Self::word_percentile,
+                "( numbers:number[] p:number [options:WordOptions] -- value:number )",
+                "Value at percentile p (0-100; null if empty). Options: method (\"linear\" default, \"lower\", \"higher\", \"nearest\", \"midpoint\"), skip_null (bool).";
+            "QUANTILES" => Self::word_quantiles,
+                "( numbers:number[] n:int [options:WordOptions] -- cuts:number[] )",
+                "The n-1 cut points dividing the data into n equal groups (4 gives quartiles). Options: method (as for PERCENTILE), skip_null (bool).";
+            "MODE" => Self::word_mode,
+                "( numbers:number[] [options:WordOptions] -- mode:number )",
+                "Most frequent value, the first seen on a tie (null if empty). Options: skip_null (bool).";
+            "HISTOGRAM" => Self::word_histogram,
+                "( numbers:number[] bins:any [options:WordOptions] -- bins:record[] )",
+                "Count values per bin, as {lower, upper, count} records. bins is a count of equal-width bins spanning min to max (min - 0.5 to max + 0.5 when all values are equal), or an array of ascending edges (values outside, NaN and infinities are not counted). Bins include their lower edge; the last also includes its upper edge. Options: skip_null (bool).";
+        });
+    }
+
+    fn word_median(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
+        let values = Self::pop_present(context, "MEDIAN", 1, &options)?;
+        let median = Self::sorted(values).map(|sorted| Interpolation::Linear.at(&sorted, 50.0));
+        context.stack_push(Self::number(median));
+        Ok(())
+    }
+
+    fn word_percentile(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "PERCENTILE";
+        let options = context.pop_options();
+        let p = context.pop_float_like(WORD, 2)?;
+        if !(0.0..=100.0).contains(&p) {
+            return Err(ForthicError::invalid_operation(format!(
+                "{WORD}: percentile must be between 0 and 100, got {p}"
+            )));
+        }
+        let method = Interpolation::from_options(&options, WORD)?;
+        let values = Self::pop_present(context, WORD, 1, &options)?;
+        let value = Self::sorted(values).map(|sorted| method.at(&sorted, p));
+        context.stack_push(Self::number(value));
+        Ok(())
+    }
+
+    fn word_quantiles(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "QUANTILES";
+        let options = context.pop_options();
+        let n = match context.stack_pop()? {
+            ForthicValue::Int(n) if n > 0 => n,
+            other => return Err(argument_error(WORD, 2, None, "positive int", &other)),
+        };
+        Self::check_bins(WORD, n)?;
+        let method = Interpolation::from_options(&options, WORD)?;
+        let values = Self::pop_present(context, WORD, 1, &options)?;
+        let cuts = match Self::sorted(values) {
+            None => Vec::new(),
+            Some(sorted) => (1..n)
+                .map(|i| Self::number(Some(method.at(&sorted, 100.0 * i as f64 / n as f64))))
+                .collect(),
+        };
+        context.stack_push(ForthicValue::Array(cuts));
+        Ok(())
+    }
+
+    fn word_mode(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
+        let values = Self::pop_present(context, "MODE", 1, &options)?;
+        // Keyed by bit pattern, with -0.0 folded into 0.0
+        let mut counts: IndexMap<u64, (f64, usize)> = IndexMap::new();
+        for value in values {
+            let key = if value == 0.0 { 0.0f64 } else { value }.to_bits();
+            counts.entry(key).or_insert((value, 0)).1 += 1;
+        }
+        let mut mode: Option<(f64, usize)> = None;
+        for (value, count) in counts.into_values() {
+            if mode.is_none_or(|(_, best)| count > best) {
+                mode = Some((value, count));
+            }
+        }
+        context.stack_push(Self::number(mode.map(|(value, _)| value)));
+        Ok(())
+    }
+
+    fn word_histogram(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "HISTOGRAM";
+        let options = context.pop_options();
+        let bins = context.stack_pop()?;
+        if let ForthicValue::Int(n) = bins {
+            Self::check_bins(WORD, n)?;
+        }
+        let mut values = Self::pop_present(context, WORD, 1, &options)?;
+        // NaN and the infinities fall in no finite bin, and would poison the
+        // min/max edges
+        values.retain(|value| value.is_finite());
+
+        let edges = match bins {
+            ForthicValue::Int(n) if n > 0 => {
+                let mut min = values.iter().copied().fold(f64::INFINITY, f64::min);
+                let mut max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                // Constant data has no width to split; center it in a unit
+                // range like numpy does
+                if min == max {
+                    min -= 0.5;
+                    max += 0.5;
+                }
+                if values.is_empty() {
+                    Vec::new()
+                } else {
+                    let width = (max - min) / n as f64;
+                    let mut edges: Vec<f64> = (0..n).map(|i| min + width * i as f64).collect();
+                    // The last edge is exactly max, whatever the rounding
+                    edges.push(max);
+                    edges
+                }
+            }
+            ForthicValue::Array(items) => {
+                let edges = Self::numbers_of(WORD, 2, &items, false)?
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+                if let Some(edge) = edges.iter().find(|edge| !edge.is_finite()) {
+                    return Err(ForthicError::invalid_operation(format!(
+                        "{WORD}: bin edges must be finite, got {edge}"
+                    )));
+                }
+                if edges.len() < 2 || edges.windows(2).any(|pair| pair[0] >= pair[1]) {
+                    return Err(ForthicError::invalid_operation(format!(
+                        "{WORD}: bin edges must be at least two strictly ascending numbers"
+                    )));
+                }
+                edges
+            }
+            other => {
+                return Err(argument_error(
+                    WORD,
+                    2,
+                    None,
+                    "positive int or number[]",
+                    &other,
+                ))
+            }
+        };
+
+        let bin_count = edges.len().saturating_sub(1);
+        let mut counts = vec![0i64; bin_count];
+        for value in values {
+            if bin_count == 0 || value < edges[0] || value > edges[bin_count] {
+                continue;
+            }
+            // The last edge <= value, capped so the top edge lands in the
+            // last bin
+            let bin = edges.partition_point(|edge| *edge <= value).min(bin_count);
+            if let Some(bin) = bin.checked_sub(1) {
+                counts[bin] += 1;
+            }
+        }
+        let histogram = counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let mut bin = IndexMap::new();
+                bin.insert("lower".to_string(), Self::number(Some(edges[i])));
+                bin.insert("upper".to_string(), Self::number(Some(edges[i + 1])));
+                bin.insert("count".to_string(), ForthicValue::Int(count));
+                ForthicValue::Record(bin)
+            })
+            .collect();
+        context.stack_push(ForthicValue::Array(histogram));
+        Ok(())
+    }
+
+    // ===== Spread =====
+
+    fn register_spread_words(module: &mut Module) {
+        register_words!(module, {
+            "VARIANCE" => Self::word_variance,
+                "( numbers:number[] [options:WordOptions] -- variance:number )",
+                "Sample variance (n - 1 denominator; null for fewer than 2 values). Options: population (bool: n denominator), skip_null (bool).";
+            "STDDEV" => Self::word_stddev,
+                "( numbers:number[] [options:WordOptions] -- stddev:number )",
+                "Sample standard deviation (null for fewer than 2 values). Options: population (bool), skip_null (bool).";
+            "ZSCORES" => Self::word_zscores,
+                "( numbers:number[] [options:WordOptions] -- zscores:number[] )",
+                "Each value's distance from the mean in standard deviations (sample by default; all null when the deviation is 0 or undefined). Options: population (bool), skip_null (bool: NULLs stay NULL in place).";
+            "MOVING-AVERAGE" => Self::word_moving_average,
+                "( numbers:number[] window:int [options:WordOptions] -- averages:number[] )",
+                "Mean of each run of window consecutive values (one result per full window). Options: skip_null (bool: NULLs are left out of each window's mean; an all-NULL window gives NULL).";
+        });
+    }
+
+    fn word_variance(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
+        let values = Self::pop_present(context, "VARIANCE", 1, &options)?;
+        let variance = Self::variance(&values, Self::population(&options));
+        context.stack_push(Self::number(variance));
+        Ok(())
+    }
+
+    fn word_stddev(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
+        let values = Self::pop_present(context, "STDDEV", 1, &options)?;
+        let variance = Self::variance(&values, Self::population(&options));
+        context.stack_push(Self::number(variance.map(f64::sqrt)));
+        Ok(())
+    }
+
+    fn word_zscores(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
+        let values = Self::pop_numbers(context, "ZSCORES", 1, &options)?;
+        let present: Vec<f64> = values.iter().flatten().copied().collect();
+        let stddev = Self::variance(&present, Self::population(&options))
+            .map(f64::sqrt)
+            .filter(|s| *s > 0.0);
+        let mean = Self::mean(&present);
+        let zscores = values
+            .into_iter()
+            .map(|value| match (value, mean, stddev) {
+                (Some(value), Some(mean), Some(stddev)) => {
+                    ForthicValue::Float((value - mean) / stddev)
+                }
+                _ => ForthicValue::Null,
+            })
+            .collect();
+        context.stack_push(ForthicValue::Array(zscores));
+        Ok(())
+    }
+
+    fn word_moving_average(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        const WORD: &str = "MOVING-AVERAGE";
+        let options = context.pop_options();
+        let window = match context.stack_pop()? {
+            ForthicValue::Int(n) if n > 0 => n as usize,
+            other => return Err(argument_error(WORD, 2, None, "positive int", &other)),
+        };
+        let values = Self::pop_numbers(context, WORD, 1, &options)?;
+        let averages = values
+            .windows(window)
+            .map(|run| {
+                let present: Vec<f64> = run.iter().flatten().copied().collect();
+                Self::number(Self::mean(&present))
+            })
+            .collect();
+        context.stack_push(ForthicValue::Array(averages));
+        Ok(())
+    }
+
+    // ===== Relationship =====
+
+    fn register_relationship_words(module: &mut Module) {
+        register_words!(module, {
+            "CORRELATION" => Self::word_correlation,
+                "( xs:number[] ys:number[] [options:WordOptions] -- r:number )",
+                "Pearson correlation of two equal-length arrays (null for fewer than 2 pairs or a constant array). Options: skip_null (bool: drops pairs with a NULL on either side).";
+            "LINEAR-REGRESSION" => Self::word_linear_regression,
+                "( xs:number[] ys:number[] [options:WordOptions] -- fit:record )",
+                "Least-squares line through (x, y) pairs, as {slope, intercept, r_squared, n} (null for fewer than 2 pairs or constant xs). Options: skip_null (bool: drops pairs with a NULL on either side).";
+        });
+    }
+
+    fn word_correlation(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
+        let pairs = Self::pop_pairs(context, "CORRELATION", &options)?;
+        let r = Self::fit(&pairs).and_then(|fit| fit.r);
+        context.stack_push(Self::number(r));
+        Ok(())
+    }
+
+    fn word_linear_regression(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
+        let pairs = Self::pop_pairs(context, "LINEAR-REGRESSION", &options)?;
+        let result = match Self::fit(&pairs) {
+            None => ForthicValue::Null,
+            Some(fit) => {
+                let mut record = IndexMap::new();
+                record.insert("slope".to_string(), Self::number(Some(fit.slope)));
+                record.insert("intercept".to_string(), Self::number(Some(fit.intercept)));
+                // A constant y is fit exactly
+                let r_squared = fit.r.map_or(1.0, |r| r * r);
+                record.insert("r_squared".to_string(), Self::number(Some(r_squared)));
+                record.insert("n".to_string(), ForthicValue::Int(pairs.len() as i64));
+                ForthicValue::Record(record)
+            }
+        };
+        context.stack_push(result);
+        Ok(())
+    }
+
+    // ===== Helpers =====
+
+    /// Pop an array argument as numbers; NULL elements are None when
+    /// skip_null is set, and an error otherwise
+    fn pop_numbers(
+        context: &mut dyn InterpreterContext,
+        word: &str,
+        position: usize,
+        options: &Option<WordOptions>,
+    ) -> Result<Vec<Option<f64>>, ForthicError> {
+        let skip_null = options
+            .as_ref()
+            .and_then(|o| o.get_bool("skip_null"))
+            .unwrap_or(false);
+        match context.stack_pop()? {
+            ForthicValue::Null => Ok(Vec::new()),
+            ForthicValue::Array(items) => Self::numbers_of(word, position, &items, skip_null),
+            other => Err(argument_error(word, position, None, "number[]", &other)),
+        }
+    }
+
+    /// The numbers of an array argument, with NULLs (if allowed) left out
+    fn pop_present(
+        context: &mut dyn InterpreterContext,
+        word: &str,
+        position: usize,
+        options: &Option<WordOptions>,
+    ) -> Result<Vec<f64>, ForthicError> {
+        Ok(Self::pop_numbers(context, word, position, options)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    fn numbers_of(
+        word: &str,
+        position: usize,
+        items: &[ForthicValue],
+        skip_null: bool,
+    ) -> Result<Vec<Option<f64>>, ForthicError> {
+        items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| match item {
+                ForthicValue::Int(n) => Ok(Some(*n as f64)),
+                ForthicValue::Float(f) => Ok(Some(*f)),
+                ForthicValue::Null if skip_null => Ok(None),
+                other => {
+                    let hint = if matches!(other, ForthicValue::Null) {
+                        " (use [.skip_null TRUE] to leave NULLs out)"
+                    } else {
+                        ""
+                    };
+                    Err(ForthicError::invalid_operation(format!(
+                        "{word}: element {i} of argument {position} must be a number, got {}{hint}",
+                        type_label(other)
+                    )))
+                }
+            })
+            .collect()
+    }
+
+    /// xs and ys, paired up; a pair with a NULL is dropped (skip_null only)
+    fn pop_pairs(
+        context: &mut dyn InterpreterContext,
+        word: &str,
+        options: &Option<WordOptions>,
+    ) -> Result<Vec<(f64, f64)>, ForthicError> {
+        let ys = Self::pop_numbers(context, word, 2, options)?;
+        let xs = Self::pop_numbers(context, word, 1, options)?;
+        if xs.len() != ys.len() {
+            return Err(ForthicError::invalid_operation(format!(
+                "{word}: xs and ys must be the same length, got {} and {}",
+                xs.len(),
+                ys.len()
+            )));
+        }
+        Ok(xs
+            .into_iter()
+            .zip(ys)
+            .filter_map(|pair| match pair {
+                (Some(x), Some(y)) => Some((x, y)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Refuse a bin or quantile count above MAX_BINS
+    fn check_bins(word: &str, n: i64) -> Result<(), ForthicError> {
+        if n > MAX_BINS {
+            return Err(ForthicError::invalid_operation(format!(
+                "{word} count {n} is too large (limit {MAX_BINS})"
+            )));
+        }
+        Ok(())
+    }
+
+    fn sorted(mut values: Vec<f64>) -> Option<Vec<f64>> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        Some(values)
+    }
+
+    fn mean(values: &[f64]) -> Option<f64> {
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    }
+
+    fn population(options: &Option<WordOptions>) -> bool {
+        options
+            .as_ref()
+            .and_then(|o| o.get_bool("population"))
+            .unwrap_or(false)
+    }
+
+    fn variance(values: &[f64], population: bool) -> Option<f64> {
+        let mean = Self::mean(values)?;
+        let denominator = if population {
+            values.len()
+        } else {
+            values.len().checked_sub(1).filter(|d| *d > 0)?
+        };
+        let squares: f64 = values.iter().map(|v| (v - mean).powi(2)).sum();
+        Some(squares / denominator as f64)
+    }
+
+    /// Least-squares fit; None without two pairs or with constant xs
+    fn fit(pairs: &[(f64, f64)]) -> Option<Fit> {
+        if pairs.len() < 2 {
+            return None;
+        }
+        let n = pairs.len() as f64;
+        let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+        for (x, y) in pairs {
+            sxx += (x - mean_x).powi(2);
+            syy += (y - mean_y).powi(2);
+            sxy += (x - mean_x) * (y - mean_y);
+        }
+        if sxx == 0.0 {
+            return None;
+        }
+        let slope = sxy / sxx;
+        Some(Fit {
+            slope,
+            intercept: mean_y - slope * mean_x,
+            r: (syy > 0.0).then(|| sxy / (sxx * syy).sqrt()),
+        })
+    }
+
+    fn number(value: Option<f64>) -> ForthicValue {
+        value.map_or(ForthicValue::Null, MathModule::number_to_value)
+    }
+}
+
+/// A least-squares line; `r` is None when y is constant
+struct Fit {
+    slope: f64,
+    intercept: f64,
+    r: Option<f64>,
+}
+
+impl Default for StatsModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -9,6 +9,7 @@
 // - Pattern: REPLACE
 // - Constants: /N, /R, /T
 
+use crate::convert::{argument_error, reject_sequence};
 use crate::errors::ForthicError;
 use crate::literals::ForthicValue;
 use crate::module::{register_words, InterpreterContext, Module};
@@ -65,6 +66,12 @@ impl StringModule {
 
     fn word_to_str(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let val = context.stack_pop()?;
+        reject_sequence(">STR", 1, &val)?;
+        if crate::modules::standard::json::JSONModule::holds_sequence(&val) {
+            return Err(ForthicError::invalid_operation(
+                ">STR argument 1 holds a sequence: REALIZE first",
+            ));
+        }
         context.stack_push(ForthicValue::String(Self::stringify(&val)));
         Ok(())
     }
@@ -246,6 +253,7 @@ impl StringModule {
     fn word_join(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let sep = context.stack_pop()?;
         let strings = context.stack_pop()?;
+        reject_sequence("JOIN", 1, &strings)?;
 
         let result = match (strings, sep) {
             (ForthicValue::Array(arr), ForthicValue::String(sep_str)) => {
@@ -284,14 +292,12 @@ impl StringModule {
                 ForthicValue::String(parts.join(""))
             }
             other => {
-                return Err(ForthicError::InvalidOperation {
-                    forthic: String::new(),
-                    message: format!(
-                        "CONCAT requires an array of strings (got {other:?}). Wrap two strings as [s1 s2] CONCAT."
-                    ),
-                    location: None,
-                    cause: None,
-                })
+                return Err(Self::type_error(
+                    "CONCAT",
+                    "array",
+                    &other,
+                    "Wrap two strings as [s1 s2] CONCAT.",
+                ))
             }
         };
 
@@ -373,13 +379,17 @@ impl StringModule {
         });
     }
 
-    fn type_error(word: &str, hint: &str) -> ForthicError {
-        ForthicError::InvalidOperation {
-            forthic: String::new(),
-            message: format!("{word} requires a string. {hint}"),
-            location: None,
-            cause: None,
+    /// Argument 1 has the wrong type; `hint` points at the word to use
+    /// instead
+    fn type_error(word: &str, expected: &str, value: &ForthicValue, hint: &str) -> ForthicError {
+        let mut error = argument_error(word, 1, None, expected, value);
+        if let ForthicError::InvalidOperation { message, .. } = &mut error {
+            if !hint.is_empty() {
+                message.push_str(". ");
+                message.push_str(hint);
+            }
         }
+        error
     }
 
     /// Compile a pattern with a clean error (ts throws a raw SyntaxError).
@@ -387,12 +397,8 @@ impl StringModule {
     /// \d/\w classes are Unicode-aware (ts's are ASCII without the u
     /// flag) — accepted divergence.
     fn compile(pattern: &str) -> Result<Regex, ForthicError> {
-        Regex::new(pattern).map_err(|e| ForthicError::InvalidOperation {
-            forthic: String::new(),
-            message: format!("Invalid regex '{pattern}': {e}"),
-            location: None,
-            cause: None,
-        })
+        Regex::new(pattern)
+            .map_err(|e| ForthicError::invalid_operation(format!("Invalid regex '{pattern}': {e}")))
     }
 
     /// Normalize JS replacement syntax for the rs regex engine: `$&` means
@@ -458,9 +464,11 @@ impl StringModule {
         let length = match value {
             ForthicValue::Null => 0,
             ForthicValue::String(s) => s.chars().count() as i64,
-            _ => {
+            other => {
                 return Err(Self::type_error(
                     "STR-LENGTH",
+                    "string",
+                    &other,
                     "For arrays/records, use LENGTH.",
                 ))
             }
@@ -485,7 +493,14 @@ impl StringModule {
                     chars[a..b].iter().collect()
                 }
             }
-            _ => return Err(Self::type_error("SUBSTR", "For arrays/records, use SLICE.")),
+            other => {
+                return Err(Self::type_error(
+                    "SUBSTR",
+                    "string",
+                    &other,
+                    "For arrays/records, use SLICE.",
+                ))
+            }
         };
         context.stack_push(ForthicValue::String(result));
         Ok(())
@@ -499,7 +514,7 @@ impl StringModule {
         let s = match value {
             ForthicValue::Null => String::new(),
             ForthicValue::String(s) => s,
-            _ => return Err(Self::type_error("SPLICE", "")),
+            other => return Err(Self::type_error("SPLICE", "string", &other, "")),
         };
         let ins = match newval {
             ForthicValue::Null => String::new(),
@@ -662,6 +677,7 @@ impl StringModule {
 
     fn word_unlines(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let value = context.stack_pop()?;
+        reject_sequence("UNLINES", 1, &value)?;
         let result = match value {
             ForthicValue::Array(lines) => lines
                 .iter()
@@ -683,6 +699,7 @@ impl StringModule {
     fn word_grep(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let pattern = context.stack_pop()?;
         let value = context.stack_pop()?;
+        reject_sequence("GREP", 1, &value)?;
         let result = match (&value, &pattern) {
             (ForthicValue::Array(items), ForthicValue::String(p)) => {
                 let re = Self::compile(p)?;
@@ -703,6 +720,7 @@ impl StringModule {
     fn word_grep_v(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
         let pattern = context.stack_pop()?;
         let value = context.stack_pop()?;
+        reject_sequence("GREP-V", 1, &value)?;
         let result = match (&value, &pattern) {
             (ForthicValue::Array(items), ForthicValue::String(p)) => {
                 let re = Self::compile(p)?;
@@ -725,6 +743,7 @@ impl StringModule {
         let repl = context.stack_pop()?;
         let pattern = context.stack_pop()?;
         let value = context.stack_pop()?;
+        reject_sequence("SED", 1, &value)?;
         let result = match (&value, &pattern) {
             (ForthicValue::Array(items), ForthicValue::String(p)) => {
                 let re = Self::compile(p)?;
@@ -756,6 +775,7 @@ impl StringModule {
         let field_val = context.stack_pop()?;
         let sep = context.stack_pop()?;
         let value = context.stack_pop()?;
+        reject_sequence("CUT", 1, &value)?;
 
         let field = match &field_val {
             ForthicValue::Int(i) => Some(*i),
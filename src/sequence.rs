@@ -0,0 +1,337 @@
+//! Sequence - a lazy, pull-based stream of values
+//!
+//! # Overview
+//!
+//! MAP, FILTER and friends materialize a full array at every step. A
+//! Sequence instead records a *source* (an array, an integer range, or a
+//! host iterator) and a chain of *stages* (map, filter, take, ...); nothing
+//! runs until a consumer pulls items, and then each item flows through the
+//! whole chain before the next one is read. No intermediate arrays are
+//! built, and consumers that stop early (TAKE, FIND, TAKE-WHILE) stop
+//! reading the source.
+//!
+//! # Usage in Forthic
+//!
+//! ```forthic
+//! 1 1000000000 [.lazy TRUE] ~> RANGE "3 *" MAP "2 MOD 0 ==" FILTER 5 TAKE REALIZE
+//! [1 2 3 4 5] LAZY "3 <" DROP-WHILE 2 CHUNKED REALIZE   # [[3 4] [5]]
+//! ```
+//!
+//! # Host API
+//!
+//! A host pushes any Rust iterator as a sequence:
+//!
+//! ```
+//! use forthic::Interpreter;
+//!
+//! let mut interp = Interpreter::standard("UTC");
+//! interp.push_sequence((1..).map(|n: i64| n * n));
+//! interp.run("'10 >' FIND").unwrap();
+//! ```
+//!
+//! # Semantics
+//!
+//! - A sequence value is immutable: adding a stage returns a new sequence
+//!   and leaves the original usable.
+//! - Stage code runs when items are pulled, on whatever stack is current at
+//!   that point, exactly as it would inside MAP.
+//! - Array and range sources can be pulled any number of times. A host
+//!   iterator is read once: copies of the sequence share it, so a second
+//!   pull continues where the first stopped.
+//! - Equality is identity: two sequences are equal when they share a source
+//!   and have the same stages.
+
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::InterpreterContext;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+type HostIter = Box<dyn Iterator<Item = ForthicValue> + Send>;
+
+/// Where a sequence's items come from
+enum Source {
+    Items(Vec<ForthicValue>),
+    /// Inclusive integer range
+    Range {
+        start: i64,
+        end: i64,
+    },
+    Host(Mutex<HostIter>),
+}
+
+/// One lazy step applied to each item as it is pulled
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stage {
+    /// Replace each item with the result of running the code on it
+    Map(String),
+    /// Keep items the code finds truthy
+    Filter(String),
+    /// Keep items while the code finds them truthy, then stop
+    TakeWhile(String),
+    /// Skip items while the code finds them truthy, then keep the rest
+    DropWhile(String),
+    /// Keep at most this many items
+    Take(usize),
+    /// Skip this many items
+    Skip(usize),
+    /// Group items into arrays of this size (the last may be shorter)
+    Chunked(usize),
+}
+
+/// A lazy sequence of values (see the module docs)
+#[derive(Clone)]
+pub struct Sequence {
+    source: Arc<Source>,
+    stages: Arc<Vec<Stage>>,
+}
+
+impl Sequence {
+    /// A sequence over the items of an array
+    pub fn from_items(items: Vec<ForthicValue>) -> Self {
+        Self::with_source(Source::Items(items))
+    }
+
+    /// The integers `start..=end` (empty if `end < start`)
+    pub fn range(start: i64, end: i64) -> Self {
+        Self::with_source(Source::Range { start, end })
+    }
+
+    /// A sequence that reads a host iterator, once
+    pub fn from_iterator<I>(items: I) -> Self
+    where
+        I: Iterator<Item = ForthicValue> + Send + 'static,
+    {
+        Self::with_source(Source::Host(Mutex::new(Box::new(items))))
+    }
+
+    fn with_source(source: Source) -> Self {
+        Self {
+            source: Arc::new(source),
+            stages: Arc::new(Vec::new()),
+        }
+    }
+
+    /// This sequence with one more stage
+    pub fn then(&self, stage: Stage) -> Self {
+        let mut stages = self.stages.to_vec();
+        stages.push(stage);
+        Self {
+            source: Arc::clone(&self.source),
+            stages: Arc::new(stages),
+        }
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    /// Start pulling items
+    pub fn cursor(&self) -> Cursor {
+        let source = match &*self.source {
+            Source::Items(_) => SourceCursor::Items(0),
+            Source::Range { start, .. } => SourceCursor::Range(Some(*start)),
+            Source::Host(_) => SourceCursor::Host,
+        };
+        let states = self
+            .stages
+            .iter()
+            .map(|stage| match stage {
+                Stage::Take(n) | Stage::Skip(n) => StageState::Remaining(*n),
+                _ => StageState::Active,
+            })
+            .collect();
+        Cursor {
+            sequence: self.clone(),
+            source,
+            states,
+        }
+    }
+
+    /// Pull every item into an array. Fails once more than `limit` items
+    /// have been pulled, so an endless sequence cannot exhaust memory.
+    pub fn realize(
+        &self,
+        context: &mut dyn InterpreterContext,
+        limit: usize,
+    ) -> Result<Vec<ForthicValue>, ForthicError> {
+        let mut cursor = self.cursor();
+        let mut items = Vec::new();
+        while let Some(item) = cursor.next(context)? {
+            if items.len() == limit {
+                return Err(ForthicError::invalid_operation(format!(
+                    "Sequence has more than {limit} items; TAKE fewer first"
+                )));
+            }
+            items.push(item);
+        }
+        Ok(items)
+    }
+}
+
+impl fmt::Debug for Sequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match &*self.source {
+            Source::Items(items) => format!("{} items", items.len()),
+            Source::Range { start, end } => format!("{start}..={end}"),
+            Source::Host(_) => "host iterator".to_string(),
+        };
+        f.debug_struct("Sequence")
+            .field("source", &source)
+            .field("stages", &self.stages)
+            .finish()
+    }
+}
+
+impl PartialEq for Sequence {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.source, &other.source) && self.stages == other.stages
+    }
+}
+
+enum SourceCursor {
+    Items(usize),
+    /// The next integer, or None once past the end
+    Range(Option<i64>),
+    Host,
+}
+
+enum StageState {
+    Active,
+    /// Items left to take or skip
+    Remaining(usize),
+    /// TAKE-WHILE has stopped, or DROP-WHILE has stopped dropping
+    Done,
+}
+
+/// A position in a sequence; each `next` pulls one item through every stage
+pub struct Cursor {
+    sequence: Sequence,
+    source: SourceCursor,
+    states: Vec<StageState>,
+}
+
+impl Cursor {
+    /// The next item, or None at the end
+    pub fn next(
+        &mut self,
+        context: &mut dyn InterpreterContext,
+    ) -> Result<Option<ForthicValue>, ForthicError> {
+        self.pull(self.states.len(), context)
+    }
+
+    /// The next item out of the first `depth` stages
+    fn pull(
+        &mut self,
+        depth: usize,
+        context: &mut dyn InterpreterContext,
+    ) -> Result<Option<ForthicValue>, ForthicError> {
+        if depth == 0 {
+            return Ok(self.pull_source());
+        }
+        let index = depth - 1;
+        let stages = Arc::clone(&self.sequence.stages);
+        match &stages[index] {
+            Stage::Map(code) => match self.pull(index, context)? {
+                Some(item) => Ok(Some(run_code(context, item, code)?)),
+                None => Ok(None),
+            },
+            Stage::Filter(code) => {
+                while let Some(item) = self.pull(index, context)? {
+                    if run_code(context, item.clone(), code)?.is_truthy() {
+                        return Ok(Some(item));
+                    }
+                }
+                Ok(None)
+            }
+            Stage::TakeWhile(code) => {
+                if let StageState::Done = self.states[index] {
+                    return Ok(None);
+                }
+                match self.pull(index, context)? {
+                    Some(item) if run_code(context, item.clone(), code)?.is_truthy() => {
+                        Ok(Some(item))
+                    }
+                    _ => {
+                        self.states[index] = StageState::Done;
+                        Ok(None)
+                    }
+                }
+            }
+            Stage::DropWhile(code) => {
+                if let StageState::Done = self.states[index] {
+                    return self.pull(index, context);
+                }
+                while let Some(item) = self.pull(index, context)? {
+                    if !run_code(context, item.clone(), code)?.is_truthy() {
+                        self.states[index] = StageState::Done;
+                        return Ok(Some(item));
+                    }
+                }
+                Ok(None)
+            }
+            Stage::Take(_) => match self.states[index] {
+                StageState::Remaining(0) => Ok(None),
+                StageState::Remaining(n) => {
+                    self.states[index] = StageState::Remaining(n - 1);
+                    self.pull(index, context)
+                }
+                _ => unreachable!("TAKE keeps a count"),
+            },
+            Stage::Skip(_) => {
+                while let StageState::Remaining(n) = self.states[index] {
+                    if n == 0 {
+                        break;
+                    }
+                    self.states[index] = StageState::Remaining(n - 1);
+                    if self.pull(index, context)?.is_none() {
+                        return Ok(None);
+                    }
+                }
+                self.pull(index, context)
+            }
+            Stage::Chunked(size) => {
+                // The size is caller-supplied; grow as items arrive rather
+                // than reserving it up front
+                let mut chunk = Vec::new();
+                while chunk.len() < *size {
+                    match self.pull(index, context)? {
+                        Some(item) => chunk.push(item),
+                        None => break,
+                    }
+                }
+                Ok((!chunk.is_empty()).then_some(ForthicValue::Array(chunk)))
+            }
+        }
+    }
+
+    fn pull_source(&mut self) -> Option<ForthicValue> {
+        match (&*self.sequence.source, &mut self.source) {
+            (Source::Items(items), SourceCursor::Items(next)) => {
+                let item = items.get(*next).cloned();
+                *next += 1;
+                item
+            }
+            (Source::Range { end, .. }, SourceCursor::Range(next)) => {
+                let current = (*next).filter(|n| n <= end)?;
+                *next = current.checked_add(1);
+                Some(ForthicValue::Int(current))
+            }
+            (Source::Host(iter), SourceCursor::Host) => {
+                iter.lock().unwrap_or_else(|e| e.into_inner()).next()
+            }
+            _ => unreachable!("cursor matches its source"),
+        }
+    }
+}
+
+/// Push the item, run the code, pop its result (as MAP does per item)
+fn run_code(
+    context: &mut dyn InterpreterContext,
+    item: ForthicValue,
+    code: &str,
+) -> Result<ForthicValue, ForthicError> {
+    context.stack_push(item);
+    context.run(code)?;
+    context.stack_pop()
+}
@@ -0,0 +1,28 @@
+//! Testing support for Forthic programs
+//!
+//! Tests are written in Forthic itself:
+//!
+//! ```text
+//! : TEST-DOUBLE   [1 2] '2 *' MAP  [2 4] ASSERT= ;
+//! {parsing
+//!     ["input"] VARIABLES
+//!     : SETUP      "1,2" .input ! ;
+//!     : TEST-SPLIT .input @ "," SPLIT LENGTH 2 ASSERT= ;
+//! }
+//! ```
+//!
+//! - **module**: the assertion words (ASSERT, ASSERT=, ASSERT-ERROR)
+//! - **runner**: discovers `: TEST-... ;` definitions and runs each one in
+//!   a fresh interpreter. A module block's `SETUP` / `TEARDOWN` words are
+//!   its fixtures, run around every test defined in that block.
+//! - **report**: text, TAP, and JUnit XML renderings of the results
+//!
+//! The `forthic-test` binary wraps the runner for CI.
+
+pub mod module;
+pub mod report;
+pub mod runner;
+
+pub use module::TestModule;
+pub use report::{SuiteReport, TestReport};
+pub use runner::{discover_tests, TestCase, TestResult, TestRunner, TestStatus};
@@ -0,0 +1,157 @@
+// Test module for Forthic
+//
+// Assertion words for tests written in Forthic. Not part of the standard
+// library (the cross-runtime word contract): the test runner imports it
+// into every test interpreter, and hosts can import it themselves.
+//
+// ## Categories
+// - Assertions: ASSERT, ASSERT=, ASSERT-ERROR
+//
+// Failures raise ForthicError::AssertionFailed, which the runner reports
+// as a failing test; any other error marks the test as errored.
+
+use crate::convert::StackArgs;
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use crate::module::{register_words, InterpreterContext, Module};
+use crate::modules::standard::{BooleanModule, JSONModule};
+use crate::word_options::WordOptions;
+
+/// TestModule provides assertion words for Forthic tests
+pub struct TestModule {
+    module: Module,
+}
+
+impl TestModule {
+    /// Create a new TestModule
+    pub fn new() -> Self {
+        let mut module = Module::new("test".to_string());
+
+        Self::register_assertion_words(&mut module);
+
+        Self { module }
+    }
+
+    /// Get a reference to the underlying module
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
+    /// Get a mutable reference to the underlying module
+    pub fn module_mut(&mut self) -> &mut Module {
+        &mut self.module
+    }
+
+    // ===== Assertions =====
+
+    fn register_assertion_words(module: &mut Module) {
+        register_words!(module, {
+            "ASSERT" => Self::word_assert,
+                "( value:any [options:WordOptions] -- )",
+                "Fail the test unless value is truthy. Option message (string) replaces the default failure message.";
+            "ASSERT=" => Self::word_assert_equals,
+                "( actual:any expected:any [options:WordOptions] -- )",
+                "Fail the test unless actual == expected (same equality as ==). Option message (string) is prepended to the failure.";
+            "ASSERT-ERROR" => Self::word_assert_error,
+                "( forthic:string [options:WordOptions] -- )",
+                "Run forthic and fail the test unless it raises an error; the stack is restored either way. Option error_type (string) also requires that error type (e.g. \"StackUnderflow\").";
+        });
+    }
+
+    /// ASSERT: ( value [options] -- )
+    fn word_assert(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
+        let value = context.stack_pop()?;
+        if value.is_truthy() {
+            return Ok(());
+        }
+        let message = Self::option_message(options.as_ref())
+            .unwrap_or_else(|| format!("ASSERT failed: got {}", Self::render(&value)));
+        Err(Self::failure(message))
+    }
+
+    /// ASSERT=: ( actual expected [options] -- )
+    fn word_assert_equals(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
+        let expected = context.stack_pop()?;
+        let actual = context.stack_pop()?;
+        if BooleanModule::values_equal(&actual, &expected) {
+            return Ok(());
+        }
+        let detail = format!(
+            "expected {}, got {}",
+            Self::render(&expected),
+            Self::render(&actual)
+        );
+        let message = match Self::option_message(options.as_ref()) {
+            Some(prefix) => format!("ASSERT= failed: {prefix}: {detail}"),
+            None => format!("ASSERT= failed: {detail}"),
+        };
+        Err(Self::failure(message))
+    }
+
+    /// ASSERT-ERROR: ( forthic [options] -- ) — the TRY recovery protocol
+    /// (stack and module stack restored), inverted: success is the failure
+    fn word_assert_error(context: &mut dyn InterpreterContext) -> Result<(), ForthicError> {
+        let options = context.pop_options();
+        let forthic = context.pop_code("ASSERT-ERROR", 1)?;
+        let expected_type = options
+            .as_ref()
+            .and_then(|opts| opts.get_string("error_type"));
+
+        let snapshot = context.stack_snapshot();
+        let module_depth = context.module_stack_depth();
+        let result = context.run(&forthic);
+        context.stack_restore(snapshot);
+        while context.module_stack_depth() > module_depth {
+            let _ = context.module_stack_pop();
+        }
+
+        match result {
+            Ok(()) => Err(Self::failure(format!(
+                "ASSERT-ERROR failed: '{forthic}' raised no error"
+            ))),
+            Err(e) => {
+                let actual = e.into_innermost();
+                match expected_type {
+                    Some(expected) if expected != actual.type_name() => {
+                        Err(Self::failure(format!(
+                            "ASSERT-ERROR failed: expected {expected}, got {}: {actual}",
+                            actual.type_name()
+                        )))
+                    }
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+
+    // ===== Helpers =====
+
+    fn option_message(options: Option<&WordOptions>) -> Option<String> {
+        options
+            .and_then(|opts| opts.get_string("message"))
+            .map(str::to_string)
+    }
+
+    /// Values render as JSON in failure messages, so strings are quoted
+    /// and `1` vs `"1"` is visible
+    fn render(value: &ForthicValue) -> String {
+        JSONModule::forthic_to_json(value).to_string()
+    }
+
+    fn failure(message: String) -> ForthicError {
+        ForthicError::AssertionFailed {
+            forthic: String::new(),
+            message,
+            location: None,
+            cause: None,
+        }
+    }
+}
+
+impl Default for TestModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
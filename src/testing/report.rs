@@ -0,0 +1,191 @@
+//! Test result reporting
+//!
+//! - **text**: human-readable summary with format_with_context diagnostics
+//! - **TAP**: Test Anything Protocol version 13, diagnostics as `#` lines
+//! - **JUnit XML**: one `<testsuite>` per program; assertion failures are
+//!   `<failure>`, other errors `<error>`
+
+use super::runner::{TestResult, TestStatus};
+use std::time::Duration;
+
+/// Results for one test program
+#[derive(Debug, Clone)]
+pub struct SuiteReport {
+    /// The program's name (typically its file path)
+    pub name: String,
+    pub results: Vec<TestResult>,
+    pub duration: Duration,
+}
+
+impl SuiteReport {
+    /// Number of results with the given status
+    pub fn count(&self, status: TestStatus) -> usize {
+        self.results.iter().filter(|r| r.status == status).count()
+    }
+}
+
+/// Results for a whole test run
+#[derive(Debug, Clone, Default)]
+pub struct TestReport {
+    pub suites: Vec<SuiteReport>,
+}
+
+impl TestReport {
+    /// Create an empty report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a suite's results
+    pub fn add_suite(&mut self, suite: SuiteReport) {
+        self.suites.push(suite);
+    }
+
+    /// Total number of tests
+    pub fn total(&self) -> usize {
+        self.suites.iter().map(|s| s.results.len()).sum()
+    }
+
+    /// Number of tests with the given status, across all suites
+    pub fn count(&self, status: TestStatus) -> usize {
+        self.suites.iter().map(|s| s.count(status)).sum()
+    }
+
+    /// True when every test passed
+    pub fn all_passed(&self) -> bool {
+        self.count(TestStatus::Passed) == self.total()
+    }
+
+    fn results(&self) -> impl Iterator<Item = (&SuiteReport, &TestResult)> {
+        self.suites
+            .iter()
+            .flat_map(|suite| suite.results.iter().map(move |r| (suite, r)))
+    }
+
+    /// Human-readable report: one line per test, diagnostics for failures,
+    /// and a summary line
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (suite, result) in self.results() {
+            let label = match result.status {
+                TestStatus::Passed => "PASS",
+                TestStatus::Failed => "FAIL",
+                TestStatus::Errored => "ERROR",
+            };
+            out.push_str(&format!(
+                "{label} {} {}\n",
+                suite.name,
+                result.case.full_name()
+            ));
+            if let Some(diagnostics) = &result.diagnostics {
+                for line in diagnostics.lines() {
+                    out.push_str(&format!("    {line}\n"));
+                }
+            }
+        }
+        out.push_str(&format!(
+            "\n{} passed, {} failed, {} errored ({} total)\n",
+            self.count(TestStatus::Passed),
+            self.count(TestStatus::Failed),
+            self.count(TestStatus::Errored),
+            self.total()
+        ));
+        out
+    }
+
+    /// TAP version 13
+    pub fn to_tap(&self) -> String {
+        let mut out = format!("TAP version 13\n1..{}\n", self.total());
+        for (i, (suite, result)) in self.results().enumerate() {
+            let ok = if result.status == TestStatus::Passed {
+                "ok"
+            } else {
+                "not ok"
+            };
+            out.push_str(&format!(
+                "{ok} {} - {} {}\n",
+                i + 1,
+                suite.name,
+                result.case.full_name()
+            ));
+            if let Some(diagnostics) = &result.diagnostics {
+                for line in diagnostics.lines() {
+                    out.push_str(&format!("# {line}\n"));
+                }
+            }
+        }
+        out
+    }
+
+    /// JUnit XML (the Ant/Surefire dialect CI servers consume)
+    pub fn to_junit_xml(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let total_time: f64 = self.suites.iter().map(|s| s.duration.as_secs_f64()).sum();
+        out.push_str(&format!(
+            "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+            self.total(),
+            self.count(TestStatus::Failed),
+            self.count(TestStatus::Errored),
+            total_time
+        ));
+        for suite in &self.suites {
+            out.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+                xml_escape(&suite.name),
+                suite.results.len(),
+                suite.count(TestStatus::Failed),
+                suite.count(TestStatus::Errored),
+                suite.duration.as_secs_f64()
+            ));
+            for result in &suite.results {
+                let case = &result.case;
+                let classname = if case.module_path.is_empty() {
+                    suite.name.clone()
+                } else {
+                    format!("{}.{}", suite.name, case.module_path.join("."))
+                };
+                let open = format!(
+                    "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                    xml_escape(&case.name),
+                    xml_escape(&classname),
+                    result.duration.as_secs_f64()
+                );
+                let element = match result.status {
+                    TestStatus::Passed => {
+                        out.push_str(&format!("{open}/>\n"));
+                        continue;
+                    }
+                    TestStatus::Failed => "failure",
+                    TestStatus::Errored => "error",
+                };
+                out.push_str(&format!("{open}>\n"));
+                out.push_str(&format!(
+                    "      <{element} message=\"{}\">{}</{element}>\n",
+                    xml_escape(result.message.as_deref().unwrap_or_default()),
+                    xml_escape(result.diagnostics.as_deref().unwrap_or_default())
+                ));
+                out.push_str("    </testcase>\n");
+            }
+            out.push_str("  </testsuite>\n");
+        }
+        out.push_str("</testsuites>\n");
+        out
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // XML 1.0 forbids most control characters, even escaped
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
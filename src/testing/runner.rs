@@ -0,0 +1,283 @@
+//! Test discovery and execution
+//!
+//! Every test runs in its own freshly built interpreter: the standard
+//! library, the TestModule, and any host modules are imported, the whole
+//! program is loaded, then the test's fixtures and body run. Nothing a
+//! test does — stack contents, variables, redefinitions — can leak into
+//! another test.
+
+use super::module::TestModule;
+use super::report::SuiteReport;
+use crate::errors::{CodeLocation, ForthicError};
+use crate::interpreter::Interpreter;
+use crate::module::{InterpreterContext, Module};
+use crate::tokenizer::{TokenType, Tokenizer};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Definitions whose names start with this prefix are tests
+pub const TEST_PREFIX: &str = "TEST-";
+
+/// A discovered test: a `: TEST-... ;` definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCase {
+    /// The definition name (e.g. `TEST-DOUBLE`)
+    pub name: String,
+    /// Enclosing `{module}` names, outermost first (empty for the app module)
+    pub module_path: Vec<String>,
+    /// Where the test is defined
+    pub location: CodeLocation,
+    /// Char index of the closing `}` of the enclosing module block. Module
+    /// contents live only while the block is open, so the program is run
+    /// up to here and the test invoked inside the still-open module.
+    block_end: Option<usize>,
+    has_setup: bool,
+    has_teardown: bool,
+}
+
+impl TestCase {
+    /// Module-qualified name (`parsing.TEST-SPLIT`)
+    pub fn full_name(&self) -> String {
+        if self.module_path.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}.{}", self.module_path.join("."), self.name)
+        }
+    }
+}
+
+/// Outcome of a single test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestStatus {
+    Passed,
+    /// An assertion did not hold
+    Failed,
+    /// The test (or its fixtures, or the program load) raised a
+    /// non-assertion error
+    Errored,
+}
+
+/// Result of running one test
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    pub case: TestCase,
+    pub status: TestStatus,
+    /// One-line error message (None when passed)
+    pub message: Option<String>,
+    /// format_with_context rendering of the failing error, pointing into
+    /// the test program (None when passed)
+    pub diagnostics: Option<String>,
+    pub duration: Duration,
+}
+
+/// Find the tests and fixtures in a program without running it
+///
+/// Fixtures belong to a module block: a `SETUP` / `TEARDOWN` defined in a
+/// `{module ... }` block wraps every test defined in that same block
+/// (top-level fixtures wrap top-level tests).
+pub fn discover_tests(code: &str) -> Result<Vec<TestCase>, ForthicError> {
+    let mut tokenizer = Tokenizer::new(code.to_string(), None, false);
+    let mut module_path: Vec<String> = Vec::new();
+    // Open module blocks, innermost last; block 0 is the top level
+    let mut open_blocks: Vec<usize> = vec![0];
+    let mut block_ends: Vec<Option<usize>> = vec![None];
+    let mut cases: Vec<(usize, TestCase)> = Vec::new();
+    let mut setups: HashSet<usize> = HashSet::new();
+    let mut teardowns: HashSet<usize> = HashSet::new();
+
+    loop {
+        let token = tokenizer.next_token()?;
+        let block = *open_blocks.last().expect("top level is never closed");
+        match token.token_type {
+            TokenType::Eos => break,
+            TokenType::StartModule => {
+                if token.string.is_empty() {
+                    module_path.clear();
+                } else {
+                    module_path.push(token.string);
+                }
+                open_blocks.push(block_ends.len());
+                block_ends.push(None);
+            }
+            TokenType::EndModule => {
+                module_path.pop();
+                if open_blocks.len() > 1 {
+                    open_blocks.pop();
+                    block_ends[block] = Some(token.location.start_pos);
+                }
+            }
+            TokenType::StartDef => match token.string.as_str() {
+                "SETUP" => {
+                    setups.insert(block);
+                }
+                "TEARDOWN" => {
+                    teardowns.insert(block);
+                }
+                name if name.starts_with(TEST_PREFIX) => cases.push((
+                    block,
+                    TestCase {
+                        name: token.string.clone(),
+                        module_path: module_path.clone(),
+                        location: token.location,
+                        block_end: None,
+                        has_setup: false,
+                        has_teardown: false,
+                    },
+                )),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    Ok(cases
+        .into_iter()
+        .map(|(block, mut case)| {
+            case.block_end = block_ends[block];
+            case.has_setup = setups.contains(&block);
+            case.has_teardown = teardowns.contains(&block);
+            case
+        })
+        .collect())
+}
+
+/// Runs Forthic test programs
+pub struct TestRunner {
+    timezone: String,
+    modules: Vec<Module>,
+}
+
+impl TestRunner {
+    /// Create a runner whose interpreters use `timezone`
+    pub fn new(timezone: &str) -> Self {
+        Self {
+            timezone: timezone.to_string(),
+            modules: Vec::new(),
+        }
+    }
+
+    /// Import a host module (unprefixed) into every test interpreter
+    pub fn add_module(&mut self, module: Module) {
+        self.modules.push(module);
+    }
+
+    /// Run every test in a program. `suite` names the program in reports
+    /// (typically its file path).
+    pub fn run_source(&self, code: &str, suite: &str) -> SuiteReport {
+        let started = Instant::now();
+        let results = match discover_tests(code) {
+            Ok(cases) => cases
+                .into_iter()
+                .map(|case| self.run_case(code, case))
+                .collect(),
+            // A program that doesn't tokenize has no discoverable tests;
+            // report the load failure as a single errored test
+            Err(e) => {
+                let location = e.get_location().cloned().unwrap_or_default();
+                vec![unsuccessful(
+                    TestCase {
+                        name: "(load)".to_string(),
+                        module_path: Vec::new(),
+                        location,
+                        block_end: None,
+                        has_setup: false,
+                        has_teardown: false,
+                    },
+                    e.with_forthic(code),
+                    started.elapsed(),
+                )]
+            }
+        };
+        SuiteReport {
+            name: suite.to_string(),
+            results,
+            duration: started.elapsed(),
+        }
+    }
+
+    /// Read and run a test file; the path is the suite name
+    pub fn run_file(&self, path: &Path) -> std::io::Result<SuiteReport> {
+        let code = std::fs::read_to_string(path)?;
+        Ok(self.run_source(&code, &path.display().to_string()))
+    }
+
+    fn make_interpreter(&self) -> Interpreter {
+        let mut interp = Interpreter::standard(&self.timezone);
+        interp.import_module(TestModule::new().module().clone(), "");
+        for module in &self.modules {
+            interp.import_module(module.clone(), "");
+        }
+        interp
+    }
+
+    fn run_case(&self, code: &str, case: TestCase) -> TestResult {
+        let started = Instant::now();
+        let mut interp = self.make_interpreter();
+
+        // Positions are char indexes into the tokenizer's (unescaped) input
+        let program: String = match case.block_end {
+            Some(end) => Tokenizer::new(code.to_string(), None, false)
+                .get_input_string()
+                .chars()
+                .take(end)
+                .collect(),
+            None => code.to_string(),
+        };
+        let mut outcome = interp.run(&program);
+        let depth = interp.module_stack_depth();
+        if outcome.is_ok() && case.has_setup {
+            outcome = interp.run("SETUP");
+        }
+        if outcome.is_ok() {
+            outcome = interp.run(&case.name);
+        }
+        if case.has_teardown && interp.module_stack_depth() >= depth {
+            // Teardown runs even after a failure, back in the test's module
+            while interp.module_stack_depth() > depth {
+                let _ = interp.module_stack_pop();
+            }
+            let teardown = interp.run("TEARDOWN");
+            if outcome.is_ok() {
+                outcome = teardown;
+            }
+        }
+
+        match outcome {
+            Ok(()) => TestResult {
+                case,
+                status: TestStatus::Passed,
+                message: None,
+                diagnostics: None,
+                duration: started.elapsed(),
+            },
+            // The innermost error's location is the failing word inside the
+            // program, so render it against the program source
+            Err(e) => unsuccessful(
+                case,
+                e.into_innermost().with_forthic(code),
+                started.elapsed(),
+            ),
+        }
+    }
+}
+
+impl Default for TestRunner {
+    fn default() -> Self {
+        Self::new("UTC")
+    }
+}
+
+fn unsuccessful(case: TestCase, e: ForthicError, duration: Duration) -> TestResult {
+    let status = match e {
+        ForthicError::AssertionFailed { .. } => TestStatus::Failed,
+        _ => TestStatus::Errored,
+    };
+    TestResult {
+        case,
+        status,
+        message: Some(e.to_string()),
+        diagnostics: Some(e.format_with_context()),
+        duration,
+    }
+}
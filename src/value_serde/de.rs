@@ -0,0 +1,212 @@
+//! `serde::Deserializer` reading from `ForthicValue`
+
+use super::zoned;
+use super::ValueError;
+use crate::convert::type_label;
+use crate::literals::ForthicValue;
+use chrono::SecondsFormat;
+use serde::de::value::{MapDeserializer, SeqDeserializer, StringDeserializer};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+impl<'de> IntoDeserializer<'de, ValueError> for ForthicValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ForthicValue {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            ForthicValue::Null => visitor.visit_unit(),
+            ForthicValue::Bool(b) => visitor.visit_bool(b),
+            ForthicValue::Int(i) => visitor.visit_i64(i),
+            ForthicValue::Float(f) => visitor.visit_f64(f),
+            ForthicValue::String(s) => visitor.visit_string(s),
+            ForthicValue::Array(items) => {
+                let mut seq = SeqDeserializer::new(items.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            ForthicValue::Record(fields) => {
+                let mut map = MapDeserializer::new(fields.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            ForthicValue::WordOptions(options) => {
+                let mut map = MapDeserializer::new(options.into_map().into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            // Temporal values present themselves as the ISO strings chrono's
+            // Deserialize impls parse
+            ForthicValue::Date(d) => visitor.visit_string(d.format("%Y-%m-%d").to_string()),
+            ForthicValue::Time(t) => visitor.visit_string(t.format("%H:%M:%S%.f").to_string()),
+            ForthicValue::DateTime(dt) => {
+                visitor.visit_string(dt.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+            }
+            other @ (ForthicValue::Sequence(_) | ForthicValue::StartArrayMarker) => {
+                Err(ValueError::UnsupportedType(type_label(&other).to_string()))
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            ForthicValue::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self {
+            ForthicValue::DateTime(dt) if name == zoned::NEWTYPE_NAME => visitor
+                .visit_newtype_struct(StringDeserializer::<ValueError>::new(zoned::format(&dt))),
+            other => visitor.visit_newtype_struct(other),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self {
+            ForthicValue::String(variant) => visitor.visit_enum(EnumDeserializer {
+                variant,
+                payload: None,
+            }),
+            ForthicValue::Record(fields) if fields.len() == 1 => {
+                let (variant, payload) = fields.into_iter().next().expect("one field");
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    payload: Some(payload),
+                })
+            }
+            other => Err(de::Error::invalid_type(unexpected(&other), &"enum variant")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+fn unexpected(value: &ForthicValue) -> de::Unexpected<'_> {
+    match value {
+        ForthicValue::Null => de::Unexpected::Unit,
+        ForthicValue::Bool(b) => de::Unexpected::Bool(*b),
+        ForthicValue::Int(i) => de::Unexpected::Signed(*i),
+        ForthicValue::Float(f) => de::Unexpected::Float(*f),
+        ForthicValue::String(s) => de::Unexpected::Str(s),
+        ForthicValue::Array(_) => de::Unexpected::Seq,
+        ForthicValue::Record(_) | ForthicValue::WordOptions(_) => de::Unexpected::Map,
+        other => de::Unexpected::Other(type_label(other)),
+    }
+}
+
+/// An externally tagged variant: `"Variant"` or `{ "Variant": payload }`
+struct EnumDeserializer {
+    variant: String,
+    payload: Option<ForthicValue>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = ValueError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<(S::Value, VariantDeserializer), ValueError> {
+        let variant = seed.deserialize(StringDeserializer::<ValueError>::new(self.variant))?;
+        Ok((
+            variant,
+            VariantDeserializer {
+                payload: self.payload,
+            },
+        ))
+    }
+}
+
+struct VariantDeserializer {
+    payload: Option<ForthicValue>,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = ValueError;
+
+    fn unit_variant(self) -> Result<(), ValueError> {
+        match self.payload {
+            None | Some(ForthicValue::Null) => Ok(()),
+            Some(other) => Err(de::Error::invalid_type(unexpected(&other), &"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<S::Value, ValueError> {
+        match self.payload {
+            Some(payload) => seed.deserialize(payload),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self.payload {
+            Some(payload @ ForthicValue::Array(_)) => {
+                de::Deserializer::deserialize_any(payload, visitor)
+            }
+            Some(other) => Err(de::Error::invalid_type(
+                unexpected(&other),
+                &"tuple variant",
+            )),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self.payload {
+            Some(payload @ ForthicValue::Record(_)) => {
+                de::Deserializer::deserialize_any(payload, visitor)
+            }
+            Some(other) => Err(de::Error::invalid_type(
+                unexpected(&other),
+                &"struct variant",
+            )),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Serde bridge between `ForthicValue` and Rust types
+//!
+//! [`to_value`] runs any `Serialize` type through a serializer that builds a
+//! `ForthicValue`; [`from_value`] deserializes any `Deserialize` type from
+//! one. Hosts use this instead of hand-assembling records:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Ticket {
+//!     key: String,
+//!     points: i64,
+//! }
+//!
+//! let ticket = Ticket { key: "PROJ-1".to_string(), points: 3 };
+//! let value = forthic::to_value(&ticket).unwrap();
+//! let forthic::ForthicValue::Record(fields) = &value else { unreachable!() };
+//! assert_eq!(fields.keys().collect::<Vec<_>>(), ["key", "points"]);
+//! assert_eq!(forthic::from_value::<Ticket>(value).unwrap(), ticket);
+//! ```
+//!
+//! Type mapping:
+//! - structs and maps → records, in field/insertion order
+//! - sequences and tuples → arrays; unit and `None` → NULL
+//! - enums are externally tagged like serde_json: a unit variant is its
+//!   name as a string, any other variant a one-field record
+//!   `{ "Variant": payload }`
+//! - chrono types → `Date` / `Time` / `DateTime`. chrono serializes these
+//!   through `collect_str`; text collected that way which is an ISO date,
+//!   time, or RFC 3339 datetime becomes the native variant (datetimes land
+//!   in UTC since only the offset survives). Strings serialized as strings
+//!   always stay strings.
+//! - `DateTime<chrono_tz::Tz>` has no chrono `Deserialize` impl and loses
+//!   its zone through `collect_str`; annotate such fields with
+//!   `#[serde(with = "forthic::value_serde::zoned")]` to round-trip the
+//!   zone as well.
+//!
+//! Deserializing reverses the mapping: `Date` / `Time` / `DateTime` present
+//! themselves as ISO strings, which is what chrono's `Deserialize` impls
+//! parse, and WordOptions deserialize as a map. Ints widen to floats, but
+//! (as everywhere in the runtime) strings are never parsed as numbers.
+
+mod de;
+mod ser;
+pub mod zoned;
+
+use crate::errors::ForthicError;
+use crate::literals::ForthicValue;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Errors converting between Rust types and `ForthicValue`
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ValueError {
+    /// Raised by a `Serialize` / `Deserialize` impl (e.g. a missing field)
+    #[error("{0}")]
+    Custom(String),
+
+    /// The value has no serde representation
+    #[error("Unsupported Forthic type: {0}")]
+    UnsupportedType(String),
+
+    /// Record keys must be strings (integer and char keys are stringified)
+    #[error("Record keys must be strings, got {0}")]
+    NonStringKey(String),
+
+    /// Forthic ints are i64
+    #[error("Integer {0} is out of range for a Forthic int")]
+    IntegerOutOfRange(String),
+}
+
+impl serde::ser::Error for ValueError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ValueError::Custom(msg.to_string())
+    }
+}
+
+impl serde::de::Error for ValueError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ValueError::Custom(msg.to_string())
+    }
+}
+
+impl From<ValueError> for ForthicError {
+    fn from(e: ValueError) -> Self {
+        ForthicError::invalid_operation(e.to_string())
+    }
+}
+
+/// Convert any `Serialize` value to a `ForthicValue`
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<ForthicValue, ValueError> {
+    value.serialize(ser::ValueSerializer)
+}
+
+/// Convert a `ForthicValue` to any `Deserialize` type
+pub fn from_value<T: DeserializeOwned>(value: ForthicValue) -> Result<T, ValueError> {
+    T::deserialize(value)
+}
@@ -0,0 +1,558 @@
+//! `serde::Serializer` producing `ForthicValue`
+
+use super::zoned;
+use super::ValueError;
+use crate::literals::ForthicValue;
+use chrono::{NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+use indexmap::IndexMap;
+use serde::ser::{self, Impossible, Serialize};
+
+pub(super) struct ValueSerializer;
+
+fn int_from<T: TryInto<i64> + std::fmt::Display + Copy>(v: T) -> Result<ForthicValue, ValueError> {
+    v.try_into()
+        .map(ForthicValue::Int)
+        .map_err(|_| ValueError::IntegerOutOfRange(v.to_string()))
+}
+
+/// `{ variant: payload }`
+fn tagged(variant: &str, payload: ForthicValue) -> ForthicValue {
+    let mut fields = IndexMap::with_capacity(1);
+    fields.insert(variant.to_string(), payload);
+    ForthicValue::Record(fields)
+}
+
+/// Text from `collect_str` (how chrono serializes) that is an ISO date,
+/// time, or RFC 3339 datetime becomes the native variant
+fn collected(text: String) -> ForthicValue {
+    if let Ok(date) = NaiveDate::parse_from_str(&text, "%Y-%m-%d") {
+        return ForthicValue::Date(date);
+    }
+    if let Ok(time) = NaiveTime::parse_from_str(&text, "%H:%M:%S%.f") {
+        return ForthicValue::Time(time);
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(&text) {
+        return ForthicValue::DateTime(dt.with_timezone(&Tz::UTC));
+    }
+    ForthicValue::String(text)
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = ForthicValue;
+    type Error = ValueError;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeRecord;
+    type SerializeStruct = SerializeRecord;
+    type SerializeStructVariant = SerializeRecord;
+
+    fn serialize_bool(self, v: bool) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<ForthicValue, ValueError> {
+        int_from(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<ForthicValue, ValueError> {
+        int_from(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<ForthicValue, ValueError> {
+        int_from(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::Int(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<ForthicValue, ValueError> {
+        int_from(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<ForthicValue, ValueError> {
+        int_from(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<ForthicValue, ValueError> {
+        int_from(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<ForthicValue, ValueError> {
+        int_from(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<ForthicValue, ValueError> {
+        int_from(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<ForthicValue, ValueError> {
+        int_from(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::Float(v as f64))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::Array(
+            v.iter().map(|b| ForthicValue::Int(*b as i64)).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<ForthicValue, ValueError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<ForthicValue, ValueError> {
+        Ok(ForthicValue::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<ForthicValue, ValueError> {
+        if name == zoned::NEWTYPE_NAME {
+            let text = value.serialize(self)?;
+            return match text {
+                ForthicValue::String(s) => zoned::parse(&s).map(ForthicValue::DateTime),
+                other => Ok(other),
+            };
+        }
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<ForthicValue, ValueError> {
+        Ok(tagged(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, ValueError> {
+        Ok(SerializeArray {
+            variant: None,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, ValueError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, ValueError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, ValueError> {
+        Ok(SerializeArray {
+            variant: Some(variant),
+            items: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeRecord, ValueError> {
+        Ok(SerializeRecord {
+            variant: None,
+            fields: IndexMap::with_capacity(len.unwrap_or(0)),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeRecord, ValueError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeRecord, ValueError> {
+        Ok(SerializeRecord {
+            variant: Some(variant),
+            fields: IndexMap::with_capacity(len),
+            next_key: None,
+        })
+    }
+
+    fn collect_str<T: std::fmt::Display + ?Sized>(
+        self,
+        value: &T,
+    ) -> Result<ForthicValue, ValueError> {
+        Ok(collected(value.to_string()))
+    }
+}
+
+pub(super) struct SerializeArray {
+    variant: Option<&'static str>,
+    items: Vec<ForthicValue>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.items.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> ForthicValue {
+        let array = ForthicValue::Array(self.items);
+        match self.variant {
+            Some(variant) => tagged(variant, array),
+            None => array,
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = ForthicValue;
+    type Error = ValueError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ForthicValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = ForthicValue;
+    type Error = ValueError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ForthicValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = ForthicValue;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ForthicValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = ForthicValue;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ForthicValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+pub(super) struct SerializeRecord {
+    variant: Option<&'static str>,
+    fields: IndexMap<String, ForthicValue>,
+    next_key: Option<String>,
+}
+
+impl SerializeRecord {
+    fn finish(self) -> ForthicValue {
+        let record = ForthicValue::Record(self.fields);
+        match self.variant {
+            Some(variant) => tagged(variant, record),
+            None => record,
+        }
+    }
+}
+
+impl ser::SerializeMap for SerializeRecord {
+    type Ok = ForthicValue;
+    type Error = ValueError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), ValueError> {
+        self.next_key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| ValueError::Custom("map value serialized before its key".into()))?;
+        self.fields.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<ForthicValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStruct for SerializeRecord {
+    type Ok = ForthicValue;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ValueError> {
+        self.fields
+            .insert(key.to_string(), value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<ForthicValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeRecord {
+    type Ok = ForthicValue;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ValueError> {
+        self.fields
+            .insert(key.to_string(), value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<ForthicValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+/// Serializes map keys: strings as-is, chars, integers, and unit variants
+/// stringified, anything else rejected
+struct KeySerializer;
+
+fn non_string_key(kind: &str) -> ValueError {
+    ValueError::NonStringKey(kind.to_string())
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = ValueError;
+
+    type SerializeSeq = Impossible<String, ValueError>;
+    type SerializeTuple = Impossible<String, ValueError>;
+    type SerializeTupleStruct = Impossible<String, ValueError>;
+    type SerializeTupleVariant = Impossible<String, ValueError>;
+    type SerializeMap = Impossible<String, ValueError>;
+    type SerializeStruct = Impossible<String, ValueError>;
+    type SerializeStructVariant = Impossible<String, ValueError>;
+
+    fn serialize_bool(self, _v: bool) -> Result<String, ValueError> {
+        Err(non_string_key("boolean"))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, ValueError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, ValueError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, ValueError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, ValueError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, ValueError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, ValueError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, ValueError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, ValueError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, ValueError> {
+        Err(non_string_key("float"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, ValueError> {
+        Err(non_string_key("float"))
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, ValueError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, ValueError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, ValueError> {
+        Err(non_string_key("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<String, ValueError> {
+        Err(non_string_key("null"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, ValueError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, ValueError> {
+        Err(non_string_key("null"))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, ValueError> {
+        Err(non_string_key("null"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, ValueError> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, ValueError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, ValueError> {
+        Err(non_string_key("record"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, ValueError> {
+        Err(non_string_key("array"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, ValueError> {
+        Err(non_string_key("array"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, ValueError> {
+        Err(non_string_key("array"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, ValueError> {
+        Err(non_string_key("record"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, ValueError> {
+        Err(non_string_key("record"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, ValueError> {
+        Err(non_string_key("record"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, ValueError> {
+        Err(non_string_key("record"))
+    }
+
+    fn collect_str<T: std::fmt::Display + ?Sized>(self, value: &T) -> Result<String, ValueError> {
+        Ok(value.to_string())
+    }
+}
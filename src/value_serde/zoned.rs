@@ -0,0 +1,83 @@
+//! `#[serde(with = "forthic::value_serde::zoned")]` for `DateTime<Tz>`
+//!
+//! chrono has no `Deserialize` impl for `DateTime<chrono_tz::Tz>`, and its
+//! `Serialize` impl keeps only the UTC offset. Fields using this module
+//! round-trip through `ForthicValue::DateTime` with their zone intact. Other
+//! serde formats see Temporal's annotated string
+//! (`2020-06-05T10:15:00-07:00[America/Los_Angeles]`, as in the JSON-RPC
+//! wire format).
+//!
+//! ```
+//! use chrono::DateTime;
+//! use chrono_tz::Tz;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Meeting {
+//!     #[serde(with = "forthic::value_serde::zoned")]
+//!     starts: DateTime<Tz>,
+//! }
+//! ```
+
+use super::ValueError;
+use chrono::{DateTime, SecondsFormat};
+use chrono_tz::Tz;
+use serde::{de, Deserializer, Serializer};
+
+/// Newtype name the Forthic serializer and deserializer recognize
+pub(super) const NEWTYPE_NAME: &str = "$forthic::ZonedDateTime";
+
+/// Temporal's annotated form: RFC 3339 plus `[Zone]`
+pub(super) fn format(dt: &DateTime<Tz>) -> String {
+    format!(
+        "{}[{}]",
+        dt.to_rfc3339_opts(SecondsFormat::AutoSi, false),
+        dt.timezone().name()
+    )
+}
+
+/// Parse the annotated form; without an annotation the instant lands in UTC
+pub(super) fn parse(text: &str) -> Result<DateTime<Tz>, ValueError> {
+    let (datetime_part, tz) = match (text.find('['), text.rfind(']')) {
+        (Some(open), Some(close)) if close > open => {
+            let name = &text[open + 1..close];
+            let tz: Tz = name
+                .parse()
+                .map_err(|_| ValueError::Custom(format!("Unknown timezone '{name}'")))?;
+            (&text[..open], tz)
+        }
+        _ => (text, Tz::UTC),
+    };
+    let dt = DateTime::parse_from_rfc3339(datetime_part)
+        .map_err(|e| ValueError::Custom(format!("Invalid datetime '{datetime_part}': {e}")))?;
+    Ok(dt.with_timezone(&tz))
+}
+
+pub fn serialize<S: Serializer>(dt: &DateTime<Tz>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_newtype_struct(NEWTYPE_NAME, &format(dt))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Tz>, D::Error> {
+    deserializer.deserialize_newtype_struct(NEWTYPE_NAME, ZonedVisitor)
+}
+
+struct ZonedVisitor;
+
+impl<'de> de::Visitor<'de> for ZonedVisitor {
+    type Value = DateTime<Tz>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a zoned datetime string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<DateTime<Tz>, E> {
+        parse(v).map_err(E::custom)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<DateTime<Tz>, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
@@ -0,0 +1,114 @@
+//! Allow/deny policy for the words a program may use
+//!
+//! A [`WordPolicy`] names words and modules; [`WordPolicy::compile`]
+//! resolves the module names against an interpreter's registered modules
+//! into a [`WordGuard`], which the interpreter consults in `find_word`.
+//! Every lookup goes through there — top-level code, definition bodies as
+//! they compile, and code strings run by RUN, MAP, TRY, and friends — so a
+//! refused word cannot be reached indirectly.
+//!
+//! The policy governs library words: those exported by a registered module.
+//! A program's own definitions and literals are not restricted (their
+//! bodies were checked word by word as they compiled), but a definition
+//! cannot reuse the name of a refused library word. Deny always wins over
+//! allow; with no allow entries, every library word not denied is allowed.
+
+use crate::errors::ForthicError;
+use crate::interpreter::Interpreter;
+use crate::module::Word;
+use std::collections::HashSet;
+
+/// Which library words and modules a program may use
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordPolicy {
+    /// When this or `allow_modules` is non-empty, only these library words
+    /// (plus the exports of `allow_modules`) are usable
+    pub allow_words: Vec<String>,
+    /// Modules whose exported words are all allowed
+    pub allow_modules: Vec<String>,
+    /// Words refused even if allowed above
+    pub deny_words: Vec<String>,
+    /// Modules whose exported words are all refused
+    pub deny_modules: Vec<String>,
+}
+
+impl WordPolicy {
+    /// True when the policy restricts nothing
+    pub fn is_empty(&self) -> bool {
+        self.allow_words.is_empty()
+            && self.allow_modules.is_empty()
+            && self.deny_words.is_empty()
+            && self.deny_modules.is_empty()
+    }
+
+    /// Resolve module names against the modules registered in `interp`.
+    /// Fails with UnknownModule for a module name that is not registered.
+    pub fn compile(&self, interp: &Interpreter) -> Result<WordGuard, ForthicError> {
+        let library: HashSet<String> = interp
+            .get_app_module()
+            .registered_modules()
+            .flat_map(|m| m.exportable_words())
+            .map(|w| w.name().to_string())
+            .collect();
+
+        let exports_of = |names: &[String]| -> Result<HashSet<String>, ForthicError> {
+            let mut words = HashSet::new();
+            for name in names {
+                let module = interp.find_module(name)?;
+                words.extend(
+                    module
+                        .exportable_words()
+                        .iter()
+                        .map(|w| w.name().to_string()),
+                );
+            }
+            Ok(words)
+        };
+
+        let mut denied = exports_of(&self.deny_modules)?;
+        denied.extend(self.deny_words.iter().cloned());
+        let allowed = if self.allow_words.is_empty() && self.allow_modules.is_empty() {
+            None
+        } else {
+            let mut allowed = exports_of(&self.allow_modules)?;
+            allowed.extend(self.allow_words.iter().cloned());
+            Some(allowed)
+        };
+
+        Ok(WordGuard {
+            library,
+            denied,
+            allowed,
+        })
+    }
+}
+
+/// A [`WordPolicy`] resolved against one set of registered modules
+#[derive(Debug, Clone)]
+pub struct WordGuard {
+    library: HashSet<String>,
+    denied: HashSet<String>,
+    allowed: Option<HashSet<String>>,
+}
+
+impl WordGuard {
+    /// Whether `word` may be used. Prefixed imports (`m.WORD`) are judged
+    /// by the imported word's own name, whatever the prefix looks like.
+    pub fn check(&self, word: &dyn Word) -> Result<(), ForthicError> {
+        let base = word.registered_name();
+        let refused = self.denied.contains(base)
+            || match &self.allowed {
+                Some(allowed) => self.library.contains(base) && !allowed.contains(base),
+                None => false,
+            };
+        if refused {
+            return Err(ForthicError::WordNotAllowed {
+                forthic: String::new(),
+                word: word.name().to_string(),
+                location: None,
+                cause: None,
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! Calendar arithmetic: months and years, period boundaries, components,
+//! business days with holiday calendars, and DATE-RANGE
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use chrono::{Datelike, NaiveDate};
+use forthic::interpreter::Interpreter;
+use forthic::literals::ForthicValue;
+use std::sync::Arc;
+
+fn run_with(mut interp: Interpreter, code: &str) -> ForthicValue {
+    interp.run(code).unwrap();
+    interp.get_stack_mut().pop().unwrap()
+}
+
+fn run(code: &str) -> ForthicValue {
+    run_with(Interpreter::standard("UTC"), code)
+}
+
+fn text(code: &str) -> String {
+    match run(code) {
+        ForthicValue::String(s) => s,
+        other => panic!("expected a string from {code:?}, got {other:?}"),
+    }
+}
+
+fn run_err(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap_err().to_string()
+}
+
+#[test]
+fn test_add_months_and_years_clamp() {
+    assert_eq!(
+        text("'2024-01-31' >DATE 1 ADD-MONTHS DATE>STR"),
+        "2024-02-29"
+    );
+    assert_eq!(
+        text("'2023-01-31' >DATE 1 ADD-MONTHS DATE>STR"),
+        "2023-02-28"
+    );
+    assert_eq!(
+        text("'2024-03-31' >DATE -13 ADD-MONTHS DATE>STR"),
+        "2023-02-28"
+    );
+    assert_eq!(
+        text("'2024-11-15' >DATE 3 ADD-MONTHS DATE>STR"),
+        "2025-02-15"
+    );
+    assert_eq!(
+        text("'2024-02-29' >DATE 1 ADD-YEARS DATE>STR"),
+        "2025-02-28"
+    );
+    assert_eq!(
+        text("'2024-02-29' >DATE 4 ADD-YEARS DATE>STR"),
+        "2028-02-29"
+    );
+    // A DateTime keeps its wall clock across a DST change
+    let mut interp = Interpreter::standard("America/New_York");
+    interp
+        .run("'2024-01-31T09:30:00' >DATETIME 2 ADD-MONTHS FORMAT-DATETIME")
+        .unwrap();
+    assert_eq!(
+        interp.get_stack_mut().pop().unwrap(),
+        ForthicValue::String("2024-03-31T09:30:00-04:00".to_string())
+    );
+    assert_eq!(run("NULL 1 ADD-MONTHS"), ForthicValue::Null);
+    assert_eq!(run("'2024-01-31' >DATE 'x' ADD-YEARS"), ForthicValue::Null);
+}
+
+#[test]
+fn test_start_and_end_of_periods() {
+    // 2024-05-15 is a Wednesday
+    for (unit, start, end) in [
+        ("day", "2024-05-15", "2024-05-15"),
+        ("week", "2024-05-13", "2024-05-19"),
+        ("month", "2024-05-01", "2024-05-31"),
+        ("quarter", "2024-04-01", "2024-06-30"),
+        ("year", "2024-01-01", "2024-12-31"),
+    ] {
+        assert_eq!(
+            text(&format!("'2024-05-15' >DATE '{unit}' START-OF DATE>STR")),
+            start,
+            "{unit}"
+        );
+        assert_eq!(
+            text(&format!("'2024-05-15' >DATE '{unit}' END-OF DATE>STR")),
+            end,
+            "{unit}"
+        );
+    }
+    assert_eq!(
+        text("'2024-02-10' >DATE 'month' END-OF DATE>STR"),
+        "2024-02-29"
+    );
+    assert_eq!(
+        text("'2024-05-15T13:45:00Z' >DATETIME 'month' START-OF FORMAT-DATETIME"),
+        "2024-05-01T00:00:00+00:00"
+    );
+    assert_eq!(
+        text("'2024-05-15T13:45:00Z' >DATETIME 'day' END-OF FORMAT-DATETIME"),
+        "2024-05-15T23:59:59.999999999+00:00"
+    );
+    assert_eq!(run("NULL 'week' START-OF"), ForthicValue::Null);
+    assert!(run_err("'2024-05-15' >DATE 'fortnight' START-OF").contains(
+        "START-OF: unknown unit 'fortnight' (expected day, week, month, quarter or year)"
+    ));
+    assert!(run_err("'2024-05-15' >DATE 7 END-OF")
+        .contains("END-OF argument 2 (unit) must be string, got int"));
+}
+
+#[test]
+fn test_components() {
+    assert_eq!(run("'2024-05-15' >DATE QUARTER"), ForthicValue::Int(2));
+    assert_eq!(run("'2024-12-31' >DATE QUARTER"), ForthicValue::Int(4));
+    assert_eq!(run("'2024-05-15' >DATE DAY"), ForthicValue::Int(15));
+    // ISO weeks: Jan 3 2021 is in week 53 of 2020
+    assert_eq!(run("'2021-01-03' >DATE ISO-WEEK"), ForthicValue::Int(53));
+    assert_eq!(run("'2024-12-30' >DATE ISO-WEEK"), ForthicValue::Int(1));
+    assert_eq!(
+        run("'2024-05-15T13:45:00Z' >DATETIME HOUR"),
+        ForthicValue::Int(13)
+    );
+    assert_eq!(run("'13:45' >TIME MINUTE"), ForthicValue::Int(45));
+    assert_eq!(run("'13:45' >TIME DAY"), ForthicValue::Null);
+    assert_eq!(run("'2024-05-15' >DATE HOUR"), ForthicValue::Null);
+}
+
+#[test]
+fn test_business_days() {
+    // 2024-05-17 is a Friday
+    assert_eq!(
+        text("'2024-05-17' >DATE 1 ADD-BUSINESS-DAYS DATE>STR"),
+        "2024-05-20"
+    );
+    assert_eq!(
+        text("'2024-05-18' >DATE 1 ADD-BUSINESS-DAYS DATE>STR"),
+        "2024-05-20"
+    );
+    assert_eq!(
+        text("'2024-05-20' >DATE -1 ADD-BUSINESS-DAYS DATE>STR"),
+        "2024-05-17"
+    );
+    assert_eq!(
+        text("'2024-05-13' >DATE 10 ADD-BUSINESS-DAYS DATE>STR"),
+        "2024-05-27"
+    );
+    assert_eq!(
+        text(
+            "'2024-05-13' >DATE 10 [.holidays ['2024-05-27' >DATE]] ~> ADD-BUSINESS-DAYS DATE>STR"
+        ),
+        "2024-05-28"
+    );
+
+    assert_eq!(
+        run("'2024-05-27' >DATE '2024-05-13' >DATE BUSINESS-DAYS-BETWEEN"),
+        ForthicValue::Int(10)
+    );
+    assert_eq!(
+        run("'2024-05-13' >DATE '2024-05-27' >DATE BUSINESS-DAYS-BETWEEN"),
+        ForthicValue::Int(-10)
+    );
+    assert_eq!(
+        run("'2024-05-27' >DATE '2024-05-13' >DATE [.holidays ['2024-05-15' >DATE]] ~> BUSINESS-DAYS-BETWEEN"),
+        ForthicValue::Int(9)
+    );
+    assert_eq!(
+        run("NULL '2024-05-13' >DATE BUSINESS-DAYS-BETWEEN"),
+        ForthicValue::Null
+    );
+    assert!(
+        run_err("'2024-05-13' >DATE 1 [.holidays [1]] ~> ADD-BUSINESS-DAYS")
+            .contains("ADD-BUSINESS-DAYS argument 3 (holidays) must be array of dates, got int")
+    );
+    // Both words walk day by day, so spans are capped like DATE-RANGE's
+    assert!(run_err("'2024-01-01' >DATE 100000000 ADD-BUSINESS-DAYS")
+        .contains("ADD-BUSINESS-DAYS: 100000000 days is too large (limit 10000000)"));
+    assert!(
+        run_err("'2024-01-01' >DATE DUP 30000 ADD-YEARS SWAP BUSINESS-DAYS-BETWEEN")
+            .contains("BUSINESS-DAYS-BETWEEN from 2024-01-01 to +32024-01-01 is too large")
+    );
+}
+
+#[test]
+fn test_host_holiday_calendar() {
+    let interp_with_calendar = || {
+        let mut interp = Interpreter::standard("UTC");
+        // Christmas and Boxing Day
+        interp.set_holiday_calendar(Some(Arc::new(|date: NaiveDate| {
+            date.month() == 12 && (date.day() == 25 || date.day() == 26)
+        })));
+        interp
+    };
+    assert_eq!(
+        run_with(
+            interp_with_calendar(),
+            "'2024-12-24' >DATE 1 ADD-BUSINESS-DAYS DATE>STR"
+        ),
+        ForthicValue::String("2024-12-27".to_string())
+    );
+    // The option adds to the host calendar
+    assert_eq!(
+        run_with(
+            interp_with_calendar(),
+            "'2024-12-24' >DATE 1 [.holidays ['2024-12-27' >DATE]] ~> ADD-BUSINESS-DAYS DATE>STR"
+        ),
+        ForthicValue::String("2024-12-30".to_string())
+    );
+    assert_eq!(
+        run_with(
+            interp_with_calendar(),
+            "'2025-01-01' >DATE '2024-12-23' >DATE BUSINESS-DAYS-BETWEEN"
+        ),
+        ForthicValue::Int(5)
+    );
+
+    let mut closed = Interpreter::standard("UTC");
+    closed.set_holiday_calendar(Some(Arc::new(|_: NaiveDate| true)));
+    assert!(closed
+        .run("'2024-05-13' >DATE 1 ADD-BUSINESS-DAYS")
+        .unwrap_err()
+        .to_string()
+        .contains("ADD-BUSINESS-DAYS: no business day within 366 days of 2024-05-13"));
+}
+
+#[test]
+fn test_date_range() {
+    let dates = |code: &str| -> Vec<String> {
+        match run(&format!("{code} '>STR' MAP")) {
+            ForthicValue::Array(items) => items
+                .into_iter()
+                .map(|item| match item {
+                    ForthicValue::String(s) => s,
+                    other => panic!("expected a string, got {other:?}"),
+                })
+                .collect(),
+            other => panic!("expected an array, got {other:?}"),
+        }
+    };
+    assert_eq!(
+        dates("'2024-02-27' >DATE '2024-03-01' >DATE DATE-RANGE"),
+        ["2024-02-27", "2024-02-28", "2024-02-29", "2024-03-01"]
+    );
+    assert_eq!(
+        dates("'2024-01-01' >DATE '2024-01-31' >DATE [.step 2 .unit 'week'] ~> DATE-RANGE"),
+        ["2024-01-01", "2024-01-15", "2024-01-29"]
+    );
+    // Month steps count from the start, so the 31st comes back
+    assert_eq!(
+        dates("'2024-01-31' >DATE '2024-05-01' >DATE [.unit 'month'] ~> DATE-RANGE"),
+        ["2024-01-31", "2024-02-29", "2024-03-31", "2024-04-30"]
+    );
+    assert_eq!(
+        dates("'2024-01-01' >DATE '2024-12-31' >DATE [.unit 'quarter'] ~> DATE-RANGE"),
+        ["2024-01-01", "2024-04-01", "2024-07-01", "2024-10-01"]
+    );
+    assert!(dates("'2024-03-01' >DATE '2024-02-01' >DATE DATE-RANGE").is_empty());
+    assert_eq!(
+        run("NULL '2024-02-01' >DATE DATE-RANGE"),
+        ForthicValue::Null
+    );
+    assert!(
+        run_err("'2024-01-01' >DATE '2024-02-01' >DATE [.step 0] ~> DATE-RANGE")
+            .contains("DATE-RANGE argument 3 (step) must be positive int, got int")
+    );
+}
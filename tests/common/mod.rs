@@ -36,6 +36,14 @@ impl TestServer {
         self.handle.as_ref().expect("server running").addr()
     }
 
+    /// Server notification to every open WebSocket
+    pub fn notify(&self, method: &str, params: Value) -> usize {
+        self.handle
+            .as_ref()
+            .expect("server running")
+            .notify(method, params)
+    }
+
     /// Well-formed JSON-RPC call; returns (HTTP status, parsed body)
     pub async fn rpc(&self, method: &str, params: Value) -> (reqwest::StatusCode, Value) {
         let id = self.next_id.fetch_add(1, Ordering::Relaxed);
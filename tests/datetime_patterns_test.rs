@@ -0,0 +1,225 @@
+//! FORMAT-DATETIME and PARSE-DATETIME: strftime and CLDR-style patterns,
+//! locale names, ISO week and RFC formats, and strict mode
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::interpreter::Interpreter;
+use forthic::literals::ForthicValue;
+
+const INSTANT: &str = "'2024-03-05T14:07:09Z' >DATETIME";
+
+fn run_in(tz: &str, code: &str) -> ForthicValue {
+    let mut interp = Interpreter::standard(tz);
+    interp.run(code).unwrap();
+    interp.get_stack_mut().pop().unwrap()
+}
+
+fn run(code: &str) -> ForthicValue {
+    run_in("UTC", code)
+}
+
+fn text(code: &str) -> String {
+    match run(code) {
+        ForthicValue::String(s) => s,
+        other => panic!("expected a string from {code:?}, got {other:?}"),
+    }
+}
+
+fn run_err(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap_err().to_string()
+}
+
+#[test]
+fn test_format_with_strftime_and_cldr_patterns() {
+    assert_eq!(
+        text(&format!(
+            "{INSTANT} [.pattern '%Y/%m/%d %H:%M'] ~> FORMAT-DATETIME"
+        )),
+        "2024/03/05 14:07"
+    );
+    assert_eq!(
+        text(&format!(
+            r#"{INSTANT} [.pattern "EEEE, d MMMM yyyy 'at' h:mm a"] ~> FORMAT-DATETIME"#
+        )),
+        "Tuesday, 5 March 2024 at 2:07 PM"
+    );
+    assert_eq!(
+        text(&format!(
+            r#"{INSTANT} [.pattern "yyyy-MM-dd'T'HH:mm:ss.SSSXXX"] ~> FORMAT-DATETIME"#
+        )),
+        "2024-03-05T14:07:09.000+00:00"
+    );
+    // Defaults by value type
+    assert_eq!(
+        text(&format!("{INSTANT} FORMAT-DATETIME")),
+        "2024-03-05T14:07:09+00:00"
+    );
+    assert_eq!(text("'2024-03-05' >DATE FORMAT-DATETIME"), "2024-03-05");
+    assert_eq!(text("'14:07:09' >TIME FORMAT-DATETIME"), "14:07:09");
+    assert_eq!(run("NULL FORMAT-DATETIME"), ForthicValue::Null);
+}
+
+#[test]
+fn test_locale_names() {
+    assert_eq!(
+        text(&format!(
+            "{INSTANT} [.pattern 'EEEE, d. MMMM yyyy' .locale 'de-DE'] ~> FORMAT-DATETIME"
+        )),
+        "Dienstag, 5. März 2024"
+    );
+    assert_eq!(
+        text(&format!(
+            "{INSTANT} [.pattern '%a %-d %b' .locale 'fr'] ~> FORMAT-DATETIME"
+        )),
+        "mar. 5 mars"
+    );
+    assert_eq!(
+        text(&format!(
+            "{INSTANT} [.pattern 'd MMMM yyyy' .locale 'pl-PL'] ~> FORMAT-DATETIME"
+        )),
+        "5 marca 2024"
+    );
+
+    assert_eq!(
+        text(
+            "'Dienstag, 5. März 2024' [.pattern 'EEEE, d. MMMM yyyy' .locale 'de'] ~> PARSE-DATETIME DATE>STR"
+        ),
+        "2024-03-05"
+    );
+    // Abbreviations read with or without their dot, in any case
+    assert_eq!(
+        text("'5 SEPT 2024' [.pattern 'd MMM yyyy' .locale 'fr'] ~> PARSE-DATETIME DATE>STR"),
+        "2024-09-05"
+    );
+    // A weekday that disagrees with the date does not parse
+    assert_eq!(
+        run(
+            "'Montag, 5. März 2024' [.pattern 'EEEE, d. MMMM yyyy' .locale 'de'] ~> PARSE-DATETIME"
+        ),
+        ForthicValue::Null
+    );
+    assert!(run_err("'x' [.locale 'ja-JP'] ~> PARSE-DATETIME").contains(
+        "PARSE-DATETIME: no month and day names for locale 'ja-JP' (supported languages: en, de,"
+    ));
+}
+
+#[test]
+fn test_iso_week_and_rfc_formats() {
+    assert_eq!(
+        text("'2024-01-01' >DATE [.pattern 'iso-week'] ~> FORMAT-DATETIME"),
+        "2024-W01-1"
+    );
+    assert_eq!(
+        text("'2021-01-03' >DATE [.pattern 'iso-week'] ~> FORMAT-DATETIME"),
+        "2020-W53-7"
+    );
+    assert_eq!(
+        text("'2020-W53-7' [.pattern 'iso-week'] ~> PARSE-DATETIME DATE>STR"),
+        "2021-01-03"
+    );
+    assert_eq!(
+        text("'2021-01-03' >DATE [.pattern \"YYYY-'W'ww\"] ~> FORMAT-DATETIME"),
+        "2020-W53"
+    );
+
+    assert_eq!(
+        text(&format!(
+            "{INSTANT} [.pattern 'rfc2822' .locale 'de'] ~> FORMAT-DATETIME"
+        )),
+        "Tue, 5 Mar 2024 14:07:09 +0000"
+    );
+    assert_eq!(
+        text("'Tue, 5 Mar 2024 15:07:09 +0100' [.pattern 'rfc2822'] ~> PARSE-DATETIME FORMAT-DATETIME"),
+        "2024-03-05T14:07:09+00:00"
+    );
+    // RFC 3339 is the parse default; the instant lands in the interpreter zone
+    assert_eq!(
+        text("'2024-03-05T14:07:09.5Z' PARSE-DATETIME FORMAT-DATETIME"),
+        "2024-03-05T14:07:09.500+00:00"
+    );
+}
+
+#[test]
+fn test_parse_result_types() {
+    assert_eq!(
+        text("'05.03.2024' [.pattern 'dd.MM.yyyy'] ~> PARSE-DATETIME DATE>STR"),
+        "2024-03-05"
+    );
+    assert_eq!(
+        text("'2:07 PM' [.pattern 'h:mm a'] ~> PARSE-DATETIME TIME>STR"),
+        "14:07"
+    );
+    // No offset: that wall clock in the interpreter timezone
+    let wall = run_in(
+        "America/New_York",
+        "'2024-03-05 14:07' [.pattern 'yyyy-MM-dd HH:mm'] ~> PARSE-DATETIME FORMAT-DATETIME",
+    );
+    assert_eq!(
+        wall,
+        ForthicValue::String("2024-03-05T14:07:00-05:00".to_string())
+    );
+    // An offset names an instant, shown in the interpreter timezone
+    let instant = run_in(
+        "America/New_York",
+        "'2024-03-05 14:07 +0100' [.pattern '%Y-%m-%d %H:%M %z'] ~> PARSE-DATETIME FORMAT-DATETIME",
+    );
+    assert_eq!(
+        instant,
+        ForthicValue::String("2024-03-05T08:07:00-05:00".to_string())
+    );
+}
+
+#[test]
+fn test_failures_are_null_or_strict_errors() {
+    for (input, pattern) in [
+        ("2024-13-01", "yyyy-MM-dd"),
+        ("2024-02-30", "yyyy-MM-dd"),
+        ("2024-03-05x", "yyyy-MM-dd"),
+        ("yesterday", "yyyy-MM-dd"),
+    ] {
+        assert_eq!(
+            run(&format!(
+                "'{input}' [.pattern '{pattern}'] ~> PARSE-DATETIME"
+            )),
+            ForthicValue::Null,
+            "{input}"
+        );
+    }
+    let strict = |input: &str| {
+        run_err(&format!(
+            "'{input}' [.pattern 'yyyy-MM-dd' .strict TRUE] ~> PARSE-DATETIME"
+        ))
+    };
+    assert!(strict("2024-13-01")
+        .contains("PARSE-DATETIME: '2024-13-01' does not match pattern 'yyyy-MM-dd'"));
+    assert!(strict("2024-02-30").contains(
+        "PARSE-DATETIME: '2024-02-30' does not name a valid date or time for pattern 'yyyy-MM-dd'"
+    ));
+    assert!(strict("2024-03-05x").contains("unexpected trailing 'x'"));
+
+    assert_eq!(
+        run("'2024-03-05' >DATE [.pattern '%H:%M'] ~> FORMAT-DATETIME"),
+        ForthicValue::Null
+    );
+    assert!(
+        run_err("'2024-03-05' >DATE [.pattern '%H:%M' .strict TRUE] ~> FORMAT-DATETIME")
+            .contains("FORMAT-DATETIME: pattern '%H:%M' needs fields a date does not have")
+    );
+    assert_eq!(run("42 FORMAT-DATETIME"), ForthicValue::Null);
+    assert!(run_err("42 [.strict TRUE] ~> FORMAT-DATETIME")
+        .contains("FORMAT-DATETIME: argument 1 must be Date, Time or DateTime, got int"));
+
+    // A malformed pattern is an error even without .strict
+    assert!(run_err(&format!(
+        "{INSTANT} [.pattern 'yyyy-nn'] ~> FORMAT-DATETIME"
+    ))
+    .contains("FORMAT-DATETIME: unsupported field 'nn' in 'yyyy-nn'"));
+    assert!(
+        run_err(&format!("{INSTANT} [.pattern '%Y-%!'] ~> FORMAT-DATETIME"))
+            .contains("FORMAT-DATETIME: invalid pattern '%Y-%!'")
+    );
+    assert!(run_err("'x' [.pattern \"'oops\"] ~> PARSE-DATETIME")
+        .contains("PARSE-DATETIME: unterminated quote in ''oops'"));
+}
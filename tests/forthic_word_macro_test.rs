@@ -0,0 +1,143 @@
+//! #[forthic_word] tests (plans/TS-PARITY-BACKLOG.md item 22(b))
+//!
+//! Typed functions become documented words: arguments pop in stack-effect
+//! order with typed errors, return values push (or propagate errors), and
+//! registration fills in the WordDoc.
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::forthic_word;
+use forthic::module::InterpreterContext;
+use forthic::prelude::*;
+
+#[forthic_word(
+    "ADD-TAX",
+    "( amount:float rate:float -- total:float )",
+    "Add tax to an amount"
+)]
+fn add_tax(amount: f64, rate: f64) -> f64 {
+    amount * (1.0 + rate)
+}
+
+#[forthic_word(
+    "REPEAT-STR",
+    "( s:string n:int -- result:string )",
+    "Repeat a string n times"
+)]
+fn repeat_str(s: String, n: i64) -> Result<String, ForthicError> {
+    if n < 0 {
+        return Err(ForthicError::InvalidOperation {
+            forthic: String::new(),
+            message: "REPEAT-STR count must be non-negative".to_string(),
+            location: None,
+            cause: None,
+        });
+    }
+    Ok(s.repeat(n as usize))
+}
+
+#[forthic_word(
+    "SUM-OR-ZERO",
+    "( items:int[] -- sum:int )",
+    "Sum ints; NULL sums to 0"
+)]
+fn sum_or_zero(items: Option<Vec<i64>>) -> i64 {
+    items.unwrap_or_default().iter().sum()
+}
+
+#[forthic_word("PUSH-TZ", "( -- tz:string )", "Push the interpreter's timezone")]
+fn push_tz(context: &mut dyn InterpreterContext) -> String {
+    context.get_timezone().to_string()
+}
+
+#[forthic_word("FORGET", "( value:any -- )", "Drop a value")]
+fn forget(_value: ForthicValue) {}
+
+fn interp() -> Interpreter {
+    let mut module = Module::new("macro-words".to_string());
+    register_add_tax(&mut module);
+    register_repeat_str(&mut module);
+    register_sum_or_zero(&mut module);
+    register_push_tz(&mut module);
+    register_forget(&mut module);
+
+    let mut interp = Interpreter::standard("America/Los_Angeles");
+    interp.import_module(module, "");
+    interp
+}
+
+fn run(code: &str) -> ForthicValue {
+    let mut interp = interp();
+    interp.run(code).unwrap();
+    interp.get_stack_mut().pop().unwrap()
+}
+
+fn run_err(code: &str) -> ForthicError {
+    interp().run(code).unwrap_err()
+}
+
+#[test]
+fn test_arguments_pop_in_stack_effect_order() {
+    assert_eq!(run("100 0.5 ADD-TAX"), ForthicValue::Float(150.0));
+    assert_eq!(
+        run("'ab' 3 REPEAT-STR"),
+        ForthicValue::String("ababab".into())
+    );
+}
+
+#[test]
+fn test_the_function_stays_callable() {
+    assert_eq!(add_tax(10.0, 0.1), 10.0 * 1.1);
+}
+
+#[test]
+fn test_type_mismatch_names_word_and_argument() {
+    let err = run_err("'ab' 'three' REPEAT-STR");
+    assert_eq!(
+        err.to_string(),
+        "REPEAT-STR argument 2 (n) must be int, got string"
+    );
+    let err = run_err("[1 'x'] SUM-OR-ZERO");
+    assert_eq!(
+        err.to_string(),
+        "SUM-OR-ZERO argument 1 (items) must be int[], got array"
+    );
+}
+
+#[test]
+fn test_result_errors_propagate() {
+    let err = run_err("'ab' -1 REPEAT-STR");
+    assert!(err.to_string().contains("non-negative"));
+}
+
+#[test]
+fn test_option_and_vec_parameters() {
+    assert_eq!(run("[1 2 3] SUM-OR-ZERO"), ForthicValue::Int(6));
+    assert_eq!(run("NULL SUM-OR-ZERO"), ForthicValue::Int(0));
+}
+
+#[test]
+fn test_context_parameter_and_unit_return() {
+    assert_eq!(
+        run("PUSH-TZ"),
+        ForthicValue::String("America/Los_Angeles".into())
+    );
+    let mut interp = interp();
+    interp.run("1 2 FORGET").unwrap();
+    assert_eq!(interp.get_stack().len(), 1);
+}
+
+#[test]
+fn test_registration_carries_word_doc() {
+    let mut module = Module::new("m".to_string());
+    register_add_tax(&mut module);
+    let docs = module.word_docs();
+    assert_eq!(docs.len(), 1);
+    assert_eq!(docs[0].0, "ADD-TAX");
+    assert_eq!(
+        docs[0].1.stack_effect,
+        "( amount:float rate:float -- total:float )"
+    );
+    assert_eq!(docs[0].1.description, "Add tax to an amount");
+}
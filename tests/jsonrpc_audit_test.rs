@@ -0,0 +1,257 @@
+//! The audit log: what each record holds, stack redaction, refused tokens,
+//! and the JSON-lines file sink
+
+#![cfg(feature = "jsonrpc")]
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use common::TestServer;
+use forthic::jsonrpc::{serve_framed, AuditLog, JsonLinesSink, ServeOptions, StackRedaction};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncWriteExt;
+
+/// An audit log collecting its records in memory
+fn collecting(redaction: StackRedaction) -> (AuditLog, Arc<Mutex<Vec<Value>>>) {
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&records);
+    let audit = AuditLog::new(Arc::new(move |record: &Value| {
+        sink.lock().unwrap().push(record.clone());
+    }))
+    .with_redaction(redaction);
+    (audit, records)
+}
+
+#[tokio::test]
+async fn test_calls_are_audited_without_stack_values() {
+    let (audit, records) = collecting(StackRedaction::default());
+    let server = TestServer::start(ServeOptions {
+        audit: Some(audit),
+        ..ServeOptions::default()
+    })
+    .await;
+
+    server
+        .rpc(
+            "executeWord",
+            json!({ "word_name": "+", "stack": [ { "int_value": 1 }, { "int_value": 2 } ] }),
+        )
+        .await;
+    server.rpc("runCode", json!({ "code": "1 2 +" })).await;
+    server
+        .rpc(
+            "executeSequence",
+            json!({ "word_names": ["DUP", "NO-SUCH-WORD"], "stack": [ { "int_value": 1 } ] }),
+        )
+        .await;
+    server
+        .post_json(
+            json!({ "jsonrpc": "2.0", "method": "runCode", "params": { "code": "1" } }).to_string(),
+            &server.endpoint,
+        )
+        .await;
+
+    let records = records.lock().unwrap().clone();
+    assert_eq!(records.len(), 4, "{records:#?}");
+
+    let word = &records[0];
+    assert_eq!(word["event"], "call");
+    assert_eq!(word["transport"], "http");
+    let peer: std::net::SocketAddr = word["peer"].as_str().unwrap().parse().unwrap();
+    assert!(peer.ip().is_loopback());
+    assert_eq!(word["method"], "executeWord");
+    assert_eq!(word["word_names"], json!(["+"]));
+    assert_eq!(word["stack_in"], 2);
+    assert_eq!(word["stack_out"], 1);
+    assert_eq!(word["outcome"], "ok");
+    assert!(word["duration_ms"].as_f64().unwrap() >= 0.0);
+    assert!(word.get("stack").is_none() && word.get("result_stack").is_none());
+    let timestamp = word["timestamp"].as_str().unwrap();
+    assert!(
+        chrono::DateTime::parse_from_rfc3339(timestamp).is_ok(),
+        "{timestamp}"
+    );
+
+    let code = &records[1];
+    assert_eq!(
+        code["code_sha256"],
+        "c6b8f1fb03356234b24989bf78682c1ca8b1779f75803deb95d203098220511e"
+    );
+    assert!(!code.to_string().contains("1 2 +"));
+
+    let failed = &records[2];
+    assert_eq!(failed["word_names"], json!(["DUP", "NO-SUCH-WORD"]));
+    assert_eq!(failed["outcome"], "error");
+    assert_eq!(failed["error_code"], -32000);
+    assert_eq!(failed["error_type"], "UnknownWord");
+    assert!(failed.get("stack_out").is_none());
+
+    // Notifications execute code, so they are audited too
+    assert_eq!(records[3]["id"], Value::Null);
+    assert_eq!(records[3]["method"], "runCode");
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_word_names_that_are_code_are_hashed() {
+    let (audit, records) = collecting(StackRedaction::default());
+    let server = TestServer::start(ServeOptions {
+        audit: Some(audit),
+        ..ServeOptions::default()
+    })
+    .await;
+
+    server
+        .rpc(
+            "executeWord",
+            json!({ "word_name": "'hunter2' DUP", "stack": [] }),
+        )
+        .await;
+    server
+        .rpc(
+            "executeSequence",
+            json!({ "word_names": ["'hunter2'", "DUP"], "stack": [] }),
+        )
+        .await;
+
+    let records = records.lock().unwrap().clone();
+    assert_eq!(records.len(), 2, "{records:#?}");
+    assert!(!format!("{records:?}").contains("hunter2"), "{records:#?}");
+    assert_eq!(
+        records[0]["word_names"],
+        json!([{ "sha256": "82cc92bb2f82516428cebd887a969600fb1b6b76d335f68d58721b5c23ca13ae" }])
+    );
+    assert_eq!(records[1]["word_names"][1], "DUP");
+    assert_eq!(
+        records[1]["word_names"][0]["sha256"].as_str().map(str::len),
+        Some(64)
+    );
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_panicking_calls_are_audited_as_internal_errors() {
+    // The sink panics on its first record, inside the call's task
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&records);
+    let audit = AuditLog::new(Arc::new(move |record: &Value| {
+        let mut records = sink.lock().unwrap();
+        if records.is_empty() {
+            records.push(Value::Null);
+            drop(records);
+            panic!("audit sink failed");
+        }
+        records.push(record.clone());
+    }));
+    let server = TestServer::start(ServeOptions {
+        audit: Some(audit),
+        ..ServeOptions::default()
+    })
+    .await;
+
+    let (_, response) = server.rpc("runCode", json!({ "code": "1 2 +" })).await;
+    assert_eq!(response["error"]["code"], -32603);
+
+    let records = records.lock().unwrap().clone();
+    assert_eq!(records.len(), 2, "{records:#?}");
+    let record = &records[1];
+    assert_eq!(record["method"], "runCode");
+    assert_eq!(record["outcome"], "internal_error");
+    assert_eq!(record["error_code"], -32603);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_redaction_levels() {
+    for (redaction, stack, result_stack) in [
+        (
+            StackRedaction::Types,
+            json!(["int_value", "string_value"]),
+            json!(["int_value", "string_value", "int_value"]),
+        ),
+        (
+            StackRedaction::Values,
+            json!([{ "int_value": 5 }, { "string_value": "secret" }]),
+            json!([{ "int_value": 5 }, { "string_value": "secret" }, { "int_value": 7 }]),
+        ),
+    ] {
+        let (audit, records) = collecting(redaction);
+        let server = TestServer::start(ServeOptions {
+            audit: Some(audit),
+            ..ServeOptions::default()
+        })
+        .await;
+        server
+            .rpc(
+                "runCode",
+                json!({ "code": "7", "stack": [ { "int_value": 5 }, { "string_value": "secret" } ] }),
+            )
+            .await;
+        let record = records.lock().unwrap()[0].clone();
+        assert_eq!(record["stack"], stack, "{redaction:?}");
+        assert_eq!(record["result_stack"], result_stack, "{redaction:?}");
+        server.stop().await;
+    }
+    assert_eq!("types".parse(), Ok(StackRedaction::Types));
+    assert!("everything".parse::<StackRedaction>().is_err());
+}
+
+#[tokio::test]
+async fn test_refused_tokens_are_audited() {
+    let (audit, records) = collecting(StackRedaction::default());
+    let server = TestServer::start(ServeOptions {
+        token: Some("s3cret".into()),
+        audit: Some(audit),
+        ..ServeOptions::default()
+    })
+    .await;
+    server.rpc("listModules", json!({})).await;
+
+    let records = records.lock().unwrap().clone();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["event"], "rejected");
+    assert_eq!(records[0]["reason"], "unauthorized");
+    assert_eq!(records[0]["transport"], "http");
+    assert!(records[0].get("method").is_none());
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_json_lines_file_sink_over_stdio() {
+    let path = std::env::temp_dir().join(format!("forthic-audit-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let audit = AuditLog::new(Arc::new(JsonLinesSink::open(&path).expect("audit file")));
+
+    let (mut input, server_in) = tokio::io::duplex(4096);
+    let (server_out, _output) = tokio::io::duplex(4096);
+    let transport = tokio::spawn(serve_framed(
+        server_in,
+        server_out,
+        ServeOptions {
+            audit: Some(audit),
+            ..ServeOptions::default()
+        },
+    ));
+    for id in 1..=2 {
+        let body = json!({ "jsonrpc": "2.0", "id": id, "method": "listModules" }).to_string();
+        let frame = format!("Content-Length: {}\r\n\r\n{body}", body.len());
+        input.write_all(frame.as_bytes()).await.expect("write");
+    }
+    drop(input);
+    transport.await.expect("transport task").expect("clean end");
+
+    let lines: Vec<Value> = std::fs::read_to_string(&path)
+        .expect("audit file reads")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("one JSON object per line"))
+        .collect();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(lines.len(), 2);
+    for line in &lines {
+        assert_eq!(line["transport"], "stdio");
+        assert_eq!(line["method"], "listModules");
+        assert!(line.get("peer").is_none());
+    }
+}
@@ -0,0 +1,160 @@
+//! Batch requests and notifications over HTTP, and the client batch builder
+
+#![cfg(feature = "jsonrpc")]
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use common::TestServer;
+use forthic::jsonrpc::{ClientError, JsonRpcClient, ServeOptions};
+use forthic::literals::ForthicValue;
+use serde_json::{json, Value};
+
+fn call(id: i64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+#[tokio::test]
+async fn test_batch_responses_keep_order() {
+    let server = TestServer::start(ServeOptions::default()).await;
+    let batch = json!([
+        call(1, "executeWord", json!({ "word_name": "DUP", "stack": [{ "int_value": 2 }] })),
+        { "jsonrpc": "1.0", "id": 2, "method": "listModules" },
+        call(3, "noSuchMethod", json!({})),
+        call(4, "runCode", json!({ "code": "1 2 +" })),
+    ]);
+    let (status, body) = server
+        .post_json(batch.to_string(), &server.endpoint.clone())
+        .await;
+    assert_eq!(status, 200);
+    let responses = body.as_array().expect("array response");
+    assert_eq!(responses.len(), 4);
+    assert_eq!(
+        responses[0]["result"]["result_stack"],
+        json!([{ "int_value": 2 }, { "int_value": 2 }])
+    );
+    assert_eq!(responses[1]["id"], 2);
+    assert_eq!(responses[1]["error"]["code"], -32600);
+    assert_eq!(responses[2]["error"]["code"], -32601);
+    assert_eq!(
+        responses[3]["result"]["result_stack"],
+        json!([{ "int_value": 3 }])
+    );
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_notifications_get_no_response() {
+    let server = TestServer::start(ServeOptions::default()).await;
+    let notification = json!({ "jsonrpc": "2.0", "method": "runCode", "params": { "code": "1" } });
+    let (status, body) = server
+        .post_raw(notification.to_string(), "application/json")
+        .await;
+    assert_eq!((status.as_u16(), body.as_str()), (204, ""));
+
+    let (status, _) = server
+        .post_raw(
+            json!([notification, notification]).to_string(),
+            "application/json",
+        )
+        .await;
+    assert_eq!(status, 204);
+
+    let mixed = json!([notification, call(9, "listModules", json!({}))]);
+    let (_, body) = server
+        .post_json(mixed.to_string(), &server.endpoint.clone())
+        .await;
+    assert_eq!(body.as_array().map(Vec::len), Some(1));
+    assert_eq!(body[0]["id"], 9);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_batch_limits() {
+    let server = TestServer::start(ServeOptions {
+        max_batch_size: Some(2),
+        max_body_bytes: Some(512),
+        ..ServeOptions::default()
+    })
+    .await;
+    let (_, body) = server
+        .post_json("[]".to_string(), &server.endpoint.clone())
+        .await;
+    assert_eq!(body["error"]["code"], -32600);
+
+    let three: Vec<Value> = (0..3)
+        .map(|id| call(id, "listModules", json!({})))
+        .collect();
+    let (status, body) = server
+        .post_json(Value::Array(three).to_string(), &server.endpoint.clone())
+        .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["error"]["message"], "Batch too large (limit 2)");
+
+    // The body cap covers the batch as a whole
+    let big = call(1, "runCode", json!({ "code": "x".repeat(300) }));
+    let (status, _) = server
+        .post_json(json!([big, big]).to_string(), &server.endpoint.clone())
+        .await;
+    assert_eq!(status, 413);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_client_batch_builder() {
+    let server = TestServer::start(ServeOptions {
+        max_batch_size: Some(4),
+        ..ServeOptions::default()
+    })
+    .await;
+    let addr = server.addr().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let client = JsonRpcClient::new(&addr).expect("client");
+        let results = client
+            .batch()
+            .execute_word("DUP", &[ForthicValue::Int(2)])
+            .notify("runCode", json!({ "code": "1" }))
+            .execute_sequence(&["DUP", "*"], &[ForthicValue::Int(3)])
+            .run_code(None, "NO-SUCH-WORD", &[])
+            .send()
+            .expect("batch sends");
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            JsonRpcClient::result_stack(results[0].as_ref().unwrap()).unwrap(),
+            vec![ForthicValue::Int(2), ForthicValue::Int(2)]
+        );
+        assert_eq!(
+            JsonRpcClient::result_stack(results[1].as_ref().unwrap()).unwrap(),
+            vec![ForthicValue::Int(9)]
+        );
+        match &results[2] {
+            Err(ClientError::Remote(info)) => assert_eq!(info.error_type, "UnknownWord"),
+            other => panic!("expected a remote error, got {other:?}"),
+        }
+
+        // Only notifications: nothing comes back
+        let results = client
+            .batch()
+            .notify("listModules", json!({}))
+            .send()
+            .expect("notification batch");
+        assert!(results.is_empty());
+        client.notify("listModules", json!({})).expect("notify");
+
+        // A batch over the server cap fails as a whole
+        let mut batch = client.batch();
+        for _ in 0..5 {
+            batch = batch.call("listModules", json!({}));
+        }
+        match batch.send() {
+            Err(ClientError::Rpc { code, .. }) => assert_eq!(code, -32600),
+            other => panic!("expected a rejected batch, got {other:?}"),
+        }
+    })
+    .await
+    .expect("blocking task");
+
+    server.stop().await;
+}
@@ -0,0 +1,194 @@
+//! Bootstrap module tests: Forthic-defined modules compiled once and served
+//! next to the runtime modules, with docs read from their comments
+
+#![cfg(feature = "jsonrpc")]
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use common::TestServer;
+use forthic::jsonrpc::{dispatch, ForthicJsonRpcServicer, JsonRpcRequest, ServeOptions};
+use forthic::module::Module;
+use serde_json::{json, Value};
+
+const OPS_CODE: &str = "\
+# Shared helpers for the ops dashboards
+
+# ( amount:float -- total:float ) Add the standard tax rate
+: ADD-TAX  1.5 * ;
+
+# ( items:array -- total:float )
+# Sum of the taxed amounts
+: TAXED-TOTAL  'ADD-TAX' MAP SUM ;
+
+: UNDOCUMENTED  1 ;
+";
+
+fn call(servicer: &ForthicJsonRpcServicer, method: &str, params: Value) -> Value {
+    let request: JsonRpcRequest = serde_json::from_value(
+        json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }),
+    )
+    .expect("request parses");
+    dispatch(servicer, &request, false)
+}
+
+fn ops_servicer() -> ForthicJsonRpcServicer {
+    let mut servicer = ForthicJsonRpcServicer::new();
+    servicer
+        .add_forthic_module(Module::new_with_code(
+            "ops".to_string(),
+            OPS_CODE.to_string(),
+        ))
+        .expect("ops compiles");
+    servicer
+}
+
+#[test]
+fn test_definitions_reach_request_interpreters() {
+    let servicer = ops_servicer();
+    let response = call(
+        &servicer,
+        "executeWord",
+        json!({ "word_name": "TAXED-TOTAL", "stack": [ { "array_value": { "items": [
+            { "int_value": 1 }, { "int_value": 2 }
+        ] } } ] }),
+    );
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "float_value": 4.5 }])
+    );
+
+    // Sessions import the module too
+    let session = call(&servicer, "createSession", json!({}));
+    let id = session["result"]["session_id"].as_str().unwrap();
+    let response = call(
+        &servicer,
+        "runCode",
+        json!({ "session_id": id, "code": "3 ADD-TAX" }),
+    );
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "float_value": 4.5 }])
+    );
+}
+
+#[test]
+fn test_listed_with_docs() {
+    let servicer = ops_servicer();
+    let response = call(&servicer, "listModules", json!({}));
+    assert_eq!(
+        response["result"]["modules"],
+        json!([{
+            "name": "ops",
+            "description": "Shared helpers for the ops dashboards",
+            "word_count": 3,
+            "runtime_specific": false,
+        }])
+    );
+
+    let response = call(&servicer, "getModuleInfo", json!({ "module_name": "ops" }));
+    assert_eq!(
+        response["result"]["words"],
+        json!([
+            {
+                "name": "ADD-TAX",
+                "stack_effect": "( amount:float -- total:float )",
+                "description": "Add the standard tax rate",
+            },
+            {
+                "name": "TAXED-TOTAL",
+                "stack_effect": "( items:array -- total:float )",
+                "description": "Sum of the taxed amounts",
+            },
+            {
+                "name": "UNDOCUMENTED",
+                "stack_effect": "( -- )",
+                "description": "UNDOCUMENTED word from ops module",
+            },
+        ])
+    );
+    assert_eq!(
+        response["result"]["description"],
+        "Shared helpers for the ops dashboards"
+    );
+}
+
+#[test]
+fn test_later_modules_build_on_earlier_ones() {
+    let mut servicer = ops_servicer();
+    servicer
+        .add_forthic_module(Module::new_with_code(
+            "billing".to_string(),
+            ": INVOICE  ADD-TAX 100 + ;".to_string(),
+        ))
+        .expect("billing compiles");
+    let response = call(
+        &servicer,
+        "executeWord",
+        json!({ "word_name": "INVOICE", "stack": [ { "int_value": 1 } ] }),
+    );
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "float_value": 101.5 }])
+    );
+    assert_eq!(
+        servicer.registered_module_names(),
+        vec!["ops".to_string(), "billing".to_string()]
+    );
+}
+
+#[test]
+fn test_compile_errors_are_reported() {
+    let mut servicer = ForthicJsonRpcServicer::new();
+    let err = servicer
+        .add_forthic_module(Module::new_with_code(
+            "broken".to_string(),
+            ": OOPS  NO-SUCH-WORD ;".to_string(),
+        ))
+        .unwrap_err();
+    assert!(err.to_string().contains("NO-SUCH-WORD"), "got: {err}");
+    assert!(servicer.registered_module_names().is_empty());
+}
+
+#[tokio::test]
+async fn test_bootstrap_files_over_http() {
+    let dir = std::env::temp_dir().join(format!("forthic-bootstrap-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("ops.forthic");
+    std::fs::write(&path, OPS_CODE).unwrap();
+
+    let server = TestServer::start(ServeOptions {
+        bootstrap_files: vec![path.clone()],
+        ..ServeOptions::default()
+    })
+    .await;
+    let (_, body) = server
+        .rpc(
+            "executeWord",
+            json!({ "word_name": "ADD-TAX", "stack": [ { "int_value": 3 } ] }),
+        )
+        .await;
+    assert_eq!(
+        body["result"]["result_stack"],
+        json!([{ "float_value": 4.5 }])
+    );
+    let (_, body) = server.rpc("listModules", json!({})).await;
+    assert_eq!(body["result"]["modules"][0]["name"], "ops");
+    server.stop().await;
+
+    // A file that fails to compile stops the server from starting
+    std::fs::write(dir.join("bad.forthic"), ": BAD  NOPE ;").unwrap();
+    let result = forthic::jsonrpc::serve(
+        0,
+        ServeOptions {
+            bootstrap_files: vec![path, dir.join("bad.forthic")],
+            ..ServeOptions::default()
+        },
+    )
+    .await;
+    let err = result.err().expect("serve fails");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("'bad'"), "got: {err}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -176,6 +176,64 @@ fn test_execute_word_unserializable_result_is_runtime_error() {
     );
 }
 
+#[test]
+fn test_execute_word_timezone_override() {
+    // A zone-less datetime literal is read in the interpreter's timezone
+    let response = rpc(
+        "executeWord",
+        json!({
+            "word_name": "2020-06-05T10:15:00",
+            "stack": [],
+            "timezone": "America/Los_Angeles"
+        }),
+    );
+    let value = &response["result"]["result_stack"][0]["zoned_datetime_value"];
+    assert_eq!(value["timezone"], "America/Los_Angeles");
+    assert_eq!(
+        value["iso8601"],
+        "2020-06-05T10:15:00-07:00[America/Los_Angeles]"
+    );
+}
+
+#[test]
+fn test_timezone_param_validation() {
+    let response = rpc(
+        "executeSequence",
+        json!({ "word_names": ["NOW"], "stack": [], "timezone": "Mars/Olympus_Mons" }),
+    );
+    let error = error_of(&response);
+    assert_eq!(error["code"], -32602);
+    assert_eq!(error["message"], "Unknown timezone 'Mars/Olympus_Mons'");
+
+    let response = rpc(
+        "executeWord",
+        json!({ "word_name": "NOW", "stack": [], "timezone": 8 }),
+    );
+    assert_eq!(
+        error_of(&response)["message"],
+        "executeWord \"timezone\" must be a string"
+    );
+}
+
+#[test]
+fn test_servicer_default_timezone() {
+    let mut servicer = ForthicJsonRpcServicer::new();
+    assert_eq!(servicer.timezone(), "UTC");
+    assert!(servicer.set_timezone("Nowhere/Special").is_err());
+    servicer.set_timezone("Asia/Tokyo").unwrap();
+
+    let request: JsonRpcRequest = serde_json::from_value(json!({
+        "jsonrpc": "2.0", "id": 1, "method": "executeWord",
+        "params": { "word_name": "2020-06-05T10:15:00", "stack": [] }
+    }))
+    .unwrap();
+    let response = dispatch(&servicer, &request, false);
+    assert_eq!(
+        response["result"]["result_stack"][0]["zoned_datetime_value"]["timezone"],
+        "Asia/Tokyo"
+    );
+}
+
 // ===== executeSequence =====
 
 #[test]
@@ -0,0 +1,209 @@
+//! /healthz, /metrics, and the ServerMetrics library API
+
+#![cfg(feature = "jsonrpc")]
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use common::TestServer;
+use forthic::jsonrpc::{
+    dispatch, ForthicJsonRpcServicer, JsonRpcRequest, ServeOptions, ServerMetrics,
+};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+async fn get(server: &TestServer, path: &str, token: Option<&str>) -> (u16, String) {
+    let mut request = reqwest::Client::new().get(format!("http://{}{path}", server.addr()));
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await.expect("request sends");
+    let status = response.status().as_u16();
+    (status, response.text().await.expect("body reads"))
+}
+
+#[tokio::test]
+async fn test_healthz_is_open_and_metrics_need_the_token() {
+    let server = TestServer::start(ServeOptions {
+        token: Some("s3cret".into()),
+        ..ServeOptions::default()
+    })
+    .await;
+
+    let (status, body) = get(&server, "/healthz", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body, r#"{"status":"ok"}"#);
+
+    assert_eq!(get(&server, "/metrics", None).await.0, 401);
+    assert_eq!(get(&server, "/metrics", Some("wrong")).await.0, 401);
+    let (status, body) = get(&server, "/metrics", Some("s3cret")).await;
+    assert_eq!(status, 200);
+    assert!(
+        body.contains("forthic_jsonrpc_auth_failures_total{transport=\"metrics\"} 2"),
+        "{body}"
+    );
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_metrics_count_calls_bytes_and_failures() {
+    let metrics = Arc::new(ServerMetrics::new());
+    let server = TestServer::start(ServeOptions {
+        token: Some("s3cret".into()),
+        metrics: Some(Arc::clone(&metrics)),
+        ..ServeOptions::default()
+    })
+    .await;
+    let call = |method: &str, params: serde_json::Value| {
+        server
+            .request()
+            .bearer_auth("s3cret")
+            .body(
+                json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })
+                    .to_string(),
+            )
+            .send()
+    };
+
+    call("runCode", json!({ "code": "1 2 +" }))
+        .await
+        .expect("sends");
+    call(
+        "executeWord",
+        json!({ "word_name": "NO-SUCH-WORD", "stack": [] }),
+    )
+    .await
+    .expect("sends");
+    call(
+        "executeSequence",
+        json!({ "word_names": ["DUP", "DROP", "DROP", "DROP"], "stack": [ { "int_value": 1 } ] }),
+    )
+    .await
+    .expect("sends");
+    call("made-up", json!({})).await.expect("sends");
+    server.post_raw("{not json", "application/json").await;
+
+    assert_eq!(metrics.requests("runCode", "ok"), 1);
+    assert_eq!(metrics.requests("executeWord", "runtime_error"), 1);
+    assert_eq!(metrics.requests("executeSequence", "runtime_error"), 1);
+    assert_eq!(metrics.requests("unknown", "method_not_found"), 1);
+    // The unauthenticated POST is refused before it is parsed
+    assert_eq!(metrics.requests("invalid", "parse_error"), 0);
+    assert_eq!(metrics.auth_failures("http"), 1);
+    // Not a served word, so not a label of its own
+    assert_eq!(metrics.word_errors("NO-SUCH-WORD"), 0);
+    assert_eq!(metrics.word_errors("(other)"), 1);
+    assert_eq!(metrics.word_errors("DROP"), 1);
+    assert_eq!(metrics.word_errors("DUP"), 0);
+    assert_eq!(metrics.blocking_in_flight(), 0);
+    assert!(metrics.received_bytes() > 0);
+    assert!(metrics.sent_bytes() > 0);
+
+    let (_, body) = get(&server, "/metrics", Some("s3cret")).await;
+    for line in [
+        "# TYPE forthic_jsonrpc_requests_total counter",
+        "forthic_jsonrpc_requests_total{method=\"runCode\",outcome=\"ok\"} 1",
+        "# TYPE forthic_jsonrpc_request_duration_seconds histogram",
+        "forthic_jsonrpc_request_duration_seconds_bucket{method=\"runCode\",le=\"+Inf\"} 1",
+        "forthic_jsonrpc_request_duration_seconds_count{method=\"executeWord\"} 1",
+        "forthic_jsonrpc_blocking_in_flight 0",
+        "forthic_jsonrpc_word_errors_total{word=\"(other)\"} 1",
+        "forthic_jsonrpc_word_errors_total{word=\"DROP\"} 1",
+    ] {
+        assert!(body.lines().any(|l| l == line), "missing {line} in\n{body}");
+    }
+    assert!(body.contains(&format!(
+        "forthic_jsonrpc_received_bytes_total {}",
+        metrics.received_bytes()
+    )));
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_invalid_messages_count_as_invalid() {
+    let server = TestServer::start(ServeOptions::default()).await;
+    server.post_raw("{not json", "application/json").await;
+    server.post_raw("[]", "application/json").await;
+    server
+        .post_raw(
+            r#"[{"jsonrpc":"1.0","id":1,"method":"x"}]"#,
+            "application/json",
+        )
+        .await;
+
+    let metrics = server_metrics(&server).await;
+    assert!(metrics
+        .contains("forthic_jsonrpc_requests_total{method=\"invalid\",outcome=\"parse_error\"} 1"));
+    assert!(metrics.contains(
+        "forthic_jsonrpc_requests_total{method=\"invalid\",outcome=\"invalid_request\"} 2"
+    ));
+    server.stop().await;
+}
+
+async fn server_metrics(server: &TestServer) -> String {
+    let (status, body) = get(server, "/metrics", None).await;
+    assert_eq!(status, 200);
+    body
+}
+
+#[test]
+fn test_metrics_library_api_with_dispatch() {
+    let metrics = Arc::new(ServerMetrics::new());
+    let mut servicer = ForthicJsonRpcServicer::new();
+    servicer.set_metrics(Some(Arc::clone(&metrics)));
+
+    let request: JsonRpcRequest = serde_json::from_value(json!({
+        "jsonrpc": "2.0", "id": 1, "method": "executeWord",
+        "params": { "word_name": "SWAP", "stack": [] }
+    }))
+    .expect("valid request");
+    let response = dispatch(&servicer, &request, false);
+    metrics.record_response(&request.method, &response, Duration::from_millis(3));
+
+    assert_eq!(metrics.requests("executeWord", "runtime_error"), 1);
+    assert_eq!(metrics.word_errors("SWAP"), 1);
+    metrics.record_word_error("say\"hi");
+    let rendered = metrics.render();
+    assert!(
+        rendered.contains("forthic_jsonrpc_word_errors_total{word=\"say\\\"hi\"} 1"),
+        "{rendered}"
+    );
+    assert!(rendered.contains(
+        "forthic_jsonrpc_request_duration_seconds_bucket{method=\"executeWord\",le=\"0.005\"} 1"
+    ));
+    assert!(rendered.contains(
+        "forthic_jsonrpc_request_duration_seconds_bucket{method=\"executeWord\",le=\"0.001\"} 0"
+    ));
+}
+
+#[test]
+fn test_word_labels_are_capped() {
+    let metrics = ServerMetrics::new();
+    for i in 0..forthic::jsonrpc::metrics::MAX_WORD_LABELS + 3 {
+        metrics.record_word_error(&format!("W{i}"));
+    }
+    metrics.record_word_error("W0");
+    assert_eq!(metrics.word_errors("W0"), 2);
+    assert_eq!(metrics.word_errors("(other)"), 3);
+}
+
+#[test]
+fn test_word_labels_are_served_word_names() {
+    let metrics = Arc::new(ServerMetrics::new());
+    let mut servicer = ForthicJsonRpcServicer::new();
+    servicer.set_metrics(Some(Arc::clone(&metrics)));
+    let code = format!("{} NO-SUCH-WORD", "1 ".repeat(1000));
+    for word_name in [code.as_str(), "DROP", "'unterminated"] {
+        let request: JsonRpcRequest = serde_json::from_value(json!({
+            "jsonrpc": "2.0", "id": 1, "method": "executeWord",
+            "params": { "word_name": word_name, "stack": [] }
+        }))
+        .expect("valid request");
+        assert!(dispatch(&servicer, &request, false).get("error").is_some());
+    }
+    assert_eq!(metrics.word_errors("DROP"), 1);
+    assert_eq!(metrics.word_errors("(other)"), 2);
+    assert!(!metrics.render().contains("NO-SUCH-WORD"));
+}
@@ -0,0 +1,138 @@
+//! runCode without a session and the server word policy
+
+#![cfg(feature = "jsonrpc")]
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use common::TestServer;
+use forthic::jsonrpc::{dispatch, ForthicJsonRpcServicer, JsonRpcRequest, ServeOptions};
+use forthic::module::Module;
+use forthic::word_policy::WordPolicy;
+use serde_json::{json, Value};
+
+fn call(servicer: &ForthicJsonRpcServicer, method: &str, params: Value) -> Value {
+    let request: JsonRpcRequest = serde_json::from_value(
+        json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }),
+    )
+    .expect("request parses");
+    dispatch(servicer, &request, false)
+}
+
+fn words(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn test_run_code_without_a_session() {
+    let servicer = ForthicJsonRpcServicer::new();
+    let response = call(
+        &servicer,
+        "runCode",
+        json!({ "code": ": SQUARE DUP * ; SQUARE 1 +", "stack": [{ "int_value": 6 }] }),
+    );
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "int_value": 37 }])
+    );
+    // Nothing persists between sessionless calls
+    let response = call(&servicer, "runCode", json!({ "code": "2 SQUARE" }));
+    assert_eq!(response["error"]["data"]["error_type"], "UnknownWord");
+}
+
+#[test]
+fn test_policy_applies_to_every_method() {
+    let mut servicer = ForthicJsonRpcServicer::new();
+    servicer
+        .set_word_policy(WordPolicy {
+            deny_words: words(&["RUN", "REVERSE"]),
+            ..WordPolicy::default()
+        })
+        .unwrap();
+
+    let response = call(
+        &servicer,
+        "executeWord",
+        json!({ "word_name": "'1 2 +' RUN", "stack": [] }),
+    );
+    assert_eq!(response["error"]["code"], -32000);
+    assert_eq!(response["error"]["data"]["error_type"], "WordNotAllowed");
+    assert_eq!(response["error"]["message"], "Word not allowed: RUN");
+
+    let response = call(
+        &servicer,
+        "runCode",
+        json!({ "code": "[[1 2]] 'REVERSE' MAP" }),
+    );
+    assert!(
+        response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Word not allowed: REVERSE"),
+        "{response}"
+    );
+
+    let id = call(&servicer, "createSession", json!({}))["result"]["session_id"].clone();
+    let response = call(
+        &servicer,
+        "runCode",
+        json!({ "session_id": id, "code": ": FLIP REVERSE ;" }),
+    );
+    assert_eq!(response["error"]["message"], "Word not allowed: REVERSE");
+    let response = call(
+        &servicer,
+        "runCode",
+        json!({ "session_id": id, "code": "1 2 +" }),
+    );
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "int_value": 3 }])
+    );
+}
+
+#[test]
+fn test_policy_covers_modules_added_later() {
+    let mut servicer = ForthicJsonRpcServicer::new();
+    servicer
+        .set_word_policy(WordPolicy {
+            allow_modules: words(&["core", "math"]),
+            ..WordPolicy::default()
+        })
+        .unwrap();
+    // Server-side bootstrap code is not restricted; its words are library
+    // words the allowlist does not include
+    servicer
+        .add_forthic_module(Module::new_with_code(
+            "ops".to_string(),
+            ": NEWEST  REVERSE FIRST ;".to_string(),
+        ))
+        .unwrap();
+    let response = call(&servicer, "runCode", json!({ "code": "[1 2] NEWEST" }));
+    assert_eq!(response["error"]["message"], "Word not allowed: NEWEST");
+
+    let err = servicer
+        .set_word_policy(WordPolicy {
+            allow_modules: words(&["core", "no-such-module"]),
+            ..WordPolicy::default()
+        })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Unknown module: no-such-module");
+}
+
+#[tokio::test]
+async fn test_serve_options_word_policy() {
+    let server = TestServer::start(ServeOptions {
+        word_policy: WordPolicy {
+            deny_modules: words(&["json"]),
+            ..WordPolicy::default()
+        },
+        ..ServeOptions::default()
+    })
+    .await;
+    let (_, body) = server.rpc("runCode", json!({ "code": "[1] >JSON" })).await;
+    assert_eq!(body["error"]["data"]["error_type"], "WordNotAllowed");
+    let (_, body) = server.rpc("runCode", json!({ "code": "[1] LENGTH" })).await;
+    assert_eq!(body["result"]["result_stack"], json!([{ "int_value": 1 }]));
+    server.stop().await;
+}
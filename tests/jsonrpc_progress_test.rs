@@ -0,0 +1,167 @@
+//! PROGRESS values streamed to JSON-RPC callers: server-sent events over
+//! HTTP, notifications over WebSocket, and the client's stream/callback API
+
+#![cfg(feature = "jsonrpc")]
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use common::TestServer;
+use forthic::jsonrpc::{JsonRpcClient, ServeOptions};
+use forthic::literals::ForthicValue;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio_tungstenite::tungstenite::Message;
+
+/// The client is blocking; run it off the tokio test runtime
+async fn on_blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    tokio::task::spawn_blocking(f).await.expect("blocking task")
+}
+
+/// The `data` of each server-sent event, parsed
+fn sse_messages(body: &str) -> Vec<Value> {
+    body.split("\n\n")
+        .filter_map(|event| {
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(str::trim_start)
+                .collect();
+            (!data.is_empty()).then(|| serde_json::from_str(&data.join("\n")).expect("JSON data"))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_event_stream_sends_progress_then_response() {
+    let server = TestServer::start(ServeOptions::default()).await;
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": 7,
+        "method": "runCode",
+        "params": { "code": "[1 2 3] '2 * DUP PROGRESS' MAP" },
+    });
+    let response = reqwest::Client::new()
+        .post(&server.endpoint)
+        .header("Content-Type", "application/json")
+        .header("Accept", "text/event-stream")
+        .body(body.to_string())
+        .send()
+        .await
+        .expect("request sends");
+    assert_eq!(response.status(), 200);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/event-stream"));
+
+    let messages = sse_messages(&response.text().await.expect("body"));
+    assert_eq!(messages.len(), 4);
+    for (message, expected) in messages.iter().zip([2, 4, 6]) {
+        assert_eq!(message["method"], "progress");
+        assert_eq!(message["params"]["request_id"], 7);
+        assert_eq!(message["params"]["value"], json!({ "int_value": expected }));
+    }
+    assert_eq!(messages[3]["id"], 7);
+    assert_eq!(
+        messages[3]["result"]["result_stack"][0]["array_value"]["items"],
+        json!([{ "int_value": 2 }, { "int_value": 4 }, { "int_value": 6 }])
+    );
+
+    // Without the Accept header the same call is a plain JSON response
+    let (status, plain) = server
+        .post_json(body.to_string(), &server.endpoint.clone())
+        .await;
+    assert_eq!(status, 200);
+    assert_eq!(plain["id"], 7);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_websocket_progress_is_tagged_with_the_request_id() {
+    let server = TestServer::start(ServeOptions::default()).await;
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", server.addr()))
+        .await
+        .expect("connects");
+    let call = json!({
+        "jsonrpc": "2.0",
+        "id": "job-1",
+        "method": "executeSequence",
+        "params": { "word_names": ["DUP", "PROGRESS"], "stack": [{ "string_value": "halfway" }] },
+    });
+    socket
+        .send(Message::text(call.to_string()))
+        .await
+        .expect("sends");
+
+    let mut messages = Vec::new();
+    while messages.len() < 2 {
+        if let Message::Text(text) = socket.next().await.expect("open").expect("frame") {
+            messages.push(serde_json::from_str::<Value>(&text).expect("JSON"));
+        }
+    }
+    assert_eq!(messages[0]["method"], "progress");
+    assert_eq!(messages[0]["params"]["request_id"], "job-1");
+    assert_eq!(
+        messages[0]["params"]["value"],
+        json!({ "string_value": "halfway" })
+    );
+    assert_eq!(messages[1]["id"], "job-1");
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_client_stream_and_callback() {
+    let server = TestServer::start(ServeOptions::default()).await;
+    let addr = server.addr().to_string();
+
+    on_blocking(move || {
+        let client = JsonRpcClient::new(&addr).expect("client");
+        let mut call = client
+            .stream("runCode", json!({ "code": "'a' PROGRESS 'b' PROGRESS 42" }))
+            .expect("stream starts");
+        let progress: Vec<ForthicValue> = call.by_ref().collect();
+        assert_eq!(
+            progress,
+            vec![
+                ForthicValue::String("a".into()),
+                ForthicValue::String("b".into())
+            ]
+        );
+        let result = call.finish().expect("result");
+        assert_eq!(
+            JsonRpcClient::result_stack(&result).unwrap(),
+            vec![ForthicValue::Int(42)]
+        );
+
+        // Sessions report progress only to the call that produced it
+        let session = client.create_session().expect("session");
+        let mut seen = Vec::new();
+        let stack = client
+            .run_code_with_progress(Some(&session), "5 DUP PROGRESS", &[], |value| {
+                seen.push(value)
+            })
+            .expect("runCode");
+        assert_eq!(seen, vec![ForthicValue::Int(5)]);
+        assert_eq!(stack, vec![ForthicValue::Int(5)]);
+        let stack = client
+            .run_code(Some(&session), "6 PROGRESS", &[])
+            .expect("later call");
+        assert_eq!(stack, vec![ForthicValue::Int(5)]);
+
+        // Errors arrive as the outcome, after any progress
+        let mut seen = Vec::new();
+        let error = client
+            .call_with_progress(
+                "runCode",
+                json!({ "code": "1 PROGRESS NO-SUCH-WORD" }),
+                |value| seen.push(value),
+            )
+            .expect_err("unknown word");
+        assert_eq!(seen, vec![ForthicValue::Int(1)]);
+        assert!(error.to_string().contains("NO-SUCH-WORD"), "{error}");
+    })
+    .await;
+    server.stop().await;
+}
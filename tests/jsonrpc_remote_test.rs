@@ -0,0 +1,252 @@
+//! RemoteModule: proxy words calling another runtime (here an in-process rs
+//! server serving a Forthic bootstrap module)
+
+#![cfg(feature = "jsonrpc")]
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use common::TestServer;
+use forthic::errors::ForthicError;
+use forthic::interpreter::Interpreter;
+use forthic::jsonrpc::{JsonRpcClient, RemoteModule, ServeOptions};
+use forthic::literals::ForthicValue;
+use forthic::module::Module;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+const REMOTE_CODE: &str = "\
+# ( a:number b:number -- sum:number ) Add two numbers
+: ADD2  + ;
+
+# ( a:any -- ) Drops one item too many
+: BAD  DROP DROP ;
+
+# ( n:number -- n:number ) Reports its input, then increments it
+: NOISY  DUP PROGRESS 1 + ;
+
+# ( value:any [options:WordOptions] -- value:any ) Leaves its input
+: SAME  ;
+
+# ( number:number [options:WordOptions] -- text:string ) Formats a number
+: FMT  FORMAT-NUMBER ;
+";
+
+async fn remote_server() -> TestServer {
+    TestServer::start(ServeOptions {
+        modules: vec![Module::new_with_code(
+            "remote".to_string(),
+            REMOTE_CODE.to_string(),
+        )],
+        expose_error_details: true,
+        ..ServeOptions::default()
+    })
+    .await
+}
+
+/// An interpreter with the server's `remote` module registered as proxies.
+/// The client is blocking; callers run this off the tokio runtime.
+fn proxy_interpreter(addr: &str) -> Interpreter {
+    let client = Arc::new(JsonRpcClient::new(addr).expect("client"));
+    let remote = RemoteModule::new(client, "remote").expect("module info");
+    let mut interp = Interpreter::standard("UTC");
+    interp.register_module(remote.module().clone());
+    interp
+}
+
+async fn on_blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    tokio::task::spawn_blocking(f).await.expect("blocking task")
+}
+
+#[tokio::test]
+async fn test_proxy_words_take_their_stack_effect_inputs() {
+    let server = remote_server().await;
+    let addr = server.addr().to_string();
+
+    let stacks = on_blocking(move || {
+        let mut interp = proxy_interpreter(&addr);
+        interp
+            .run("['remote'] USE-MODULES  100 2 3 ADD2  'x' SAME")
+            .unwrap();
+        let bare = interp.get_stack().items().to_vec();
+
+        let mut interp = proxy_interpreter(&addr);
+        interp
+            .run("[['remote' 'r']] USE-MODULES  [1 2 3] '10 r.ADD2' MAP")
+            .unwrap();
+        (bare, interp.get_stack().items().to_vec())
+    })
+    .await;
+
+    assert_eq!(
+        stacks.0,
+        vec![
+            ForthicValue::Int(100),
+            ForthicValue::Int(5),
+            ForthicValue::String("x".into())
+        ]
+    );
+    assert_eq!(
+        stacks.1,
+        vec![ForthicValue::Array(vec![
+            ForthicValue::Int(11),
+            ForthicValue::Int(12),
+            ForthicValue::Int(13)
+        ])]
+    );
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_proxy_words_send_their_options() {
+    let server = remote_server().await;
+    let addr = server.addr().to_string();
+
+    let (formatted, failed) = on_blocking(move || {
+        let mut interp = proxy_interpreter(&addr);
+        interp
+            .run("['remote'] USE-MODULES  1234.5 [.decimals 2 .grouping FALSE] ~> FMT  7 FMT")
+            .unwrap();
+        let formatted = interp.get_stack().items().to_vec();
+
+        // A failed call leaves its arguments where they were
+        let mut interp = proxy_interpreter(&addr);
+        interp.run("['remote'] USE-MODULES").unwrap();
+        let error = interp.run("'x' [.decimals 2] ~> FMT").unwrap_err();
+        (formatted, (error, interp.get_stack().items().to_vec()))
+    })
+    .await;
+
+    assert_eq!(
+        formatted,
+        vec![
+            ForthicValue::String("1234.50".into()),
+            ForthicValue::String("7".into())
+        ]
+    );
+    let (error, stack) = failed;
+    assert_eq!(error.type_name(), "Remote", "{error}");
+    assert_eq!(stack.len(), 2, "{stack:?}");
+    assert_eq!(stack[0], ForthicValue::String("x".into()));
+    match &stack[1] {
+        ForthicValue::WordOptions(options) => assert_eq!(options.get_int("decimals"), Some(2)),
+        other => panic!("expected the options back, got {other:?}"),
+    }
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_remote_errors_keep_the_remote_details() {
+    let server = remote_server().await;
+    let addr = server.addr().to_string();
+
+    let error = on_blocking(move || {
+        let mut interp = proxy_interpreter(&addr);
+        interp.run("['remote'] USE-MODULES  1 BAD").unwrap_err()
+    })
+    .await;
+
+    match &error {
+        ForthicError::Remote {
+            runtime,
+            error_type,
+            remote_location,
+            ..
+        } => {
+            assert_eq!(runtime, "rust");
+            // The remote wraps a failure inside a definition
+            assert_eq!(error_type, "WordExecution", "{error}");
+            assert!(remote_location.is_some(), "{error:?}");
+        }
+        other => panic!("expected a Remote error, got {other:?}"),
+    }
+    assert_eq!(error.type_name(), "Remote");
+    // The local call site is attached like any other word's
+    assert!(error.get_location().is_some(), "{error:?}");
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_remote_progress_is_reported_locally() {
+    let server = remote_server().await;
+    let addr = server.addr().to_string();
+
+    let (seen, stack) = on_blocking(move || {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let mut interp = proxy_interpreter(&addr);
+        interp.set_progress_handler(Some(Arc::new(move |value: &ForthicValue| {
+            sink.lock().unwrap().push(value.clone());
+        })));
+        interp.run("['remote'] USE-MODULES  41 NOISY").unwrap();
+        let seen = seen.lock().unwrap().clone();
+        (seen, interp.get_stack().items().to_vec())
+    })
+    .await;
+
+    assert_eq!(seen, vec![ForthicValue::Int(41)]);
+    assert_eq!(stack, vec![ForthicValue::Int(42)]);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_transport_failures_and_unreadable_stack_effects() {
+    // A port nothing listens on
+    let addr = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    };
+    let info = json!({
+        "name": "gone",
+        "description": "A runtime that went away",
+        "words": [
+            { "name": "PING", "stack_effect": "( -- pong:string )", "description": "" },
+            { "name": "ODD", "stack_effect": "takes two numbers", "description": "" },
+            { "name": "PAIR", "stack_effect": "( a:any b:any -- pair:array )", "description": "" },
+        ],
+    });
+
+    let (ping, odd, short) = on_blocking(move || {
+        let client = Arc::new(JsonRpcClient::new(&addr).expect("client"));
+        let remote = RemoteModule::from_module_info(client, &info).expect("proxies");
+        assert_eq!(remote.description(), "A runtime that went away");
+        let mut interp = Interpreter::standard("UTC");
+        interp.register_module(remote.module().clone());
+        interp.run("['gone'] USE-MODULES").unwrap();
+        let ping = interp.run("PING").unwrap_err();
+        let odd = interp.run("1 2 ODD").unwrap_err();
+        // Too few arguments: the one that was there stays put
+        interp.get_stack_mut().set_items(Vec::new());
+        interp.run("1 PAIR").unwrap_err();
+        (ping, odd, interp.get_stack().items().to_vec())
+    })
+    .await;
+
+    assert_eq!(ping.type_name(), "Module", "{ping}");
+    assert!(ping.to_string().contains("PING"), "{ping}");
+    assert_eq!(odd.type_name(), "InvalidOperation", "{odd}");
+    assert!(odd.to_string().contains("stack effect"), "{odd}");
+    assert_eq!(short, vec![ForthicValue::Int(1)]);
+}
+
+#[test]
+fn test_proxy_words_carry_the_remote_docs() {
+    let info = json!({
+        "name": "calc",
+        "description": "",
+        "words": [
+            { "name": "ADD2", "stack_effect": "( a:number b:number -- sum:number )", "description": "Add two numbers" },
+        ],
+    });
+    let client = Arc::new(JsonRpcClient::new("127.0.0.1:1").expect("client"));
+    let remote = RemoteModule::from_module_info(client, &info).expect("proxies");
+    let docs = remote.module().word_docs();
+    assert_eq!(docs.len(), 1);
+    assert_eq!(docs[0].0, "ADD2");
+    assert_eq!(
+        docs[0].1.stack_effect,
+        "( a:number b:number -- sum:number )"
+    );
+    assert_eq!(docs[0].1.description, "Add two numbers");
+}
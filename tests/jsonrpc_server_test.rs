@@ -123,13 +123,13 @@ async fn test_invalid_params_and_unknown_method() {
 }
 
 #[tokio::test]
-async fn test_batch_requests_rejected() {
+async fn test_batch_of_one_answers_with_an_array() {
+    // rs extension: batches are supported (jsonrpc_batch_test.rs)
     let server = TestServer::start(ServeOptions::default()).await;
     let batch = format!("[{}]", any_valid_envelope());
     let (status, body) = server.post_json(batch, &server.endpoint.clone()).await;
     assert_eq!(status, 200);
-    assert_eq!(body["error"]["code"], -32600);
-    assert_eq!(body["error"]["message"], "Batch requests are not supported");
+    assert_eq!(body[0]["result"]["modules"], json!([]));
     server.stop().await;
 }
 
@@ -156,10 +156,9 @@ async fn test_malformed_json_is_parse_error() {
 async fn test_invalid_envelopes_rejected() {
     let server = TestServer::start(ServeOptions::default()).await;
     let cases = [
-        json!({ "jsonrpc": "2.0", "method": "listModules" }), // no id
         json!({ "jsonrpc": "1.0", "id": 1, "method": "listModules" }), // wrong version
-        json!({ "id": 1, "method": "listModules" }),          // no jsonrpc
-        json!({ "jsonrpc": "2.0", "id": 1, "method": 42 }),   // non-string method
+        json!({ "id": 1, "method": "listModules" }),                   // no jsonrpc
+        json!({ "jsonrpc": "2.0", "id": 1, "method": 42 }),            // non-string method
         json!("just a string"),
         json!(42),
     ];
@@ -235,3 +234,36 @@ async fn test_transport_rejections() {
 
     server.stop().await;
 }
+
+#[tokio::test]
+async fn test_serve_options_timezone() {
+    let server = TestServer::start(ServeOptions {
+        timezone: Some("America/Los_Angeles".to_string()),
+        ..ServeOptions::default()
+    })
+    .await;
+    let (_, body) = server
+        .rpc(
+            "executeWord",
+            json!({ "word_name": "2020-06-05T10:15:00", "stack": [] }),
+        )
+        .await;
+    assert_eq!(
+        body["result"]["result_stack"][0]["zoned_datetime_value"]["timezone"],
+        "America/Los_Angeles"
+    );
+    server.stop().await;
+
+    let result = forthic::jsonrpc::serve(
+        0,
+        ServeOptions {
+            timezone: Some("Not/AZone".to_string()),
+            ..ServeOptions::default()
+        },
+    )
+    .await;
+    assert_eq!(
+        result.err().map(|e| e.kind()),
+        Some(std::io::ErrorKind::InvalidInput)
+    );
+}
@@ -0,0 +1,281 @@
+//! Session tests: createSession / runCode / closeSession, idle eviction,
+//! the session cap, and per-session serialization
+
+#![cfg(feature = "jsonrpc")]
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use common::TestServer;
+use forthic::jsonrpc::{
+    dispatch, ClientError, ForthicJsonRpcServicer, JsonRpcClient, JsonRpcRequest, ServeOptions,
+    SessionConfig,
+};
+use forthic::literals::ForthicValue;
+use forthic::module::{Module, ModuleWord};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn call(servicer: &ForthicJsonRpcServicer, method: &str, params: Value) -> Value {
+    let request: JsonRpcRequest = serde_json::from_value(
+        json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }),
+    )
+    .expect("request parses");
+    dispatch(servicer, &request, false)
+}
+
+fn open(servicer: &ForthicJsonRpcServicer) -> String {
+    let response = call(servicer, "createSession", json!({}));
+    response["result"]["session_id"]
+        .as_str()
+        .expect("session id")
+        .to_string()
+}
+
+fn run(servicer: &ForthicJsonRpcServicer, session_id: &str, code: &str) -> Value {
+    call(
+        servicer,
+        "runCode",
+        json!({ "session_id": session_id, "code": code }),
+    )
+}
+
+#[test]
+fn test_definitions_and_variables_persist() {
+    let servicer = ForthicJsonRpcServicer::new();
+    let id = open(&servicer);
+    assert_eq!(id.len(), 32, "128-bit hex id");
+
+    run(
+        &servicer,
+        &id,
+        ": DOUBLE 2 * ; ['total'] VARIABLES 5 .total !",
+    );
+    let response = run(&servicer, &id, ".total @ DOUBLE");
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "int_value": 10 }])
+    );
+
+    // The stack persists too; a pushed stack lands on top of it
+    let response = call(
+        &servicer,
+        "runCode",
+        json!({ "session_id": id, "code": "+", "stack": [{ "int_value": 1 }] }),
+    );
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "int_value": 11 }])
+    );
+
+    // Sessions are independent of each other
+    let other = open(&servicer);
+    let response = run(&servicer, &other, "3 DOUBLE");
+    assert_eq!(response["error"]["code"], -32000);
+}
+
+#[test]
+fn test_failed_run_restores_stack() {
+    let servicer = ForthicJsonRpcServicer::new();
+    let id = open(&servicer);
+    run(&servicer, &id, "1 2");
+    let response = run(&servicer, &id, "3 {mod NO-SUCH-WORD");
+    assert_eq!(response["error"]["data"]["error_type"], "UnknownWord");
+    let response = run(&servicer, &id, "");
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "int_value": 1 }, { "int_value": 2 }])
+    );
+}
+
+#[test]
+fn test_close_and_unknown_sessions() {
+    let servicer = ForthicJsonRpcServicer::new();
+    let id = open(&servicer);
+    let response = call(&servicer, "closeSession", json!({ "session_id": id }));
+    assert_eq!(response["result"], json!({ "closed": true }));
+
+    let response = run(&servicer, &id, "1");
+    assert_eq!(response["error"]["code"], -32002);
+    let response = call(&servicer, "closeSession", json!({ "session_id": id }));
+    assert_eq!(response["error"]["code"], -32002);
+
+    let response = call(
+        &servicer,
+        "runCode",
+        json!({ "code": "1", "session_id": 5 }),
+    );
+    assert_eq!(response["error"]["code"], -32602);
+    assert_eq!(
+        response["error"]["message"],
+        "runCode \"session_id\" must be a string"
+    );
+}
+
+#[test]
+fn test_session_timezone_is_set_at_creation() {
+    let servicer = ForthicJsonRpcServicer::new();
+    let response = call(
+        &servicer,
+        "createSession",
+        json!({ "timezone": "Asia/Tokyo" }),
+    );
+    let id = response["result"]["session_id"]
+        .as_str()
+        .expect("session id");
+    assert_eq!(
+        run(&servicer, id, "NOW TZ@")["result"]["result_stack"],
+        json!([{ "string_value": "Asia/Tokyo" }])
+    );
+
+    // A session call cannot switch timezones
+    let response = call(
+        &servicer,
+        "runCode",
+        json!({ "session_id": id, "code": "NOW TZ@", "timezone": "UTC" }),
+    );
+    assert_eq!(response["error"]["code"], -32602);
+    assert_eq!(
+        response["error"]["message"],
+        "runCode \"timezone\" cannot be used with \"session_id\"; pass it to createSession"
+    );
+
+    let response = call(&servicer, "createSession", json!({ "timezone": 9 }));
+    assert_eq!(response["error"]["code"], -32602);
+    assert_eq!(
+        response["error"]["message"],
+        "createSession \"timezone\" must be a string"
+    );
+}
+
+#[test]
+fn test_max_sessions_cap() {
+    let mut servicer = ForthicJsonRpcServicer::new();
+    servicer.set_session_config(SessionConfig {
+        max_sessions: 2,
+        ..SessionConfig::default()
+    });
+    let first = open(&servicer);
+    open(&servicer);
+    let response = call(&servicer, "createSession", json!({}));
+    assert_eq!(response["error"]["code"], -32003);
+
+    call(&servicer, "closeSession", json!({ "session_id": first }));
+    assert!(call(&servicer, "createSession", json!({}))
+        .get("result")
+        .is_some());
+}
+
+#[test]
+fn test_idle_sessions_are_evicted() {
+    let mut servicer = ForthicJsonRpcServicer::new();
+    servicer.set_session_config(SessionConfig {
+        idle_timeout: Duration::from_millis(50),
+        max_sessions: 1,
+    });
+    let id = open(&servicer);
+    std::thread::sleep(Duration::from_millis(120));
+    assert_eq!(run(&servicer, &id, "1")["error"]["code"], -32002);
+    // The evicted session no longer counts against the cap
+    assert!(call(&servicer, "createSession", json!({}))
+        .get("result")
+        .is_some());
+}
+
+#[test]
+fn test_sessions_are_not_evicted_during_a_slow_call() {
+    let mut module = Module::new("host".to_string());
+    module.add_exportable_word(Arc::new(ModuleWord::new("NAP".to_string(), |_| {
+        std::thread::sleep(Duration::from_millis(200));
+        Ok(())
+    })));
+    let mut servicer = ForthicJsonRpcServicer::new();
+    servicer.add_runtime_module(module);
+    servicer.set_session_config(SessionConfig {
+        idle_timeout: Duration::from_millis(50),
+        max_sessions: 1,
+    });
+    let servicer = Arc::new(servicer);
+    let id = open(&servicer);
+
+    let slow = {
+        let servicer = Arc::clone(&servicer);
+        let id = id.clone();
+        std::thread::spawn(move || run(&servicer, &id, "['n'] VARIABLES 7 .n ! NAP"))
+    };
+    std::thread::sleep(Duration::from_millis(120));
+    // What the background sweeper does, well past the timeout mid-call
+    assert_eq!(servicer.sessions().evict_idle(), 0);
+    let response = slow.join().unwrap();
+    assert!(response.get("error").is_none(), "{response}");
+
+    // Idle time counts from the end of the call, not its start
+    let response = run(&servicer, &id, ".n @");
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "int_value": 7 }])
+    );
+}
+
+#[test]
+fn test_concurrent_calls_to_one_session_serialize() {
+    let servicer = Arc::new(ForthicJsonRpcServicer::new());
+    let id = open(&servicer);
+    run(&servicer, &id, "['n'] VARIABLES 0 .n !");
+
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let servicer = Arc::clone(&servicer);
+            let id = id.clone();
+            std::thread::spawn(move || {
+                for _ in 0..25 {
+                    let response = run(&servicer, &id, ".n @ 1 + .n !");
+                    assert!(response.get("error").is_none(), "{response}");
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let response = run(&servicer, &id, ".n @");
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "int_value": 200 }])
+    );
+}
+
+#[tokio::test]
+async fn test_sessions_over_http_with_client() {
+    let server = TestServer::start(ServeOptions {
+        max_sessions: Some(4),
+        ..ServeOptions::default()
+    })
+    .await;
+    let addr = server.addr().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let client = JsonRpcClient::new(&addr).expect("client");
+        let id = client.create_session().expect("createSession");
+        client
+            .run_code(Some(&id), ": SQUARE DUP * ;", &[])
+            .expect("define");
+        let stack = client
+            .run_code(Some(&id), "SQUARE", &[ForthicValue::Int(7)])
+            .expect("runCode");
+        assert_eq!(stack, vec![ForthicValue::Int(49)]);
+
+        client.close_session(&id).expect("closeSession");
+        match client.run_code(Some(&id), "1", &[]) {
+            Err(ClientError::Rpc { code, .. }) => assert_eq!(code, -32002),
+            other => panic!("expected session-not-found, got {other:?}"),
+        }
+    })
+    .await
+    .expect("blocking task");
+
+    server.stop().await;
+}
@@ -0,0 +1,236 @@
+//! The stdio transport's Content-Length framing, auth, and body cap, over
+//! an in-memory stream pair
+
+#![cfg(feature = "jsonrpc")]
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::jsonrpc::{serve_framed, ServeOptions};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, DuplexStream};
+use tokio::task::JoinHandle;
+
+struct StdioPeer {
+    input: DuplexStream,
+    output: BufReader<DuplexStream>,
+    server: JoinHandle<std::io::Result<()>>,
+}
+
+impl StdioPeer {
+    fn start(options: ServeOptions) -> Self {
+        let (input, server_in) = tokio::io::duplex(64 * 1024);
+        let (server_out, output) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(serve_framed(server_in, server_out, options));
+        Self {
+            input,
+            output: BufReader::new(output),
+            server,
+        }
+    }
+
+    async fn send_raw(&mut self, bytes: &[u8]) {
+        self.input.write_all(bytes).await.expect("write");
+    }
+
+    async fn send(&mut self, message: Value, extra_headers: &str) {
+        let body = message.to_string();
+        let frame = format!(
+            "Content-Length: {}\r\n{extra_headers}\r\n{body}",
+            body.len()
+        );
+        self.send_raw(frame.as_bytes()).await;
+    }
+
+    async fn receive(&mut self) -> Value {
+        let mut length = None;
+        loop {
+            let mut line = String::new();
+            self.output.read_line(&mut line).await.expect("header");
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(value) = line.strip_prefix("Content-Length: ") {
+                length = Some(value.parse::<usize>().expect("length"));
+            }
+        }
+        let mut body = vec![0; length.expect("Content-Length header")];
+        self.output.read_exact(&mut body).await.expect("body");
+        serde_json::from_slice(&body).expect("JSON body")
+    }
+
+    /// Close the input and wait for the transport to finish
+    async fn finish(self) -> (std::io::Result<()>, BufReader<DuplexStream>) {
+        drop(self.input);
+        let result = self.server.await.expect("transport task");
+        (result, self.output)
+    }
+}
+
+fn call(id: i64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+#[tokio::test]
+async fn test_framed_calls_and_notifications() {
+    let mut peer = StdioPeer::start(ServeOptions::default());
+    peer.send(call(1, "runCode", json!({ "code": "20 22 +" })), "")
+        .await;
+    let response = peer.receive().await;
+    assert_eq!(response["id"], 1);
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "int_value": 42 }])
+    );
+
+    // Notifications are answered with nothing; the batch answer comes next
+    peer.send(
+        json!({ "jsonrpc": "2.0", "method": "runCode", "params": { "code": "1" } }),
+        "Content-Type: application/vscode-jsonrpc; charset=utf-8\r\n",
+    )
+    .await;
+    peer.send(
+        json!([
+            call(2, "listModules", json!({})),
+            call(3, "noSuchMethod", json!({}))
+        ]),
+        "",
+    )
+    .await;
+    let batch = peer.receive().await;
+    assert_eq!(batch[0]["id"], 2);
+    assert_eq!(batch[1]["error"]["code"], -32601);
+
+    let (result, mut output) = peer.finish().await;
+    result.expect("clean end of input");
+    let mut rest = Vec::new();
+    output.read_to_end(&mut rest).await.expect("output closes");
+    assert!(rest.is_empty());
+}
+
+#[tokio::test]
+async fn test_rejected_messages_keep_the_framing() {
+    let mut peer = StdioPeer::start(ServeOptions {
+        token: Some("s3cret".into()),
+        max_body_bytes: Some(128),
+        ..ServeOptions::default()
+    });
+    let auth = "Authorization: Bearer s3cret\r\n";
+
+    peer.send(call(1, "listModules", json!({})), "").await;
+    assert_eq!(peer.receive().await["error"]["message"], "Unauthorized");
+
+    let code = "1 ".repeat(100);
+    peer.send(call(2, "runCode", json!({ "code": code })), auth)
+        .await;
+    assert_eq!(
+        peer.receive().await["error"]["message"],
+        "Payload too large"
+    );
+
+    peer.send(call(3, "runCode", json!({ "code": "7" })), auth)
+        .await;
+    let response = peer.receive().await;
+    assert_eq!(response["id"], 3);
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "int_value": 7 }])
+    );
+    peer.finish().await.0.expect("clean end of input");
+}
+
+#[tokio::test]
+async fn test_unframeable_input_ends_the_transport() {
+    for input in [
+        "Content-Type: application/json\r\n\r\n{}",
+        "Content-Length: lots\r\n\r\n{}",
+        "not a header\r\n\r\n",
+        "Content-Length: 2\r\n",
+    ] {
+        let mut peer = StdioPeer::start(ServeOptions::default());
+        peer.send_raw(input.as_bytes()).await;
+        let (result, _) = peer.finish().await;
+        let error = result.expect_err(input);
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "{input}");
+    }
+}
+
+#[test]
+fn test_print_keeps_off_the_framed_stdout() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut server = Command::new(env!("CARGO_BIN_EXE_forthic-jsonrpc"))
+        .arg("--stdio")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn forthic-jsonrpc");
+    let mut stdin = server.stdin.take().expect("stdin");
+    for message in [
+        call(1, "runCode", json!({ "code": "\"x\" PRINT 1 PEEK!" })),
+        call(2, "runCode", json!({ "code": "20 22 +" })),
+    ] {
+        let body = message.to_string();
+        write!(stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).expect("write");
+    }
+    drop(stdin);
+    let output = server.wait_with_output().expect("server output");
+    assert!(output.status.success());
+
+    // stdout is nothing but frames, one response per call
+    let mut stdout = &output.stdout[..];
+    let mut responses = Vec::new();
+    while !stdout.is_empty() {
+        let end = stdout
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .expect("frame header");
+        let header = String::from_utf8_lossy(&stdout[..end]).into_owned();
+        stdout = &stdout[end + 4..];
+        let length: usize = header
+            .strip_prefix("Content-Length: ")
+            .and_then(|n| n.parse().ok())
+            .unwrap_or_else(|| panic!("bad frame header {header:?}"));
+        let (body, rest) = stdout.split_at(length);
+        responses.push(serde_json::from_slice::<Value>(body).expect("JSON body"));
+        stdout = rest;
+    }
+    responses.sort_by_key(|response| response["id"].as_i64());
+    assert_eq!(responses.len(), 2, "{responses:?}");
+    assert_eq!(
+        responses[1]["result"]["result_stack"],
+        json!([{ "int_value": 42 }])
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.lines().any(|line| line == "x"), "{stderr}");
+    assert!(stderr.lines().any(|line| line == "1"), "{stderr}");
+}
+
+#[tokio::test]
+async fn test_more_messages_than_run_at_once_are_all_answered() {
+    let mut peer = StdioPeer::start(ServeOptions::default());
+    for id in 0..100 {
+        peer.send(
+            call(id, "runCode", json!({ "code": format!("{id} DUP *") })),
+            "",
+        )
+        .await;
+    }
+    let mut answered = Vec::new();
+    for _ in 0..100 {
+        let response = peer.receive().await;
+        let id = response["id"].as_i64().expect("id");
+        assert_eq!(
+            response["result"]["result_stack"],
+            json!([{ "int_value": id * id }])
+        );
+        answered.push(id);
+    }
+    answered.sort_unstable();
+    assert_eq!(answered, (0..100).collect::<Vec<_>>());
+    let (result, _) = peer.finish().await;
+    result.expect("clean end of input");
+}
@@ -0,0 +1,197 @@
+//! The WebSocket endpoint: calls, auth before upgrade, the message cap, and
+//! server-initiated notifications
+
+#![cfg(feature = "jsonrpc")]
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use common::TestServer;
+use forthic::jsonrpc::ServeOptions;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn connect(server: &TestServer, query: &str, token: Option<&str>) -> Result<Socket, WsError> {
+    let mut request = format!("ws://{}/ws{query}", server.addr())
+        .into_client_request()
+        .expect("valid url");
+    if let Some(token) = token {
+        request.headers_mut().insert(
+            "Authorization",
+            format!("Bearer {token}").parse().expect("valid header"),
+        );
+    }
+    tokio_tungstenite::connect_async(request)
+        .await
+        .map(|(socket, _)| socket)
+}
+
+async fn send(socket: &mut Socket, message: Value) {
+    socket
+        .send(Message::text(message.to_string()))
+        .await
+        .expect("message sends");
+}
+
+async fn receive(socket: &mut Socket) -> Value {
+    loop {
+        match socket.next().await.expect("socket open").expect("frame") {
+            Message::Text(text) => return serde_json::from_str(&text).expect("JSON message"),
+            Message::Ping(_) | Message::Pong(_) => continue,
+            other => panic!("unexpected frame {other:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_calls_and_batches_over_websocket() {
+    let server = TestServer::start(ServeOptions::default()).await;
+    let mut socket = connect(&server, "", None).await.expect("connects");
+
+    send(
+        &mut socket,
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "runCode", "params": { "code": "2 3 *" } }),
+    )
+    .await;
+    let response = receive(&mut socket).await;
+    assert_eq!(response["id"], 1);
+    assert_eq!(
+        response["result"]["result_stack"],
+        json!([{ "int_value": 6 }])
+    );
+
+    // A notification gets nothing back; the batch answer is the next message
+    send(
+        &mut socket,
+        json!({ "jsonrpc": "2.0", "method": "runCode", "params": { "code": "1" } }),
+    )
+    .await;
+    send(
+        &mut socket,
+        json!([
+            { "jsonrpc": "2.0", "id": 2, "method": "listModules" },
+            { "jsonrpc": "2.0", "id": 3, "method": "noSuchMethod" },
+        ]),
+    )
+    .await;
+    let batch = receive(&mut socket).await;
+    assert_eq!(batch[0]["id"], 2);
+    assert_eq!(batch[1]["error"]["code"], -32601);
+
+    socket
+        .send(Message::text("{not json"))
+        .await
+        .expect("message sends");
+    assert_eq!(receive(&mut socket).await["error"]["code"], -32700);
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_more_messages_than_run_at_once_are_all_answered() {
+    let server = TestServer::start(ServeOptions::default()).await;
+    let mut socket = connect(&server, "", None).await.expect("connects");
+    for id in 0..100 {
+        send(
+            &mut socket,
+            json!({ "jsonrpc": "2.0", "id": id, "method": "runCode", "params": { "code": format!("{id} DUP *") } }),
+        )
+        .await;
+    }
+    let mut answered = Vec::new();
+    for _ in 0..100 {
+        let response = receive(&mut socket).await;
+        let id = response["id"].as_i64().expect("id");
+        assert_eq!(
+            response["result"]["result_stack"],
+            json!([{ "int_value": id * id }])
+        );
+        answered.push(id);
+    }
+    answered.sort_unstable();
+    assert_eq!(answered, (0..100).collect::<Vec<_>>());
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_websocket_requires_token_before_upgrade() {
+    let server = TestServer::start(ServeOptions {
+        token: Some("s3cret".into()),
+        ..ServeOptions::default()
+    })
+    .await;
+
+    for (query, token) in [
+        ("", None),
+        ("", Some("wrong")),
+        ("?access_token=wrong", None),
+    ] {
+        match connect(&server, query, token).await {
+            Err(WsError::Http(response)) => assert_eq!(response.status(), 401),
+            other => panic!("expected 401, got {other:?}"),
+        }
+    }
+
+    for (query, token) in [("", Some("s3cret")), ("?access_token=s3cret", None)] {
+        let mut socket = connect(&server, query, token).await.expect("connects");
+        send(
+            &mut socket,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "listModules" }),
+        )
+        .await;
+        assert!(receive(&mut socket).await.get("result").is_some());
+    }
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_websocket_message_over_cap_closes_connection() {
+    let server = TestServer::start(ServeOptions {
+        max_body_bytes: Some(256),
+        ..ServeOptions::default()
+    })
+    .await;
+    let mut socket = connect(&server, "", None).await.expect("connects");
+    let code = "1 ".repeat(200);
+    send(
+        &mut socket,
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "runCode", "params": { "code": code } }),
+    )
+    .await;
+    match socket.next().await {
+        None | Some(Err(_)) | Some(Ok(Message::Close(_))) => {}
+        Some(Ok(other)) => panic!("expected the connection to close, got {other:?}"),
+    }
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_server_notifications_reach_open_websockets() {
+    let server = TestServer::start(ServeOptions::default()).await;
+    let mut first = connect(&server, "", None).await.expect("connects");
+    let mut second = connect(&server, "", None).await.expect("connects");
+    // A round trip on each guarantees both connections are being served
+    for socket in [&mut first, &mut second] {
+        send(
+            socket,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "listModules" }),
+        )
+        .await;
+        receive(socket).await;
+    }
+
+    assert_eq!(server.notify("progress", json!({ "done": 3 })), 2);
+    for socket in [&mut first, &mut second] {
+        let notification = receive(socket).await;
+        assert_eq!(notification["method"], "progress");
+        assert_eq!(notification["params"]["done"], 3);
+        assert!(notification.get("id").is_none());
+    }
+    server.stop().await;
+}
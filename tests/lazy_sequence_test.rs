@@ -0,0 +1,218 @@
+//! Lazy sequences: LAZY, REALIZE, TAKE-WHILE, DROP-WHILE, CHUNKED, the
+//! sequence-aware array words, and host iterators
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::interpreter::Interpreter;
+use forthic::literals::ForthicValue;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Run `code` and return its result as compact JSON text
+fn run_json(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(&format!("{code} >JSON")).unwrap();
+    top_json(&mut interp)
+}
+
+fn top_json(interp: &mut Interpreter) -> String {
+    match interp.get_stack_mut().pop().unwrap() {
+        ForthicValue::String(s) => serde_json::from_str::<Value>(&s).unwrap().to_string(),
+        other => panic!("expected JSON text, got {other:?}"),
+    }
+}
+
+fn run_err(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap_err().to_string()
+}
+
+/// A host sequence of 1, 2, 3, ... counting how many items were read
+fn counted_naturals(interp: &mut Interpreter) -> Arc<AtomicUsize> {
+    let reads = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&reads);
+    interp.push_sequence((1i64..).inspect(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    }));
+    reads
+}
+
+#[test]
+fn test_lazy_range_pipeline_reads_only_what_it_needs() {
+    assert_eq!(
+        run_json(
+            r#"1 1000000000 [.lazy TRUE] ~> RANGE "3 *" MAP "2 MOD 0 ==" FILTER 5 TAKE REALIZE"#
+        ),
+        "[6,12,18,24,30]"
+    );
+    // The same pipeline over an array gives the same answer
+    assert_eq!(
+        run_json(r#"1 20 RANGE LAZY "3 *" MAP "2 MOD 0 ==" FILTER 5 TAKE REALIZE"#),
+        "[6,12,18,24,30]"
+    );
+}
+
+#[test]
+fn test_host_iterator_is_pulled_on_demand() {
+    let mut interp = Interpreter::standard("UTC");
+    let reads = counted_naturals(&mut interp);
+    interp.run("'10 *' MAP 3 TAKE").unwrap();
+    assert_eq!(
+        reads.load(Ordering::SeqCst),
+        0,
+        "building stages runs nothing"
+    );
+    interp.run("REALIZE >JSON").unwrap();
+    assert_eq!(top_json(&mut interp), "[10,20,30]");
+    assert_eq!(reads.load(Ordering::SeqCst), 3);
+
+    let mut interp = Interpreter::standard("UTC");
+    let reads = counted_naturals(&mut interp);
+    interp.run("'DUP * 50 >' FIND").unwrap();
+    assert_eq!(interp.get_stack_mut().pop().unwrap(), ForthicValue::Int(8));
+    assert_eq!(reads.load(Ordering::SeqCst), 8, "FIND short-circuits");
+}
+
+#[test]
+fn test_host_iterator_is_read_once() {
+    let mut interp = Interpreter::standard("UTC");
+    interp.push_sequence(vec!["a", "b", "c"]);
+    interp
+        .run("DUP 2 TAKE REALIZE >JSON SWAP REALIZE >JSON")
+        .unwrap();
+    assert_eq!(top_json(&mut interp), r#"["c"]"#);
+    assert_eq!(top_json(&mut interp), r#"["a","b"]"#);
+}
+
+#[test]
+fn test_sequences_are_immutable_and_repeatable() {
+    assert_eq!(
+        run_json("[ [1 2 3] LAZY DUP '10 *' MAP REALIZE SWAP REALIZE ]"),
+        json!([[10, 20, 30], [1, 2, 3]]).to_string()
+    );
+    assert_eq!(
+        run_json("[ 5 9 [.lazy TRUE] ~> RANGE DUP REALIZE SWAP REALIZE ]"),
+        json!([[5, 6, 7, 8, 9], [5, 6, 7, 8, 9]]).to_string()
+    );
+    // Equality is identity: a copy is equal, an equal-looking sequence is not
+    assert_eq!(
+        run_json("[ [1 2 3] LAZY DUP == [1 2 3] LAZY [1 2 3] LAZY == ]"),
+        "[true,false]"
+    );
+}
+
+#[test]
+fn test_take_while_drop_while_chunked() {
+    for lazy in ["", "LAZY", "LAZY LAZY"] {
+        let items = format!("[1 2 3 4 1 5] {lazy}");
+        assert_eq!(
+            run_json(&format!("{items} '4 <' TAKE-WHILE REALIZE")),
+            "[1,2,3]",
+            "{lazy:?}"
+        );
+        assert_eq!(
+            run_json(&format!("{items} '4 <' DROP-WHILE REALIZE")),
+            "[4,1,5]",
+            "{lazy:?}"
+        );
+        assert_eq!(
+            run_json(&format!("{items} 4 CHUNKED REALIZE")),
+            "[[1,2,3,4],[1,5]]",
+            "{lazy:?}"
+        );
+    }
+    assert_eq!(
+        run_json("1 1000000000 [.lazy TRUE] ~> RANGE '3 <' DROP-WHILE 2 CHUNKED 2 TAKE REALIZE"),
+        "[[3,4],[5,6]]"
+    );
+    // The chunk size is not reserved up front
+    assert_eq!(
+        run_json("[1 2 3] LAZY 1000000000000 CHUNKED REALIZE"),
+        "[[1,2,3]]"
+    );
+    assert_eq!(
+        run_json("[1 2 3] LAZY 9223372036854775807 CHUNKED REALIZE"),
+        "[[1,2,3]]"
+    );
+}
+
+#[test]
+fn test_array_words_consume_sequences() {
+    let seq = "[1 2 3 4] LAZY";
+    assert_eq!(run_json(&format!("{seq} LENGTH")), "4");
+    assert_eq!(run_json(&format!("{seq} '2 >' COUNT")), "2");
+    assert_eq!(run_json(&format!("{seq} 0 '+' REDUCE")), "10");
+    assert_eq!(
+        run_json(&format!("[ {seq} '10 *' [.with_key TRUE] ~> FOREACH ]")),
+        "[0,10,1,20,2,30,3,40]"
+    );
+    assert_eq!(
+        run_json(&format!(
+            "[ {seq} 1 [.push_rest TRUE] ~> TAKE REALIZE SWAP REALIZE SWAP ]"
+        )),
+        "[[1],[2,3,4]]"
+    );
+    assert_eq!(run_json(&format!("{seq} 3 SKIP REALIZE")), "[4]");
+    assert_eq!(run_json(&format!("{seq} '9 >' FIND")), "null");
+    // REALIZE leaves everything else alone
+    assert_eq!(run_json("[1 2] REALIZE"), "[1,2]");
+}
+
+#[test]
+fn test_sequence_errors() {
+    assert!(run_err("[1 2] LAZY '2 *' [.with_key TRUE] ~> MAP")
+        .contains("MAP takes no options on a sequence; REALIZE it first"));
+    assert!(run_err("[1 2] LAZY 0 CHUNKED")
+        .contains("CHUNKED argument 2 must be positive int, got int"));
+    assert!(run_err("'abc' LAZY").contains("LAZY argument 1 must be array or sequence, got string"));
+    // Words that need every item in hand refuse a sequence
+    let seq = "1 3 [.lazy TRUE] ~> RANGE";
+    assert!(run_err(&format!("{seq} REVERSE"))
+        .contains("REVERSE argument 1 is a sequence: REALIZE first"));
+    assert!(run_err(&format!("[0] {seq} ZIP")).contains("ZIP argument 2 is a sequence"));
+    assert!(run_err(&format!("{seq} SORT")).contains("SORT argument 1 is a sequence"));
+    assert!(run_err(&format!("{seq} >JSON")).contains(">JSON argument 1 is a sequence"));
+    assert!(run_err(&format!("[{seq}] >JSON"))
+        .contains(">JSON argument 1 holds a sequence: REALIZE first"));
+    assert_eq!(run_json(&format!("{seq} REALIZE REVERSE")), "[3,2,1]");
+    // ... in every module, not only array and JSON
+    for (code, word) in [
+        ("SUM", "SUM"),
+        ("MAX", "MAX"),
+        ("MIN", "MIN"),
+        ("MEAN", "MEAN"),
+        ("PRODUCT", "PRODUCT"),
+        ("',' JOIN", "JOIN"),
+        (">STR", ">STR"),
+        ("2 CONTAINS?", "CONTAINS?"),
+        ("EMPTY?", "EMPTY?"),
+        ("REC", "REC"),
+        ("'k' REC@", "REC@"),
+        ("KEYS", "KEYS"),
+        ("VALUES", "VALUES"),
+    ] {
+        assert!(
+            run_err(&format!("{seq} {code}"))
+                .contains(&format!("{word} argument 1 is a sequence: REALIZE first")),
+            "{code}"
+        );
+    }
+    assert!(run_err(&format!("[{seq}] >STR"))
+        .contains(">STR argument 1 holds a sequence: REALIZE first"));
+    assert_eq!(
+        run_json(&format!(
+            "[ {seq} REALIZE DUP SUM SWAP DUP MAX SWAP DUP MIN SWAP DUP MEAN SWAP PRODUCT ]"
+        )),
+        json!([6, 3, 1, 2, 6]).to_string()
+    );
+    // Errors in stage code surface when the sequence is pulled
+    let mut interp = Interpreter::standard("UTC");
+    interp.run("[1 2] LAZY 'NO-SUCH-WORD' MAP").unwrap();
+    assert!(interp
+        .run("REALIZE")
+        .unwrap_err()
+        .to_string()
+        .contains("NO-SUCH-WORD"));
+}
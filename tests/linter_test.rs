@@ -0,0 +1,215 @@
+//! Linter tests
+//!
+//! Each rule fires on a minimal program, stays quiet on its clean
+//! counterpart, and can be switched off from a config record. Findings
+//! point at the offending token.
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::linter::{lint, LintConfig, LintFinding, LintRule, Linter};
+use forthic::ForthicValue;
+use indexmap::IndexMap;
+
+fn rules(findings: &[LintFinding]) -> Vec<LintRule> {
+    findings.iter().map(|f| f.rule).collect()
+}
+
+fn config(entries: Vec<(&str, ForthicValue)>) -> LintConfig {
+    let record: IndexMap<String, ForthicValue> = entries
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+    LintConfig::from_value(&ForthicValue::Record(record)).unwrap()
+}
+
+// ===== Individual rules =====
+
+#[test]
+fn test_clean_program_has_no_findings() {
+    let findings = lint(": DOUBLE 2 * ;  [1 2 3] 'DOUBLE' MAP").unwrap();
+    assert!(findings.is_empty(), "{findings:?}");
+}
+
+#[test]
+fn test_unused_definition() {
+    let code = ": USED 1 ;  : UNUSED 2 ;  USED";
+    let findings = lint(code).unwrap();
+    assert_eq!(rules(&findings), vec![LintRule::UnusedDefinition]);
+    assert!(findings[0].message.contains("UNUSED"));
+    assert_eq!(
+        findings[0].location.column,
+        code.find("UNUSED").unwrap() + 1
+    );
+}
+
+#[test]
+fn test_memo_used_through_bang_variants_is_not_unused() {
+    let findings = lint("@: COUNTER 0 ;  COUNTER!").unwrap();
+    assert!(findings.is_empty(), "{findings:?}");
+}
+
+#[test]
+fn test_prefixed_reference_counts_as_use() {
+    let findings = lint("{util : HELPER 1 ; }  util.HELPER").unwrap();
+    assert!(findings.is_empty(), "{findings:?}");
+}
+
+#[test]
+fn test_unused_variable() {
+    let code = "['x' 'y' 'z'] VARIABLES  5 .x !  \"${.y}\" DROP  '.z @' RUN";
+    assert!(lint(code).unwrap().is_empty());
+
+    let code = "['x' 'unused'] VARIABLES  5 .x !";
+    let findings = lint(code).unwrap();
+    assert_eq!(rules(&findings), vec![LintRule::UnusedVariable]);
+    assert!(findings[0].message.contains("'unused'"));
+    // Points inside the quotes, at the name itself
+    assert_eq!(findings[0].location.start_pos, code.find("unused").unwrap());
+}
+
+#[test]
+fn test_shadowed_standard_word() {
+    let findings = lint(": MAP 1 ;  MAP").unwrap();
+    assert_eq!(rules(&findings), vec![LintRule::ShadowedWord]);
+}
+
+#[test]
+fn test_concat_then_run_is_dynamic_code() {
+    let code = "['1 ' '2 +'] CONCAT RUN";
+    let findings = lint(code).unwrap();
+    assert_eq!(rules(&findings), vec![LintRule::DynamicCode]);
+    assert!(findings[0].message.contains("CONCAT"));
+    assert_eq!(findings[0].location.start_pos, code.find("RUN").unwrap());
+
+    // RUN of a literal is fine
+    assert!(lint("'1 2 +' RUN").unwrap().is_empty());
+
+    // The deprecated ts alias is caught too
+    let findings = lint("['1 ' '2 +'] CONCAT INTERPRET").unwrap();
+    assert_eq!(
+        rules(&findings),
+        vec![LintRule::DeprecatedWord, LintRule::DynamicCode]
+    );
+    assert!(findings[1].message.starts_with("INTERPRET executes"));
+}
+
+#[test]
+fn test_dynamic_code_inside_code_string_reports_real_position() {
+    let code = "[1] \"[x] CONCAT RUN\" MAP";
+    let findings = lint(code).unwrap();
+    assert_eq!(rules(&findings), vec![LintRule::DynamicCode]);
+    assert_eq!(findings[0].location.start_pos, code.find("RUN").unwrap());
+}
+
+#[test]
+fn test_deprecated_word_names_replacement() {
+    let findings = lint("[1 2] 'POP' MAP").unwrap();
+    assert_eq!(rules(&findings), vec![LintRule::DeprecatedWord]);
+    assert!(findings[0].message.contains("use DROP"));
+}
+
+#[test]
+fn test_redefinition_in_same_module_only() {
+    let code = ": A 1 ;  : A 2 ;  A";
+    let findings = lint(code).unwrap();
+    assert_eq!(rules(&findings), vec![LintRule::Redefinition]);
+    assert_eq!(
+        findings[0].location.start_pos,
+        code.rfind(": A").unwrap() + 2
+    );
+
+    // Same name in different modules is not a redefinition
+    let findings = lint("{m1 : A 1 ; } {m2 : A 2 ; } m1.A m2.A").unwrap();
+    assert!(findings.is_empty(), "{findings:?}");
+}
+
+#[test]
+fn test_long_definition() {
+    let body = "1 ".repeat(51);
+    let findings = lint(&format!(": LONG {body};  LONG")).unwrap();
+    assert_eq!(rules(&findings), vec![LintRule::LongDefinition]);
+    assert!(findings[0].message.contains("51 tokens"));
+}
+
+// ===== Configuration =====
+
+#[test]
+fn test_config_record_toggles_rules() {
+    let code = ": MAP 1 ;  : UNUSED 2 ;";
+    assert_eq!(lint(code).unwrap().len(), 3);
+
+    let linter = Linter::new(config(vec![
+        ("unused_definition", ForthicValue::Bool(false)),
+        ("shadowed_word", ForthicValue::Bool(false)),
+    ]));
+    assert!(linter.lint(code).unwrap().is_empty());
+}
+
+#[test]
+fn test_config_options() {
+    let mut deprecated = IndexMap::new();
+    deprecated.insert(
+        "OLD-WORD".to_string(),
+        ForthicValue::String("NEW-WORD".to_string()),
+    );
+    let linter = Linter::new(config(vec![
+        ("max_definition_length", ForthicValue::Int(2)),
+        ("deprecated_words", ForthicValue::Record(deprecated)),
+    ]));
+    let findings = linter.lint(": F 1 2 3 ; F OLD-WORD POP").unwrap();
+    // POP is no longer deprecated: the config replaced the default list
+    assert_eq!(
+        rules(&findings),
+        vec![LintRule::LongDefinition, LintRule::DeprecatedWord]
+    );
+}
+
+#[test]
+fn test_config_rejects_unknown_keys() {
+    let mut record = IndexMap::new();
+    record.insert("unused_defintion".to_string(), ForthicValue::Bool(false));
+    let err = LintConfig::from_value(&ForthicValue::Record(record)).unwrap_err();
+    assert!(err.to_string().contains("unused_defintion"));
+
+    let mut deprecated = IndexMap::new();
+    deprecated.insert("OLD-WORD".to_string(), ForthicValue::Int(1));
+    let mut record = IndexMap::new();
+    record.insert(
+        "deprecated_words".to_string(),
+        ForthicValue::Record(deprecated),
+    );
+    let err = LintConfig::from_value(&ForthicValue::Record(record)).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("'deprecated_words' values must be strings"));
+}
+
+// ===== Output =====
+
+#[test]
+fn test_finding_to_value_is_machine_readable() {
+    let linter = Linter::default();
+    let findings = linter.lint_source(": UNUSED 1 ;", "lib.forthic").unwrap();
+    let ForthicValue::Record(rec) = findings[0].to_value() else {
+        panic!("finding is a record");
+    };
+    assert_eq!(
+        rec.get("rule"),
+        Some(&ForthicValue::String("unused_definition".to_string()))
+    );
+    let Some(ForthicValue::Record(loc)) = rec.get("location") else {
+        panic!("location is a record");
+    };
+    assert_eq!(
+        loc.get("source"),
+        Some(&ForthicValue::String("lib.forthic".to_string()))
+    );
+    assert_eq!(loc.get("line"), Some(&ForthicValue::Int(1)));
+    assert_eq!(loc.get("column"), Some(&ForthicValue::Int(3)));
+}
+
+#[test]
+fn test_tokenizer_errors_are_returned() {
+    assert!(lint("'unterminated").is_err());
+}
@@ -0,0 +1,184 @@
+//! Locale-aware FORMAT-NUMBER and PARSE-NUMBER: styles, bundled locales,
+//! round trips, and option errors
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::interpreter::Interpreter;
+use forthic::literals::ForthicValue;
+
+fn run(code: &str) -> ForthicValue {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap();
+    interp.get_stack_mut().pop().unwrap()
+}
+
+/// FORMAT-NUMBER `value` with `options` (Forthic option pairs)
+fn format(value: &str, options: &str) -> String {
+    match run(&format!("{value} [{options}] ~> FORMAT-NUMBER")) {
+        ForthicValue::String(s) => s,
+        other => panic!("expected a string, got {other:?}"),
+    }
+}
+
+fn parse(text: &str, locale: &str) -> ForthicValue {
+    run(&format!("\"{text}\" [.locale '{locale}'] ~> PARSE-NUMBER"))
+}
+
+fn run_err(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap_err().to_string()
+}
+
+#[test]
+fn test_decimal_style_and_separators() {
+    assert_eq!(format("1234567.891", ""), "1,234,567.891");
+    assert_eq!(format("1234.5", ".decimals 2"), "1,234.50");
+    assert_eq!(
+        format("2.34567", ""),
+        "2.346",
+        "three decimals at most by default"
+    );
+    assert_eq!(format("1234.5", ".grouping FALSE"), "1234.5");
+    assert_eq!(format("1234567.891", ".locale 'de-DE'"), "1.234.567,891");
+    assert_eq!(format("1234.5", ".locale 'fr-FR'"), "1\u{202f}234,5");
+    assert_eq!(format("1234567", ".locale 'de-CH'"), "1’234’567");
+    // Spanish leaves four-digit numbers ungrouped
+    assert_eq!(format("1234", ".locale 'es-ES'"), "1234");
+    assert_eq!(format("12345", ".locale 'es-ES'"), "12.345");
+    // Ints print exactly, past the f64-exact range
+    assert_eq!(format("9007199254740993", ""), "9,007,199,254,740,993");
+    assert_eq!(format("-0.0001", ".decimals 2"), "0.00");
+    assert_eq!(run("NULL FORMAT-NUMBER"), ForthicValue::Null);
+    assert_eq!(
+        run("1234.5 FORMAT-NUMBER"),
+        ForthicValue::String("1,234.5".to_string())
+    );
+}
+
+#[test]
+fn test_percent_and_currency() {
+    assert_eq!(format("0.256", ".style 'percent'"), "26%");
+    assert_eq!(format("0.256", ".style 'percent' .decimals 1"), "25.6%");
+    assert_eq!(
+        format("0.256", ".style 'percent' .locale 'de-DE'"),
+        "26\u{a0}%"
+    );
+    // Scaling by 100 can overflow to infinity
+    assert_eq!(
+        format("1.7976931348623157e308", ".style 'percent'"),
+        "Infinity"
+    );
+    assert_eq!(format("-1 SQRT", ".style 'percent'"), "NaN");
+    // ...while rounding a huge finite number must not
+    let max = format("1.7976931348623157e308", ".style 'currency'");
+    assert!(
+        max.starts_with("$179,769,313,486,231,570,") && max.ends_with(".00"),
+        "{max}"
+    );
+
+    assert_eq!(format("1234.5", ".style 'currency'"), "$1,234.50");
+    assert_eq!(format("-5", ".style 'currency'"), "-$5.00");
+    assert_eq!(
+        format("1234.5", ".style 'currency' .locale 'de-DE'"),
+        "1.234,50\u{a0}€"
+    );
+    assert_eq!(
+        format("1234.5", ".currency 'EUR' .locale 'nl'"),
+        "€\u{a0}1.234,50"
+    );
+    assert_eq!(format("1234.5", ".currency 'jpy'"), "¥1,235");
+    assert_eq!(format("5", ".currency 'CHF'"), "CHF\u{a0}5.00");
+    assert_eq!(format("5", ".currency 'NOK'"), "NOK\u{a0}5.00");
+    assert_eq!(
+        format("5", ".style 'currency' .decimals 0 .locale 'sv_SE'"),
+        "5\u{a0}kr"
+    );
+}
+
+#[test]
+fn test_compact_and_scientific() {
+    assert_eq!(format("999", ".style 'compact'"), "999");
+    assert_eq!(format("1234", ".style 'compact'"), "1.2K");
+    assert_eq!(format("12345", ".style 'compact'"), "12K");
+    assert_eq!(format("-1500000000", ".style 'compact'"), "-1.5B");
+    assert_eq!(format("999960", ".style 'compact'"), "1M");
+    assert_eq!(format("1234", ".style 'compact' .decimals 2"), "1.23K");
+    assert_eq!(
+        format("1234", ".style 'compact' .locale 'de-DE'"),
+        "1,2\u{a0}Tsd."
+    );
+    // Italian does not abbreviate thousands
+    assert_eq!(format("1234", ".style 'compact' .locale 'it-IT'"), "1.234");
+
+    assert_eq!(format("12345", ".style 'scientific'"), "1.235E4");
+    assert_eq!(format("0.00012", ".style 'scientific'"), "1.2E-4");
+    assert_eq!(format("0", ".style 'scientific'"), "0E0");
+    assert_eq!(
+        format("-12345", ".style 'scientific' .decimals 1 .locale 'de-DE'"),
+        "-1,2E4"
+    );
+}
+
+#[test]
+fn test_parse_number() {
+    assert_eq!(parse("1,234", "en-US"), ForthicValue::Int(1234));
+    assert_eq!(parse("1,234.5", "en-US"), ForthicValue::Float(1234.5));
+    assert_eq!(parse("1.234,5", "de-DE"), ForthicValue::Float(1234.5));
+    assert_eq!(parse("1 234,5", "fr-FR"), ForthicValue::Float(1234.5));
+    assert_eq!(parse("CHF 1'234.50", "de-CH"), ForthicValue::Float(1234.5));
+    assert_eq!(parse("-$5.00", "en-US"), ForthicValue::Int(-5));
+    assert_eq!(parse("12.5%", "en-US"), ForthicValue::Float(0.125));
+    assert_eq!(parse("1.2K", "en-US"), ForthicValue::Int(1200));
+    assert_eq!(parse("1,2 Mio.", "de-DE"), ForthicValue::Int(1_200_000));
+    assert_eq!(parse("1.5E3", "en-US"), ForthicValue::Int(1500));
+    assert_eq!(parse(".5", "en-US"), ForthicValue::Float(0.5));
+    assert_eq!(
+        run("'1,234' PARSE-NUMBER"),
+        ForthicValue::Int(1234),
+        "en-US by default"
+    );
+    // Not numbers in the locale: NULL
+    for text in ["abc", "", "1,23,4", "1.234,5", "--5", "5 apples"] {
+        assert_eq!(parse(text, "en-US"), ForthicValue::Null, "{text:?}");
+    }
+    assert_eq!(run("NULL PARSE-NUMBER"), ForthicValue::Null);
+}
+
+#[test]
+fn test_format_then_parse_round_trips() {
+    for locale in [
+        "en-US", "en-GB", "de-DE", "de-CH", "fr-FR", "es-ES", "it-IT", "nl-NL", "pt-BR", "sv-SE",
+        "pl-PL",
+    ] {
+        for (value, style) in [
+            ("-1234567.25", "decimal"),
+            ("0.25", "percent"),
+            ("98765.43", "currency"),
+            ("2500000", "compact"),
+            ("0.000123", "scientific"),
+        ] {
+            let text = format(value, &format!(".locale '{locale}' .style '{style}'"));
+            assert_eq!(
+                parse(&text, locale),
+                run(value),
+                "{locale} {style}: {text:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_option_errors() {
+    assert!(run_err("1 [.locale 'xx-YY'] ~> FORMAT-NUMBER")
+        .contains("FORMAT-NUMBER: unknown locale 'xx-YY' (supported: en-US, en-GB,"));
+    assert!(run_err("1 [.style 'roman'] ~> FORMAT-NUMBER")
+        .contains("FORMAT-NUMBER: unknown style 'roman'"));
+    assert!(run_err("1 [.decimals 25] ~> FORMAT-NUMBER")
+        .contains("FORMAT-NUMBER argument 2 (decimals) must be int between 0 and 20, got int"));
+    assert!(run_err("1 [.currency 'EURO'] ~> FORMAT-NUMBER")
+        .contains("FORMAT-NUMBER: currency must be a three-letter code, got 'EURO'"));
+    assert!(run_err("'1' FORMAT-NUMBER")
+        .contains("FORMAT-NUMBER argument 1 must be number, got string"));
+    assert!(run_err("12 PARSE-NUMBER").contains("PARSE-NUMBER argument 1 must be string, got int"));
+}
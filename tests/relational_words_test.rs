@@ -0,0 +1,269 @@
+//! Relational words: JOIN-ON, PIVOT, UNPIVOT, DISTINCT-ON, AGGREGATE
+//!
+//! Results are compared as JSON text, so field order is checked too.
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::interpreter::Interpreter;
+use forthic::literals::ForthicValue;
+use serde_json::{json, Value};
+
+const USERS: &str =
+    r#"'[{"user_id":1,"name":"ann"},{"user_id":2,"name":"bob"},{"user_id":3,"name":"cy"}]' JSON>"#;
+const ORDERS: &str = r#"'[{"order":10,"user_id":1,"total":5},{"order":11,"user_id":1,"total":7},{"order":12,"user_id":4,"total":1}]' JSON>"#;
+const SALES: &str = r#"'[
+    {"region":"east","quarter":"Q1","amount":10},
+    {"region":"west","quarter":"Q1","amount":4},
+    {"region":"east","quarter":"Q2","amount":3.5},
+    {"region":"east","quarter":"Q1","amount":2},
+    {"region":"west","quarter":"Q2","amount":null}
+]' JSON>"#;
+
+/// Run `code` and return its result as compact JSON text
+fn run_json(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(&format!("{code} >JSON")).unwrap();
+    match interp.get_stack_mut().pop().unwrap() {
+        ForthicValue::String(s) => serde_json::from_str::<Value>(&s).unwrap().to_string(),
+        other => panic!("expected JSON text, got {other:?}"),
+    }
+}
+
+fn run_err(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap_err().to_string()
+}
+
+fn join(how: &str) -> String {
+    run_json(&format!(
+        "{USERS} {ORDERS} 'user_id' [.how '{how}'] ~> JOIN-ON"
+    ))
+}
+
+// ===== JOIN-ON =====
+
+#[test]
+fn test_inner_join_pairs_matching_rows_in_left_order() {
+    let expected = json!([
+        { "user_id": 1, "name": "ann", "order": 10, "total": 5 },
+        { "user_id": 1, "name": "ann", "order": 11, "total": 7 },
+    ]);
+    assert_eq!(
+        run_json(&format!("{USERS} {ORDERS} 'user_id' JOIN-ON")),
+        expected.to_string()
+    );
+    assert_eq!(join("inner"), expected.to_string());
+}
+
+#[test]
+fn test_outer_joins_fill_the_missing_side_with_null() {
+    let ann = [
+        json!({ "user_id": 1, "name": "ann", "order": 10, "total": 5 }),
+        json!({ "user_id": 1, "name": "ann", "order": 11, "total": 7 }),
+    ];
+    let bob = json!({ "user_id": 2, "name": "bob", "order": null, "total": null });
+    let cy = json!({ "user_id": 3, "name": "cy", "order": null, "total": null });
+    // An unmatched right row takes the shared key from the right
+    let stray = json!({ "user_id": 4, "name": null, "order": 12, "total": 1 });
+
+    assert_eq!(join("left"), json!([ann[0], ann[1], bob, cy]).to_string());
+    assert_eq!(join("right"), json!([ann[0], ann[1], stray]).to_string());
+    assert_eq!(
+        join("full"),
+        json!([ann[0], ann[1], bob, cy, stray]).to_string()
+    );
+}
+
+#[test]
+fn test_join_on_several_keys_renames_clashing_fields() {
+    let left = r#"'[{"a":1,"b":"x","v":"L1"},{"a":1,"b":"y","v":"L2"},{"a":null,"b":"x","v":"L3"}]' JSON>"#;
+    let right = r#"'[{"a":1,"b":"y","v":"R1"},{"a":null,"b":"x","v":"R2"}]' JSON>"#;
+    assert_eq!(
+        run_json(&format!("{left} {right} ['a' 'b'] JOIN-ON")),
+        json!([{ "a": 1, "b": "y", "v": "L2", "v_right": "R1" }]).to_string(),
+        "NULL keys never match"
+    );
+    assert_eq!(
+        run_json(&format!(
+            "{left} {right} ['a' 'b'] [.how 'full'] ~> JOIN-ON LENGTH"
+        )),
+        "4",
+        "L1, L2+R1, L3, R2"
+    );
+    assert_eq!(
+        run_json(&format!(
+            "{left} {right} ['a' 'b'] [.suffix '2'] ~> JOIN-ON 0 NTH"
+        )),
+        json!({ "a": 1, "b": "y", "v": "L2", "v2": "R1" }).to_string()
+    );
+}
+
+#[test]
+fn test_join_on_key_code() {
+    let left = r#"'[{"name":"Ann","age":30},{"name":"Bob","age":41}]' JSON>"#;
+    let right = r#"'[{"name":"ann","city":"Oslo"}]' JSON>"#;
+    assert_eq!(
+        run_json(&format!(
+            "{left} {right} \"'name' REC@ LOWERCASE\" [.code TRUE] ~> JOIN-ON"
+        )),
+        json!([{ "name": "Ann", "age": 30, "name_right": "ann", "city": "Oslo" }]).to_string()
+    );
+}
+
+#[test]
+fn test_join_on_errors() {
+    assert!(run_err(&format!("{USERS} [ 1 ] 'user_id' JOIN-ON"))
+        .contains("element 0 of argument 2 must be a record, got int"),);
+    assert!(run_err(&format!(
+        "{USERS} {ORDERS} 'user_id' [.how 'outer'] ~> JOIN-ON"
+    ))
+    .contains("unknown join 'outer'"));
+    assert!(run_err(&format!("{USERS} {ORDERS} [] JOIN-ON"))
+        .contains("JOIN-ON argument 3 must be string or non-empty string[]"));
+    assert_eq!(run_json("NULL NULL 'id' JOIN-ON"), "[]");
+}
+
+// ===== PIVOT / UNPIVOT =====
+
+#[test]
+fn test_pivot_reduces_cells_in_first_seen_order() {
+    assert_eq!(
+        run_json(&format!("{SALES} 'region' 'quarter' 'amount' 'sum' PIVOT")),
+        json!([
+            { "region": "east", "Q1": 12, "Q2": 3.5 },
+            { "region": "west", "Q1": 4, "Q2": null },
+        ])
+        .to_string()
+    );
+    assert_eq!(
+        run_json(&format!(
+            "{SALES} 'quarter' 'region' 'amount' 'count' PIVOT"
+        )),
+        json!([
+            { "quarter": "Q1", "east": 2, "west": 1 },
+            { "quarter": "Q2", "east": 1, "west": 0 },
+        ])
+        .to_string()
+    );
+    assert!(run_err(&format!(
+        "{SALES} 'region' 'quarter' 'amount' 'median' PIVOT"
+    ))
+    .contains("unknown reducer 'median'"));
+}
+
+#[test]
+fn test_unpivot_melts_non_id_fields() {
+    let wide = r#"'[{"id":1,"a":2,"b":3},{"b":4,"id":2}]' JSON>"#;
+    assert_eq!(
+        run_json(&format!("{wide} 'id' UNPIVOT")),
+        json!([
+            { "id": 1, "field": "a", "value": 2 },
+            { "id": 1, "field": "b", "value": 3 },
+            { "id": 2, "field": "b", "value": 4 },
+        ])
+        .to_string()
+    );
+    assert_eq!(
+        run_json(&format!(
+            "{wide} ['id'] [.key 'column' .value 'n'] ~> UNPIVOT 0 NTH"
+        )),
+        json!({ "id": 1, "column": "a", "n": 2 }).to_string()
+    );
+}
+
+#[test]
+fn test_pivot_of_unpivot_round_trips() {
+    let wide = r#"'[{"id":1,"a":2,"b":3},{"id":2,"a":5,"b":6}]' JSON>"#;
+    assert_eq!(
+        run_json(&format!(
+            "{wide} 'id' UNPIVOT 'id' 'field' 'value' 'first' PIVOT"
+        )),
+        json!([{ "id": 1, "a": 2, "b": 3 }, { "id": 2, "a": 5, "b": 6 }]).to_string()
+    );
+}
+
+// ===== DISTINCT-ON =====
+
+#[test]
+fn test_distinct_on_keeps_first_record_per_key() {
+    assert_eq!(
+        run_json(&format!("{SALES} 'region' DISTINCT-ON")),
+        json!([
+            { "region": "east", "quarter": "Q1", "amount": 10 },
+            { "region": "west", "quarter": "Q1", "amount": 4 },
+        ])
+        .to_string()
+    );
+    assert_eq!(
+        run_json(&format!("{SALES} ['region' 'quarter'] DISTINCT-ON LENGTH")),
+        "4"
+    );
+    // 1 and 1.0 are the same key; a missing field is NULL
+    assert_eq!(
+        run_json(r#"'[{"k":1},{"k":1.0},{},{"k":null}]' JSON> 'k' DISTINCT-ON"#),
+        json!([{ "k": 1 }, {}]).to_string()
+    );
+}
+
+// ===== AGGREGATE =====
+
+#[test]
+fn test_aggregate_whole_table() {
+    assert_eq!(
+        run_json(&format!(
+            r#"{SALES} [.sum "amount" .count "*" .count "amount" .mean "amount" .min "amount" .max "quarter" .last "region"] AGGREGATE"#
+        )),
+        json!({
+            "sum_amount": 19.5,
+            "count": 5,
+            "count_amount": 4,
+            "mean_amount": 4.875,
+            "min_amount": 2,
+            "max_quarter": "Q2",
+            "last_region": "west",
+        })
+        .to_string()
+    );
+    assert_eq!(
+        run_json(r#"[] [.sum "x" .count "*" .list "x"] AGGREGATE"#),
+        json!({ "sum_x": null, "count": 0, "list_x": [] }).to_string()
+    );
+}
+
+#[test]
+fn test_aggregate_by_groups() {
+    assert_eq!(
+        run_json(&format!(
+            r#"{SALES} [.sum "amount" .count "*"] [.by "region"] ~> AGGREGATE"#
+        )),
+        json!([
+            { "region": "east", "sum_amount": 15.5, "count": 3 },
+            { "region": "west", "sum_amount": 4, "count": 2 },
+        ])
+        .to_string()
+    );
+    assert_eq!(
+        run_json(&format!(
+            r#"{SALES} [.list "amount"] [.by ["region" "quarter"]] ~> AGGREGATE"#
+        )),
+        json!([
+            { "region": "east", "quarter": "Q1", "list_amount": [10, 2] },
+            { "region": "west", "quarter": "Q1", "list_amount": [4] },
+            { "region": "east", "quarter": "Q2", "list_amount": [3.5] },
+            { "region": "west", "quarter": "Q2", "list_amount": [null] },
+        ])
+        .to_string()
+    );
+}
+
+#[test]
+fn test_aggregate_errors() {
+    assert!(run_err(&format!(r#"{SALES} [.sum] AGGREGATE"#))
+        .contains("spec must pair each reducer with a column"));
+    assert!(run_err(&format!(r#"{SALES} [.sum "*"] AGGREGATE"#))
+        .contains("only count applies to \"*\""));
+    assert!(run_err(&format!(r#"{SALES} [.sum "region"] AGGREGATE"#))
+        .contains("cannot sum string values"));
+    assert!(run_err(r#"[ 1 ] [.count "*"] AGGREGATE"#).contains("must be a record"));
+}
@@ -0,0 +1,166 @@
+//! Scientific and integer math words: powers, logs, trig, constants, SIGN,
+//! DIV/REM, GCD/LCM/IS-PRIME?, and bit operations
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::interpreter::Interpreter;
+use forthic::literals::ForthicValue;
+
+fn run(code: &str) -> ForthicValue {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap();
+    interp.get_stack_mut().pop().unwrap()
+}
+
+fn run_float(code: &str) -> f64 {
+    match run(code) {
+        ForthicValue::Float(f) => f,
+        ForthicValue::Int(i) => i as f64,
+        other => panic!("expected a number from {code:?}, got {other:?}"),
+    }
+}
+
+fn run_err(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap_err().to_string()
+}
+
+fn assert_close(code: &str, expected: f64) {
+    let actual = run_float(code);
+    assert!(
+        (actual - expected).abs() < 1e-12,
+        "{code}: expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn test_pow_promotion() {
+    assert_eq!(run("2 10 POW"), ForthicValue::Int(1024));
+    assert_eq!(run("-3 3 POW"), ForthicValue::Int(-27));
+    assert_eq!(run("2 -1 POW"), ForthicValue::Float(0.5));
+    assert_eq!(run("4 0.5 POW"), ForthicValue::Int(2));
+    assert_eq!(run("2.5 2 POW"), ForthicValue::Float(6.25));
+    // Past 2^53 the result is a Float, as with * and +
+    assert_eq!(run("2 53 POW"), ForthicValue::Int(1 << 53));
+    assert_eq!(run("2 60 POW"), ForthicValue::Float(2f64.powi(60)));
+    assert_eq!(run("'x' 2 POW"), ForthicValue::Null);
+}
+
+#[test]
+fn test_logs_and_exp() {
+    assert_eq!(run("0 EXP"), ForthicValue::Int(1));
+    assert_close("1 EXP", std::f64::consts::E);
+    assert_eq!(run("E LN"), ForthicValue::Int(1));
+    assert_eq!(run("1000 LOG10"), ForthicValue::Int(3));
+    assert_eq!(run("1024 LOG2"), ForthicValue::Int(10));
+    assert_eq!(run("0 LN"), ForthicValue::Float(f64::NEG_INFINITY));
+    assert!(run_float("-1 LN").is_nan());
+    assert_eq!(run("NULL LN"), ForthicValue::Null);
+}
+
+#[test]
+fn test_trig_and_constants() {
+    assert_eq!(run("PI"), ForthicValue::Float(std::f64::consts::PI));
+    assert_eq!(run("0 SIN"), ForthicValue::Int(0));
+    assert_close("PI 2 / SIN", 1.0);
+    assert_close("PI COS", -1.0);
+    assert_close("PI 4 / TAN", 1.0);
+    assert_close("1 ASIN", std::f64::consts::FRAC_PI_2);
+    assert_close("1 ACOS", 0.0);
+    assert_close("1 ATAN", std::f64::consts::FRAC_PI_4);
+    assert!(run_float("2 ASIN").is_nan());
+    // ( y x -- angle )
+    assert_close("1 0 ATAN2", std::f64::consts::FRAC_PI_2);
+    assert_close("0 -1 ATAN2", std::f64::consts::PI);
+}
+
+#[test]
+fn test_sign() {
+    assert_eq!(run("-7 SIGN"), ForthicValue::Int(-1));
+    assert_eq!(run("0.25 SIGN"), ForthicValue::Int(1));
+    assert_eq!(run("0 SIGN"), ForthicValue::Int(0));
+    assert!(run_float("-1 SQRT SIGN").is_nan());
+    assert_eq!(run("'x' SIGN"), ForthicValue::Null);
+}
+
+#[test]
+fn test_div_and_rem_floor_and_trunc() {
+    for (a, b, q, r) in [
+        (7, 2, 3, 1),
+        (-7, 2, -4, 1),
+        (7, -2, -4, -1),
+        (-7, -2, 3, -1),
+    ] {
+        assert_eq!(
+            run(&format!("{a} {b} DIV")),
+            ForthicValue::Int(q),
+            "{a} {b}"
+        );
+        assert_eq!(
+            run(&format!("{a} {b} REM")),
+            ForthicValue::Int(r),
+            "{a} {b}"
+        );
+    }
+    assert_eq!(run("-7 2 [.trunc TRUE] ~> DIV"), ForthicValue::Int(-3));
+    assert_eq!(run("-7 2 [.trunc TRUE] ~> REM"), ForthicValue::Int(-1));
+    assert_eq!(run("7.5 2 DIV"), ForthicValue::Int(3));
+    assert_eq!(run("-7.5 2 REM"), ForthicValue::Float(0.5));
+    assert_eq!(run("7 0 DIV"), ForthicValue::Null);
+    assert_eq!(run("7 0.0 REM"), ForthicValue::Null);
+    // Exact beyond 2^53, where / would round
+    assert_eq!(
+        run("9007199254740993 1 DIV"),
+        ForthicValue::Int(9_007_199_254_740_993)
+    );
+    assert!(
+        run_err("-9223372036854775808 -1 DIV").contains("DIV: result overflows a 64-bit integer")
+    );
+}
+
+#[test]
+fn test_number_theory() {
+    assert_eq!(run("12 18 GCD"), ForthicValue::Int(6));
+    assert_eq!(run("-12 18 GCD"), ForthicValue::Int(6));
+    assert_eq!(run("0 0 GCD"), ForthicValue::Int(0));
+    assert_eq!(run("4 6 LCM"), ForthicValue::Int(12));
+    assert_eq!(run("-4 6 LCM"), ForthicValue::Int(12));
+    assert_eq!(run("0 6 LCM"), ForthicValue::Int(0));
+    assert_eq!(run("12.0 18 GCD"), ForthicValue::Int(6));
+    assert!(run_err("4611686018427387904 3 LCM").contains("LCM: result overflows"));
+
+    let primes: Vec<i64> = (0..30)
+        .filter(|n| run(&format!("{n} IS-PRIME?")) == ForthicValue::Bool(true))
+        .collect();
+    assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    assert_eq!(run("-7 IS-PRIME?"), ForthicValue::Bool(false));
+    // Largest prime below 2^63, and a Carmichael number
+    assert_eq!(
+        run("9223372036854775783 IS-PRIME?"),
+        ForthicValue::Bool(true)
+    );
+    assert_eq!(run("561 IS-PRIME?"), ForthicValue::Bool(false));
+    assert_eq!(run("NULL IS-PRIME?"), ForthicValue::Null);
+}
+
+#[test]
+fn test_bit_operations() {
+    assert_eq!(run("12 10 BIT-AND"), ForthicValue::Int(8));
+    assert_eq!(run("12 10 BIT-OR"), ForthicValue::Int(14));
+    assert_eq!(run("12 10 BIT-XOR"), ForthicValue::Int(6));
+    assert_eq!(run("0 BIT-NOT"), ForthicValue::Int(-1));
+    assert_eq!(run("1 10 SHL"), ForthicValue::Int(1024));
+    assert_eq!(run("1024 3 SHR"), ForthicValue::Int(128));
+    assert_eq!(run("-16 2 SHR"), ForthicValue::Int(-4));
+    assert_eq!(run("NULL 2 SHL"), ForthicValue::Null);
+    assert!(run_err("1 64 SHL").contains("SHL: shift must be between 0 and 63, got 64"));
+    assert!(run_err("1 -1 SHR").contains("SHR: shift must be between 0 and 63, got -1"));
+}
+
+#[test]
+fn test_integer_words_reject_fractions() {
+    assert!(run_err("2.5 4 GCD").contains("GCD argument 1 must be int, got float"));
+    assert!(run_err("1 'a' BIT-AND").contains("BIT-AND argument 2 must be int, got string"));
+    assert!(run_err("2.5 IS-PRIME?").contains("IS-PRIME? argument 1 must be int, got float"));
+}
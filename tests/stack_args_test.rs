@@ -0,0 +1,96 @@
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use chrono::NaiveDate;
+use forthic::convert::StackArgs;
+use forthic::interpreter::Interpreter;
+use forthic::ForthicValue;
+
+fn interp_with(code: &str) -> Interpreter {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap();
+    interp
+}
+
+fn run_err(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap_err().into_innermost().to_string()
+}
+
+#[test]
+fn test_typed_pops() {
+    let mut interp = interp_with("[1 2] [['a' 1]] REC 2020-06-05 'x' 2.5 3 7");
+    assert_eq!(interp.pop_int("W", 7).unwrap(), 7);
+    assert_eq!(interp.pop_float_like("W", 6).unwrap(), 3.0);
+    assert_eq!(interp.pop_float_like("W", 5).unwrap(), 2.5);
+    assert_eq!(interp.pop_string("W", 4).unwrap(), "x");
+    assert_eq!(
+        interp.pop_date("W", 3).unwrap(),
+        NaiveDate::from_ymd_opt(2020, 6, 5).unwrap()
+    );
+    let record = interp.pop_record("W", 2).unwrap();
+    assert_eq!(record["a"], ForthicValue::Int(1));
+    assert_eq!(
+        interp.pop_array("W", 1).unwrap(),
+        vec![ForthicValue::Int(1), ForthicValue::Int(2)]
+    );
+}
+
+#[test]
+fn test_mismatch_names_word_and_position() {
+    let mut interp = interp_with("'x'");
+    let err = interp.pop_int("REPEAT", 2).unwrap_err();
+    assert_eq!(err.to_string(), "REPEAT argument 2 must be int, got string");
+
+    let mut interp = interp_with("NULL");
+    let err = interp.pop_code("RUN", 1).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "RUN argument 1 must be Forthic string, got null"
+    );
+
+    let mut interp = interp_with("NULL");
+    assert_eq!(interp.pop_typed::<Option<i64>>("W", 1).unwrap(), None);
+}
+
+#[test]
+fn test_pop_options_and_pop_n() {
+    let mut interp = interp_with("1 2 3 [.depth 2] ~>");
+    let options = interp.pop_options().unwrap();
+    assert_eq!(options.get_int("depth"), Some(2));
+    assert!(interp.pop_options().is_none());
+
+    let [a, b] = interp.pop_n().unwrap();
+    assert_eq!((a, b), (ForthicValue::Int(2), ForthicValue::Int(3)));
+    assert!(interp.pop_n::<2>().is_err());
+    // A short stack is left as it was
+    assert_eq!(interp.stack_pop().unwrap(), ForthicValue::Int(1));
+}
+
+#[test]
+fn test_standard_words_report_uniform_errors() {
+    assert_eq!(
+        run_err("[1 2] 5 MAP"),
+        "MAP argument 2 must be Forthic string, got int"
+    );
+    assert_eq!(
+        run_err("5 TRY"),
+        "TRY argument 1 must be Forthic string, got int"
+    );
+    assert_eq!(
+        run_err("TRUE 'X' 5 IF-RUN"),
+        "IF-RUN argument 3 must be Forthic string, got int"
+    );
+    assert_eq!(
+        run_err("[] 5 GROUP-BY-FIELD"),
+        "GROUP-BY-FIELD argument 2 must be string, got int"
+    );
+    assert_eq!(
+        run_err("[1] STR-LENGTH"),
+        "STR-LENGTH argument 1 must be string, got array. For arrays/records, use LENGTH."
+    );
+    assert_eq!(
+        run_err("'a' CONCAT"),
+        "CONCAT argument 1 must be array, got string. Wrap two strings as [s1 s2] CONCAT."
+    );
+}
@@ -0,0 +1,188 @@
+//! Statistics module: location, spread, distribution, relationship and
+//! smoothing words, NULL handling, and typed errors
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::interpreter::Interpreter;
+use forthic::literals::ForthicValue;
+use serde_json::{json, Value};
+
+/// Run `code` and return its result as compact JSON text
+fn run_json(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(&format!("{code} >JSON")).unwrap();
+    match interp.get_stack_mut().pop().unwrap() {
+        ForthicValue::String(s) => serde_json::from_str::<Value>(&s).unwrap().to_string(),
+        other => panic!("expected JSON text, got {other:?}"),
+    }
+}
+
+fn run_err(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap_err().to_string()
+}
+
+#[test]
+fn test_median_percentile_quantiles() {
+    assert_eq!(run_json("[3 1 4 1 5] MEDIAN"), "3");
+    assert_eq!(run_json("[4 1 3 2] MEDIAN"), "2.5");
+    assert_eq!(run_json("[] MEDIAN"), "null");
+
+    assert_eq!(run_json("[1 2 3 4 5] 25 PERCENTILE"), "2");
+    assert_eq!(run_json("[1 2 3 4 5] 0 PERCENTILE"), "1");
+    assert_eq!(run_json("[1 2 3 4 5] 100 PERCENTILE"), "5");
+    for (method, expected) in [
+        ("linear", "2.5"),
+        ("lower", "2"),
+        ("higher", "3"),
+        ("nearest", "3"),
+        ("midpoint", "2.5"),
+    ] {
+        assert_eq!(
+            run_json(&format!("[1 2 3 4] 50 [.method '{method}'] ~> PERCENTILE")),
+            expected,
+            "{method}"
+        );
+    }
+    assert_eq!(run_json("[1 2 3 4] 25 PERCENTILE"), "1.75");
+
+    assert_eq!(run_json("[9 8 7 6 5 4 3 2 1] 4 QUANTILES"), "[3,5,7]");
+    assert_eq!(run_json("[1 2 3 4] 1 QUANTILES"), "[]");
+    assert_eq!(run_json("[] 4 QUANTILES"), "[]");
+}
+
+#[test]
+fn test_spread() {
+    let data = "[2 4 4 4 5 5 7 9]";
+    assert_eq!(
+        run_json(&format!("{data} [.population TRUE] ~> VARIANCE")),
+        "4"
+    );
+    assert_eq!(
+        run_json(&format!("{data} [.population TRUE] ~> STDDEV")),
+        "2"
+    );
+    assert_eq!(run_json(&format!("{data} VARIANCE")), "4.571428571428571");
+    assert_eq!(run_json("[5] VARIANCE"), "null");
+    assert_eq!(run_json("[5] [.population TRUE] ~> STDDEV"), "0");
+
+    assert_eq!(
+        run_json(&format!("{data} [.population TRUE] ~> ZSCORES")),
+        json!([-1.5, -0.5, -0.5, -0.5, 0.0, 0.0, 1.0, 2.0]).to_string()
+    );
+    // No spread, no scores
+    assert_eq!(run_json("[3 3] ZSCORES"), "[null,null]");
+}
+
+#[test]
+fn test_mode_and_histogram() {
+    assert_eq!(run_json("[1 3 3 2 2] MODE"), "3", "first seen wins a tie");
+    assert_eq!(run_json("[1 2.0 2] MODE"), "2");
+    assert_eq!(run_json("[] MODE"), "null");
+
+    assert_eq!(
+        run_json("[1 2 2 3 4] 3 HISTOGRAM"),
+        json!([
+            { "lower": 1, "upper": 2, "count": 1 },
+            { "lower": 2, "upper": 3, "count": 2 },
+            { "lower": 3, "upper": 4, "count": 2 },
+        ])
+        .to_string()
+    );
+    assert_eq!(
+        run_json("[1 2 3 5 -1] [0 2 4] HISTOGRAM"),
+        json!([
+            { "lower": 0, "upper": 2, "count": 1 },
+            { "lower": 2, "upper": 4, "count": 2 },
+        ])
+        .to_string()
+    );
+    assert_eq!(run_json("[] 3 HISTOGRAM"), "[]");
+    // Constant data gets a unit range centered on the value
+    assert_eq!(
+        run_json("[1 1 1] 2 HISTOGRAM"),
+        json!([
+            { "lower": 0.5, "upper": 1, "count": 0 },
+            { "lower": 1, "upper": 1.5, "count": 3 },
+        ])
+        .to_string()
+    );
+    // NaN and infinities are not counted and do not stretch the bins
+    let one_two = json!([
+        { "lower": 1, "upper": 1.5, "count": 1 },
+        { "lower": 1.5, "upper": 2, "count": 1 },
+    ])
+    .to_string();
+    assert_eq!(run_json("[-1 LN 1 2] 2 HISTOGRAM"), one_two, "NaN");
+    assert_eq!(run_json("[0 LN 1 2] 2 HISTOGRAM"), one_two, "-inf");
+    assert_eq!(run_json("[0 LN -1 * 1 2] 2 HISTOGRAM"), one_two, "inf");
+    assert_eq!(run_json("[-1 LN] 2 HISTOGRAM"), "[]");
+    assert_eq!(
+        run_json("[-1 LN 1] [0 2] HISTOGRAM"),
+        json!([{ "lower": 0, "upper": 2, "count": 1 }]).to_string()
+    );
+    assert!(
+        run_err("[1] [0 LN 2] HISTOGRAM").contains("HISTOGRAM: bin edges must be finite, got -inf")
+    );
+    assert!(run_err("[1] [2 1] HISTOGRAM")
+        .contains("HISTOGRAM: bin edges must be at least two strictly ascending numbers"));
+    assert!(run_err("[1] 'x' HISTOGRAM")
+        .contains("HISTOGRAM argument 2 must be positive int or number[], got string"));
+    // Bin counts are capped before anything is allocated
+    assert!(run_err("[1 2] 1000000000 HISTOGRAM")
+        .contains("HISTOGRAM count 1000000000 is too large (limit 10000000)"));
+    assert!(run_err("[1 2] 1000000000 QUANTILES")
+        .contains("QUANTILES count 1000000000 is too large (limit 10000000)"));
+}
+
+#[test]
+fn test_correlation_and_regression() {
+    assert_eq!(run_json("[1 2 3] [2 4 6] CORRELATION"), "1");
+    assert_eq!(run_json("[1 2 3] [3 2 1] CORRELATION"), "-1");
+    assert_eq!(run_json("[1 2 3] [5 5 5] CORRELATION"), "null");
+    assert_eq!(
+        run_json("[1 2 3 4] [3 5 7 9] LINEAR-REGRESSION"),
+        json!({ "slope": 2, "intercept": 1, "r_squared": 1, "n": 4 }).to_string()
+    );
+    assert_eq!(run_json("[1 1] [2 3] LINEAR-REGRESSION"), "null");
+    assert!(run_err("[1 2 3] [1 2] CORRELATION")
+        .contains("CORRELATION: xs and ys must be the same length, got 3 and 2"));
+    // skip_null drops the whole pair
+    assert_eq!(
+        run_json("[1 2 NULL 3] [2 4 100 6] [.skip_null TRUE] ~> CORRELATION"),
+        "1"
+    );
+}
+
+#[test]
+fn test_moving_average() {
+    assert_eq!(run_json("[1 2 3 4 5] 3 MOVING-AVERAGE"), "[2,3,4]");
+    assert_eq!(run_json("[1 2] 3 MOVING-AVERAGE"), "[]");
+    assert_eq!(
+        run_json("[1 NULL 3 5 NULL NULL] 2 [.skip_null TRUE] ~> MOVING-AVERAGE"),
+        "[1,3,4,5,null]"
+    );
+    assert!(run_err("[1 2] 0 MOVING-AVERAGE")
+        .contains("MOVING-AVERAGE argument 2 must be positive int, got int"));
+}
+
+#[test]
+fn test_null_handling_and_typed_errors() {
+    assert!(run_err("[1 NULL 3] MEDIAN").contains(
+        "MEDIAN: element 1 of argument 1 must be a number, got null (use [.skip_null TRUE] to leave NULLs out)"
+    ));
+    assert_eq!(run_json("[1 NULL 3] [.skip_null TRUE] ~> MEDIAN"), "2");
+    assert_eq!(
+        run_json("[1 NULL 3] [.skip_null TRUE .population TRUE] ~> ZSCORES"),
+        json!([-1.0, null, 1.0]).to_string()
+    );
+    assert!(run_err("[1 'a'] STDDEV")
+        .contains("STDDEV: element 1 of argument 1 must be a number, got string"));
+    assert!(run_err("[1 TRUE] MODE").contains("got boolean"));
+    assert!(run_err("5 MEDIAN").contains("MEDIAN argument 1 must be number[], got int"));
+    assert_eq!(run_json("NULL MEDIAN"), "null");
+    assert!(run_err("[1] 120 PERCENTILE")
+        .contains("PERCENTILE: percentile must be between 0 and 100, got 120"));
+    assert!(run_err("[1] 50 [.method 'cubic'] ~> PERCENTILE").contains("unknown method 'cubic'"));
+}
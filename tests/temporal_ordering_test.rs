@@ -0,0 +1,132 @@
+//! Temporal ordering: <, <=, >, >= and SORT on Dates, Times and DateTimes
+//! (DateTimes by instant), Int against Float, cross-type rules, and
+//! SAME-INSTANT? / SAME-WALL-TIME?
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::interpreter::Interpreter;
+use forthic::literals::ForthicValue;
+
+fn run(code: &str) -> ForthicValue {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap();
+    interp.get_stack_mut().pop().unwrap()
+}
+
+fn truth(code: &str) -> bool {
+    match run(code) {
+        ForthicValue::Bool(b) => b,
+        other => panic!("expected a boolean from {code:?}, got {other:?}"),
+    }
+}
+
+/// 16:00Z as seen in New York (12:00) and in Tokyo (01:00 the next day)
+const NEW_YORK: &str = "'2024-07-01T16:00:00Z' >DATETIME 'America/New_York' >TZ";
+const TOKYO: &str = "'2024-07-01T16:00:00Z' >DATETIME 'Asia/Tokyo' >TZ";
+
+#[test]
+fn test_dates_and_times_order() {
+    assert!(truth("2024-01-31 2024-02-01 <"));
+    assert!(truth("2024-02-01 2024-02-01 <="));
+    assert!(!truth("2024-02-01 2024-01-31 <="));
+    assert!(truth("2024-02-01 2024-01-31 >="));
+    assert!(truth("9:30 14:00 <"));
+    assert!(truth("14:00 9:30 >"));
+}
+
+#[test]
+fn test_datetimes_order_by_instant() {
+    // Tokyo's wall clock reads later, but 11:00 in New York is the earlier instant
+    let earlier_in_new_york = "'2024-07-01T15:00:00Z' >DATETIME 'America/New_York' >TZ";
+    assert!(truth(&format!("{earlier_in_new_york} {TOKYO} <")));
+    assert!(!truth(&format!("{TOKYO} {earlier_in_new_york} <")));
+
+    // The same instant: ordered equal, and == agrees
+    assert!(!truth(&format!("{NEW_YORK} {TOKYO} <")));
+    assert!(!truth(&format!("{NEW_YORK} {TOKYO} >")));
+    assert!(truth(&format!("{NEW_YORK} {TOKYO} <=")));
+    assert!(truth(&format!("{NEW_YORK} {TOKYO} >=")));
+    assert!(truth(&format!("{NEW_YORK} {TOKYO} ==")));
+    assert!(!truth(&format!("{NEW_YORK} {TOKYO} !=")));
+    assert!(truth(&format!("{NEW_YORK} {NEW_YORK} ==")));
+}
+
+#[test]
+fn test_cross_type_values_have_no_order() {
+    // A Date against a DateTime needs a timezone; convert one side first
+    assert!(!truth("2024-07-01 '2024-07-02T00:00:00Z' >DATETIME <"));
+    assert!(!truth("2024-07-01 '2024-07-02T00:00:00Z' >DATETIME >"));
+    assert!(truth(
+        "2024-07-01 >DATETIME '2024-07-02T00:00:00Z' >DATETIME <"
+    ));
+    assert!(!truth("2024-07-01 9:30 <"));
+    assert!(!truth("2024-07-01 '2024-07-01' <="));
+}
+
+#[test]
+fn test_sort_mixed_zones_by_instant() {
+    let sorted = run(&format!(
+        "[{TOKYO} '2024-07-01T17:00:00Z' >DATETIME '2024-07-01T15:00:00Z' >DATETIME 'Europe/Paris' >TZ] SORT 'TZ@' MAP"
+    ));
+    assert_eq!(
+        sorted,
+        ForthicValue::Array(vec![
+            ForthicValue::String("Europe/Paris".to_string()),
+            ForthicValue::String("Asia/Tokyo".to_string()),
+            ForthicValue::String("UTC".to_string()),
+        ])
+    );
+    // Same instant: the stable sort keeps input order
+    assert_eq!(
+        run(&format!("[{TOKYO} {NEW_YORK}] SORT 'TZ@' MAP")),
+        ForthicValue::Array(vec![
+            ForthicValue::String("Asia/Tokyo".to_string()),
+            ForthicValue::String("America/New_York".to_string()),
+        ])
+    );
+    // Across types by rank: Dates, then Times, then DateTimes, NULL last
+    assert_eq!(
+        run("[NULL '2020-01-01T00:00:00Z' >DATETIME 9:30 2030-01-01] SORT '>STR' MAP"),
+        ForthicValue::Array(
+            [
+                "2030-01-01",
+                "09:30:00",
+                "2020-01-01T00:00:00+00:00[UTC]",
+                ""
+            ]
+            .map(|s| ForthicValue::String(s.to_string()))
+            .to_vec()
+        )
+    );
+}
+
+#[test]
+fn test_comparisons_and_sort_agree_on_int_against_float() {
+    // 2^53 + 1 has no exact f64; casting the Int would call it equal to 2^53
+    let (int, float) = ("9007199254740993", "9007199254740992.0");
+    assert!(!truth(&format!("{int} {float} <")));
+    assert!(truth(&format!("{int} {float} >")));
+    assert!(truth(&format!("{float} {int} <")));
+    assert!(!truth(&format!("{int} {float} <=")));
+    assert_eq!(
+        run(&format!("[{int} {float}] SORT")),
+        ForthicValue::Array(vec![
+            ForthicValue::Float(9007199254740992.0),
+            ForthicValue::Int(9007199254740993),
+        ])
+    );
+}
+
+#[test]
+fn test_same_instant_and_same_wall_time() {
+    assert!(truth(&format!("{NEW_YORK} {TOKYO} SAME-INSTANT?")));
+    assert!(!truth(&format!("{NEW_YORK} {TOKYO} SAME-WALL-TIME?")));
+
+    let noon_utc = "'2024-07-01T12:00:00Z' >DATETIME";
+    assert!(truth(&format!("{NEW_YORK} {noon_utc} SAME-WALL-TIME?")));
+    assert!(!truth(&format!("{NEW_YORK} {noon_utc} SAME-INSTANT?")));
+
+    assert!(!truth("2024-07-01 2024-07-01 SAME-INSTANT?"));
+    assert!(!truth(&format!("{NEW_YORK} NULL SAME-WALL-TIME?")));
+}
@@ -0,0 +1,172 @@
+//! Forthic test framework tests
+//!
+//! The assertion words (ASSERT, ASSERT=, ASSERT-ERROR), `: TEST-... ;`
+//! discovery with `{module}` SETUP/TEARDOWN fixtures, per-test isolation,
+//! and the text / TAP / JUnit XML reports.
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::errors::ForthicError;
+use forthic::interpreter::Interpreter;
+use forthic::testing::{discover_tests, TestModule, TestReport, TestRunner, TestStatus};
+
+fn interp() -> Interpreter {
+    let mut interp = Interpreter::standard("UTC");
+    interp.import_module(TestModule::new().module().clone(), "");
+    interp
+}
+
+fn assert_fails(code: &str) -> ForthicError {
+    let err = interp().run(code).unwrap_err().into_innermost();
+    assert_eq!(err.type_name(), "AssertionFailed", "{err}");
+    err
+}
+
+fn report(code: &str) -> TestReport {
+    let mut report = TestReport::new();
+    report.add_suite(TestRunner::default().run_source(code, "suite.forthic"));
+    report
+}
+
+// ===== Assertion words =====
+
+#[test]
+fn test_assert() {
+    interp().run("TRUE ASSERT  1 ASSERT").unwrap();
+    let err = assert_fails("0 ASSERT");
+    assert_eq!(err.to_string(), "ASSERT failed: got 0");
+    let err = assert_fails("FALSE [.message 'flag must be set'] ~> ASSERT");
+    assert_eq!(err.to_string(), "flag must be set");
+}
+
+#[test]
+fn test_assert_equals() {
+    interp()
+        .run("[1 2] '2 *' MAP [2 4] ASSERT=  1 1.0 ASSERT=")
+        .unwrap();
+    let err = assert_fails("1 '1' ASSERT=");
+    assert_eq!(err.to_string(), "ASSERT= failed: expected \"1\", got 1");
+    let err = assert_fails("1 2 [.message 'sum'] ~> ASSERT=");
+    assert_eq!(err.to_string(), "ASSERT= failed: sum: expected 2, got 1");
+}
+
+#[test]
+fn test_assert_error() {
+    let mut interp = interp();
+    interp
+        .run("42 'DROP DROP DROP' ASSERT-ERROR  'NO-SUCH-WORD' [.error_type 'UnknownWord'] ~> ASSERT-ERROR")
+        .unwrap();
+    // The stack is restored after the failing code
+    assert_eq!(interp.get_stack_mut().pop().unwrap().as_int(), Some(42));
+
+    let err = assert_fails("'1 2 +' ASSERT-ERROR");
+    assert!(err.to_string().contains("raised no error"));
+    let err = assert_fails("'DROP' [.error_type 'UnknownWord'] ~> ASSERT-ERROR");
+    assert!(err
+        .to_string()
+        .contains("expected UnknownWord, got StackUnderflow"));
+}
+
+// ===== Discovery =====
+
+#[test]
+fn test_discovery_finds_tests_in_modules() {
+    let code = ": HELPER 1 ;\n: TEST-A HELPER 1 ASSERT= ;\n{m : SETUP ; : TEST-B ; }";
+    let cases = discover_tests(code).unwrap();
+    let names: Vec<String> = cases.iter().map(|c| c.full_name()).collect();
+    assert_eq!(names, vec!["TEST-A", "m.TEST-B"]);
+    assert_eq!(cases[0].location.line, 2);
+}
+
+// ===== Runner =====
+
+#[test]
+fn test_runner_statuses() {
+    let code = "
+: TEST-PASS   1 1 ASSERT= ;
+: TEST-FAIL   1 2 ASSERT= ;
+: TEST-ERROR  DROP ;
+";
+    let report = report(code);
+    let statuses: Vec<TestStatus> = report.suites[0].results.iter().map(|r| r.status).collect();
+    assert_eq!(
+        statuses,
+        vec![TestStatus::Passed, TestStatus::Failed, TestStatus::Errored]
+    );
+    assert!(!report.all_passed());
+
+    // Diagnostics point at the failing word inside the test program
+    let diagnostics = report.suites[0].results[1].diagnostics.clone().unwrap();
+    assert!(diagnostics.contains("at line 3"), "{diagnostics}");
+    assert!(
+        diagnostics.contains(": TEST-FAIL   1 2 ASSERT= ;"),
+        "{diagnostics}"
+    );
+}
+
+#[test]
+fn test_each_test_gets_a_fresh_interpreter() {
+    let code = "
+['counter'] VARIABLES  0 .counter !
+: BUMP  .counter @ 1 + .counter ! ;
+: TEST-FIRST   BUMP .counter @ 1 ASSERT= ;
+: TEST-SECOND  BUMP .counter @ 1 ASSERT= ;
+";
+    assert!(report(code).all_passed(), "{}", report(code).to_text());
+}
+
+#[test]
+fn test_module_fixtures() {
+    let code = "
+{parsing
+    ['input'] VARIABLES
+    : SETUP      '1,2' .input ! ;
+    : TEST-SPLIT .input @ ',' SPLIT LENGTH 2 ASSERT= ;
+    : TEST-FAILS 1 0 ASSERT= ;
+    : TEARDOWN   NULL .input ! ;
+}
+: TEST-TOP-LEVEL-HAS-NO-SETUP  TRUE ASSERT ;
+";
+    let report = report(code);
+    let results = &report.suites[0].results;
+    assert_eq!(results.len(), 3);
+    assert_eq!(
+        results[0].status,
+        TestStatus::Passed,
+        "{}",
+        report.to_text()
+    );
+    assert_eq!(results[1].status, TestStatus::Failed);
+    assert_eq!(results[2].status, TestStatus::Passed);
+}
+
+#[test]
+fn test_unloadable_program_reports_one_error() {
+    let report = report(": TEST-A 'unterminated ;");
+    assert_eq!(report.total(), 1);
+    assert_eq!(report.count(TestStatus::Errored), 1);
+}
+
+// ===== Reports =====
+
+#[test]
+fn test_tap_output() {
+    let tap = report(": TEST-OK TRUE ASSERT ; : TEST-BAD FALSE ASSERT ;").to_tap();
+    assert!(tap.starts_with("TAP version 13\n1..2\n"));
+    assert!(tap.contains("ok 1 - suite.forthic TEST-OK\n"));
+    assert!(tap.contains("not ok 2 - suite.forthic TEST-BAD\n"));
+    assert!(tap.contains("# ASSERT failed: got false"));
+}
+
+#[test]
+fn test_junit_output() {
+    let xml = report("{m : TEST-OK TRUE ASSERT ; : TEST-BAD 1 '<2>' ASSERT= ; : TEST-ERR DROP ; }")
+        .to_junit_xml();
+    assert!(xml.contains("<testsuites tests=\"3\" failures=\"1\" errors=\"1\""));
+    assert!(xml.contains("<testcase name=\"TEST-OK\" classname=\"suite.forthic.m\""));
+    assert!(
+        xml.contains("<failure message=\"ASSERT= failed: expected &quot;&lt;2&gt;&quot;, got 1\">")
+    );
+    assert!(xml.contains("<error message=\"Stack underflow\">"));
+}
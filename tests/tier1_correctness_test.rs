@@ -85,9 +85,10 @@ fn test_equal_times_are_equal() {
 }
 
 #[test]
-fn test_datetime_equality_requires_same_timezone() {
+fn test_datetime_equality_is_by_instant() {
     // ts compares Temporal values by ISO string (includes the tz
-    // annotation): the same instant in different timezones is NOT equal
+    // annotation); rs compares instants, as <= and >= do, so the same
+    // instant in different timezones is equal
     let la: chrono_tz::Tz = "America/Los_Angeles".parse().unwrap();
     let instant_utc = chrono_tz::UTC
         .with_ymd_and_hms(2020, 6, 5, 17, 15, 0)
@@ -100,7 +101,7 @@ fn test_datetime_equality_requires_same_timezone() {
     interp.run("==").unwrap();
     assert_eq!(
         interp.get_stack_mut().pop().unwrap(),
-        ForthicValue::Bool(false)
+        ForthicValue::Bool(true)
     );
 
     interp.stack_push(ForthicValue::DateTime(instant_utc));
@@ -0,0 +1,163 @@
+//! Timezone words: >TZ, >UTC, WITH-TZ, LOCAL-DATETIME, TZ@, UTC-OFFSET, and
+//! the .on_ambiguous policies for DST gaps and overlaps
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::interpreter::Interpreter;
+use forthic::literals::ForthicValue;
+
+const INSTANT: &str = "'2024-07-01T16:00:00Z' >DATETIME";
+
+fn run(code: &str) -> ForthicValue {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap();
+    interp.get_stack_mut().pop().unwrap()
+}
+
+fn text(code: &str) -> String {
+    match run(code) {
+        ForthicValue::String(s) => s,
+        other => panic!("expected a string from {code:?}, got {other:?}"),
+    }
+}
+
+fn run_err(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap_err().to_string()
+}
+
+/// LOCAL-DATETIME of `date` and `time` in `tz` with `options`, formatted
+fn local(date: &str, time: &str, tz: &str, options: &str) -> String {
+    text(&format!(
+        "'{date}' >DATE '{time}' >TIME '{tz}' [{options}] ~> LOCAL-DATETIME FORMAT-DATETIME"
+    ))
+}
+
+#[test]
+fn test_convert_the_instant() {
+    assert_eq!(
+        text(&format!("{INSTANT} 'Asia/Tokyo' >TZ FORMAT-DATETIME")),
+        "2024-07-02T01:00:00+09:00"
+    );
+    assert_eq!(
+        text(&format!(
+            "{INSTANT} 'America/New_York' >TZ >UTC FORMAT-DATETIME"
+        )),
+        "2024-07-01T16:00:00+00:00"
+    );
+    assert_eq!(
+        text(&format!("{INSTANT} 'Europe/Berlin' >TZ TZ@")),
+        "Europe/Berlin"
+    );
+    assert_eq!(text(&format!("{INSTANT} TZ@")), "UTC");
+    assert_eq!(
+        run(&format!("{INSTANT} 'America/New_York' >TZ UTC-OFFSET")),
+        ForthicValue::Int(-4 * 3600)
+    );
+    assert_eq!(
+        run("'2024-01-15T12:00:00Z' >DATETIME 'Asia/Kolkata' >TZ UTC-OFFSET"),
+        ForthicValue::Int(5 * 3600 + 1800)
+    );
+    assert_eq!(
+        run("'2024-07-01' >DATE 'Asia/Tokyo' >TZ"),
+        ForthicValue::Null
+    );
+    assert_eq!(run("NULL TZ@"), ForthicValue::Null);
+    assert_eq!(run("NULL >UTC"), ForthicValue::Null);
+}
+
+#[test]
+fn test_with_tz_keeps_the_wall_clock() {
+    assert_eq!(
+        text(&format!(
+            "{INSTANT} 'America/New_York' WITH-TZ FORMAT-DATETIME"
+        )),
+        "2024-07-01T16:00:00-04:00"
+    );
+    // Reinterpreting and converting differ by the offset
+    assert_eq!(
+        run(&format!(
+            "{INSTANT} 'Asia/Tokyo' WITH-TZ >UTC {INSTANT} 'Asia/Tokyo' >TZ >UTC =="
+        )),
+        ForthicValue::Bool(false)
+    );
+    assert!(run_err(&format!("{INSTANT} 'Mars/Olympus' WITH-TZ"))
+        .contains("WITH-TZ: unknown timezone 'Mars/Olympus'"));
+    assert!(run_err(&format!("{INSTANT} 5 >TZ"))
+        .contains(">TZ argument 2 (timezone) must be string, got int"));
+}
+
+#[test]
+fn test_dst_overlap_policies() {
+    // New York repeats 01:00-02:00 on 2024-11-03
+    let overlap = |options: &str| local("2024-11-03", "01:30", "America/New_York", options);
+    assert_eq!(overlap(""), "2024-11-03T01:30:00-04:00");
+    assert_eq!(
+        overlap(".on_ambiguous 'earlier'"),
+        "2024-11-03T01:30:00-04:00"
+    );
+    assert_eq!(
+        overlap(".on_ambiguous 'later'"),
+        "2024-11-03T01:30:00-05:00"
+    );
+    assert!(run_err(
+        "'2024-11-03' >DATE '01:30' >TIME 'America/New_York' [.on_ambiguous 'error'] ~> LOCAL-DATETIME"
+    )
+    .contains(
+        "LOCAL-DATETIME: 2024-11-03 01:30:00 is ambiguous in America/New_York (repeated by a DST change)"
+    ));
+}
+
+#[test]
+fn test_dst_gap_policies() {
+    // New York skips 02:00-03:00 on 2024-03-10
+    let gap = |options: &str| local("2024-03-10", "02:30", "America/New_York", options);
+    assert_eq!(gap(""), "2024-03-10T03:30:00-04:00");
+    assert_eq!(gap(".on_ambiguous 'later'"), "2024-03-10T03:30:00-04:00");
+    assert_eq!(gap(".on_ambiguous 'earlier'"), "2024-03-10T01:30:00-05:00");
+    assert!(run_err(
+        "'2024-03-10' >DATE '02:30' >TIME 'America/New_York' [.on_ambiguous 'error'] ~> LOCAL-DATETIME"
+    )
+    .contains(
+        "LOCAL-DATETIME: 2024-03-10 02:30:00 does not exist in America/New_York (skipped by a DST change)"
+    ));
+    // Lord Howe Island moves its clocks by half an hour
+    assert_eq!(
+        local("2024-10-06", "02:15", "Australia/Lord_Howe", ""),
+        "2024-10-06T02:45:00+11:00"
+    );
+
+    // WITH-TZ follows the same policy
+    assert_eq!(
+        text(
+            "'2024-03-10T02:30:00Z' >DATETIME 'America/New_York' [.on_ambiguous 'earlier'] ~> WITH-TZ FORMAT-DATETIME"
+        ),
+        "2024-03-10T01:30:00-05:00"
+    );
+    assert!(run_err(
+        "'2024-03-10T02:30:00Z' >DATETIME 'America/New_York' [.on_ambiguous 'never'] ~> WITH-TZ"
+    )
+    .contains(
+        "WITH-TZ: unknown on_ambiguous 'never' (expected compatible, earlier, later or error)"
+    ));
+}
+
+#[test]
+fn test_local_datetime_operands() {
+    assert_eq!(
+        local("2024-07-01", "09:00", "Europe/Paris", ""),
+        "2024-07-01T09:00:00+02:00"
+    );
+    // A DateTime contributes its calendar date, as with AT
+    assert_eq!(
+        text(&format!(
+            "{INSTANT} '09:00' >TIME 'Europe/Paris' LOCAL-DATETIME FORMAT-DATETIME"
+        )),
+        "2024-07-01T09:00:00+02:00"
+    );
+    assert_eq!(
+        run("'2024-07-01' >DATE NULL 'Europe/Paris' LOCAL-DATETIME"),
+        ForthicValue::Null
+    );
+}
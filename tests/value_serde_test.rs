@@ -0,0 +1,253 @@
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use forthic::interpreter::Interpreter;
+use forthic::{from_value, to_value, ForthicValue, ValueError};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Ticket {
+    key: String,
+    points: i64,
+    estimate: f64,
+    labels: Vec<String>,
+    assignee: Option<String>,
+    status: Status,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Status {
+    Open,
+    Blocked(String),
+    Moved { project: String, key: String },
+    Split(i64, i64),
+}
+
+fn ticket() -> Ticket {
+    Ticket {
+        key: "PROJ-1".to_string(),
+        points: 3,
+        estimate: 1.5,
+        labels: vec!["backend".to_string()],
+        assignee: None,
+        status: Status::Open,
+    }
+}
+
+fn record(fields: Vec<(&str, ForthicValue)>) -> ForthicValue {
+    ForthicValue::Record(
+        fields
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect::<IndexMap<_, _>>(),
+    )
+}
+
+#[test]
+fn test_struct_becomes_ordered_record() {
+    let value = to_value(&ticket()).unwrap();
+    assert_eq!(
+        value,
+        record(vec![
+            ("key", ForthicValue::String("PROJ-1".to_string())),
+            ("points", ForthicValue::Int(3)),
+            ("estimate", ForthicValue::Float(1.5)),
+            (
+                "labels",
+                ForthicValue::Array(vec![ForthicValue::String("backend".to_string())])
+            ),
+            ("assignee", ForthicValue::Null),
+            ("status", ForthicValue::String("Open".to_string())),
+        ])
+    );
+    let ForthicValue::Record(fields) = &value else {
+        unreachable!()
+    };
+    assert_eq!(
+        fields.keys().collect::<Vec<_>>(),
+        ["key", "points", "estimate", "labels", "assignee", "status"]
+    );
+    assert_eq!(from_value::<Ticket>(value).unwrap(), ticket());
+}
+
+#[test]
+fn test_enum_variants_are_externally_tagged() {
+    let blocked = to_value(&Status::Blocked("waiting".to_string())).unwrap();
+    assert_eq!(
+        blocked,
+        record(vec![(
+            "Blocked",
+            ForthicValue::String("waiting".to_string())
+        )])
+    );
+
+    let moved = Status::Moved {
+        project: "OPS".to_string(),
+        key: "OPS-7".to_string(),
+    };
+    let value = to_value(&moved).unwrap();
+    assert_eq!(
+        value,
+        record(vec![(
+            "Moved",
+            record(vec![
+                ("project", ForthicValue::String("OPS".to_string())),
+                ("key", ForthicValue::String("OPS-7".to_string())),
+            ])
+        )])
+    );
+    assert_eq!(from_value::<Status>(value).unwrap(), moved);
+
+    let split = to_value(&Status::Split(2, 3)).unwrap();
+    assert_eq!(
+        split,
+        record(vec![(
+            "Split",
+            ForthicValue::Array(vec![ForthicValue::Int(2), ForthicValue::Int(3)])
+        )])
+    );
+    assert_eq!(from_value::<Status>(split).unwrap(), Status::Split(2, 3));
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Schedule {
+    day: NaiveDate,
+    at: NaiveTime,
+    logged: DateTime<Utc>,
+    #[serde(with = "forthic::value_serde::zoned")]
+    starts: DateTime<Tz>,
+}
+
+#[test]
+fn test_chrono_types_map_to_native_variants() {
+    let la: Tz = "America/Los_Angeles".parse().unwrap();
+    let schedule = Schedule {
+        day: NaiveDate::from_ymd_opt(2020, 6, 5).unwrap(),
+        at: NaiveTime::from_hms_opt(10, 15, 0).unwrap(),
+        logged: Utc.with_ymd_and_hms(2020, 6, 5, 17, 15, 0).unwrap(),
+        starts: la.with_ymd_and_hms(2020, 6, 5, 10, 15, 0).unwrap(),
+    };
+    let value = to_value(&schedule).unwrap();
+    let ForthicValue::Record(fields) = &value else {
+        panic!("expected record, got {value:?}");
+    };
+    assert_eq!(fields["day"], ForthicValue::Date(schedule.day));
+    assert_eq!(fields["at"], ForthicValue::Time(schedule.at));
+    assert_eq!(
+        fields["logged"],
+        ForthicValue::DateTime(schedule.logged.with_timezone(&Tz::UTC))
+    );
+    // The zoned helper keeps the zone, not just the offset
+    match &fields["starts"] {
+        ForthicValue::DateTime(dt) => {
+            assert_eq!(dt.timezone(), la);
+            assert_eq!(*dt, schedule.starts);
+        }
+        other => panic!("expected datetime, got {other:?}"),
+    }
+    assert_eq!(from_value::<Schedule>(value).unwrap(), schedule);
+}
+
+#[test]
+fn test_zoned_helper_in_other_formats() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Meeting {
+        #[serde(with = "forthic::value_serde::zoned")]
+        starts: DateTime<Tz>,
+    }
+    let la: Tz = "America/Los_Angeles".parse().unwrap();
+    let meeting = Meeting {
+        starts: la.with_ymd_and_hms(2020, 6, 5, 10, 15, 0).unwrap(),
+    };
+    let json = serde_json::to_string(&meeting).unwrap();
+    assert_eq!(
+        json,
+        r#"{"starts":"2020-06-05T10:15:00-07:00[America/Los_Angeles]"}"#
+    );
+    assert_eq!(serde_json::from_str::<Meeting>(&json).unwrap(), meeting);
+}
+
+#[test]
+fn test_plain_strings_stay_strings() {
+    // Only collect_str output is recognized as temporal; a String that
+    // happens to look like a date is left alone
+    assert_eq!(
+        to_value("2020-06-05").unwrap(),
+        ForthicValue::String("2020-06-05".to_string())
+    );
+    assert_eq!(
+        from_value::<String>(ForthicValue::String("2020-06-05".to_string())).unwrap(),
+        "2020-06-05"
+    );
+}
+
+#[test]
+fn test_maps_and_keys() {
+    let mut counts = BTreeMap::new();
+    counts.insert(2, "two");
+    counts.insert(1, "one");
+    assert_eq!(
+        to_value(&counts).unwrap(),
+        record(vec![
+            ("1", ForthicValue::String("one".to_string())),
+            ("2", ForthicValue::String("two".to_string())),
+        ])
+    );
+
+    let mut by_pair = BTreeMap::new();
+    by_pair.insert((1, 2), true);
+    assert_eq!(
+        to_value(&by_pair).unwrap_err(),
+        ValueError::NonStringKey("array".to_string())
+    );
+}
+
+#[test]
+fn test_numbers() {
+    assert_eq!(to_value(&7u8).unwrap(), ForthicValue::Int(7));
+    assert_eq!(
+        to_value(&u64::MAX).unwrap_err(),
+        ValueError::IntegerOutOfRange(u64::MAX.to_string())
+    );
+    // Ints widen to floats; strings never parse as numbers
+    assert_eq!(from_value::<f64>(ForthicValue::Int(2)).unwrap(), 2.0);
+    assert_eq!(from_value::<u8>(ForthicValue::Int(200)).unwrap(), 200);
+    assert!(from_value::<u8>(ForthicValue::Int(300)).is_err());
+    assert!(from_value::<i64>(ForthicValue::String("5".to_string())).is_err());
+}
+
+#[test]
+fn test_deserialize_errors() {
+    let missing = record(vec![("key", ForthicValue::String("PROJ-1".to_string()))]);
+    assert_eq!(
+        from_value::<Ticket>(missing).unwrap_err().to_string(),
+        "missing field `points`"
+    );
+    let err = from_value::<Status>(ForthicValue::Int(1)).unwrap_err();
+    assert!(err.to_string().contains("expected enum variant"), "{err}");
+}
+
+#[test]
+fn test_stack_pop_as_and_push_as() {
+    let mut interp = Interpreter::standard("UTC");
+    interp
+        .run("[['key' 'PROJ-2'] ['points' 5] ['estimate' 2] ['labels' []] ['status' 'Open']] REC")
+        .unwrap();
+    let popped: Ticket = interp.get_stack_mut().pop_as().unwrap();
+    assert_eq!(popped.key, "PROJ-2");
+    assert_eq!(popped.points, 5);
+    assert_eq!(popped.estimate, 2.0);
+    assert_eq!(popped.assignee, None);
+
+    interp.get_stack_mut().push_as(&ticket()).unwrap();
+    interp.run("'points' REC@").unwrap();
+    assert_eq!(interp.stack_pop().unwrap(), ForthicValue::Int(3));
+
+    interp.run("42").unwrap();
+    let err = interp.get_stack_mut().pop_as::<Ticket>().unwrap_err();
+    assert!(err.to_string().contains("invalid type"), "{err}");
+}
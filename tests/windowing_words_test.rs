@@ -0,0 +1,155 @@
+//! Windowing, scanning and partitioning words: WINDOW, PAIRWISE, SCAN,
+//! PARTITION, SPLIT-WHEN, CHUNK-BY, INTERLEAVE, TRANSPOSE, FLAT-MAP
+
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::interpreter::Interpreter;
+use forthic::literals::ForthicValue;
+use serde_json::{json, Value};
+
+/// Run `code` and return its results (the whole stack) as compact JSON
+fn run_json(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(&format!("[ {code} ] >JSON")).unwrap();
+    match interp.get_stack_mut().pop().unwrap() {
+        ForthicValue::String(s) => {
+            let results: Vec<Value> = serde_json::from_str(&s).unwrap();
+            match results.as_slice() {
+                [one] => one.to_string(),
+                _ => Value::Array(results).to_string(),
+            }
+        }
+        other => panic!("expected JSON text, got {other:?}"),
+    }
+}
+
+fn run_err(code: &str) -> String {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run(code).unwrap_err().to_string()
+}
+
+const RECORD: &str = r#"'{"a":1,"b":2,"c":3}' JSON>"#;
+
+#[test]
+fn test_window() {
+    assert_eq!(run_json("[1 2 3 4] 2 WINDOW"), "[[1,2],[2,3],[3,4]]");
+    assert_eq!(
+        run_json("[1 2 3 4 5] 2 [.step 2] ~> WINDOW"),
+        "[[1,2],[3,4]]"
+    );
+    assert_eq!(
+        run_json("[1 2 3 4 5] 2 [.step 2 .partial TRUE] ~> WINDOW"),
+        "[[1,2],[3,4],[5]]"
+    );
+    assert_eq!(run_json("[1 2] 3 WINDOW"), "[]");
+    assert_eq!(run_json("NULL 3 WINDOW"), "[]");
+    assert_eq!(
+        run_json(&format!("{RECORD} 2 WINDOW")),
+        json!([{ "a": 1, "b": 2 }, { "b": 2, "c": 3 }]).to_string()
+    );
+    assert!(run_err("[1 2] 0 WINDOW").contains("WINDOW argument 2 must be positive int, got int"));
+    assert!(run_err("[1 2] 1 [.step 0] ~> WINDOW")
+        .contains("WINDOW argument 3 (step) must be positive int"));
+}
+
+#[test]
+fn test_pairwise() {
+    assert_eq!(run_json("[1 2 3] PAIRWISE"), "[[1,2],[2,3]]");
+    assert_eq!(run_json("[1] PAIRWISE"), "[]");
+    assert!(run_err("'abc' PAIRWISE").contains("PAIRWISE argument 1 must be array, got string"));
+}
+
+#[test]
+fn test_scan_keeps_running_totals() {
+    assert_eq!(run_json("[1 2 3 4] 0 '+' SCAN"), "[1,3,6,10]");
+    assert_eq!(run_json("[] 0 '+' SCAN"), "[]");
+    // The last running value is REDUCE's result
+    assert_eq!(
+        run_json("[3 1 4 1 5] DUP 0 '+' SCAN LAST SWAP 0 '+' REDUCE =="),
+        "true"
+    );
+    assert_eq!(
+        run_json(&format!("{RECORD} 10 '+' SCAN")),
+        json!({ "a": 11, "b": 13, "c": 16 }).to_string()
+    );
+    // ( acc index item -- acc ): acc + index * item
+    assert_eq!(
+        run_json("[5 6 7] 0 '* +' [.with_key TRUE] ~> SCAN"),
+        "[0,6,20]"
+    );
+}
+
+#[test]
+fn test_partition() {
+    assert_eq!(
+        run_json("[1 2 3 4 5] '2 MOD 0 ==' PARTITION"),
+        "[[2,4],[1,3,5]]"
+    );
+    assert_eq!(
+        run_json(&format!(
+            "{RECORD} 'DROP \"b\" ==' [.with_key TRUE] ~> PARTITION"
+        )),
+        json!([{ "b": 2 }, { "a": 1, "c": 3 }]).to_string()
+    );
+}
+
+#[test]
+fn test_split_when_and_chunk_by() {
+    assert_eq!(
+        run_json("[1 2 4 5 7] '- -1 <' SPLIT-WHEN"),
+        "[[1,2],[4,5],[7]]"
+    );
+    assert_eq!(run_json("[] '<' SPLIT-WHEN"), "[]");
+    assert_eq!(
+        run_json("[1 3 2 4 6 5] '2 MOD' CHUNK-BY"),
+        "[[1,3],[2,4,6],[5]]"
+    );
+    // A key seen again later starts a new run; 1 and 1.0 are the same key
+    assert_eq!(run_json("[1 1.0 2 1] '' CHUNK-BY"), "[[1,1.0],[2],[1]]");
+    assert_eq!(
+        run_json(&format!("{RECORD} '3 <' CHUNK-BY")),
+        json!([{ "a": 1, "b": 2 }, { "c": 3 }]).to_string()
+    );
+    assert_eq!(
+        run_json("['x' 'y' 'z'] 'DROP 2 <' [.with_key TRUE] ~> CHUNK-BY"),
+        json!([["x", "y"], ["z"]]).to_string()
+    );
+}
+
+#[test]
+fn test_interleave_and_transpose() {
+    assert_eq!(
+        run_json("[[1 2 3] ['a'] [] ['x' 'y']] INTERLEAVE"),
+        json!([1, "a", "x", 2, "y", 3]).to_string()
+    );
+    assert_eq!(
+        run_json("[[1 2 3] [4 5 6]] TRANSPOSE"),
+        "[[1,4],[2,5],[3,6]]"
+    );
+    assert_eq!(run_json("[[1 2] [3]] TRANSPOSE"), "[[1,3],[2,null]]");
+    assert_eq!(
+        run_json("[[1 2 3] [4 5 6]] TRANSPOSE TRANSPOSE"),
+        "[[1,2,3],[4,5,6]]"
+    );
+    assert!(run_err("[[1] 2] TRANSPOSE")
+        .contains("TRANSPOSE: element 1 of argument 1 must be an array, got int"));
+}
+
+#[test]
+fn test_flat_map() {
+    assert_eq!(run_json("[1 2 3] '1 SWAP RANGE' FLAT-MAP"), "[1,1,2,1,2,3]");
+    // NULL adds nothing, other values add themselves: odd items only
+    assert_eq!(
+        run_json("[1 2 3 4] 'DUP 2 MOD 1 == SWAP NULL IF' FLAT-MAP"),
+        "[1,3]"
+    );
+    assert_eq!(
+        run_json("['x' 'y' 'z'] 'DROP 1 SWAP 1 + RANGE' [.with_key TRUE] ~> FLAT-MAP"),
+        "[1,1,2,1,2,3]"
+    );
+    assert_eq!(
+        run_json(&format!("{RECORD} '1 SWAP RANGE' FLAT-MAP")),
+        "[1,1,2,1,2,3]"
+    );
+}
@@ -165,7 +165,7 @@ fn test_any_q_and_all_q() {
 fn test_any_q_requires_an_array() {
     let mut interp = Interpreter::standard("UTC");
     let err = interp.run("5 ANY?").unwrap_err();
-    assert!(err.to_string().contains("requires an array"), "got: {err}");
+    assert_eq!(err.to_string(), "ANY? argument 1 must be array, got int");
 }
 
 // ===== >BOOL truthiness fixes =====
@@ -136,6 +136,54 @@ fn test_sort_natural() {
     );
 }
 
+#[test]
+fn test_sort_is_a_total_order_with_nan() {
+    // Enough elements for the sort to check its comparator; NaN (-1 SQRT)
+    // ranks after every number and before NULL
+    let items: Vec<String> = (0..60)
+        .map(|i| match i % 4 {
+            0 => "-1 SQRT".to_string(),
+            1 => format!("{}", 60 - i),
+            2 => format!("{}.5", i),
+            _ => "NULL".to_string(),
+        })
+        .collect();
+    let sorted = match run(&format!("[{}] SORT", items.join(" "))) {
+        ForthicValue::Array(items) => items,
+        other => panic!("expected an array, got {other:?}"),
+    };
+    let numbers: Vec<f64> = sorted
+        .iter()
+        .filter_map(|item| match item {
+            ForthicValue::Int(i) => Some(*i as f64),
+            ForthicValue::Float(f) => Some(*f),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(numbers.len(), 45);
+    assert!(numbers[..30].windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(numbers[30..].iter().all(|n| n.is_nan()));
+    assert!(sorted[45..].iter().all(|item| *item == ForthicValue::Null));
+
+    // Ints past 2^53 compare exactly against the Float they round to
+    assert_eq!(
+        run("[9007199254740993 9007199254740992.0 9007199254740992] SORT"),
+        ForthicValue::Array(vec![
+            ForthicValue::Float(9_007_199_254_740_992.0),
+            ForthicValue::Int(9_007_199_254_740_992),
+            ForthicValue::Int(9_007_199_254_740_993),
+        ])
+    );
+    assert_eq!(
+        run("9007199254740993 9007199254740992.0 =="),
+        ForthicValue::Bool(false)
+    );
+    assert_eq!(
+        run("9007199254740992 9007199254740992.0 =="),
+        ForthicValue::Bool(true)
+    );
+}
+
 #[test]
 fn test_sort_comparator_is_a_key_function() {
     // The comparator option is a KEY function (the ts docstring's "SWAP -"
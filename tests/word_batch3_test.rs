@@ -240,7 +240,10 @@ fn test_delete_is_copy_on_write_flavor() {
     assert_eq!(run("[ 1 2 ] 9 DELETE"), ints(&[1, 2]));
     // Non-integer array key errors (no ts NaN->0 splice surprise)
     let err = run_err("[ 1 2 ] 'x' DELETE");
-    assert!(err.to_string().contains("integer index"), "got: {err}");
+    assert_eq!(
+        err.to_string(),
+        "DELETE argument 2 (index) must be int, got string"
+    );
 }
 
 // ===== REC>ENTRIES / ENTRIES>REC =====
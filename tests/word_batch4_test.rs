@@ -341,3 +341,38 @@ fn test_print_rejects_expression_holes_too() {
     let err = run_err("'value: ${6 * 7}' PRINT");
     assert!(err.to_string().contains("not expressions"), "got: {err}");
 }
+
+// ===== PROGRESS (host callback) =====
+
+#[test]
+fn test_progress_reports_to_the_host_handler() {
+    use std::sync::{Arc, Mutex};
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&seen);
+    let mut interp = Interpreter::standard("UTC");
+    interp.set_progress_handler(Some(Arc::new(move |value: &ForthicValue| {
+        sink.lock().unwrap().push(value.clone());
+    })));
+    interp
+        .run("'start' PROGRESS [1 2] '10 * DUP PROGRESS' MAP")
+        .unwrap();
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![s("start"), ForthicValue::Int(10), ForthicValue::Int(20)]
+    );
+    assert_eq!(
+        interp.get_stack().items(),
+        &[ForthicValue::Array(vec![
+            ForthicValue::Int(10),
+            ForthicValue::Int(20)
+        ])]
+    );
+}
+
+#[test]
+fn test_progress_without_a_handler_just_pops() {
+    let mut interp = Interpreter::standard("UTC");
+    interp.run("1 2 PROGRESS").unwrap();
+    assert_eq!(interp.get_stack().items(), &[ForthicValue::Int(1)]);
+}
@@ -146,7 +146,10 @@ fn test_format_fixed_errors() {
     let err = run_err("3.14 101 FORMAT-FIXED");
     assert!(err.to_string().contains("between 0 and 100"), "got: {err}");
     let err = run_err("'x' 2 FORMAT-FIXED");
-    assert!(err.to_string().contains("requires a number"), "got: {err}");
+    assert_eq!(
+        err.to_string(),
+        "FORMAT-FIXED argument 1 must be number, got string"
+    );
 }
 
 // ===== AM / PM =====
@@ -339,7 +342,10 @@ fn test_use_modules_errors() {
     let err = run_err("[ 'no-such-module' ] USE-MODULES");
     assert!(err.to_string().contains("no-such-module"), "got: {err}");
     let err = run_err("'greet' USE-MODULES");
-    assert!(err.to_string().contains("requires an array"), "got: {err}");
+    assert_eq!(
+        err.to_string(),
+        "USE-MODULES argument 1 must be array, got string"
+    );
     // NULL names is a silent no-op
     let mut interp = Interpreter::standard("UTC");
     interp.run("NULL USE-MODULES").unwrap();
@@ -0,0 +1,115 @@
+// ForthicError is large; accepted trade-off (see lib.rs / backlog item 11)
+#![allow(clippy::result_large_err)]
+
+use forthic::interpreter::Interpreter;
+use forthic::word_policy::WordPolicy;
+use forthic::ForthicError;
+
+fn words(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+fn guarded(policy: WordPolicy) -> Interpreter {
+    let mut interp = Interpreter::standard("UTC");
+    interp.set_word_policy(&policy).unwrap();
+    interp
+}
+
+fn refused_word(interp: &mut Interpreter, code: &str) -> String {
+    match interp.run(code).unwrap_err().into_innermost() {
+        ForthicError::WordNotAllowed { word, .. } => word,
+        other => panic!("expected WordNotAllowed, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_denied_words_are_refused_everywhere() {
+    let policy = WordPolicy {
+        deny_words: words(&["REVERSE"]),
+        ..WordPolicy::default()
+    };
+    let refused = |code: &str| refused_word(&mut guarded(policy.clone()), code);
+    guarded(policy.clone()).run("[1 2] LENGTH").unwrap();
+    assert_eq!(refused("[1 2] REVERSE"), "REVERSE");
+    // Inside a definition body, at compile time
+    assert_eq!(refused(": FLIP REVERSE ;"), "REVERSE");
+    // Inside code strings run by other words
+    assert_eq!(refused("'[1] REVERSE' RUN"), "REVERSE");
+    assert_eq!(refused("[[1]] 'REVERSE' MAP"), "REVERSE");
+    assert_eq!(
+        guarded(policy).run("REVERSE").unwrap_err().to_string(),
+        "Word not allowed: REVERSE"
+    );
+}
+
+#[test]
+fn test_allowlist_of_modules_and_words() {
+    let mut interp = guarded(WordPolicy {
+        allow_modules: words(&["core", "math"]),
+        allow_words: words(&["LENGTH"]),
+        ..WordPolicy::default()
+    });
+    // Own definitions, variables, and literals are not library words
+    interp
+        .run(": DOUBLE 2 * ; ['x'] VARIABLES 4 .x ! .x @ DOUBLE [1 2 3] LENGTH +")
+        .unwrap();
+    assert_eq!(interp.stack_pop().unwrap(), forthic::ForthicValue::Int(11));
+    assert_eq!(refused_word(&mut interp, "[1] REVERSE"), "REVERSE");
+    assert_eq!(refused_word(&mut interp, "'a' LOWERCASE"), "LOWERCASE");
+}
+
+#[test]
+fn test_deny_wins_and_prefixed_imports_are_checked() {
+    let mut interp = guarded(WordPolicy {
+        allow_modules: words(&["core", "array"]),
+        deny_modules: words(&["array"]),
+        ..WordPolicy::default()
+    });
+    assert_eq!(refused_word(&mut interp, "[1] REVERSE"), "REVERSE");
+
+    let mut interp = guarded(WordPolicy {
+        deny_words: words(&["REVERSE"]),
+        ..WordPolicy::default()
+    });
+    assert_eq!(
+        refused_word(&mut interp, "[['array' 'a']] USE-MODULES [1] a.REVERSE"),
+        "a.REVERSE"
+    );
+}
+
+#[test]
+fn test_dotted_import_prefixes_are_checked() {
+    let import = "[['core' 'a.b']] USE-MODULES ";
+    let mut interp = guarded(WordPolicy {
+        deny_words: words(&["RUN"]),
+        ..WordPolicy::default()
+    });
+    assert_eq!(
+        refused_word(&mut interp, &format!("{import}'1 2 +' a.b.RUN")),
+        "a.b.RUN"
+    );
+
+    let mut interp = guarded(WordPolicy {
+        allow_modules: words(&["math"]),
+        allow_words: words(&["USE-MODULES"]),
+        ..WordPolicy::default()
+    });
+    assert_eq!(
+        refused_word(&mut interp, &format!("{import}'1 2 +' a.b.RUN")),
+        "a.b.RUN"
+    );
+    interp.run(&format!("{import}1 2 +")).unwrap();
+    assert_eq!(interp.stack_pop().unwrap(), forthic::ForthicValue::Int(3));
+}
+
+#[test]
+fn test_unknown_policy_module_is_an_error() {
+    let mut interp = Interpreter::standard("UTC");
+    let err = interp
+        .set_word_policy(&WordPolicy {
+            deny_modules: words(&["nope"]),
+            ..WordPolicy::default()
+        })
+        .unwrap_err();
+    assert!(matches!(err, ForthicError::UnknownModule { .. }));
+}